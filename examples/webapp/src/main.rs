@@ -34,17 +34,19 @@ const CACHE_PATH: &str = ".spotify_cache/";
 const CLIENT_ID: &str = "e1dce60f1e274e20861ce5d96142a4d3";
 const CLIENT_SECRET: &str = "0e4e03b9be8d465d87fc32857a4b5aa3";
 
-/// Generate `length` random chars
-fn generate_random_uuid(length: usize) -> String {
+/// Generate `length` random chars, or fail if the system has no source of
+/// randomness.
+fn generate_random_uuid(length: usize) -> Result<String, getrandom::Error> {
     let alphanum: &[u8] =
         "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789".as_bytes();
     let mut buf = vec![0u8; length];
-    getrandom(&mut buf).unwrap();
+    getrandom(&mut buf)?;
     let range = alphanum.len();
 
-    buf.iter()
+    Ok(buf
+        .iter()
         .map(|byte| alphanum[*byte as usize % range] as char)
-        .collect()
+        .collect())
 }
 
 /// We store the cache locally within the current directory.
@@ -166,7 +168,14 @@ fn index(jar: &CookieJar<'_>) -> Template {
     // them.
     let authenticated = jar.get("uuid").is_some() && cache_path_exists(jar);
     if !authenticated {
-        let uuid = Cookie::build(("uuid", generate_random_uuid(64)))
+        let uuid = match generate_random_uuid(64) {
+            Ok(uuid) => uuid,
+            Err(err) => {
+                context.insert("err_msg", format!("Failed to generate the session id: {err}"));
+                return Template::render("error", context);
+            }
+        };
+        let uuid = Cookie::build(("uuid", uuid))
             .path("/")
             .secure(true)
             .max_age(Duration::minutes(30))
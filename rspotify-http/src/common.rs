@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use maybe_async::maybe_async;
 use serde_json::Value;
@@ -8,6 +10,227 @@ pub type Headers = HashMap<String, String>;
 pub type Query<'a> = HashMap<&'a str, &'a str>;
 pub type Form<'a> = HashMap<&'a str, &'a str>;
 
+/// The URL with the query parameters of a `GET` request appended. The URL is
+/// returned as is if it's invalid, so that the request fails when it's sent.
+#[cfg(any(feature = "client-reqwest", feature = "client-mock"))]
+pub(crate) fn url_with_query<'a, I>(url: &str, query: I) -> String
+where
+    I: IntoIterator<Item = (&'a str, &'a str)>,
+{
+    let mut query = query.into_iter().peekable();
+    if query.peek().is_none() {
+        return url.to_owned();
+    }
+    url::Url::parse_with_params(url, query).map_or_else(|_| url.to_owned(), String::from)
+}
+
+/// The proxy used by a single HTTP client, instead of the one configured with
+/// the `http_proxy`, `https_proxy` or `all_proxy` environment variables.
+///
+/// This is ignored on WebAssembly, where the browser handles the connections.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct ProxyConfig {
+    /// The URL of the proxy, e.g., `http://127.0.0.1:3128` or
+    /// `socks5://proxy.example.com:1080`.
+    pub url: String,
+    /// The username and password for basic authentication, if required.
+    /// With ureq, the username can't contain colons.
+    pub basic_auth: Option<(String, String)>,
+    /// The hosts that are reached directly rather than through the proxy.
+    /// `*` matches all of them, and the rest also match their subdomains
+    /// (e.g., `example.com` matches `api.example.com`).
+    pub no_proxy: Vec<String>,
+}
+
+impl ProxyConfig {
+    /// Builds a proxy configuration for the given URL, without
+    /// authentication nor exceptions.
+    #[must_use]
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_owned(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A client certificate for mutual TLS, for setups where the requests have to
+/// go through a gateway that requires one.
+///
+/// The supported TLS backends are `reqwest-native-tls`, `reqwest-rustls-tls`
+/// and `ureq-native-tls`. With the rest, building the client fails.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TlsIdentity {
+    /// The certificate chain in PEM format.
+    pub cert_pem: Vec<u8>,
+    /// The private key in PKCS #8 PEM format.
+    pub key_pem: Vec<u8>,
+}
+
+impl TlsIdentity {
+    /// Builds the identity from the certificate chain and the PKCS #8 private
+    /// key, both in PEM format.
+    #[must_use]
+    pub fn from_pem(cert_pem: impl Into<Vec<u8>>, key_pem: impl Into<Vec<u8>>) -> Self {
+        Self {
+            cert_pem: cert_pem.into(),
+            key_pem: key_pem.into(),
+        }
+    }
+}
+
+// The private key is left out so that it isn't accidentally logged.
+impl fmt::Debug for TlsIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsIdentity")
+            .field("cert_pem", &String::from_utf8_lossy(&self.cert_pem))
+            .finish_non_exhaustive()
+    }
+}
+
+/// The outcome of a request, as seen by a [`RequestInterceptor`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InterceptedResponse<'a> {
+    /// The status code of the response, or `None` if the request couldn't be
+    /// completed.
+    pub status: Option<u16>,
+    /// The body of the response if it was successful.
+    pub body: Option<&'a str>,
+    /// The time since the request was sent. It's not measured on WebAssembly,
+    /// where the clock isn't available.
+    pub elapsed: Option<Duration>,
+}
+
+/// Middleware invoked by the HTTP client around every request, e.g., to add
+/// tracing headers, to log the requests or to record metrics.
+///
+/// The URL given to both methods is the full one, including the query
+/// parameters of the `GET` requests. Both methods do nothing by default.
+pub trait RequestInterceptor: Send + Sync + fmt::Debug {
+    /// Invoked before sending a request. The headers inserted in `headers`
+    /// are sent along with it, in addition to the ones set by the client,
+    /// which aren't included so that the access token isn't exposed.
+    fn before_request(&self, _method: &str, _url: &str, _headers: &mut Headers) {}
+
+    /// Invoked once the response is received, or the request fails.
+    fn after_response(&self, _method: &str, _url: &str, _response: &InterceptedResponse<'_>) {}
+}
+
+/// The timeout of the whole request, from connecting until the body of the
+/// response is read, when no other one is configured.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The settings to build an HTTP client with, on top of the defaults.
+#[derive(Clone, Debug, Default)]
+pub struct HttpOptions {
+    /// The proxy to connect through, instead of the one in the environment.
+    pub proxy: Option<ProxyConfig>,
+    /// The client certificate to present to the servers.
+    pub identity: Option<TlsIdentity>,
+    /// The interceptors invoked around every request, in order.
+    pub interceptors: Vec<Arc<dyn RequestInterceptor>>,
+    /// The timeout of the whole request, [`DEFAULT_TIMEOUT`] if `None`.
+    pub timeout: Option<Duration>,
+    /// The timeout to establish the connection, which is only limited by
+    /// [`Self::timeout`] if `None`.
+    pub connect_timeout: Option<Duration>,
+    /// The `User-Agent` header sent with every request, instead of the
+    /// default one of the HTTP library.
+    pub user_agent: Option<String>,
+}
+
+/// The interceptors of a client, which are invoked together.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Interceptors(Arc<Vec<Arc<dyn RequestInterceptor>>>);
+
+impl Interceptors {
+    pub(crate) fn new(interceptors: &[Arc<dyn RequestInterceptor>]) -> Self {
+        Self(Arc::new(interceptors.to_vec()))
+    }
+
+    /// Runs [`RequestInterceptor::before_request`], returning the headers to
+    /// add to the request.
+    pub(crate) fn before_request(&self, method: &str, url: &str) -> Headers {
+        let mut headers = Headers::new();
+        for interceptor in self.0.iter() {
+            interceptor.before_request(method, url, &mut headers);
+        }
+        headers
+    }
+
+    pub(crate) fn after_response(
+        &self,
+        method: &str,
+        url: &str,
+        response: &InterceptedResponse<'_>,
+    ) {
+        for interceptor in self.0.iter() {
+            interceptor.after_response(method, url, response);
+        }
+    }
+}
+
+/// Parses the `Retry-After` header of a response, which Spotify sends with
+/// the `429 Too Many Requests` responses. Only the number of seconds is
+/// supported, not the HTTP date format.
+pub(crate) fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse().ok().map(Duration::from_secs)
+}
+
+/// The moment a request is sent, to measure its duration. `Instant::now`
+/// panics on WebAssembly, so it's not measured there.
+pub(crate) fn request_start() -> Option<Instant> {
+    if cfg!(target_arch = "wasm32") {
+        None
+    } else {
+        Some(Instant::now())
+    }
+}
+
+/// The span of a request with the `tracing` feature, which is entered while
+/// it's sent. Its status and latency are recorded with [`record_response`].
+#[cfg(feature = "tracing")]
+pub(crate) fn request_span(method: &str, url: &str) -> tracing::Span {
+    // The query is left out, since it may be long and the rest of the URL is
+    // enough to tell the endpoint
+    let url = url.split('?').next().unwrap_or(url);
+    tracing::debug_span!(
+        "spotify_http_request",
+        method,
+        url,
+        status = tracing::field::Empty,
+        latency_ms = tracing::field::Empty,
+    )
+}
+
+/// Records the outcome of a request into its span, see [`request_span`].
+#[cfg(feature = "tracing")]
+pub(crate) fn record_response(span: &tracing::Span, response: &InterceptedResponse<'_>) {
+    if let Some(status) = response.status {
+        span.record("status", status);
+    }
+    if let Some(elapsed) = response.elapsed {
+        span.record("latency_ms", elapsed.as_millis() as u64);
+    }
+}
+
+// The password is left out so that it isn't accidentally logged.
+impl fmt::Debug for ProxyConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProxyConfig")
+            .field("url", &self.url)
+            .field(
+                "basic_auth",
+                &self
+                    .basic_auth
+                    .as_ref()
+                    .map(|(username, _)| (username, "...")),
+            )
+            .field("no_proxy", &self.no_proxy)
+            .finish()
+    }
+}
+
 /// This trait represents the interface to be implemented for an HTTP client,
 /// which is kept separate from the Spotify client for cleaner code. Thus, it
 /// also requires other basic traits that are needed for the Spotify client.
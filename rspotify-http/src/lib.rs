@@ -6,28 +6,43 @@
 // confusing errors..
 
 #[cfg(feature = "client-reqwest")]
-#[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
+#[cfg(not(feature = "client-ureq"))]
 mod reqwest;
 
 #[cfg(feature = "client-ureq")]
-#[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
+#[cfg(not(feature = "client-reqwest"))]
 mod ureq;
 
 #[cfg(any(feature = "client-reqwest", feature = "client-ureq"))]
 #[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
 mod common;
 
-#[cfg(feature = "client-reqwest")]
+#[cfg(feature = "client-mock")]
+#[cfg(any(feature = "client-reqwest", feature = "client-ureq"))]
 #[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
+pub mod mock;
+
+#[cfg(feature = "client-reqwest")]
+#[cfg(not(feature = "client-ureq"))]
 pub use self::reqwest::{ReqwestClient as HttpClient, ReqwestError as HttpError};
 
 #[cfg(feature = "client-ureq")]
-#[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
+#[cfg(not(feature = "client-reqwest"))]
 pub use self::ureq::{UreqClient as HttpClient, UreqError as HttpError};
 
+// The mock client is converted into the real one, which still decides whether
+// it's async or not.
+#[cfg(feature = "client-mock")]
+#[cfg(any(feature = "client-reqwest", feature = "client-ureq"))]
+#[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
+pub use self::mock::{MockError, MockHttpClient, MockResponse, RecordedRequest};
+
 #[cfg(any(feature = "client-reqwest", feature = "client-ureq"))]
 #[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
-pub use common::{BaseHttpClient, Form, Headers, Query};
+pub use common::{
+    BaseHttpClient, Form, Headers, HttpOptions, InterceptedResponse, ProxyConfig, Query,
+    RequestInterceptor, TlsIdentity, DEFAULT_TIMEOUT,
+};
 
 #[cfg(all(feature = "client-reqwest", feature = "client-ureq"))]
 compile_error!(
@@ -39,5 +54,6 @@ compile_error!(
 #[cfg(not(any(feature = "client-reqwest", feature = "client-ureq")))]
 compile_error!(
     "You have to enable at least one of the available clients with the \
-    `client-reqwest` or `client-ureq` features."
+    `client-reqwest` or `client-ureq` features. This is also required by \
+    `client-mock`, to decide whether the client is async or not."
 );
@@ -0,0 +1,556 @@
+//! An offline client that answers with canned responses instead of sending
+//! the requests, to test the code that uses RSpotify without network access.
+//!
+//! It's enabled with the `client-mock` feature, which only adds
+//! [`MockHttpClient`]; the regular client is still available. A mock client is
+//! used by converting it into the regular one, which then sends every request
+//! to the mock instead. It's asynchronous or synchronous depending on whether
+//! `client-reqwest` or `client-ureq` is enabled. Since features are only
+//! unified for the targets that need them, it's usually enabled in the
+//! `[dev-dependencies]`, so that only the tests use it.
+//!
+//! The responses are registered by method and path, and the requests are
+//! recorded so that the tests can check them afterwards:
+//!
+//! ```
+//! use rspotify_http::{BaseHttpClient, HttpClient, MockHttpClient, MockResponse};
+//! # #[maybe_async::maybe_async]
+//! # async fn run() {
+//!
+//! let mock = MockHttpClient::default();
+//! mock.respond("GET", "/v1/me", MockResponse::ok(r#"{"id": "test"}"#));
+//!
+//! // Clones share the responses and the recorded requests
+//! let client = HttpClient::from(mock.clone());
+//! let response = client
+//!     .get("https://api.spotify.com/v1/me", None, &Default::default())
+//!     .await;
+//! assert_eq!(response.unwrap(), r#"{"id": "test"}"#);
+//! assert_eq!(mock.requests()[0].url, "https://api.spotify.com/v1/me");
+//! # }
+//! # #[cfg(feature = "__async")]
+//! # tokio::runtime::Runtime::new().unwrap().block_on(run());
+//! # #[cfg(feature = "__sync")]
+//! # run();
+//! ```
+//!
+//! When converted, the unsuccessful responses are returned as the regular
+//! `HttpError::StatusCode`, and the requests without a registered response
+//! are answered with a `404 Not Found`.
+
+#[cfg(feature = "tracing")]
+use super::common::{record_response, request_span};
+use super::{
+    common::{parse_retry_after, request_start, url_with_query, Interceptors},
+    BaseHttpClient, Form, Headers, HttpOptions, InterceptedResponse, Query,
+};
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use maybe_async::maybe_async;
+use serde_json::Value;
+
+/// The errors returned by [`MockHttpClient`].
+#[derive(thiserror::Error, Debug)]
+pub enum MockError {
+    /// There's no response registered for the request.
+    #[error("no response registered for {method} {path}")]
+    Unmatched { method: String, path: String },
+
+    /// The registered response has an unsuccessful status code, such as 404
+    /// or 503.
+    #[error("status code {status}")]
+    StatusCode {
+        status: u16,
+        body: String,
+        headers: Headers,
+    },
+}
+
+impl MockError {
+    /// The status code of the response, in case it was unsuccessful.
+    #[must_use]
+    pub fn status_code(&self) -> Option<u16> {
+        match self {
+            Self::StatusCode { status, .. } => Some(*status),
+            Self::Unmatched { .. } => None,
+        }
+    }
+
+    /// The delay requested by the `Retry-After` header of the response, in
+    /// case it was unsuccessful.
+    #[must_use]
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::StatusCode { headers, .. } => headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("retry-after"))
+                .and_then(|(_, value)| parse_retry_after(value)),
+            Self::Unmatched { .. } => None,
+        }
+    }
+
+    /// The response that the error stands for, as its status code, body and
+    /// headers. The requests without a registered response are answered with
+    /// a `404 Not Found`, in the same format as Spotify's errors.
+    pub(crate) fn into_response(self) -> (u16, String, Headers) {
+        match self {
+            Self::StatusCode {
+                status,
+                body,
+                headers,
+            } => (status, body, headers),
+            Self::Unmatched { method, path } => {
+                let message = format!("no response registered for {method} {path}");
+                let body = serde_json::json!({
+                    "error": { "status": 404, "message": message }
+                });
+                (404, body.to_string(), Headers::new())
+            }
+        }
+    }
+
+    /// Consumes the error, returning the status code and body of the
+    /// response in case it was unsuccessful. Otherwise, the error itself is
+    /// returned.
+    #[maybe_async]
+    pub async fn into_status_and_body(self) -> Result<(u16, String), Self> {
+        match self {
+            Self::StatusCode { status, body, .. } => Ok((status, body)),
+            err => Err(err),
+        }
+    }
+}
+
+/// A canned response, registered with [`MockHttpClient::respond`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MockResponse {
+    pub status: u16,
+    pub body: String,
+    pub headers: Headers,
+}
+
+impl MockResponse {
+    /// A successful response with the given body.
+    #[must_use]
+    pub fn ok(body: impl Into<String>) -> Self {
+        Self::with_status(200, body)
+    }
+
+    /// A successful response with the given JSON as its body.
+    #[must_use]
+    pub fn json(body: &Value) -> Self {
+        Self::ok(body.to_string())
+    }
+
+    /// A response with the given status code and body.
+    #[must_use]
+    pub fn with_status(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+            headers: Headers::new(),
+        }
+    }
+
+    /// Adds a header to the response, e.g., `Retry-After`.
+    #[must_use]
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.insert(name.to_owned(), value.to_owned());
+        self
+    }
+}
+
+/// A request received by [`MockHttpClient`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// The full URL, without the query parameters of `GET` requests.
+    pub url: String,
+    /// The headers given by the Spotify client, like the authorization, and
+    /// the ones added by the interceptors.
+    pub headers: Headers,
+    /// The query parameters of `GET` requests.
+    pub query: HashMap<String, String>,
+    /// The JSON body, or the fields of a form as a JSON object. It's `null`
+    /// for `GET` requests.
+    pub body: Value,
+}
+
+/// A registered response, along with the query parameters the requests must
+/// have for it, if any.
+#[derive(Debug)]
+struct MockEntry {
+    query: Option<BTreeMap<String, String>>,
+    response: MockResponse,
+}
+
+impl MockEntry {
+    fn matches(&self, query: &HashMap<String, String>) -> bool {
+        match &self.query {
+            Some(expected) => {
+                expected.len() == query.len()
+                    && expected
+                        .iter()
+                        .all(|(key, value)| query.get(key) == Some(value))
+            }
+            None => true,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    responses: HashMap<(String, String), Vec<MockEntry>>,
+    requests: Vec<RecordedRequest>,
+}
+
+/// An HTTP client that answers with the responses registered for each method
+/// and path, see the [module-level documentation](self).
+///
+/// Clones of the client share the responses and the recorded requests, so a
+/// clone can be kept to check them after converting it into the regular
+/// client.
+#[derive(Clone, Debug, Default)]
+pub struct MockHttpClient {
+    state: Arc<Mutex<MockState>>,
+    interceptors: Interceptors,
+}
+
+impl MockHttpClient {
+    /// Builds a client with the given options, of which only the
+    /// interceptors are used.
+    pub fn with_options(options: &HttpOptions) -> Result<Self, MockError> {
+        Ok(Self {
+            state: Arc::default(),
+            interceptors: Interceptors::new(&options.interceptors),
+        })
+    }
+
+    /// Registers a response for the requests with the given method (e.g.,
+    /// `"GET"`) and path (e.g., `"/v1/me"`, or a full URL, whose query is
+    /// ignored). Trailing slashes are ignored as well.
+    ///
+    /// The responses for the same method and path are returned in the order
+    /// they were registered, and the last one is repeated for any further
+    /// requests.
+    pub fn respond(&self, method: &str, path: &str, response: MockResponse) {
+        self.register(method, path, None, response);
+    }
+
+    /// Like [`Self::respond`], but the response is only returned for the
+    /// requests with exactly the given query parameters. It's meant for the
+    /// `GET` requests, whose parameters are sent in the query.
+    pub fn respond_with_query<'a, I>(
+        &self,
+        method: &str,
+        path: &str,
+        query: I,
+        response: MockResponse,
+    ) where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        let query = query
+            .into_iter()
+            .map(|(key, value)| (key.to_owned(), value.to_owned()))
+            .collect();
+        self.register(method, path, Some(query), response);
+    }
+
+    fn register(
+        &self,
+        method: &str,
+        path: &str,
+        query: Option<BTreeMap<String, String>>,
+        response: MockResponse,
+    ) {
+        let key = (method.to_ascii_uppercase(), url_path(path).to_owned());
+        let mut state = self.state.lock().unwrap();
+        let entry = MockEntry { query, response };
+        state.responses.entry(key).or_default().push(entry);
+    }
+
+    /// The requests received so far, in order.
+    #[must_use]
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// Forgets both the registered responses and the recorded requests.
+    pub fn reset(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.responses.clear();
+        state.requests.clear();
+    }
+
+    pub(crate) fn request(
+        &self,
+        method: &str,
+        url: &str,
+        headers: Option<&Headers>,
+        query: HashMap<String, String>,
+        body: Value,
+    ) -> Result<String, MockError> {
+        let mut all_headers = headers.cloned().unwrap_or_default();
+        // Like the regular clients, the interceptors get the URL with the
+        // query, which is sorted to keep it deterministic
+        let sorted = query.iter().collect::<BTreeMap<_, _>>();
+        let full_url = url_with_query(url, sorted.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+        all_headers.extend(self.interceptors.before_request(method, &full_url));
+
+        #[cfg(feature = "tracing")]
+        let span = request_span(method, url);
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+
+        log::info!("Making mock request {method} {url}");
+        let start = request_start();
+        let path = url_path(url);
+        // Some endpoints take their parameters in the URL itself (e.g.,
+        // `tracks/?ids=...`), which count as part of the query too
+        let mut matched_query = query.clone();
+        if let Ok(parsed) = url::Url::parse(url) {
+            matched_query.extend(parsed.query_pairs().into_owned());
+        }
+        let response = {
+            let mut state = self.state.lock().unwrap();
+            let key = (method.to_owned(), path.to_owned());
+            let response = state.responses.get_mut(&key).and_then(|entries| {
+                let mut matching = entries
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, entry)| entry.matches(&matched_query))
+                    .map(|(i, _)| i);
+                let first = matching.next()?;
+                if matching.next().is_some() {
+                    Some(entries.remove(first).response)
+                } else {
+                    Some(entries[first].response.clone())
+                }
+            });
+
+            state.requests.push(RecordedRequest {
+                method: method.to_owned(),
+                url: url.to_owned(),
+                headers: all_headers,
+                query,
+                body,
+            });
+            response
+        };
+
+        let status = response.as_ref().map(|response| response.status);
+        let result = match response {
+            Some(response) if (200..300).contains(&response.status) => Ok(response.body),
+            Some(response) => Err(MockError::StatusCode {
+                status: response.status,
+                body: response.body,
+                headers: response.headers,
+            }),
+            None => Err(MockError::Unmatched {
+                method: method.to_owned(),
+                path: path.to_owned(),
+            }),
+        };
+
+        let response = InterceptedResponse {
+            status,
+            body: result.as_deref().ok(),
+            elapsed: start.map(|start| start.elapsed()),
+        };
+        #[cfg(feature = "tracing")]
+        record_response(&span, &response);
+        self.interceptors
+            .after_response(method, &full_url, &response);
+        result
+    }
+}
+
+/// The query parameters of a `GET` request, as recorded.
+pub(crate) fn query_map(payload: &Query) -> HashMap<String, String> {
+    payload
+        .iter()
+        .map(|(key, value)| ((*key).to_owned(), (*value).to_owned()))
+        .collect()
+}
+
+/// The fields of a form, recorded as a JSON object.
+pub(crate) fn form_value(payload: &Form<'_>) -> Value {
+    let form = payload
+        .iter()
+        .map(|(key, value)| ((*key).to_owned(), Value::from(*value)))
+        .collect();
+    Value::Object(form)
+}
+
+/// The path of the given URL, without its scheme, host, query and trailing
+/// slash, since Spotify accepts it either way.
+fn url_path(url: &str) -> &str {
+    let rest = match url.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("", |start| &rest[start..]),
+        None => url,
+    };
+    let path = rest.split(['?', '#']).next().unwrap_or_default();
+    path.trim_end_matches('/')
+}
+
+#[cfg_attr(target_arch = "wasm32", maybe_async(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), maybe_async)]
+impl BaseHttpClient for MockHttpClient {
+    type Error = MockError;
+
+    #[inline]
+    async fn get(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Query,
+    ) -> Result<String, Self::Error> {
+        self.request("GET", url, headers, query_map(payload), Value::Null)
+    }
+
+    #[inline]
+    async fn post(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Value,
+    ) -> Result<String, Self::Error> {
+        self.request("POST", url, headers, HashMap::new(), payload.clone())
+    }
+
+    #[inline]
+    async fn post_form(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Form<'_>,
+    ) -> Result<String, Self::Error> {
+        self.request("POST", url, headers, HashMap::new(), form_value(payload))
+    }
+
+    #[inline]
+    async fn put(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Value,
+    ) -> Result<String, Self::Error> {
+        self.request("PUT", url, headers, HashMap::new(), payload.clone())
+    }
+
+    #[inline]
+    async fn delete(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Value,
+    ) -> Result<String, Self::Error> {
+        self.request("DELETE", url, headers, HashMap::new(), payload.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_url_path() {
+        assert_eq!(url_path("https://api.spotify.com/v1/me"), "/v1/me");
+        assert_eq!(url_path("https://api.spotify.com/v1/me?limit=20"), "/v1/me");
+        assert_eq!(
+            url_path("https://api.spotify.com/v1/me/?limit=20"),
+            "/v1/me"
+        );
+        assert_eq!(url_path("https://accounts.spotify.com"), "");
+        assert_eq!(url_path("/v1/tracks/abc#top"), "/v1/tracks/abc");
+    }
+
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_mock_responses() {
+        let client = MockHttpClient::default();
+        client.respond("put", "/v1/me/tracks", MockResponse::ok(""));
+        client.respond(
+            "GET",
+            "https://api.spotify.com/v1/me",
+            MockResponse::with_status(429, "slow down").with_header("Retry-After", "2"),
+        );
+        client.respond("GET", "/v1/me", MockResponse::json(&serde_json::json!({})));
+
+        let me = "https://api.spotify.com/v1/me";
+        let first = client.get(me, None, &Query::new()).await.unwrap_err();
+        assert_eq!(first.status_code(), Some(429));
+        assert_eq!(first.retry_after(), Some(Duration::from_secs(2)));
+        // The last response is repeated
+        for _ in 0..2 {
+            let response = client.get(me, None, &Query::new()).await;
+            assert_eq!(response.unwrap(), "{}");
+        }
+
+        let body = serde_json::json!({ "ids": ["abc"] });
+        let tracks = "https://api.spotify.com/v1/me/tracks";
+        client.put(tracks, None, &body).await.unwrap();
+        let unmatched = client.delete(tracks, None, &body).await.unwrap_err();
+        assert!(matches!(unmatched, MockError::Unmatched { .. }));
+
+        let requests = client.requests();
+        assert_eq!(requests.len(), 5);
+        assert_eq!(requests[3].method, "PUT");
+        assert_eq!(requests[3].body, body);
+
+        // Clones share the same state
+        client.clone().reset();
+        assert!(client.requests().is_empty());
+    }
+
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_query_matching() {
+        let client = MockHttpClient::default();
+        let path = "/v1/me/tracks";
+        client.respond_with_query("GET", path, [("offset", "0")], MockResponse::ok("first"));
+        client.respond_with_query("GET", path, [("offset", "1")], MockResponse::ok("second"));
+        client.respond("GET", path, MockResponse::ok("any"));
+
+        let tracks = "https://api.spotify.com/v1/me/tracks";
+        let query = |offset| Query::from([("offset", offset)]);
+        let second = client.get(tracks, None, &query("1")).await;
+        assert_eq!(second.unwrap(), "second");
+        // Including the ones in the URL itself
+        let first = client
+            .get(&format!("{tracks}?offset=0"), None, &Query::new())
+            .await;
+        assert_eq!(first.unwrap(), "first");
+        // The parameters have to be exactly the same
+        let other = client.get(tracks, None, &Query::new()).await;
+        assert_eq!(other.unwrap(), "any");
+    }
+
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_into_http_client() {
+        let mock = MockHttpClient::default();
+        mock.respond(
+            "GET",
+            "/v1/me",
+            MockResponse::with_status(429, "slow down").with_header("Retry-After", "2"),
+        );
+        let client = crate::HttpClient::from(mock.clone());
+
+        let me = "https://api.spotify.com/v1/me";
+        let err = client.get(me, None, &Query::new()).await.unwrap_err();
+        assert_eq!(err.status_code(), Some(429));
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(2)));
+        let (status, body) = err.into_status_and_body().await.unwrap();
+        assert_eq!((status, body.as_str()), (429, "slow down"));
+
+        let tracks = "https://api.spotify.com/v1/me/tracks";
+        let err = client.get(tracks, None, &Query::new()).await.unwrap_err();
+        assert_eq!(err.status_code(), Some(404));
+
+        // The mock kept by the test records the requests
+        assert_eq!(mock.requests().len(), 2);
+    }
+}
@@ -1,12 +1,22 @@
 //! The client implementation for the reqwest HTTP client, which is async by
 //! default.
 
-use super::{BaseHttpClient, Form, Headers, Query};
-
-use std::convert::TryInto;
+#[cfg(feature = "tracing")]
+use super::common::{record_response, request_span};
+use super::{
+    common::{parse_retry_after, request_start, url_with_query, Interceptors},
+    BaseHttpClient, Form, Headers, HttpOptions, InterceptedResponse, Query,
+};
+#[cfg(feature = "client-mock")]
+use super::{
+    mock::{form_value, query_map},
+    MockError, MockHttpClient,
+};
 
 #[cfg(not(target_arch = "wasm32"))]
-use std::time::Duration;
+use super::{TlsIdentity, DEFAULT_TIMEOUT};
+
+use std::{convert::TryInto, time::Duration};
 
 use maybe_async::async_impl;
 use reqwest::{Method, RequestBuilder};
@@ -27,10 +37,12 @@ use serde_json::Value;
 /// match response {
 ///     Ok(data) => println!("request succeeded: {:?}", data),
 ///     Err(HttpError::Client(e)) => eprintln!("request failed: {}", e),
+///     Err(HttpError::Tls(e)) => eprintln!("invalid TLS configuration: {}", e),
+///     Err(HttpError::Header(e)) => eprintln!("invalid header: {}", e),
 ///     Err(HttpError::StatusCode(response)) => {
 ///         let code = response.status().as_u16();
-///         match response.json::<rspotify_model::ApiError>().await {
-///             Ok(api_error) => eprintln!("status code {}: {:?}", code, api_error),
+///         match response.json::<rspotify_model::ApiErrorResponse>().await {
+///             Ok(body) => eprintln!("status code {}: {:?}", code, body.error),
 ///             Err(_) => eprintln!("status code {}", code),
 ///         }
 ///     },
@@ -47,29 +59,182 @@ pub enum ReqwestError {
     /// The request was made, but the server returned an unsuccessful status
     /// code, such as 404 or 503. In some cases, the response may contain a
     /// custom message from Spotify with more information, which can be
-    /// serialized into `rspotify_model::ApiError`.
+    /// serialized into `rspotify_model::ApiErrorResponse`.
     #[error("status code {}", reqwest::Response::status(.0))]
     StatusCode(reqwest::Response),
+
+    /// The client couldn't be built because the TLS configuration isn't
+    /// supported by the enabled TLS backend.
+    #[error("TLS: {0}")]
+    Tls(String),
+
+    /// The request wasn't sent because one of its headers isn't a valid name
+    /// or value.
+    #[error("invalid header: {0}")]
+    Header(String),
+}
+
+impl ReqwestError {
+    /// The status code of the response, in case the request was made but it
+    /// was unsuccessful.
+    #[must_use]
+    pub fn status_code(&self) -> Option<u16> {
+        match self {
+            Self::StatusCode(response) => Some(response.status().as_u16()),
+            Self::Client(_) | Self::Tls(_) | Self::Header(_) => None,
+        }
+    }
+
+    /// The delay requested by the `Retry-After` header of the response, in
+    /// case the request was made but it was unsuccessful, e.g., with a `429
+    /// Too Many Requests`.
+    #[must_use]
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::StatusCode(response) => response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_retry_after),
+            Self::Client(_) | Self::Tls(_) | Self::Header(_) => None,
+        }
+    }
+
+    /// Consumes the error and reads the response, returning its status code
+    /// and body in case the request was made but it was unsuccessful.
+    /// Otherwise, or if the body couldn't be read, an error is returned.
+    pub async fn into_status_and_body(self) -> Result<(u16, String), Self> {
+        match self {
+            Self::StatusCode(response) => {
+                let status = response.status().as_u16();
+                Ok((status, response.text().await?))
+            }
+            err => Err(err),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct ReqwestClient {
     /// reqwest needs an instance of its client to perform requests.
     client: reqwest::Client,
+    interceptors: Interceptors,
+    /// The client that handles the requests instead, if it was converted from
+    /// one.
+    #[cfg(feature = "client-mock")]
+    mock: Option<MockHttpClient>,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 impl Default for ReqwestClient {
     fn default() -> Self {
         let client = reqwest::ClientBuilder::new()
-            .timeout(Duration::from_secs(10))
+            .timeout(DEFAULT_TIMEOUT)
             .build()
             // building with these options cannot fail
             .unwrap();
-        Self { client }
+        Self {
+            client,
+            interceptors: Interceptors::default(),
+            #[cfg(feature = "client-mock")]
+            mock: None,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ReqwestClient {
+    /// Builds a client with the given options. Fails if the proxy URL or the
+    /// client certificate are invalid.
+    ///
+    /// Adding a proxy disables the ones in the environment variables.
+    pub fn with_options(options: &HttpOptions) -> Result<Self, ReqwestError> {
+        let mut builder =
+            reqwest::ClientBuilder::new().timeout(options.timeout.unwrap_or(DEFAULT_TIMEOUT));
+        if let Some(timeout) = options.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(user_agent) = &options.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+
+        if let Some(config) = &options.proxy {
+            let mut proxy = reqwest::Proxy::all(config.url.as_str())?
+                .no_proxy(reqwest::NoProxy::from_string(&config.no_proxy.join(",")));
+            if let Some((username, password)) = &config.basic_auth {
+                proxy = proxy.basic_auth(username, password);
+            }
+            builder = builder.proxy(proxy);
+        }
+        if let Some(identity) = &options.identity {
+            builder = with_identity(builder, identity)?;
+        }
+
+        Ok(Self {
+            client: builder.build()?,
+            interceptors: Interceptors::new(&options.interceptors),
+            #[cfg(feature = "client-mock")]
+            mock: None,
+        })
     }
 }
 
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    any(
+        feature = "reqwest-native-tls",
+        feature = "reqwest-native-tls-vendored"
+    )
+))]
+fn with_identity(
+    builder: reqwest::ClientBuilder,
+    identity: &TlsIdentity,
+) -> Result<reqwest::ClientBuilder, ReqwestError> {
+    let identity = reqwest::Identity::from_pkcs8_pem(&identity.cert_pem, &identity.key_pem)?;
+    Ok(builder.identity(identity))
+}
+
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    feature = "reqwest-rustls-tls",
+    not(any(
+        feature = "reqwest-native-tls",
+        feature = "reqwest-native-tls-vendored"
+    ))
+))]
+fn with_identity(
+    builder: reqwest::ClientBuilder,
+    identity: &TlsIdentity,
+) -> Result<reqwest::ClientBuilder, ReqwestError> {
+    // rustls takes both the certificates and the key in the same buffer
+    let mut pem = identity.cert_pem.clone();
+    pem.push(b'\n');
+    pem.extend_from_slice(&identity.key_pem);
+    let identity = reqwest::Identity::from_pem(&pem)?;
+
+    // In case `reqwest-default-tls` is enabled as well, which is used by
+    // default and doesn't accept this identity
+    Ok(builder.use_rustls_tls().identity(identity))
+}
+
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    not(any(
+        feature = "reqwest-native-tls",
+        feature = "reqwest-native-tls-vendored",
+        feature = "reqwest-rustls-tls"
+    ))
+))]
+fn with_identity(
+    _builder: reqwest::ClientBuilder,
+    _identity: &TlsIdentity,
+) -> Result<reqwest::ClientBuilder, ReqwestError> {
+    Err(ReqwestError::Tls(
+        "client certificates require the `reqwest-native-tls` or `reqwest-rustls-tls` features"
+            .to_owned(),
+    ))
+}
+
 #[cfg(target_arch = "wasm32")]
 impl Default for ReqwestClient {
     fn default() -> Self {
@@ -77,7 +242,65 @@ impl Default for ReqwestClient {
             .build()
             // building with these options cannot fail
             .unwrap();
-        Self { client }
+        Self {
+            client,
+            interceptors: Interceptors::default(),
+            #[cfg(feature = "client-mock")]
+            mock: None,
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl ReqwestClient {
+    /// The browser handles the connections on WebAssembly, so the options
+    /// are ignored, except for the interceptors. Fails if a client
+    /// certificate is set, since it can't be presented.
+    pub fn with_options(options: &HttpOptions) -> Result<Self, ReqwestError> {
+        if options.identity.is_some() {
+            return Err(ReqwestError::Tls(
+                "client certificates aren't supported on WebAssembly".to_owned(),
+            ));
+        }
+        if options.proxy.is_some() {
+            log::warn!("Proxies can't be configured on WebAssembly");
+        }
+        if options.timeout.is_some()
+            || options.connect_timeout.is_some()
+            || options.user_agent.is_some()
+        {
+            log::warn!("Timeouts and user agents can't be configured on WebAssembly");
+        }
+        Ok(Self {
+            interceptors: Interceptors::new(&options.interceptors),
+            #[cfg(feature = "client-mock")]
+            mock: None,
+            ..Self::default()
+        })
+    }
+}
+
+#[cfg(feature = "client-mock")]
+impl From<MockHttpClient> for ReqwestClient {
+    fn from(mock: MockHttpClient) -> Self {
+        Self {
+            mock: Some(mock),
+            ..Self::default()
+        }
+    }
+}
+
+/// The unsuccessful responses of the mock client are converted into regular
+/// ones, so that they're handled in the same way.
+#[cfg(feature = "client-mock")]
+impl From<MockError> for ReqwestError {
+    fn from(err: MockError) -> Self {
+        let (status, body, headers) = err.into_response();
+        let mut response = http::Response::builder().status(status);
+        for (name, value) in headers {
+            response = response.header(name, value);
+        }
+        Self::StatusCode(response.body(body).expect("invalid mock response").into())
     }
 }
 
@@ -97,29 +320,62 @@ impl ReqwestClient {
         // Setting the headers, if any
         if let Some(headers) = headers {
             // The headers need to be converted into a `reqwest::HeaderMap`,
-            // which won't fail as long as its contents are ASCII. This is an
-            // internal function, so the condition cannot be broken by the user
-            // and will always be true.
+            // which fails if a name or value isn't valid. They may come from
+            // the user, like the extra token headers, so it's an error
+            // instead of a panic.
             //
             // The content-type header will be set automatically.
-            let headers = headers.try_into().unwrap();
+            let headers: reqwest::header::HeaderMap = headers
+                .try_into()
+                .map_err(|err| ReqwestError::Header(format!("{err}")))?;
 
             request = request.headers(headers);
         }
 
+        // The headers from the interceptors are added separately, since they
+        // aren't guaranteed to be valid. Otherwise, the request will fail.
+        for (key, val) in self.interceptors.before_request(method.as_str(), url) {
+            request = request.header(key, val);
+        }
+
         // Configuring the request for the specific type (get/post/put/delete)
         request = add_data(request);
 
         // Finally performing the request and handling the response
         log::info!("Making request {:?}", request);
-        let response = request.send().await?;
+        #[cfg(feature = "tracing")]
+        let span = request_span(method.as_str(), url);
+        let start = request_start();
+        let send = async {
+            match request.send().await {
+                // Making sure that the status code is OK
+                Ok(response) if response.status().is_success() => (
+                    Some(response.status().as_u16()),
+                    response.text().await.map_err(Into::into),
+                ),
+                Ok(response) => (
+                    Some(response.status().as_u16()),
+                    Err(ReqwestError::StatusCode(response)),
+                ),
+                Err(err) => (None, Err(err.into())),
+            }
+        };
+        // The span can't be entered across the `.await`, so the future is
+        // instrumented instead
+        #[cfg(feature = "tracing")]
+        let send = tracing::Instrument::instrument(send, span.clone());
+        let (status, result) = send.await;
 
-        // Making sure that the status code is OK
-        if response.status().is_success() {
-            response.text().await.map_err(Into::into)
-        } else {
-            Err(ReqwestError::StatusCode(response))
-        }
+        let response = InterceptedResponse {
+            status,
+            body: result.as_deref().ok(),
+            elapsed: start.map(|start| start.elapsed()),
+        };
+        #[cfg(feature = "tracing")]
+        record_response(&span, &response);
+        self.interceptors
+            .after_response(method.as_str(), url, &response);
+        result
     }
 }
 
@@ -135,8 +391,15 @@ impl BaseHttpClient for ReqwestClient {
         headers: Option<&Headers>,
         payload: &Query,
     ) -> Result<String, Self::Error> {
-        self.request(Method::GET, url, headers, |req| req.query(payload))
-            .await
+        #[cfg(feature = "client-mock")]
+        if let Some(mock) = &self.mock {
+            return Ok(mock.request("GET", url, headers, query_map(payload), Value::Null)?);
+        }
+
+        // The query is added to the URL beforehand so that the interceptors
+        // see it as well
+        let url = url_with_query(url, payload.iter().map(|(k, v)| (*k, *v)));
+        self.request(Method::GET, &url, headers, |req| req).await
     }
 
     #[inline]
@@ -146,6 +409,11 @@ impl BaseHttpClient for ReqwestClient {
         headers: Option<&Headers>,
         payload: &Value,
     ) -> Result<String, Self::Error> {
+        #[cfg(feature = "client-mock")]
+        if let Some(mock) = &self.mock {
+            return Ok(mock.request("POST", url, headers, Default::default(), payload.clone())?);
+        }
+
         self.request(Method::POST, url, headers, |req| req.json(payload))
             .await
     }
@@ -157,6 +425,17 @@ impl BaseHttpClient for ReqwestClient {
         headers: Option<&Headers>,
         payload: &Form<'_>,
     ) -> Result<String, Self::Error> {
+        #[cfg(feature = "client-mock")]
+        if let Some(mock) = &self.mock {
+            return Ok(mock.request(
+                "POST",
+                url,
+                headers,
+                Default::default(),
+                form_value(payload),
+            )?);
+        }
+
         self.request(Method::POST, url, headers, |req| req.form(payload))
             .await
     }
@@ -168,6 +447,11 @@ impl BaseHttpClient for ReqwestClient {
         headers: Option<&Headers>,
         payload: &Value,
     ) -> Result<String, Self::Error> {
+        #[cfg(feature = "client-mock")]
+        if let Some(mock) = &self.mock {
+            return Ok(mock.request("PUT", url, headers, Default::default(), payload.clone())?);
+        }
+
         self.request(Method::PUT, url, headers, |req| req.json(payload))
             .await
     }
@@ -179,6 +463,17 @@ impl BaseHttpClient for ReqwestClient {
         headers: Option<&Headers>,
         payload: &Value,
     ) -> Result<String, Self::Error> {
+        #[cfg(feature = "client-mock")]
+        if let Some(mock) = &self.mock {
+            return Ok(mock.request(
+                "DELETE",
+                url,
+                headers,
+                Default::default(),
+                payload.clone(),
+            )?);
+        }
+
         self.request(Method::DELETE, url, headers, |req| req.json(payload))
             .await
     }
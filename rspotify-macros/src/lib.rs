@@ -25,23 +25,149 @@
 /// manually.insert("playlist-read-collaborative".to_owned());
 /// assert_eq!(macro_with_whitespace, manually);
 /// ```
+/// With the `validate-scopes` feature, the literals are checked at compile
+/// time, so that misspelled scopes are caught early:
+///
+#[cfg_attr(feature = "validate-scopes", doc = "```compile_fail")]
+#[cfg_attr(not(feature = "validate-scopes"), doc = "```ignore")]
+/// use rspotify_macros::scopes;
+///
+/// let scopes = scopes!("user-read-playack-state");
+/// ```
+///
+/// Any other expression is accepted as long as it implements `AsRef<str>`,
+/// like the `Scope` enum of `rspotify-model`, but these aren't validated:
+///
+/// ```
+/// use rspotify_macros::scopes;
+///
+/// let extra = String::from("user-read-private");
+/// let scopes = scopes!("streaming", extra);
+/// assert_eq!(scopes.len(), 2);
+/// ```
 #[macro_export]
 macro_rules! scopes {
-    ($($key:expr),*) => {{
+    ($($key:literal),* $(,)?) => {{
+        const _: () = {
+            $(
+                if $crate::__private::VALIDATE && !$crate::__private::are_valid_scopes($key) {
+                    panic!(concat!("unknown Spotify scope in `scopes!`: ", $key));
+                }
+            )*
+        };
         let mut container = ::std::collections::HashSet::new();
         $(
-            for scope in $key.split_whitespace(){
-            container.insert(scope.to_owned());
+            for scope in $key.split_whitespace() {
+                container.insert(scope.to_owned());
             }
         )*
         container
     }};
+    ($($key:expr),* $(,)?) => {{
+        let mut container = ::std::collections::HashSet::new();
+        $(
+            for scope in ::std::convert::AsRef::<str>::as_ref(&$key).split_whitespace() {
+                container.insert(scope.to_owned());
+            }
+        )*
+        container
+    }};
+}
+
+/// Invokes the given macro with the list of scopes known by Spotify, as
+/// `Variant => "name"` pairs. It's the only place where they're listed, so
+/// that both the `Scope` enum of `rspotify-model` and the validation of
+/// [`scopes!`] are generated from it.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __scopes {
+    ($callback:ident) => {
+        $callback! {
+            UgcImageUpload => "ugc-image-upload",
+            UserReadPlaybackState => "user-read-playback-state",
+            UserModifyPlaybackState => "user-modify-playback-state",
+            UserReadCurrentlyPlaying => "user-read-currently-playing",
+            AppRemoteControl => "app-remote-control",
+            Streaming => "streaming",
+            PlaylistReadPrivate => "playlist-read-private",
+            PlaylistReadCollaborative => "playlist-read-collaborative",
+            PlaylistModifyPrivate => "playlist-modify-private",
+            PlaylistModifyPublic => "playlist-modify-public",
+            UserFollowModify => "user-follow-modify",
+            UserFollowRead => "user-follow-read",
+            UserReadPlaybackPosition => "user-read-playback-position",
+            UserTopRead => "user-top-read",
+            UserReadRecentlyPlayed => "user-read-recently-played",
+            UserLibraryModify => "user-library-modify",
+            UserLibraryRead => "user-library-read",
+            UserReadEmail => "user-read-email",
+            UserReadPrivate => "user-read-private",
+        }
+    };
+}
+
+#[doc(hidden)]
+pub mod __private {
+    /// Whether the literals of [`scopes!`](crate::scopes) are validated.
+    pub const VALIDATE: bool = cfg!(feature = "validate-scopes");
+
+    macro_rules! scope_names {
+        ($($variant:ident => $name:literal),* $(,)?) => {
+            /// The scopes known by Spotify.
+            pub const SCOPES: &[&str] = &[$($name),*];
+        };
+    }
+    crate::__scopes!(scope_names);
+
+    /// Whether all the whitespace-separated words in `scopes` are in
+    /// [`SCOPES`]. This is a `const fn` so that the macro can check its
+    /// literals at compile time, which is why it's written with plain loops.
+    pub const fn are_valid_scopes(scopes: &str) -> bool {
+        let bytes = scopes.as_bytes();
+        let mut start = 0;
+        while start < bytes.len() {
+            if bytes[start].is_ascii_whitespace() {
+                start += 1;
+                continue;
+            }
+            let mut end = start;
+            while end < bytes.len() && !bytes[end].is_ascii_whitespace() {
+                end += 1;
+            }
+            if !is_known(bytes, start, end) {
+                return false;
+            }
+            start = end;
+        }
+        true
+    }
+
+    /// Whether `bytes[start..end]` is in [`SCOPES`].
+    const fn is_known(bytes: &[u8], start: usize, end: usize) -> bool {
+        let mut i = 0;
+        while i < SCOPES.len() {
+            let scope = SCOPES[i].as_bytes();
+            if scope.len() == end - start {
+                let mut j = 0;
+                while j < scope.len() && scope[j] == bytes[start + j] {
+                    j += 1;
+                }
+                if j == scope.len() {
+                    return true;
+                }
+            }
+            i += 1;
+        }
+        false
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::scopes;
+    use super::__private::are_valid_scopes;
 
+    // These scopes don't exist, so they would be rejected when validated
+    #[cfg(not(feature = "validate-scopes"))]
     #[test]
     fn test_hashset() {
         let scopes = scopes!("hello", "world", "foo", "bar");
@@ -52,6 +178,7 @@ mod test {
         assert!(scopes.contains("bar"));
     }
 
+    #[cfg(not(feature = "validate-scopes"))]
     #[test]
     fn test_scopes_with_whitespace() {
         let scopes = scopes!("      hello world foo bar");
@@ -62,4 +189,21 @@ mod test {
         assert!(scopes.contains("foo"));
         assert!(scopes.contains("bar"));
     }
+
+    #[test]
+    fn test_unvalidated_expressions() {
+        let scope = "hello world".to_owned();
+        let scopes = scopes!(scope, "streaming");
+        assert_eq!(scopes.len(), 3);
+        assert!(scopes.contains("hello"));
+    }
+
+    #[test]
+    fn test_are_valid_scopes() {
+        assert!(are_valid_scopes(""));
+        assert!(are_valid_scopes(" streaming\tuser-read-private "));
+        assert!(!are_valid_scopes("user-read-playack-state"));
+        assert!(!are_valid_scopes("streaming user"));
+        assert!(!are_valid_scopes("streamings"));
+    }
 }
@@ -6,8 +6,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::{
-    AlbumId, AlbumType, Copyright, DatePrecision, Image, Page, RestrictionReason, SimplifiedArtist,
-    SimplifiedTrack,
+    spotify_url, AlbumId, AlbumType, Copyright, DatePrecision, Id, Image, Page, RestrictionReason,
+    SimplifiedArtist, SimplifiedTrack,
 };
 
 /// Simplified Album Object
@@ -32,6 +32,21 @@ pub struct SimplifiedAlbum {
     pub restrictions: Option<Restriction>,
 }
 
+impl SimplifiedAlbum {
+    /// Same as [`FullAlbum::external_url`].
+    #[must_use]
+    pub fn external_url(&self) -> Option<&str> {
+        spotify_url(&self.external_urls)
+    }
+
+    /// Same as [`FullAlbum::open_url`], although simplified albums may lack
+    /// an ID.
+    #[must_use]
+    pub fn open_url(&self) -> Option<String> {
+        self.id.as_ref().map(Id::url)
+    }
+}
+
 /// Full Album Object
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct FullAlbum {
@@ -54,6 +69,20 @@ pub struct FullAlbum {
     pub label: Option<String>,
 }
 
+impl FullAlbum {
+    /// The link to the album in Spotify's apps, as given by Spotify.
+    #[must_use]
+    pub fn external_url(&self) -> Option<&str> {
+        spotify_url(&self.external_urls)
+    }
+
+    /// The link to the album in Spotify's apps, built from its ID.
+    #[must_use]
+    pub fn open_url(&self) -> String {
+        self.id.url()
+    }
+}
+
 /// Intermediate full Albums wrapped by Vec object
 #[derive(Deserialize)]
 pub struct FullAlbums {
@@ -74,7 +103,7 @@ pub struct SavedAlbum {
 }
 
 /// Album restriction object
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct Restriction {
     pub reason: RestrictionReason,
 }
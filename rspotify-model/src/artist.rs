@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use std::collections::HashMap;
 
-use crate::{ArtistId, CursorBasedPage, Followers, Image};
+use crate::{spotify_url, ArtistId, CursorBasedPage, Followers, Id, Image};
 
 /// Simplified Artist Object
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
@@ -15,6 +15,21 @@ pub struct SimplifiedArtist {
     pub name: String,
 }
 
+impl SimplifiedArtist {
+    /// Same as [`FullArtist::external_url`].
+    #[must_use]
+    pub fn external_url(&self) -> Option<&str> {
+        spotify_url(&self.external_urls)
+    }
+
+    /// Same as [`FullArtist::open_url`]. The artists of local tracks have no
+    /// ID, so there's no link for them.
+    #[must_use]
+    pub fn open_url(&self) -> Option<String> {
+        self.id.as_ref().map(Id::url)
+    }
+}
+
 /// Full Artist Object
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct FullArtist {
@@ -28,6 +43,20 @@ pub struct FullArtist {
     pub popularity: u32,
 }
 
+impl FullArtist {
+    /// The link to the artist in Spotify's apps, as given by Spotify.
+    #[must_use]
+    pub fn external_url(&self) -> Option<&str> {
+        spotify_url(&self.external_urls)
+    }
+
+    /// The link to the artist in Spotify's apps, built from its ID.
+    #[must_use]
+    pub fn open_url(&self) -> String {
+        self.id.url()
+    }
+}
+
 /// Intermediate full artist object wrapped by `Vec`
 #[derive(Deserialize)]
 pub struct FullArtists {
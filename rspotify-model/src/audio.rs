@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use chrono::Duration;
 
 use crate::{
-    custom_serde::{duration_ms, modality},
+    custom_serde::{duration_float_second, duration_ms, modality},
     Modality, TrackId,
 };
 
@@ -50,12 +50,90 @@ pub struct AudioAnalysis {
     pub track: AudioAnalysisTrack,
 }
 
+impl AudioAnalysis {
+    /// The section playing at the given position of the track, if any.
+    #[must_use]
+    pub fn section_at(&self, position: Duration) -> Option<&AudioAnalysisSection> {
+        self.sections
+            .iter()
+            .find(|section| section.time_interval.contains(position))
+    }
+
+    /// The segment playing at the given position of the track, if any.
+    #[must_use]
+    pub fn segment_at(&self, position: Duration) -> Option<&AudioAnalysisSegment> {
+        self.segments
+            .iter()
+            .find(|segment| segment.time_interval.contains(position))
+    }
+
+    /// The beats that start between the given positions of the track, with
+    /// `from` included and `to` excluded.
+    pub fn beats_between(
+        &self,
+        from: Duration,
+        to: Duration,
+    ) -> impl Iterator<Item = &TimeInterval> + '_ {
+        self.beats
+            .iter()
+            .skip_while(move |beat| beat.start < from)
+            .take_while(move |beat| beat.start < to)
+    }
+}
+
+/// The confidence, from 0.0 to 1.0, of a value in the audio analysis.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, PartialOrd, Default)]
+#[serde(transparent)]
+pub struct Confidence(pub f32);
+
+impl Confidence {
+    /// The confidence as a number, from 0.0 to 1.0.
+    #[must_use]
+    pub fn value(self) -> f32 {
+        self.0
+    }
+
+    /// Whether the confidence is at least the given threshold, e.g., to
+    /// ignore the beats that aren't reliable enough.
+    #[must_use]
+    pub fn is_at_least(self, threshold: f32) -> bool {
+        self.0 >= threshold
+    }
+}
+
 /// Time interval object
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct TimeInterval {
-    pub start: f32,
-    pub duration: f32,
-    pub confidence: f32,
+    #[serde(with = "duration_float_second")]
+    pub start: Duration,
+    #[serde(with = "duration_float_second")]
+    pub duration: Duration,
+    pub confidence: Confidence,
+}
+
+impl Default for TimeInterval {
+    fn default() -> Self {
+        Self {
+            start: Duration::zero(),
+            duration: Duration::zero(),
+            confidence: Confidence::default(),
+        }
+    }
+}
+
+impl TimeInterval {
+    /// The position of the track where the interval ends.
+    #[must_use]
+    pub fn end(&self) -> Duration {
+        self.start + self.duration
+    }
+
+    /// Whether the given position of the track is within the interval, with
+    /// its start included and its end excluded.
+    #[must_use]
+    pub fn contains(&self, position: Duration) -> bool {
+        self.start <= position && position < self.end()
+    }
 }
 
 /// Audio analysis section object
@@ -65,14 +143,14 @@ pub struct AudioAnalysisSection {
     pub time_interval: TimeInterval,
     pub loudness: f32,
     pub tempo: f32,
-    pub tempo_confidence: f32,
+    pub tempo_confidence: Confidence,
     pub key: i32,
-    pub key_confidence: f32,
+    pub key_confidence: Confidence,
     #[serde(with = "modality")]
     pub mode: Modality,
-    pub mode_confidence: f32,
+    pub mode_confidence: Confidence,
     pub time_signature: i32,
-    pub time_signature_confidence: f32,
+    pub time_signature_confidence: Confidence,
 }
 
 /// Audio analysis meta object
@@ -104,7 +182,8 @@ pub struct AudioAnalysisSegment {
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct AudioAnalysisTrack {
     pub num_samples: u32,
-    pub duration: f32,
+    #[serde(with = "duration_float_second")]
+    pub duration: Duration,
     pub sample_md5: String,
     pub offset_seconds: u32,
     pub window_seconds: u32,
@@ -114,14 +193,14 @@ pub struct AudioAnalysisTrack {
     pub start_of_fade_out: f32,
     pub loudness: f32,
     pub tempo: f32,
-    pub tempo_confidence: f32,
+    pub tempo_confidence: Confidence,
     pub time_signature: i32,
-    pub time_signature_confidence: f32,
+    pub time_signature_confidence: Confidence,
     pub key: u32,
-    pub key_confidence: f32,
+    pub key_confidence: Confidence,
     #[serde(with = "modality")]
     pub mode: Modality,
-    pub mode_confidence: f32,
+    pub mode_confidence: Confidence,
     pub codestring: String,
     pub code_version: f32,
     pub echoprintstring: String,
@@ -131,3 +210,22 @@ pub struct AudioAnalysisTrack {
     pub rhythmstring: String,
     pub rhythm_version: f32,
 }
+
+/// Intermediate object with only the sections of an audio analysis, so that
+/// the rest is skipped when deserializing it
+#[derive(Deserialize)]
+pub struct AudioAnalysisSectionsPayload {
+    pub sections: Vec<AudioAnalysisSection>,
+}
+
+/// Intermediate object with only the segments of an audio analysis
+#[derive(Deserialize)]
+pub struct AudioAnalysisSegmentsPayload {
+    pub segments: Vec<AudioAnalysisSegment>,
+}
+
+/// Intermediate object with only the beats of an audio analysis
+#[derive(Deserialize)]
+pub struct AudioAnalysisBeatsPayload {
+    pub beats: Vec<TimeInterval>,
+}
@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+
+use chrono::Duration;
+use std::collections::HashMap;
+
+use crate::{
+    custom_serde::duration_ms, AudiobookId, ChapterId, Copyright, DatePrecision, Image, Page,
+    ResumePoint,
+};
+
+/// Author object
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Author {
+    pub name: String,
+}
+
+/// Narrator object
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Narrator {
+    pub name: String,
+}
+
+/// Simplified audiobook object
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SimplifiedAudiobook {
+    pub authors: Vec<Author>,
+    pub available_markets: Vec<String>,
+    pub copyrights: Vec<Copyright>,
+    pub description: String,
+    pub html_description: String,
+    pub edition: Option<String>,
+    pub explicit: bool,
+    pub external_urls: HashMap<String, String>,
+    pub href: String,
+    pub id: AudiobookId<'static>,
+    pub images: Vec<Image>,
+    pub languages: Vec<String>,
+    pub media_type: String,
+    pub name: String,
+    pub narrators: Vec<Narrator>,
+    pub publisher: String,
+    pub total_chapters: u32,
+}
+
+/// Full audiobook object
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FullAudiobook {
+    pub authors: Vec<Author>,
+    pub available_markets: Vec<String>,
+    pub chapters: Page<SimplifiedChapter>,
+    pub copyrights: Vec<Copyright>,
+    pub description: String,
+    pub html_description: String,
+    pub edition: Option<String>,
+    pub explicit: bool,
+    pub external_urls: HashMap<String, String>,
+    pub href: String,
+    pub id: AudiobookId<'static>,
+    pub images: Vec<Image>,
+    pub languages: Vec<String>,
+    pub media_type: String,
+    pub name: String,
+    pub narrators: Vec<Narrator>,
+    pub publisher: String,
+    pub total_chapters: u32,
+}
+
+/// Intermediate audiobooks object wrapped by `Vec`. Note that the audiobooks
+/// that couldn't be found are `null`.
+#[derive(Deserialize)]
+pub struct AudiobooksPayload {
+    pub audiobooks: Vec<Option<FullAudiobook>>,
+}
+
+/// Simplified chapter object
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SimplifiedChapter {
+    pub audio_preview_url: Option<String>,
+    pub available_markets: Option<Vec<String>>,
+    pub chapter_number: u32,
+    pub description: String,
+    pub html_description: String,
+    #[serde(with = "duration_ms", rename = "duration_ms")]
+    pub duration: Duration,
+    pub explicit: bool,
+    pub external_urls: HashMap<String, String>,
+    pub href: String,
+    pub id: ChapterId<'static>,
+    pub images: Vec<Image>,
+    pub is_playable: Option<bool>,
+    pub languages: Vec<String>,
+    pub name: String,
+    pub release_date: String,
+    pub release_date_precision: DatePrecision,
+    pub resume_point: Option<ResumePoint>,
+}
+
+/// Full chapter object
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FullChapter {
+    pub audio_preview_url: Option<String>,
+    pub audiobook: SimplifiedAudiobook,
+    pub available_markets: Option<Vec<String>>,
+    pub chapter_number: u32,
+    pub description: String,
+    pub html_description: String,
+    #[serde(with = "duration_ms", rename = "duration_ms")]
+    pub duration: Duration,
+    pub explicit: bool,
+    pub external_urls: HashMap<String, String>,
+    pub href: String,
+    pub id: ChapterId<'static>,
+    pub images: Vec<Image>,
+    pub is_playable: Option<bool>,
+    pub languages: Vec<String>,
+    pub name: String,
+    pub release_date: String,
+    pub release_date_precision: DatePrecision,
+    pub resume_point: Option<ResumePoint>,
+}
+
+/// Intermediate chapters object wrapped by `Vec`. Note that the chapters that
+/// couldn't be found are `null`.
+#[derive(Deserialize)]
+pub struct ChaptersPayload {
+    pub chapters: Vec<Option<FullChapter>>,
+}
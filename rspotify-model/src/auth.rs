@@ -84,9 +84,17 @@ impl Token {
     /// is how much a request would take in the worst case scenario).
     #[must_use]
     pub fn is_expired(&self) -> bool {
-        self.expires_at.map_or(true, |expiration| {
-            Utc::now() + TimeDelta::try_seconds(10).unwrap() >= expiration
-        })
+        self.is_expired_within(TimeDelta::try_seconds(10).unwrap())
+    }
+
+    /// Check if the token is expired or will be within the given margin,
+    /// e.g., to account for the clock skew with Spotify's servers.
+    #[must_use]
+    pub fn is_expired_within(&self, margin: Duration) -> bool {
+        match self.expires_at {
+            Some(expiration) => Utc::now() + margin >= expiration,
+            None => true,
+        }
     }
 
     /// Generates an HTTP token authorization header with proper formatting
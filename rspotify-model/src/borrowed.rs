@@ -0,0 +1,207 @@
+//! Borrowed variants of the models that make up most of the bulk downloads,
+//! like the saved tracks of a large library, available with the `borrowed`
+//! feature.
+//!
+//! The regular models own all of their strings, so each page of 50 tracks
+//! allocates hundreds of them. These ones borrow them from the response
+//! instead, unless they contain escape sequences, and leave out the fields
+//! that are rarely needed in bulk, like the available markets or the images.
+//! They're deserialized from a body that must outlive them:
+//!
+//! ```
+//! use rspotify_model::borrowed::{BorrowedPage, BorrowedSavedTrack};
+//! use std::borrow::Cow;
+//!
+//! let body = r#"{
+//!     "href": "https://api.spotify.com/v1/me/tracks",
+//!     "items": [],
+//!     "limit": 50,
+//!     "next": null,
+//!     "offset": 0,
+//!     "previous": null,
+//!     "total": 0
+//! }"#;
+//! let page: BorrowedPage<BorrowedSavedTrack> = serde_json::from_str(body).unwrap();
+//! assert!(matches!(page.href, Cow::Borrowed(_)));
+//! ```
+//!
+//! The items that have to be kept around after the body is dropped can be
+//! converted with `into_owned`.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use std::borrow::Cow;
+
+use crate::{
+    custom_serde::{duration_ms, option_cow_str},
+    AlbumId, ArtistId, IdError, TrackId,
+};
+
+/// Borrowed version of [`Page`](crate::Page).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BorrowedPage<'a, T> {
+    #[serde(borrow)]
+    pub href: Cow<'a, str>,
+    pub items: Vec<T>,
+    pub limit: u32,
+    #[serde(borrow, deserialize_with = "option_cow_str::deserialize", default)]
+    pub next: Option<Cow<'a, str>>,
+    pub offset: u32,
+    #[serde(borrow, deserialize_with = "option_cow_str::deserialize", default)]
+    pub previous: Option<Cow<'a, str>>,
+    pub total: u32,
+}
+
+/// Borrowed version of [`SimplifiedArtist`](crate::SimplifiedArtist).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BorrowedSimplifiedArtist<'a> {
+    #[serde(borrow, deserialize_with = "option_cow_str::deserialize", default)]
+    pub href: Option<Cow<'a, str>>,
+    #[serde(borrow, deserialize_with = "option_cow_str::deserialize", default)]
+    pub id: Option<Cow<'a, str>>,
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+}
+
+impl BorrowedSimplifiedArtist<'_> {
+    /// The typed ID of the artist, which is absent in local files.
+    pub fn artist_id(&self) -> Option<Result<ArtistId<'_>, IdError>> {
+        self.id.as_deref().map(ArtistId::from_id)
+    }
+
+    /// Copies the borrowed strings so that it outlives the response.
+    #[must_use]
+    pub fn into_owned(self) -> BorrowedSimplifiedArtist<'static> {
+        BorrowedSimplifiedArtist {
+            href: self.href.map(into_owned),
+            id: self.id.map(into_owned),
+            name: into_owned(self.name),
+        }
+    }
+}
+
+/// Borrowed version of [`SimplifiedAlbum`](crate::SimplifiedAlbum).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BorrowedSimplifiedAlbum<'a> {
+    #[serde(borrow, deserialize_with = "option_cow_str::deserialize", default)]
+    pub album_type: Option<Cow<'a, str>>,
+    #[serde(borrow)]
+    pub artists: Vec<BorrowedSimplifiedArtist<'a>>,
+    #[serde(borrow, deserialize_with = "option_cow_str::deserialize", default)]
+    pub href: Option<Cow<'a, str>>,
+    #[serde(borrow, deserialize_with = "option_cow_str::deserialize", default)]
+    pub id: Option<Cow<'a, str>>,
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+    #[serde(borrow, deserialize_with = "option_cow_str::deserialize", default)]
+    pub release_date: Option<Cow<'a, str>>,
+    #[serde(borrow, deserialize_with = "option_cow_str::deserialize", default)]
+    pub release_date_precision: Option<Cow<'a, str>>,
+}
+
+impl BorrowedSimplifiedAlbum<'_> {
+    /// The typed ID of the album, which is absent in local files.
+    pub fn album_id(&self) -> Option<Result<AlbumId<'_>, IdError>> {
+        self.id.as_deref().map(AlbumId::from_id)
+    }
+
+    /// Copies the borrowed strings so that it outlives the response.
+    #[must_use]
+    pub fn into_owned(self) -> BorrowedSimplifiedAlbum<'static> {
+        BorrowedSimplifiedAlbum {
+            album_type: self.album_type.map(into_owned),
+            artists: self
+                .artists
+                .into_iter()
+                .map(BorrowedSimplifiedArtist::into_owned)
+                .collect(),
+            href: self.href.map(into_owned),
+            id: self.id.map(into_owned),
+            name: into_owned(self.name),
+            release_date: self.release_date.map(into_owned),
+            release_date_precision: self.release_date_precision.map(into_owned),
+        }
+    }
+}
+
+/// Borrowed version of [`FullTrack`](crate::FullTrack).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BorrowedFullTrack<'a> {
+    #[serde(borrow)]
+    pub album: BorrowedSimplifiedAlbum<'a>,
+    #[serde(borrow)]
+    pub artists: Vec<BorrowedSimplifiedArtist<'a>>,
+    pub disc_number: i32,
+    #[serde(with = "duration_ms", rename = "duration_ms")]
+    pub duration: Duration,
+    pub explicit: bool,
+    #[serde(borrow, deserialize_with = "option_cow_str::deserialize", default)]
+    pub href: Option<Cow<'a, str>>,
+    /// Note that a track may not have an ID/URI if it's local
+    #[serde(borrow, deserialize_with = "option_cow_str::deserialize", default)]
+    pub id: Option<Cow<'a, str>>,
+    pub is_local: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_playable: Option<bool>,
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+    pub popularity: u32,
+    #[serde(borrow, deserialize_with = "option_cow_str::deserialize", default)]
+    pub preview_url: Option<Cow<'a, str>>,
+    pub track_number: u32,
+}
+
+impl BorrowedFullTrack<'_> {
+    /// The typed ID of the track, which is absent in local files.
+    pub fn track_id(&self) -> Option<Result<TrackId<'_>, IdError>> {
+        self.id.as_deref().map(TrackId::from_id)
+    }
+
+    /// Copies the borrowed strings so that it outlives the response.
+    #[must_use]
+    pub fn into_owned(self) -> BorrowedFullTrack<'static> {
+        BorrowedFullTrack {
+            album: self.album.into_owned(),
+            artists: self
+                .artists
+                .into_iter()
+                .map(BorrowedSimplifiedArtist::into_owned)
+                .collect(),
+            disc_number: self.disc_number,
+            duration: self.duration,
+            explicit: self.explicit,
+            href: self.href.map(into_owned),
+            id: self.id.map(into_owned),
+            is_local: self.is_local,
+            is_playable: self.is_playable,
+            name: into_owned(self.name),
+            popularity: self.popularity,
+            preview_url: self.preview_url.map(into_owned),
+            track_number: self.track_number,
+        }
+    }
+}
+
+/// Borrowed version of [`SavedTrack`](crate::SavedTrack).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BorrowedSavedTrack<'a> {
+    pub added_at: DateTime<Utc>,
+    #[serde(borrow)]
+    pub track: BorrowedFullTrack<'a>,
+}
+
+impl BorrowedSavedTrack<'_> {
+    /// Copies the borrowed strings so that it outlives the response.
+    #[must_use]
+    pub fn into_owned(self) -> BorrowedSavedTrack<'static> {
+        BorrowedSavedTrack {
+            added_at: self.added_at,
+            track: self.track.into_owned(),
+        }
+    }
+}
+
+fn into_owned(s: Cow<'_, str>) -> Cow<'static, str> {
+    Cow::Owned(s.into_owned())
+}
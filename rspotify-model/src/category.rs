@@ -2,9 +2,10 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::{Image, Page};
+use crate::{CategoryId, Image, Page};
 
-/// Category object
+/// Category object. Its name and icons are localized when a locale is given
+/// to the endpoints.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct Category {
     pub href: String,
@@ -13,6 +14,15 @@ pub struct Category {
     pub name: String,
 }
 
+impl Category {
+    /// The ID of the category, to pass it to the endpoints, if it's a valid
+    /// one.
+    #[must_use]
+    pub fn category_id(&self) -> Option<CategoryId<'_>> {
+        CategoryId::from_id(self.id.as_str()).ok()
+    }
+}
+
 /// Intermediate categories wrapped by page object
 #[derive(Deserialize)]
 pub struct PageCategory {
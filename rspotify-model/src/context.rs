@@ -2,13 +2,13 @@
 
 use chrono::serde::ts_milliseconds;
 use chrono::{DateTime, Duration, Utc};
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use std::collections::HashMap;
 
 use crate::{
-    custom_serde::option_duration_ms, CurrentlyPlayingType, Device, DisallowKey, PlayableItem,
-    RepeatState, Type,
+    custom_serde::option_duration_ms, CurrentlyPlayingType, Device, DisallowKey, FullEpisode,
+    FullTrack, PlayableId, PlayableItem, PlayerError, RepeatState, Type,
 };
 
 /// Context object
@@ -53,6 +53,119 @@ pub struct CurrentPlaybackContext {
     pub currently_playing_type: CurrentlyPlayingType,
     pub actions: Actions,
 }
+
+/// What's currently playing according to both the `currently_playing_type`
+/// and the `item` of the playback, see
+/// [`CurrentlyPlayingContext::currently_playing`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurrentlyPlaying<'a> {
+    Track(&'a FullTrack),
+    Episode(&'a FullEpisode),
+    /// An ad break, for which Spotify doesn't send an item.
+    Advertisement,
+    /// Nothing is playing, the type is unknown, or it doesn't match the
+    /// item.
+    Unknown,
+}
+
+impl<'a> CurrentlyPlaying<'a> {
+    fn new(kind: CurrentlyPlayingType, item: Option<&'a PlayableItem>) -> Self {
+        match (kind, item) {
+            (CurrentlyPlayingType::Track, Some(PlayableItem::Track(track))) => Self::Track(track),
+            (CurrentlyPlayingType::Episode, Some(PlayableItem::Episode(episode))) => {
+                Self::Episode(episode)
+            }
+            (CurrentlyPlayingType::Advertisement, _) => Self::Advertisement,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl CurrentlyPlayingContext {
+    /// The item that's currently playing, checked against
+    /// `currently_playing_type`. Ads are reported as
+    /// [`CurrentlyPlaying::Advertisement`] even though there's no item.
+    #[must_use]
+    pub fn currently_playing(&self) -> CurrentlyPlaying<'_> {
+        CurrentlyPlaying::new(self.currently_playing_type, self.item.as_ref())
+    }
+}
+
+/// A change in the player between two playback states, see
+/// [`CurrentPlaybackContext::changes_since`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PlaybackChange {
+    /// Another item started playing, given by their IDs. Local tracks and ads
+    /// have no ID.
+    Item {
+        from: Option<PlayableId<'static>>,
+        to: Option<PlayableId<'static>>,
+    },
+    /// The playback was resumed (`true`) or paused (`false`).
+    Playing(bool),
+    /// The playback moved to another device, given by their IDs.
+    Device {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    /// The volume of the device changed, in percent.
+    Volume { from: Option<u32>, to: Option<u32> },
+    /// Shuffle was turned on or off.
+    Shuffle(bool),
+    /// The repeat mode changed to the given one.
+    Repeat(RepeatState),
+}
+
+impl CurrentPlaybackContext {
+    /// Same as [`CurrentlyPlayingContext::currently_playing`].
+    #[must_use]
+    pub fn currently_playing(&self) -> CurrentlyPlaying<'_> {
+        CurrentlyPlaying::new(self.currently_playing_type, self.item.as_ref())
+    }
+
+    /// Returns the changes in the playing item, whether it's paused, the
+    /// device, its volume, shuffle and repeat modes since the `previous`
+    /// playback state, e.g., to react to them when polling
+    /// `current_playback`. The volume is only compared if the device is the
+    /// same. The progress isn't compared, since it changes all the time.
+    #[must_use]
+    pub fn changes_since(&self, previous: &Self) -> Vec<PlaybackChange> {
+        let mut changes = Vec::new();
+        let item_id = |playback: &Self| {
+            playback
+                .item
+                .as_ref()
+                .and_then(PlayableItem::id)
+                .map(PlayableId::into_static)
+        };
+        let (from, to) = (item_id(previous), item_id(self));
+        if from != to {
+            changes.push(PlaybackChange::Item { from, to });
+        }
+        if self.is_playing != previous.is_playing {
+            changes.push(PlaybackChange::Playing(self.is_playing));
+        }
+        if self.device.id != previous.device.id {
+            changes.push(PlaybackChange::Device {
+                from: previous.device.id.clone(),
+                to: self.device.id.clone(),
+            });
+        } else if self.device.volume_percent != previous.device.volume_percent {
+            changes.push(PlaybackChange::Volume {
+                from: previous.device.volume_percent,
+                to: self.device.volume_percent,
+            });
+        }
+        if self.shuffle_state != previous.shuffle_state {
+            changes.push(PlaybackChange::Shuffle(self.shuffle_state));
+        }
+        if self.repeat_state != previous.repeat_state {
+            changes.push(PlaybackChange::Repeat(self.repeat_state));
+        }
+        changes
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct CurrentUserQueue {
     pub currently_playing: Option<PlayableItem>,
@@ -60,11 +173,29 @@ pub struct CurrentUserQueue {
 }
 
 /// Actions object
-#[derive(Clone, Debug, Serialize, PartialEq, Eq, Default)]
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub struct Actions {
     pub disallows: Vec<DisallowKey>,
 }
 
+/// Serialized in the same format Spotify uses, so that it can be deserialized
+/// back.
+impl Serialize for Actions {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[derive(Serialize)]
+        struct OriginalActions<'a> {
+            disallows: HashMap<&'a DisallowKey, bool>,
+        }
+        OriginalActions {
+            disallows: self.disallows.iter().map(|key| (key, true)).collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
 impl<'de> Deserialize<'de> for Actions {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -75,13 +206,82 @@ impl<'de> Deserialize<'de> for Actions {
             pub disallows: HashMap<DisallowKey, bool>,
         }
         let orignal_actions = OriginalActions::deserialize(deserializer)?;
-        Ok(Self {
-            disallows: orignal_actions
-                .disallows
-                .into_iter()
-                .filter(|(_, value)| *value)
-                .map(|(key, _)| key)
-                .collect(),
-        })
+        let mut disallows = orignal_actions
+            .disallows
+            .into_iter()
+            .filter(|(_, value)| *value)
+            .map(|(key, _)| key)
+            .collect::<Vec<_>>();
+        // Sorted so that the order doesn't depend on the map's
+        disallows.sort_unstable();
+        Ok(Self { disallows })
+    }
+}
+
+impl Actions {
+    /// Whether the given action is currently allowed.
+    #[must_use]
+    pub fn is_allowed(&self, action: DisallowKey) -> bool {
+        !self.disallows.contains(&action)
+    }
+
+    /// Returns [`PlayerError::DisallowedAction`] if the given action is
+    /// currently disallowed.
+    pub fn check(&self, action: DisallowKey) -> Result<(), PlayerError> {
+        if self.is_allowed(action) {
+            Ok(())
+        } else {
+            Err(PlayerError::DisallowedAction(action))
+        }
+    }
+
+    #[must_use]
+    pub fn can_interrupt_playback(&self) -> bool {
+        self.is_allowed(DisallowKey::InterruptingPlayback)
+    }
+
+    #[must_use]
+    pub fn can_pause(&self) -> bool {
+        self.is_allowed(DisallowKey::Pausing)
+    }
+
+    #[must_use]
+    pub fn can_resume(&self) -> bool {
+        self.is_allowed(DisallowKey::Resuming)
+    }
+
+    #[must_use]
+    pub fn can_seek(&self) -> bool {
+        self.is_allowed(DisallowKey::Seeking)
+    }
+
+    #[must_use]
+    pub fn can_skip_next(&self) -> bool {
+        self.is_allowed(DisallowKey::SkippingNext)
+    }
+
+    #[must_use]
+    pub fn can_skip_prev(&self) -> bool {
+        self.is_allowed(DisallowKey::SkippingPrev)
+    }
+
+    #[must_use]
+    pub fn can_toggle_repeat_context(&self) -> bool {
+        self.is_allowed(DisallowKey::TogglingRepeatContext)
+    }
+
+    #[must_use]
+    pub fn can_toggle_repeat_track(&self) -> bool {
+        self.is_allowed(DisallowKey::TogglingRepeatTrack)
+    }
+
+    #[must_use]
+    pub fn can_toggle_shuffle(&self) -> bool {
+        self.is_allowed(DisallowKey::TogglingShuffle)
+    }
+
+    #[must_use]
+    pub fn can_transfer_playback(&self) -> bool {
+        self.is_allowed(DisallowKey::TransferringPlayback)
     }
 }
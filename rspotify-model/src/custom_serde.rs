@@ -181,6 +181,40 @@ pub mod duration_second {
     }
 }
 
+/// Deserialize/Serialize `chrono::Duration` from/to fractional seconds, as
+/// used in the audio analysis.
+pub mod duration_float_second {
+    use chrono::Duration;
+    use serde::{de, Deserialize, Serializer};
+
+    /// Deserialize `chrono::Duration` from seconds (represented as f64),
+    /// rounded to microseconds
+    pub fn deserialize<'de, D>(d: D) -> Result<Duration, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let duration: f64 = Deserialize::deserialize(d)?;
+        if !duration.is_finite() {
+            return Err(de::Error::invalid_value(
+                de::Unexpected::Float(duration),
+                &"a finite duration in seconds",
+            ));
+        }
+        Ok(Duration::microseconds(
+            (duration * 1_000_000.0).round() as i64
+        ))
+    }
+
+    /// Serialize `chrono::Duration` to seconds (represented as f64)
+    pub fn serialize<S>(x: &Duration, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let micros = x.num_microseconds().unwrap_or(i64::MAX);
+        s.serialize_f64(micros as f64 / 1_000_000.0)
+    }
+}
+
 pub mod space_separated_scopes {
     use serde::{de, Deserialize, Serializer};
     use std::collections::HashSet;
@@ -201,3 +235,56 @@ pub mod space_separated_scopes {
         s.serialize_str(&scopes)
     }
 }
+
+#[cfg(feature = "borrowed")]
+pub mod option_cow_str {
+    use serde::{de, Deserialize, Deserializer};
+    use std::{borrow::Cow, fmt};
+
+    /// A string borrowed from the input when it has no escape sequences.
+    struct CowStr<'a>(Cow<'a, str>);
+
+    impl<'de: 'a, 'a> Deserialize<'de> for CowStr<'a> {
+        fn deserialize<D>(d: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            struct CowStrVisitor;
+            impl<'de> de::Visitor<'de> for CowStrVisitor {
+                type Value = CowStr<'de>;
+                fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("a string")
+                }
+                fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+                where
+                    E: de::Error,
+                {
+                    Ok(CowStr(Cow::Borrowed(v)))
+                }
+                fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+                where
+                    E: de::Error,
+                {
+                    Ok(CowStr(Cow::Owned(v.to_owned())))
+                }
+                fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+                where
+                    E: de::Error,
+                {
+                    Ok(CowStr(Cow::Owned(v)))
+                }
+            }
+
+            d.deserialize_str(CowStrVisitor)
+        }
+    }
+
+    /// Deserialize `Option<Cow<str>>` borrowing from the input, which serde
+    /// only does by itself for `Cow<str>` fields that aren't wrapped.
+    pub fn deserialize<'de: 'a, 'a, D>(d: D) -> Result<Option<Cow<'a, str>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<CowStr<'a>>::deserialize(d).map(|s| s.map(|s| s.0))
+    }
+}
@@ -1,4 +1,4 @@
-use crate::DeviceType;
+use crate::{DeviceId, DeviceType};
 use serde::{Deserialize, Serialize};
 
 /// Device object
@@ -14,6 +14,15 @@ pub struct Device {
     pub volume_percent: Option<u32>,
 }
 
+impl Device {
+    /// The ID of the device, to target it in the player endpoints, if
+    /// Spotify provided one.
+    #[must_use]
+    pub fn device_id(&self) -> Option<DeviceId<'_>> {
+        self.id.as_deref().and_then(|id| DeviceId::from_id(id).ok())
+    }
+}
+
 /// Intermediate device payload object
 #[derive(Deserialize)]
 pub struct DevicePayload {
@@ -3,7 +3,9 @@ use strum::IntoStaticStr;
 
 /// ISO 3166-1 alpha-2 country code, from
 /// [country-list](https://datahub.io/core/country-list)
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, IntoStaticStr)]
+#[derive(
+    Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, IntoStaticStr,
+)]
 pub enum Country {
     #[strum(serialize = "AF")]
     #[serde(rename = "AF")]
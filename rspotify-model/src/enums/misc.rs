@@ -1,13 +1,15 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use strum::IntoStaticStr;
+use strum::{AsRefStr, Display, EnumString, IntoStaticStr};
 
 use super::Country;
 
 /// Disallows object: `interrupting_playback`, `pausing`, `resuming`, `seeking`,
 /// `skipping_next`, `skipping_prev`, `toggling_repeat_context`,
 /// `toggling_shuffle`, `toggling_repeat_track`, `transferring_playback`.
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, Hash, IntoStaticStr)]
+#[derive(
+    Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, Hash, PartialOrd, Ord, IntoStaticStr,
+)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum DisallowKey {
@@ -24,7 +26,9 @@ pub enum DisallowKey {
 }
 
 /// Time range: `long-term`, `medium-term`, `short-term`.
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, IntoStaticStr)]
+#[derive(
+    Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, IntoStaticStr,
+)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum TimeRange {
@@ -34,7 +38,9 @@ pub enum TimeRange {
 }
 
 /// Repeat state: `track`, `context` or `off`.
-#[derive(Clone, Debug, Copy, Serialize, Deserialize, PartialEq, Eq, IntoStaticStr)]
+#[derive(
+    Clone, Debug, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord, IntoStaticStr,
+)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum RepeatState {
@@ -44,7 +50,9 @@ pub enum RepeatState {
 }
 
 /// Type for `include_external`: `audio`.
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, IntoStaticStr)]
+#[derive(
+    Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, IntoStaticStr,
+)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum IncludeExternal {
@@ -52,7 +60,9 @@ pub enum IncludeExternal {
 }
 
 /// Date precision: `year`, `month`, `day`.
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, IntoStaticStr)]
+#[derive(
+    Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, IntoStaticStr,
+)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum DatePrecision {
@@ -62,7 +72,9 @@ pub enum DatePrecision {
 }
 
 /// The reason for the restriction: `market`, `product`, `explicit`
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, IntoStaticStr)]
+#[derive(
+    Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, IntoStaticStr,
+)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum RestrictionReason {
@@ -71,11 +83,75 @@ pub enum RestrictionReason {
     Explicit,
 }
 
+/// The reason of a failed player command, e.g., `NO_ACTIVE_DEVICE`. See the
+/// [player error
+/// reasons](https://developer.spotify.com/documentation/web-api/concepts/api-calls#player-error-reasons).
+///
+/// `Unknown` is also used for the reasons that aren't listed here yet.
+#[derive(
+    Clone,
+    Serialize,
+    Deserialize,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Debug,
+    Display,
+    IntoStaticStr,
+)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum PlayerErrorReason {
+    /// There's no previous track in the context.
+    NoPrevTrack,
+    /// There's no next track in the context.
+    NoNextTrack,
+    /// The requested track doesn't exist.
+    NoSpecificTrack,
+    /// The playback is already paused.
+    AlreadyPaused,
+    /// The playback isn't paused.
+    NotPaused,
+    /// The playback isn't on the local device.
+    NotPlayingLocally,
+    /// No track is currently playing.
+    NotPlayingTrack,
+    /// No context is currently playing.
+    NotPlayingContext,
+    /// The current context can't be shuffled.
+    EndlessContext,
+    /// The command is disallowed in the current context.
+    ContextDisallow,
+    /// The command requested a new track and context to play, but it's the
+    /// same as the current one.
+    AlreadyPlaying,
+    /// The user is rate limited due to too frequent track play.
+    RateLimited,
+    /// The context can't be remote controlled.
+    RemoteControlDisallow,
+    /// The device can't be remote controlled.
+    DeviceNotControllable,
+    /// The volume of the device can't be changed.
+    VolumeControlDisallow,
+    /// There's no active device to play on; one has to be chosen.
+    NoActiveDevice,
+    /// The command requires Spotify Premium.
+    PremiumRequired,
+    /// Any other reason.
+    #[serde(other)]
+    Unknown,
+}
+
 /// Indicates the modality (major or minor) of a track.
 ///
 /// This field will contain a 0 for `minor`, a 1 for `major` or a -1 for `no
 /// result`
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, IntoStaticStr)]
+#[derive(
+    Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, IntoStaticStr,
+)]
 pub enum Modality {
     Minor = 0,
     Major = 1,
@@ -86,7 +162,7 @@ pub enum Modality {
 ///
 /// `FromToken` is the same thing as setting the market parameter to the user's
 /// country.
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Market {
     Country(Country),
     FromToken,
@@ -102,8 +178,58 @@ impl From<Market> for &'static str {
 }
 
 /// Time limits in miliseconds (unix timestamps)
-#[derive(Clone, Debug, Serialize, Deserialize, Copy, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, Deserialize, Copy, PartialEq, Eq, Hash)]
 pub enum TimeLimits {
     Before(DateTime<Utc>),
     After(DateTime<Utc>),
 }
+
+macro_rules! scope_enum {
+    ($($variant:ident => $name:literal),* $(,)?) => {
+        /// Authorization scope, e.g., `user-read-private`. See the [list of
+        /// scopes](https://developer.spotify.com/documentation/web-api/concepts/scopes)
+        /// for what each of them grants access to.
+        ///
+        /// The scopes of [`Token`](crate::Token) are kept as strings so that the new
+        /// ones are supported before they're added here. A set of them can be built
+        /// from this enum with `scopes!(Scope::UserReadPrivate, Scope::Streaming)`.
+        #[derive(
+            Clone,
+            Serialize,
+            Deserialize,
+            Copy,
+            PartialEq,
+            Eq,
+            Debug,
+            Hash,
+            PartialOrd,
+            Ord,
+            AsRefStr,
+            Display,
+            EnumString,
+            IntoStaticStr,
+        )]
+        pub enum Scope {
+            $(
+                #[serde(rename = $name)]
+                #[strum(serialize = $name)]
+                $variant,
+            )*
+        }
+
+        impl Scope {
+            /// All the scopes, in the same order as in Spotify's documentation.
+            pub const ALL: &'static [Scope] = &[$(Scope::$variant),*];
+        }
+    };
+}
+
+// The scopes are listed in `rspotify-macros`, which validates the ones given
+// to `scopes!` with the same list
+rspotify_macros::__scopes!(scope_enum);
+
+impl From<Scope> for String {
+    fn from(scope: Scope) -> Self {
+        scope.as_ref().to_owned()
+    }
+}
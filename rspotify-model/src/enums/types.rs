@@ -3,7 +3,9 @@ use strum::{Display, EnumString, IntoStaticStr};
 
 /// Copyright type: `C` = the copyright, `P` = the sound recording (performance)
 /// copyright.
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, IntoStaticStr)]
+#[derive(
+    Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, IntoStaticStr,
+)]
 pub enum CopyrightType {
     #[strum(serialize = "P")]
     #[serde(rename = "P")]
@@ -14,7 +16,9 @@ pub enum CopyrightType {
 }
 
 /// Album type: `album`, `single`, `appears_on`, `compilation`
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, IntoStaticStr)]
+#[derive(
+    Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, IntoStaticStr,
+)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum AlbumType {
@@ -24,9 +28,22 @@ pub enum AlbumType {
     Compilation,
 }
 
-/// Type: `artist`, `album`, `track`, `playlist`, `show` or `episode`
+/// Type: `artist`, `album`, `track`, `playlist`, `show`, `episode`,
+/// `audiobook` or `chapter`
 #[derive(
-    Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, Display, EnumString, IntoStaticStr,
+    Clone,
+    Serialize,
+    Deserialize,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Debug,
+    Display,
+    EnumString,
+    IntoStaticStr,
 )]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
@@ -38,12 +55,16 @@ pub enum Type {
     User,
     Show,
     Episode,
+    Audiobook,
+    Chapter,
     Collection,
     Collectionyourepisodes, // rename to collectionyourepisodes
 }
 
 /// Additional typs: `track`, `episode`
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, IntoStaticStr)]
+#[derive(
+    Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, IntoStaticStr,
+)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum AdditionalType {
@@ -52,7 +73,12 @@ pub enum AdditionalType {
 }
 
 /// Currently playing type: `track`, `episode`, `ad`, `unknown`
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, IntoStaticStr)]
+///
+/// Any other type that Spotify may add in the future is deserialized as
+/// `Unknown`.
+#[derive(
+    Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, IntoStaticStr,
+)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum CurrentlyPlayingType {
@@ -61,11 +87,14 @@ pub enum CurrentlyPlayingType {
     #[strum(serialize = "ad")]
     #[serde(rename = "ad")]
     Advertisement,
+    #[serde(other)]
     Unknown,
 }
 
 /// Type for search: `artist`, `album`, `track`, `playlist`, `show`, `episode`
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, IntoStaticStr)]
+#[derive(
+    Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, IntoStaticStr,
+)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum SearchType {
@@ -77,20 +106,29 @@ pub enum SearchType {
     Episode,
 }
 
-/// The user's Spotify subscription level: `premium`, `free`
+/// The user's Spotify subscription level: `premium`, `free`, `unknown`
 ///
 /// (The subscription level "open" can be considered the same as "free".)
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, IntoStaticStr)]
+///
+/// Any other level, e.g., of the plans only available in some markets, is
+/// deserialized as `Unknown`.
+#[derive(
+    Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, IntoStaticStr,
+)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum SubscriptionLevel {
     Premium,
     #[serde(alias = "open")]
     Free,
+    #[serde(other)]
+    Unknown,
 }
 
 /// Device Type: `computer`, `smartphone`, `speaker`, `TV`
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, IntoStaticStr)]
+#[derive(
+    Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord, IntoStaticStr,
+)]
 #[strum(serialize_all = "snake_case")]
 pub enum DeviceType {
     Computer,
@@ -117,7 +155,9 @@ pub enum DeviceType {
 }
 
 /// Recommendations seed type
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, IntoStaticStr)]
+#[derive(
+    Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord, IntoStaticStr,
+)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum RecommendationsSeedType {
     Artist,
@@ -1,3 +1,5 @@
+use crate::{DisallowKey, PlayerErrorReason};
+
 use serde::Deserialize;
 use thiserror::Error;
 
@@ -7,18 +9,35 @@ pub type ModelResult<T> = Result<T, ModelError>;
 /// Matches errors that are returned from the Spotfiy
 /// API as part of the JSON response object.
 #[derive(Debug, Error, Deserialize)]
+#[serde(untagged)]
 pub enum ApiError {
-    /// See [Error Object](https://developer.spotify.com/documentation/web-api/reference/#object-errorobject)
-    #[error("{status}: {message}")]
-    Regular { status: u16, message: String },
-
     /// See [Play Error Object](https://developer.spotify.com/documentation/web-api/reference/#object-playererrorobject)
     #[error("{status} ({reason}): {message}")]
     Player {
         status: u16,
         message: String,
-        reason: String,
+        reason: PlayerErrorReason,
     },
+
+    /// See [Error Object](https://developer.spotify.com/documentation/web-api/reference/#object-errorobject)
+    #[error("{status}: {message}")]
+    Regular { status: u16, message: String },
+}
+
+/// The body of an unsuccessful response from the Web API, which wraps the
+/// [`ApiError`].
+#[derive(Debug, Deserialize)]
+pub struct ApiErrorResponse {
+    pub error: ApiError,
+}
+
+/// Errors detected before sending a request to the player.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerError {
+    /// The action is disallowed in the current playback context, e.g.,
+    /// skipping an ad.
+    #[error("the player currently disallows `{}`", <&'static str>::from(.0))]
+    DisallowedAction(DisallowKey),
 }
 
 /// Groups up the kinds of errors that may happen in this crate.
@@ -15,6 +15,19 @@
 //! * [`Type::User`] => [`UserId`]
 //! * [`Type::Show`] => [`ShowId`]
 //! * [`Type::Episode`] => [`EpisodeId`]
+//! * [`Type::Audiobook`] => [`AudiobookId`]
+//! * [`Type::Chapter`] => [`ChapterId`]
+//!
+//! Devices and browse categories aren't Spotify objects, so [`DeviceId`] and
+//! [`CategoryId`] stand on their own.
+//!
+//! When the type of a URI isn't known beforehand, [`AnyId::from_uri`] parses
+//! it into the right kind of ID.
+//!
+//! The links copied from Spotify's apps, like
+//! `https://open.spotify.com/intl-de/track/4iV5W9uYEdYUVa79Axb7Rh?si=abc`, are
+//! parsed with the `from_url` methods instead, or with the `from_any` ones when
+//! it may be either an ID, a URI or a URL.
 //!
 //! Every kind of ID defines its own validity function, i.e., what characters it
 //! can be made up of, such as alphanumeric or any.
@@ -99,7 +112,7 @@ use serde::{Deserialize, Serialize};
 use strum::Display;
 use thiserror::Error;
 
-use std::{borrow::Cow, fmt::Debug, hash::Hash};
+use std::{borrow::Cow, collections::HashSet, convert::TryFrom, fmt::Debug, hash::Hash};
 
 use crate::Type;
 
@@ -156,7 +169,8 @@ pub trait Id {
 /// whether it's alphanumeric; that should be done in `Id::from_id`).
 ///
 /// This is only useful for advanced use-cases, such as implementing your own ID
-/// type.
+/// type. See [`AnyId::from_uri`] to parse a URI of any type into its ID
+/// instead.
 pub fn parse_uri(uri: &str) -> Result<(Type, &str), IdError> {
     let mut chars = uri
         .strip_prefix("spotify")
@@ -181,6 +195,68 @@ pub fn parse_uri(uri: &str) -> Result<(Type, &str), IdError> {
     }
 }
 
+/// A lower level function to parse the URL of a Spotify object into both its
+/// type and its actual ID, like [`parse_uri`]. Its query and fragment are
+/// ignored, as well as the locale and `embed` prefixes of the path, e.g.,
+/// `https://open.spotify.com/intl-de/track/4iV5W9uYEdYUVa79Axb7Rh?si=abc`.
+///
+/// # Errors
+///
+/// - `IdError::InvalidPrefix` - if it doesn't start with
+///   `https://open.spotify.com/` (or `http://`, or no scheme at all),
+/// - `IdError::InvalidFormat` - if its path isn't made up of the type and the
+///   ID,
+/// - `IdError::InvalidType` - if the type in its path is unknown.
+pub fn parse_url(url: &str) -> Result<(Type, &str), IdError> {
+    let url = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .unwrap_or(url);
+    let path = url
+        .strip_prefix("open.spotify.com/")
+        .ok_or(IdError::InvalidPrefix)?;
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+
+    let mut segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .skip_while(|segment| segment.starts_with("intl-") || *segment == "embed");
+    let (tpe, id) = match (segments.next(), segments.next(), segments.next()) {
+        (Some(tpe), Some(id), None) => (tpe, id),
+        _ => return Err(IdError::InvalidFormat),
+    };
+
+    match tpe.parse::<Type>() {
+        Ok(tpe) => Ok((tpe, id)),
+        _ => Err(IdError::InvalidType),
+    }
+}
+
+/// Removes the repeated IDs, keeping the first occurrence of each of them in
+/// their original order, e.g., before saving a large library in chunks.
+///
+/// The IDs implement [`Hash`] and [`Ord`], so they can be collected into a
+/// `HashSet` or a `BTreeSet` as well when the order doesn't matter.
+///
+/// ```
+/// use rspotify_model::{dedup_ids, TrackId};
+///
+/// let ids = ["4iV5W9uYEdYUVa79Axb7Rh", "1301WleyT98MSxVHPZCA6M", "4iV5W9uYEdYUVa79Axb7Rh"]
+///     .map(|id| TrackId::from_id(id).unwrap());
+/// assert_eq!(dedup_ids(ids.clone()), ids[..2]);
+/// ```
+#[must_use]
+pub fn dedup_ids<T, I>(ids: I) -> Vec<T>
+where
+    T: Hash + Eq + Clone,
+    I: IntoIterator<Item = T>,
+{
+    let mut seen = HashSet::new();
+    ids.into_iter()
+        .filter(|id| seen.insert(id.clone()))
+        .collect()
+}
+
 /// This macro helps consistently define ID types.
 ///
 /// * The `$type` parameter indicates what variant in `Type` the ID is for (say,
@@ -200,7 +276,7 @@ macro_rules! define_idtypes {
                 docs][`crate::idtypes`] for more information. "
             )]
             #[repr(transparent)]
-            #[derive(Clone, Debug, PartialEq, Eq, Serialize, Hash)]
+            #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Hash)]
             pub struct $name<'a>(Cow<'a, str>);
 
             impl<'a> $name<'a> {
@@ -332,6 +408,40 @@ macro_rules! define_idtypes {
                     }
                 }
 
+                /// Parse the URL of a Spotify object from string slice, as
+                /// copied from Spotify's apps, e.g.,
+                /// `https://open.spotify.com/track/4y4VO05kYgUTo2bzbox1an?si=abc`.
+                /// See [`parse_url`] for the accepted formats.
+                ///
+                /// # Errors
+                ///
+                /// The same as [`parse_url`], plus `IdError::InvalidType` if
+                /// the type in its path isn't `T`, and `IdError::InvalidId` if
+                /// the ID in its path isn't valid.
+                pub fn from_url(url: &'a str) -> Result<Self, IdError> {
+                    let (tpe, id) = parse_url(url)?;
+                    if tpe == Type::$type {
+                        Self::from_id(id)
+                    } else {
+                        Err(IdError::InvalidType)
+                    }
+                }
+
+                /// Parse Spotify ID, URI or URL from string slice, i.e.,
+                /// anything that [`Self::from_id_or_uri`] or
+                /// [`Self::from_url`] accept, such as user input.
+                ///
+                /// # Errors
+                ///
+                /// The same as [`Self::from_url`] if it's a URL, or as
+                /// [`Self::from_id_or_uri`] otherwise.
+                pub fn from_any(s: &'a str) -> Result<Self, IdError> {
+                    match Self::from_url(s) {
+                        Err(IdError::InvalidPrefix) => Self::from_id_or_uri(s),
+                        result => result,
+                    }
+                }
+
                 /// This creates an ID with the underlying `&str` variant from a
                 /// reference. Useful to use an ID multiple times without having
                 /// to clone it.
@@ -441,8 +551,8 @@ macro_rules! define_idtypes {
     }
 }
 
-// First declaring the regular IDs. Those with custom behaviour will have to be
-// declared manually later on.
+// First declaring the regular IDs. Those without a URI are declared later on
+// with `define_plain_idtypes`, and those with custom behaviour manually.
 define_idtypes!(
     Artist => {
         name: ArtistId,
@@ -468,18 +578,161 @@ define_idtypes!(
         name: EpisodeId,
         validity: |id| id.chars().all(|ch| ch.is_ascii_alphanumeric())
     },
+    Audiobook => {
+        name: AudiobookId,
+        validity: |id| id.chars().all(|ch| ch.is_ascii_alphanumeric())
+    },
+    Chapter => {
+        name: ChapterId,
+        validity: |id| id.chars().all(|ch| ch.is_ascii_alphanumeric())
+    },
     User => {
         name: UserId,
         validity: |_| true
     }
 );
 
+/// This macro defines the IDs that have no URI because they aren't Spotify
+/// objects, like devices. Unlike those of `define_idtypes`, they don't
+/// implement [`Id`].
+///
+/// * The attributes, like the documentation, are placed on the struct.
+/// * The `$name` parameter is the identifier of the struct.
+/// * The `$validity` parameter is the implementation of `id_is_valid`.
+macro_rules! define_plain_idtypes {
+    ($($(#[$attr:meta])* $name:ident => {
+        validity: $validity:expr
+    }),+) => {
+        $(
+            $(#[$attr])*
+            #[repr(transparent)]
+            #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Hash)]
+            pub struct $name<'a>(Cow<'a, str>);
+
+            impl<'a> $name<'a> {
+                /// Only returns `true` in case the given string is a valid ID.
+                #[must_use]
+                pub fn id_is_valid(id: &str) -> bool {
+                    const VALID_FN: fn(&str) -> bool = $validity;
+                    VALID_FN(id)
+                }
+
+                /// Initialize the ID without checking its validity.
+                ///
+                /// # Safety
+                ///
+                /// The string passed to this method must be a valid ID, as
+                /// checked by [`Self::id_is_valid`]; otherwise undefined
+                /// behaviour may occur.
+                pub unsafe fn from_id_unchecked<S>(id: S) -> Self
+                where
+                    S: Into<Cow<'a, str>>,
+                {
+                    Self(id.into())
+                }
+
+                /// Parse the ID from a string.
+                ///
+                /// # Errors
+                ///
+                /// - `IdError::InvalidId` - if `id` isn't valid according to
+                ///   [`Self::id_is_valid`].
+                pub fn from_id<S>(id: S) -> Result<Self, IdError>
+                where
+                    S: Into<Cow<'a, str>>,
+                {
+                    let id = id.into();
+                    if Self::id_is_valid(&id) {
+                        // Safe, we've just checked that the ID is valid.
+                        Ok(unsafe { Self::from_id_unchecked(id) })
+                    } else {
+                        Err(IdError::InvalidId)
+                    }
+                }
+
+                /// The inner ID.
+                #[must_use]
+                pub fn id(&self) -> &str {
+                    &self.0
+                }
+
+                /// This creates an ID with the underlying `&str` variant from a
+                /// reference. Useful to use an ID multiple times without having
+                /// to clone it.
+                #[must_use]
+                pub fn as_ref(&'a self) -> Self {
+                    Self(Cow::Borrowed(self.0.as_ref()))
+                }
+
+                /// Switches to the owned version of the ID, which has a
+                /// `'static` lifetime.
+                #[must_use]
+                pub fn into_static(self) -> $name<'static> {
+                    $name(Cow::Owned(self.0.into_owned()))
+                }
+
+                /// Similar to [`Self::into_static`], but without consuming the
+                /// original ID.
+                #[must_use]
+                pub fn clone_static(&self) -> $name<'static> {
+                    $name(Cow::Owned(self.0.clone().into_owned()))
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $name<'static> {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: serde::Deserializer<'de>,
+                {
+                    let id = String::deserialize(deserializer)?;
+                    $name::from_id(id).map_err(serde::de::Error::custom)
+                }
+            }
+
+            impl std::borrow::Borrow<str> for $name<'_> {
+                fn borrow(&self) -> &str {
+                    self.id()
+                }
+            }
+
+            /// Displaying the ID shows the ID itself, since there's no URI
+            impl std::fmt::Display for $name<'_> {
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    f.write_str(self.id())
+                }
+            }
+        )+
+    }
+}
+
+define_plain_idtypes!(
+    /// ID of a device, as given by [`Device::id`](crate::Device::id). Devices
+    /// aren't Spotify objects, so unlike the rest of the IDs this doesn't
+    /// implement [`Id`], as it has no URI. Any non-empty string is valid.
+    DeviceId => {
+        validity: |id| !id.is_empty()
+    },
+    /// ID of a browse category, as given by
+    /// [`Category::id`](crate::Category::id), e.g. `party` or
+    /// `0JQ5DAqbMKFzHmL4tf05da`. Like devices, categories have no URI, so this
+    /// doesn't implement [`Id`]. It may only contain alphanumeric characters,
+    /// `_` and `-`, so that it's always safe to place in a path.
+    CategoryId => {
+        validity: |id| {
+            !id.is_empty()
+                && id
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+        }
+    }
+);
+
 // We use `enum_dispatch` for dynamic dispatch, which is not only easier to use
 // than `dyn`, but also more efficient.
 /// Grouping up multiple kinds of IDs to treat them generically. This also
 /// implements [`Id`], and [`From`] to instantiate it.
 #[enum_dispatch(Id)]
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Hash)]
 pub enum PlayContextId<'a> {
     Artist(ArtistId<'a>),
     Album(AlbumId<'a>),
@@ -522,7 +775,7 @@ impl<'a> PlayContextId<'a> {
 /// Grouping up multiple kinds of IDs to treat them generically. This also
 /// implements [`Id`] and [`From`] to instantiate it.
 #[enum_dispatch(Id)]
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Hash)]
 pub enum PlayableId<'a> {
     Track(TrackId<'a>),
     Episode(EpisodeId<'a>),
@@ -554,6 +807,177 @@ impl<'a> PlayableId<'a> {
     }
 }
 
+/// Any kind of ID, to handle the URIs whose type isn't known beforehand, e.g.,
+/// because they're given by the user. This also implements [`Id`], and
+/// [`From`] to instantiate it from the concrete IDs.
+///
+/// ```
+/// use rspotify_model::{AnyId, Id, PlayableId, Type};
+/// use std::convert::TryFrom;
+///
+/// let id = AnyId::from_uri("spotify:episode:0lbiy3LKzIY2fnyjioC11p").unwrap();
+/// assert_eq!(id._type(), Type::Episode);
+/// match PlayableId::try_from(id) {
+///     Ok(playable) => println!("Adding {} to the queue", playable.uri()),
+///     Err(_) => println!("It can't be played on its own"),
+/// }
+/// ```
+#[enum_dispatch(Id)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Hash)]
+pub enum AnyId<'a> {
+    Artist(ArtistId<'a>),
+    Album(AlbumId<'a>),
+    Track(TrackId<'a>),
+    Playlist(PlaylistId<'a>),
+    User(UserId<'a>),
+    Show(ShowId<'a>),
+    Episode(EpisodeId<'a>),
+    Audiobook(AudiobookId<'a>),
+    Chapter(ChapterId<'a>),
+}
+// These don't work with `enum_dispatch`, unfortunately.
+impl<'a> AnyId<'a> {
+    /// Parses a URI of any type, in the same formats as the `from_uri`
+    /// method of the concrete IDs.
+    ///
+    /// # Errors
+    ///
+    /// The same as the `from_uri` method of the concrete IDs, plus
+    /// `IdError::InvalidType` for the types that have no ID, like
+    /// `collection`.
+    pub fn from_uri(uri: &'a str) -> Result<Self, IdError> {
+        let (tpe, id) = parse_uri(uri)?;
+        Self::from_parts(tpe, id)
+    }
+
+    /// Parses the URL of a Spotify object of any type, in the same formats as
+    /// [`parse_url`].
+    ///
+    /// # Errors
+    ///
+    /// The same as the `from_url` method of the concrete IDs, plus
+    /// `IdError::InvalidType` for the types that have no ID, like
+    /// `collection`.
+    pub fn from_url(url: &'a str) -> Result<Self, IdError> {
+        let (tpe, id) = parse_url(url)?;
+        Self::from_parts(tpe, id)
+    }
+
+    /// Parses either a URI or a URL of any type. Unlike the `from_any` method
+    /// of the concrete IDs, plain IDs fail with `IdError::InvalidPrefix`,
+    /// since their type is unknown.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Self::from_url`] if it's a URL, or as
+    /// [`Self::from_uri`] otherwise.
+    pub fn from_any(s: &'a str) -> Result<Self, IdError> {
+        match Self::from_url(s) {
+            Err(IdError::InvalidPrefix) => Self::from_uri(s),
+            result => result,
+        }
+    }
+
+    fn from_parts(tpe: Type, id: &'a str) -> Result<Self, IdError> {
+        match tpe {
+            Type::Artist => ArtistId::from_id(id).map(Self::Artist),
+            Type::Album => AlbumId::from_id(id).map(Self::Album),
+            Type::Track => TrackId::from_id(id).map(Self::Track),
+            Type::Playlist => PlaylistId::from_id(id).map(Self::Playlist),
+            Type::User => UserId::from_id(id).map(Self::User),
+            Type::Show => ShowId::from_id(id).map(Self::Show),
+            Type::Episode => EpisodeId::from_id(id).map(Self::Episode),
+            Type::Audiobook => AudiobookId::from_id(id).map(Self::Audiobook),
+            Type::Chapter => ChapterId::from_id(id).map(Self::Chapter),
+            Type::Collection | Type::Collectionyourepisodes => Err(IdError::InvalidType),
+        }
+    }
+
+    #[must_use]
+    pub fn as_ref(&'a self) -> Self {
+        match self {
+            AnyId::Artist(x) => AnyId::Artist(x.as_ref()),
+            AnyId::Album(x) => AnyId::Album(x.as_ref()),
+            AnyId::Track(x) => AnyId::Track(x.as_ref()),
+            AnyId::Playlist(x) => AnyId::Playlist(x.as_ref()),
+            AnyId::User(x) => AnyId::User(x.as_ref()),
+            AnyId::Show(x) => AnyId::Show(x.as_ref()),
+            AnyId::Episode(x) => AnyId::Episode(x.as_ref()),
+            AnyId::Audiobook(x) => AnyId::Audiobook(x.as_ref()),
+            AnyId::Chapter(x) => AnyId::Chapter(x.as_ref()),
+        }
+    }
+
+    #[must_use]
+    pub fn into_static(self) -> AnyId<'static> {
+        match self {
+            AnyId::Artist(x) => AnyId::Artist(x.into_static()),
+            AnyId::Album(x) => AnyId::Album(x.into_static()),
+            AnyId::Track(x) => AnyId::Track(x.into_static()),
+            AnyId::Playlist(x) => AnyId::Playlist(x.into_static()),
+            AnyId::User(x) => AnyId::User(x.into_static()),
+            AnyId::Show(x) => AnyId::Show(x.into_static()),
+            AnyId::Episode(x) => AnyId::Episode(x.into_static()),
+            AnyId::Audiobook(x) => AnyId::Audiobook(x.into_static()),
+            AnyId::Chapter(x) => AnyId::Chapter(x.into_static()),
+        }
+    }
+
+    #[must_use]
+    pub fn clone_static(&'a self) -> AnyId<'static> {
+        self.as_ref().into_static()
+    }
+}
+
+impl<'a> From<PlayableId<'a>> for AnyId<'a> {
+    fn from(id: PlayableId<'a>) -> Self {
+        match id {
+            PlayableId::Track(x) => AnyId::Track(x),
+            PlayableId::Episode(x) => AnyId::Episode(x),
+        }
+    }
+}
+
+impl<'a> From<PlayContextId<'a>> for AnyId<'a> {
+    fn from(id: PlayContextId<'a>) -> Self {
+        match id {
+            PlayContextId::Artist(x) => AnyId::Artist(x),
+            PlayContextId::Album(x) => AnyId::Album(x),
+            PlayContextId::Playlist(x) => AnyId::Playlist(x),
+            PlayContextId::Show(x) => AnyId::Show(x),
+        }
+    }
+}
+
+/// Fails with `IdError::InvalidType` if it's neither a track nor an episode.
+impl<'a> TryFrom<AnyId<'a>> for PlayableId<'a> {
+    type Error = IdError;
+
+    fn try_from(id: AnyId<'a>) -> Result<Self, IdError> {
+        match id {
+            AnyId::Track(x) => Ok(PlayableId::Track(x)),
+            AnyId::Episode(x) => Ok(PlayableId::Episode(x)),
+            _ => Err(IdError::InvalidType),
+        }
+    }
+}
+
+/// Fails with `IdError::InvalidType` if it's not an artist, album, playlist
+/// or show.
+impl<'a> TryFrom<AnyId<'a>> for PlayContextId<'a> {
+    type Error = IdError;
+
+    fn try_from(id: AnyId<'a>) -> Result<Self, IdError> {
+        match id {
+            AnyId::Artist(x) => Ok(PlayContextId::Artist(x)),
+            AnyId::Album(x) => Ok(PlayContextId::Album(x)),
+            AnyId::Playlist(x) => Ok(PlayContextId::Playlist(x)),
+            AnyId::Show(x) => Ok(PlayContextId::Show(x)),
+            _ => Err(IdError::InvalidType),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -636,6 +1060,86 @@ mod test {
         assert_eq!(json, json_expected);
     }
 
+    #[test]
+    fn test_any_id() {
+        let id = AnyId::from_uri(URI).unwrap();
+        assert_eq!(id, AnyId::Track(TrackId::from_id(ID).unwrap()));
+        assert_eq!(id.uri(), URI);
+        let playable = PlayableId::try_from(id.clone()).unwrap();
+        assert_eq!(AnyId::from(playable), id);
+        assert_eq!(PlayContextId::try_from(id), Err(IdError::InvalidType));
+
+        let id = AnyId::from_uri("spotify/playlist/37i9dQZF1DXcBWIGoYBM5M").unwrap();
+        assert_eq!(id._type(), Type::Playlist);
+        assert!(PlayContextId::try_from(id.clone_static()).is_ok());
+        assert_eq!(PlayableId::try_from(id), Err(IdError::InvalidType));
+
+        let id = AnyId::from_uri("spotify:user:wizzler").unwrap();
+        assert_eq!(id.id(), "wizzler");
+        assert_eq!(AnyId::from_uri(ID), Err(IdError::InvalidPrefix));
+        assert_eq!(AnyId::from_uri(URI_WRONGTYPE1), Err(IdError::InvalidType));
+        assert_eq!(
+            AnyId::from_uri("spotify:collection:abc"),
+            Err(IdError::InvalidType)
+        );
+        assert_eq!(
+            AnyId::from_uri("spotify:track:not-valid"),
+            Err(IdError::InvalidId)
+        );
+    }
+
+    #[test]
+    fn test_url_parse() {
+        let urls = [
+            "https://open.spotify.com/track/4iV5W9uYEdYUVa79Axb7Rh",
+            "https://open.spotify.com/track/4iV5W9uYEdYUVa79Axb7Rh?si=a1b2c3",
+            "https://open.spotify.com/intl-de/track/4iV5W9uYEdYUVa79Axb7Rh/",
+            "http://open.spotify.com/embed/track/4iV5W9uYEdYUVa79Axb7Rh#t=10",
+            "open.spotify.com/track/4iV5W9uYEdYUVa79Axb7Rh",
+        ];
+        for url in urls {
+            assert_eq!(TrackId::from_url(url).unwrap().id(), ID);
+            assert_eq!(TrackId::from_any(url).unwrap().id(), ID);
+        }
+
+        assert_eq!(TrackId::from_url(URI), Err(IdError::InvalidPrefix));
+        assert_eq!(
+            TrackId::from_url("https://example.com/track/4iV5W9uYEdYUVa79Axb7Rh"),
+            Err(IdError::InvalidPrefix)
+        );
+        assert_eq!(
+            TrackId::from_url("https://open.spotify.com/track"),
+            Err(IdError::InvalidFormat)
+        );
+        assert_eq!(
+            TrackId::from_url("https://open.spotify.com/album/4iV5W9uYEdYUVa79Axb7Rh"),
+            Err(IdError::InvalidType)
+        );
+        assert_eq!(
+            TrackId::from_url("https://open.spotify.com/track/not-valid?si=abc"),
+            Err(IdError::InvalidId)
+        );
+        assert_eq!(TrackId::from_any(ID).unwrap().id(), ID);
+        assert_eq!(TrackId::from_any(URI).unwrap().id(), ID);
+    }
+
+    #[test]
+    fn test_any_id_url() {
+        let id =
+            AnyId::from_url("https://open.spotify.com/intl-es/episode/0lbiy3LKzIY2fnyjioC11p?si=x")
+                .unwrap();
+        assert_eq!(id._type(), Type::Episode);
+        assert_eq!(id.id(), "0lbiy3LKzIY2fnyjioC11p");
+        assert_eq!(AnyId::from_url(&id.url()), Ok(id));
+
+        assert_eq!(AnyId::from_any(URI).unwrap()._type(), Type::Track);
+        assert_eq!(AnyId::from_any(ID), Err(IdError::InvalidPrefix));
+        assert_eq!(
+            AnyId::from_any("https://open.spotify.com/collection/tracks"),
+            Err(IdError::InvalidType)
+        );
+    }
+
     #[test]
     fn test_multiple_types() {
         fn endpoint<'a>(_ids: impl IntoIterator<Item = PlayableId<'a>>) {}
@@ -695,4 +1199,52 @@ mod test {
         check_static(id.clone());
         check_static(id);
     }
+
+    #[test]
+    fn test_device_id() {
+        let id = DeviceId::from_id("0d1841b0976bae2a3a310dd74c0f3df354899bc8").unwrap();
+        assert_eq!(id.to_string(), "0d1841b0976bae2a3a310dd74c0f3df354899bc8");
+        assert_eq!(DeviceId::from_id(""), Err(IdError::InvalidId));
+
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, r#""0d1841b0976bae2a3a310dd74c0f3df354899bc8""#);
+        assert_eq!(serde_json::from_str::<DeviceId>(&json).unwrap(), id);
+        assert!(serde_json::from_str::<DeviceId>(r#""""#).is_err());
+    }
+
+    #[test]
+    fn test_category_id() {
+        let id = CategoryId::from_id("0JQ5DAqbMKFzHmL4tf05da").unwrap();
+        assert_eq!(id.to_string(), "0JQ5DAqbMKFzHmL4tf05da");
+        assert!(CategoryId::from_id("in_the_car").is_ok());
+        assert_eq!(CategoryId::from_id(""), Err(IdError::InvalidId));
+        assert_eq!(CategoryId::from_id("pop/rock"), Err(IdError::InvalidId));
+
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(serde_json::from_str::<CategoryId>(&json).unwrap(), id);
+        assert!(serde_json::from_str::<CategoryId>(r#""a?b""#).is_err());
+    }
+
+    #[test]
+    fn test_id_collections() {
+        let a = TrackId::from_id("4iV5W9uYEdYUVa79Axb7Rh").unwrap();
+        let b = TrackId::from_id("1301WleyT98MSxVHPZCA6M").unwrap();
+        let ids = vec![a.clone(), b.clone(), a.clone(), b.clone()];
+        assert_eq!(dedup_ids(ids.clone()), [a.clone(), b.clone()]);
+        assert_eq!(dedup_ids(ids.iter().map(TrackId::as_ref)).len(), 2);
+
+        // Sorted by their inner ID, and by their type first when mixed
+        let set = ids.into_iter().collect::<std::collections::BTreeSet<_>>();
+        assert_eq!(set.into_iter().collect::<Vec<_>>(), [b.clone(), a.clone()]);
+        let episode = EpisodeId::from_id("0lbiy3LKzIY2fnyjioC11p").unwrap();
+        let mut playable = vec![
+            PlayableId::Episode(episode.clone()),
+            PlayableId::Track(a.clone()),
+        ];
+        playable.sort();
+        assert_eq!(
+            playable,
+            [PlayableId::Track(a), PlayableId::Episode(episode)]
+        );
+    }
 }
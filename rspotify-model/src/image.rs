@@ -3,7 +3,7 @@
 use serde::{Deserialize, Serialize};
 
 /// Image object
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
 pub struct Image {
     pub height: Option<u32>,
     pub url: String,
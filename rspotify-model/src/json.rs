@@ -0,0 +1,140 @@
+//! Conversion of the models from and to JSON, for applications that persist
+//! them on their own.
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    Actions, AudioAnalysis, AudioAnalysisMeta, AudioAnalysisSection, AudioAnalysisSegment,
+    AudioAnalysisTrack, AudioFeatures, Author, Category, Confidence, Context, Copyright,
+    CurrentPlaybackContext, CurrentUserQueue, CurrentlyPlayingContext, CursorBasedPage, Device,
+    ExplicitContentSettings, FeaturedPlaylists, Followers, FullAlbum, FullArtist, FullAudiobook,
+    FullChapter, FullEpisode, FullPlaylist, FullShow, FullTrack, Image, LocalTrack, ModelResult,
+    Narrator, Page, PartialPlaylistItem, PlayHistory, PlayableItem, PlaylistItem,
+    PlaylistSnapshotId, PlaylistTracksRef, PrivateUser, PublicUser, Recommendations,
+    RecommendationsSeed, Restriction, ResumePoint, SavedAlbum, SavedEpisode, SavedTrack,
+    SearchAlbums, SearchArtists, SearchEpisodes, SearchMultipleResult, SearchPlaylists,
+    SearchResult, SearchShows, SearchTracks, Show, SimplifiedAlbum, SimplifiedArtist,
+    SimplifiedAudiobook, SimplifiedChapter, SimplifiedEpisode, SimplifiedPlaylist, SimplifiedShow,
+    SimplifiedTrack, TimeInterval, Token, TrackLink,
+};
+
+/// Serializes and deserializes a model as JSON. It's implemented for every
+/// model, as well as the lists and pages of them. Other types, like the ones
+/// of an application that embed the models, may implement it as well.
+///
+/// The JSON produced by [`to_json_string`](Self::to_json_string) is the same
+/// format Spotify sends, so it always deserializes back into the same value
+/// with [`from_json_str`](Self::from_json_str). This includes the fields that
+/// are converted when deserialized, like durations or the mode of a track.
+///
+/// ```
+/// use rspotify_model::{JsonModel, Modality, AudioFeatures};
+/// # let json = r#"{"acousticness":0.00242,"analysis_url":"https://api.spotify.com/v1/audio-analysis/2takcwOaAZWiXQijPHIx7B","danceability":0.585,"duration_ms":237040,"energy":0.842,"id":"2takcwOaAZWiXQijPHIx7B","instrumentalness":0.00686,"key":9,"liveness":0.0866,"loudness":-5.883,"mode":0,"speechiness":0.0556,"tempo":118.211,"time_signature":4,"track_href":"https://api.spotify.com/v1/tracks/2takcwOaAZWiXQijPHIx7B","valence":0.428}"#;
+///
+/// let features = AudioFeatures::from_json_str(json).unwrap();
+/// assert_eq!(features.mode, Modality::Minor);
+///
+/// let stored = features.to_json_string().unwrap();
+/// assert_eq!(AudioFeatures::from_json_str(&stored).unwrap(), features);
+/// ```
+pub trait JsonModel: Serialize + DeserializeOwned {
+    /// Serializes the model into a JSON string.
+    fn to_json_string(&self) -> ModelResult<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Deserializes the model from a JSON string.
+    fn from_json_str(json: &str) -> ModelResult<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+impl<T: JsonModel> JsonModel for Vec<T> {}
+impl<T: JsonModel> JsonModel for Page<T> {}
+impl<T: JsonModel> JsonModel for CursorBasedPage<T> {}
+
+macro_rules! impl_json_model {
+    ($($model:ty),+ $(,)?) => {
+        $(impl JsonModel for $model {})+
+    };
+}
+
+impl_json_model!(
+    // Albums and artists
+    SimplifiedAlbum,
+    FullAlbum,
+    SavedAlbum,
+    Restriction,
+    SimplifiedArtist,
+    FullArtist,
+    // Audio analysis and features
+    AudioFeatures,
+    AudioAnalysis,
+    AudioAnalysisMeta,
+    AudioAnalysisSection,
+    AudioAnalysisSegment,
+    AudioAnalysisTrack,
+    Confidence,
+    TimeInterval,
+    // Audiobooks
+    Author,
+    Narrator,
+    SimplifiedAudiobook,
+    FullAudiobook,
+    SimplifiedChapter,
+    FullChapter,
+    // Authentication
+    Token,
+    // Browse
+    Category,
+    FeaturedPlaylists,
+    Recommendations,
+    RecommendationsSeed,
+    // Player
+    Actions,
+    Context,
+    CurrentlyPlayingContext,
+    CurrentPlaybackContext,
+    CurrentUserQueue,
+    Device,
+    PlayHistory,
+    // Playlists
+    PlaylistSnapshotId,
+    PlaylistTracksRef,
+    SimplifiedPlaylist,
+    FullPlaylist,
+    PlaylistItem,
+    PartialPlaylistItem,
+    // Search
+    SearchPlaylists,
+    SearchAlbums,
+    SearchArtists,
+    SearchTracks,
+    SearchShows,
+    SearchEpisodes,
+    SearchResult,
+    SearchMultipleResult,
+    // Shows and episodes
+    Copyright,
+    SimplifiedShow,
+    Show,
+    FullShow,
+    SimplifiedEpisode,
+    FullEpisode,
+    SavedEpisode,
+    ResumePoint,
+    // Tracks
+    PlayableItem,
+    FullTrack,
+    LocalTrack,
+    TrackLink,
+    SimplifiedTrack,
+    SavedTrack,
+    // Users
+    PublicUser,
+    PrivateUser,
+    ExplicitContentSettings,
+    // Shared
+    Followers,
+    Image,
+);
@@ -4,7 +4,10 @@
 pub mod album;
 pub mod artist;
 pub mod audio;
+pub mod audiobook;
 pub mod auth;
+#[cfg(feature = "borrowed")]
+pub mod borrowed;
 pub mod category;
 pub mod context;
 pub(crate) mod custom_serde;
@@ -13,6 +16,7 @@ pub mod enums;
 pub mod error;
 pub mod idtypes;
 pub mod image;
+pub mod json;
 pub mod offset;
 pub mod page;
 pub mod playing;
@@ -24,13 +28,20 @@ pub mod track;
 pub mod user;
 
 pub use {
-    album::*, artist::*, audio::*, auth::*, category::*, context::*, device::*, enums::*, error::*,
-    idtypes::*, image::*, offset::*, page::*, playing::*, playlist::*, recommend::*, search::*,
-    show::*, track::*, user::*,
+    album::*, artist::*, audio::*, audiobook::*, auth::*, category::*, context::*, device::*,
+    enums::*, error::*, idtypes::*, image::*, json::*, offset::*, page::*, playing::*, playlist::*,
+    recommend::*, search::*, show::*, track::*, user::*,
 };
 
 use serde::{Deserialize, Serialize};
 
+use std::collections::HashMap;
+
+/// The link to open an object in Spotify's apps, out of its `external_urls`.
+pub(crate) fn spotify_url(external_urls: &HashMap<String, String>) -> Option<&str> {
+    external_urls.get("spotify").map(String::as_str)
+}
+
 /// Followers object
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct Followers {
@@ -39,28 +50,96 @@ pub struct Followers {
     pub total: u32,
 }
 
-/// A full track object or a full episode object
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+/// A full track object, a full episode object, or a local file
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum PlayableItem {
     Track(track::FullTrack),
     Episode(show::FullEpisode),
+    /// Local files may only be found in playlists.
+    LocalTrack(track::LocalTrack),
 }
 
 impl PlayableItem {
-    /// Utility to get the ID from either variant in the enum.
+    /// Utility to get the ID from any variant in the enum.
     ///
-    /// Note that if it's a track and if it's local, it may not have an ID, in
-    /// which case this function will return `None`.
+    /// Note that local tracks don't have an ID, in which case this function
+    /// will return `None`.
     #[must_use]
     pub fn id(&self) -> Option<PlayableId<'_>> {
         match self {
             PlayableItem::Track(t) => t.id.as_ref().map(|t| PlayableId::Track(t.as_ref())),
             PlayableItem::Episode(e) => Some(PlayableId::Episode(e.id.as_ref())),
+            PlayableItem::LocalTrack(_) => None,
+        }
+    }
+
+    /// Utility to get the duration from any variant in the enum.
+    #[must_use]
+    pub fn duration(&self) -> chrono::Duration {
+        match self {
+            PlayableItem::Track(t) => t.duration,
+            PlayableItem::Episode(e) => e.duration,
+            PlayableItem::LocalTrack(t) => t.duration,
+        }
+    }
+
+    /// Whether it's a local file, i.e., [`Self::LocalTrack`].
+    #[must_use]
+    pub fn is_local(&self) -> bool {
+        matches!(self, PlayableItem::LocalTrack(_))
+    }
+}
+
+/// The local files are told apart by their `is_local` field, since otherwise
+/// they'd be valid tracks. The rest are tried as tracks and then as episodes.
+impl<'de> Deserialize<'de> for PlayableItem {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let value = serde_json::Value::deserialize(deserializer)?;
+        if value.get("is_local").and_then(serde_json::Value::as_bool) == Some(true) {
+            return track::LocalTrack::deserialize(value)
+                .map(PlayableItem::LocalTrack)
+                .map_err(D::Error::custom);
+        }
+
+        let track_err = match track::FullTrack::deserialize(&value) {
+            Ok(track) => return Ok(PlayableItem::Track(track)),
+            Err(err) => err,
+        };
+        match show::FullEpisode::deserialize(&value) {
+            Ok(episode) => Ok(PlayableItem::Episode(episode)),
+            // The episodes have a `type`, so the track error is more useful
+            // otherwise
+            Err(err)
+                if value.get("type").and_then(serde_json::Value::as_str) == Some("episode") =>
+            {
+                Err(D::Error::custom(err))
+            }
+            Err(_) => Err(D::Error::custom(track_err)),
         }
     }
 }
 
+/// The kinds of items in the top of the current user, which are either
+/// [`FullArtist`] or [`FullTrack`].
+pub trait TopItem: serde::de::DeserializeOwned + Unpin + 'static {
+    /// The type of item in the endpoint's path, i.e., `me/top/{type}`.
+    const TYPE: &'static str;
+}
+
+impl TopItem for FullArtist {
+    const TYPE: &'static str = "artists";
+}
+
+impl TopItem for FullTrack {
+    const TYPE: &'static str = "tracks";
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -31,4 +31,7 @@ pub struct CursorBasedPage<T> {
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct Cursor {
     pub after: Option<String>,
+    /// Only present in the cursors of the recently played tracks.
+    #[serde(default)]
+    pub before: Option<String>,
 }
@@ -1,18 +1,141 @@
 //! All kinds of playlists objects
 
 use chrono::prelude::*;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
 
 use std::collections::HashMap;
 
-use crate::{Followers, Image, Page, PlayableItem, PlaylistId, PublicUser};
+use crate::{spotify_url, Followers, Id, Image, Page, PlayableItem, PlaylistId, PublicUser};
 
-/// Playlist result object
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
-pub struct PlaylistResult {
+/// The details to change with `OAuthClient::playlist_change_detail`, where
+/// those left as `None` aren't modified:
+///
+/// ```
+/// use rspotify_model::PlaylistDetails;
+///
+/// let details = PlaylistDetails::new()
+///     .name("Road trip")
+///     .description("For the long drives");
+/// assert_eq!(details.public, None);
+/// ```
+///
+/// Like [`ItemPositions`](crate::ItemPositions), this is a short-lived struct
+/// for endpoint parameters, so it borrows its strings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct PlaylistDetails<'a> {
+    pub name: Option<&'a str>,
+    pub public: Option<bool>,
+    /// Only private playlists can be collaborative.
+    pub collaborative: Option<bool>,
+    pub description: Option<&'a str>,
+}
+
+impl<'a> PlaylistDetails<'a> {
+    /// No changes, to be set with the rest of the methods.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Renames the playlist.
+    #[must_use]
+    pub fn name(mut self, name: &'a str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Makes the playlist public or private.
+    #[must_use]
+    pub fn public(mut self, public: bool) -> Self {
+        self.public = Some(public);
+        self
+    }
+
+    /// Makes the playlist collaborative or not.
+    #[must_use]
+    pub fn collaborative(mut self, collaborative: bool) -> Self {
+        self.collaborative = Some(collaborative);
+        self
+    }
+
+    /// Changes the description of the playlist.
+    #[must_use]
+    pub fn description(mut self, description: &'a str) -> Self {
+        self.description = Some(description);
+        self
+    }
+}
+
+/// The version of a playlist, which changes every time it's modified. It's
+/// returned by the endpoints that modify a playlist, and may be passed to the
+/// endpoints that remove or reorder items, so that they're applied to that
+/// exact version.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+pub struct PlaylistSnapshotId {
     pub snapshot_id: String,
 }
 
+/// The previous name of [`PlaylistSnapshotId`], which was only returned by
+/// some of the endpoints that modify a playlist.
+#[deprecated(note = "use `PlaylistSnapshotId` instead")]
+pub type PlaylistResult = PlaylistSnapshotId;
+
+impl PlaylistSnapshotId {
+    #[must_use]
+    pub fn new(snapshot_id: impl Into<String>) -> Self {
+        Self {
+            snapshot_id: snapshot_id.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.snapshot_id
+    }
+
+    #[must_use]
+    pub fn into_string(self) -> String {
+        self.snapshot_id
+    }
+}
+
+impl std::fmt::Display for PlaylistSnapshotId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.snapshot_id)
+    }
+}
+
+impl AsRef<str> for PlaylistSnapshotId {
+    fn as_ref(&self) -> &str {
+        &self.snapshot_id
+    }
+}
+
+impl PartialEq<str> for PlaylistSnapshotId {
+    fn eq(&self, other: &str) -> bool {
+        self.snapshot_id == other
+    }
+}
+
+impl PartialEq<&str> for PlaylistSnapshotId {
+    fn eq(&self, other: &&str) -> bool {
+        self.snapshot_id == *other
+    }
+}
+
+impl From<String> for PlaylistSnapshotId {
+    fn from(snapshot_id: String) -> Self {
+        Self { snapshot_id }
+    }
+}
+
+impl From<PlaylistSnapshotId> for String {
+    fn from(snapshot_id: PlaylistSnapshotId) -> Self {
+        snapshot_id.snapshot_id
+    }
+}
+
 /// Playlist Track Reference Object
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct PlaylistTracksRef {
@@ -44,6 +167,20 @@ pub struct SimplifiedPlaylist {
     pub tracks: PlaylistTracksRef,
 }
 
+impl SimplifiedPlaylist {
+    /// Same as [`FullPlaylist::external_url`].
+    #[must_use]
+    pub fn external_url(&self) -> Option<&str> {
+        spotify_url(&self.external_urls)
+    }
+
+    /// Same as [`FullPlaylist::open_url`].
+    #[must_use]
+    pub fn open_url(&self) -> String {
+        self.id.url()
+    }
+}
+
 /// Full playlist object
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct FullPlaylist {
@@ -61,6 +198,20 @@ pub struct FullPlaylist {
     pub tracks: Page<PlaylistItem>,
 }
 
+impl FullPlaylist {
+    /// The link to the playlist in Spotify's apps, as given by Spotify.
+    #[must_use]
+    pub fn external_url(&self) -> Option<&str> {
+        spotify_url(&self.external_urls)
+    }
+
+    /// The link to the playlist in Spotify's apps, built from its ID.
+    #[must_use]
+    pub fn open_url(&self) -> String {
+        self.id.url()
+    }
+}
+
 /// Playlist track object
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct PlaylistItem {
@@ -70,6 +221,54 @@ pub struct PlaylistItem {
     pub track: Option<PlayableItem>,
 }
 
+/// A playlist item requested with a `fields` filter, e.g., with
+/// `playlist_items_with_fields`. Any of the fields of [`PlaylistItem`] may be
+/// missing, including the nested ones, so the JSON is kept as is. Single
+/// fields can be read with [`Self::get`], and custom projections with only the
+/// requested fields can be deserialized with [`Self::project`].
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
+#[serde(transparent)]
+pub struct PartialPlaylistItem(pub Value);
+
+impl PartialPlaylistItem {
+    /// Returns the field at the given path, with the names separated by dots
+    /// like in the `fields` filter, e.g., `"track.album.name"`. Array
+    /// elements are accessed by their index, e.g., `"track.artists.0.name"`.
+    #[must_use]
+    pub fn get(&self, path: &str) -> Option<&Value> {
+        path.split('.').try_fold(&self.0, |value, key| match value {
+            Value::Object(map) => map.get(key),
+            Value::Array(values) => values.get(key.parse::<usize>().ok()?),
+            _ => None,
+        })
+    }
+
+    /// Same as [`Self::get`], for fields that are strings.
+    #[must_use]
+    pub fn get_str(&self, path: &str) -> Option<&str> {
+        self.get(path).and_then(Value::as_str)
+    }
+
+    /// The date and time the item was added, if requested.
+    #[must_use]
+    pub fn added_at(&self) -> Option<DateTime<Utc>> {
+        self.get_str("added_at")?.parse().ok()
+    }
+
+    /// Deserializes the item into a custom type that only includes the
+    /// requested fields.
+    pub fn project<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        T::deserialize(&self.0)
+    }
+
+    /// Deserializes the item into a [`PlaylistItem`], which only works if all
+    /// of its fields were requested.
+    #[must_use]
+    pub fn to_full(&self) -> Option<PlaylistItem> {
+        self.project().ok()
+    }
+}
+
 /// Featured playlists object
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct FeaturedPlaylists {
@@ -3,8 +3,8 @@
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    FullArtist, FullTrack, Page, SimplifiedAlbum, SimplifiedEpisode, SimplifiedPlaylist,
-    SimplifiedShow,
+    FullArtist, FullTrack, Page, SearchType, SimplifiedAlbum, SimplifiedEpisode,
+    SimplifiedPlaylist, SimplifiedShow,
 };
 
 /// Search for playlists
@@ -61,7 +61,78 @@ pub enum SearchResult {
     Episodes(Page<SimplifiedEpisode>),
 }
 
-/// Search result of any multiple kinds
+impl SearchResult {
+    /// The type of the results, i.e., the one that was searched for.
+    #[must_use]
+    pub fn search_type(&self) -> SearchType {
+        match self {
+            Self::Playlists(_) => SearchType::Playlist,
+            Self::Albums(_) => SearchType::Album,
+            Self::Artists(_) => SearchType::Artist,
+            Self::Tracks(_) => SearchType::Track,
+            Self::Shows(_) => SearchType::Show,
+            Self::Episodes(_) => SearchType::Episode,
+        }
+    }
+
+    /// The playlists found, if they were searched for.
+    #[must_use]
+    pub fn playlists(&self) -> Option<&Page<SimplifiedPlaylist>> {
+        match self {
+            Self::Playlists(page) => Some(page),
+            _ => None,
+        }
+    }
+
+    /// The albums found, if they were searched for.
+    #[must_use]
+    pub fn albums(&self) -> Option<&Page<SimplifiedAlbum>> {
+        match self {
+            Self::Albums(page) => Some(page),
+            _ => None,
+        }
+    }
+
+    /// The artists found, if they were searched for.
+    #[must_use]
+    pub fn artists(&self) -> Option<&Page<FullArtist>> {
+        match self {
+            Self::Artists(page) => Some(page),
+            _ => None,
+        }
+    }
+
+    /// The tracks found, if they were searched for.
+    #[must_use]
+    pub fn tracks(&self) -> Option<&Page<FullTrack>> {
+        match self {
+            Self::Tracks(page) => Some(page),
+            _ => None,
+        }
+    }
+
+    /// The shows found, if they were searched for.
+    #[must_use]
+    pub fn shows(&self) -> Option<&Page<SimplifiedShow>> {
+        match self {
+            Self::Shows(page) => Some(page),
+            _ => None,
+        }
+    }
+
+    /// The episodes found, if they were searched for.
+    #[must_use]
+    pub fn episodes(&self) -> Option<&Page<SimplifiedEpisode>> {
+        match self {
+            Self::Episodes(page) => Some(page),
+            _ => None,
+        }
+    }
+}
+
+/// Search result of any multiple kinds, where only the types that were
+/// searched for are present. See
+/// [`search_multiple`](https://docs.rs/rspotify/latest/rspotify/clients/trait.BaseClient.html#method.search_multiple).
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct SearchMultipleResult {
     pub playlists: Option<Page<SimplifiedPlaylist>>,
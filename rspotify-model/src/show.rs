@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use chrono::Duration;
+use chrono::{DateTime, Duration, Utc};
 use std::collections::HashMap;
 
 use crate::{
@@ -116,6 +116,13 @@ pub struct FullEpisode {
     pub show: SimplifiedShow,
 }
 
+/// Saved episode object
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SavedEpisode {
+    pub added_at: DateTime<Utc>,
+    pub episode: FullEpisode,
+}
+
 /// Intermediate episodes feature object wrapped by `Vec`
 #[derive(Deserialize)]
 pub struct EpisodesPayload {
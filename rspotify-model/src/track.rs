@@ -7,8 +7,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::{
-    custom_serde::duration_ms, PlayableId, Restriction, SimplifiedAlbum, SimplifiedArtist, TrackId,
-    Type,
+    custom_serde::duration_ms, spotify_url, Id, PlayableId, Restriction, SimplifiedAlbum,
+    SimplifiedArtist, TrackId, Type,
 };
 
 /// Full track object
@@ -40,6 +40,179 @@ pub struct FullTrack {
     pub track_number: u32,
 }
 
+impl FullTrack {
+    /// The ID of the track that was requested, if Spotify relinked it to this
+    /// one because the original isn't available in the given market. The
+    /// latter is only known when a `market` is passed to the endpoint.
+    #[must_use]
+    pub fn relinked_id(&self) -> Option<TrackId<'_>> {
+        relinked_id(self.linked_from.as_ref())
+    }
+
+    /// The ID this track was requested with, i.e., [`Self::relinked_id`] if
+    /// it was relinked, or its own ID otherwise. Useful to match the tracks
+    /// in a response with the requested IDs.
+    #[must_use]
+    pub fn requested_id(&self) -> Option<TrackId<'_>> {
+        self.relinked_id()
+            .or_else(|| self.id.as_ref().map(|id| id.as_ref()))
+    }
+
+    /// The link to the track in Spotify's apps, as given by Spotify.
+    #[must_use]
+    pub fn external_url(&self) -> Option<&str> {
+        spotify_url(&self.external_urls)
+    }
+
+    /// The link to the track in Spotify's apps, built from its ID, so that
+    /// it's available even if `external_urls` was left out, e.g. by a cache.
+    /// Local tracks have neither.
+    #[must_use]
+    pub fn open_url(&self) -> Option<String> {
+        self.id.as_ref().map(Id::url)
+    }
+}
+
+/// A local file in a playlist, i.e., a track that the owner of the playlist
+/// added from their device rather than from Spotify's catalog. It has neither
+/// an ID nor most of the metadata of [`FullTrack`].
+///
+/// It's serialized in the same format Spotify uses, minus the empty fields,
+/// so that it can be deserialized back.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(from = "RawLocalTrack", into = "RawLocalTrack")]
+pub struct LocalTrack {
+    pub name: String,
+    /// The names of the artists.
+    pub artists: Vec<String>,
+    /// The name of the album, if it's known.
+    pub album: Option<String>,
+    pub duration: Duration,
+    /// The local URI, e.g.,
+    /// `spotify:local:Daft+Punk:Discovery:One+More+Time:320`.
+    pub uri: String,
+    /// Always `true`, kept for consistency with the rest of the tracks.
+    pub is_local: bool,
+}
+
+impl LocalTrack {
+    /// Fills the metadata missing in the tags of the file with the one in its
+    /// URI, which has the format `spotify:local:{artist}:{album}:{title}:{seconds}`.
+    fn with_uri_metadata(mut self) -> Self {
+        let parts = match self.uri.strip_prefix("spotify:local:") {
+            Some(rest) => rest.split(':').map(decode_uri_part).collect::<Vec<_>>(),
+            None => return self,
+        };
+        let part = |i: usize| parts.get(i).filter(|part| !part.is_empty()).cloned();
+
+        if self.artists.is_empty() {
+            self.artists.extend(part(0));
+        }
+        if self.album.is_none() {
+            self.album = part(1);
+        }
+        if self.name.is_empty() {
+            self.name = part(2).unwrap_or_default();
+        }
+        if self.duration.is_zero() {
+            let seconds = part(3).and_then(|seconds| seconds.parse().ok());
+            if let Some(duration) = seconds.and_then(Duration::try_seconds) {
+                self.duration = duration;
+            }
+        }
+        self
+    }
+}
+
+/// Decodes a part of a local URI, which has `+` instead of spaces and is
+/// percent-encoded otherwise.
+fn decode_uri_part(part: &str) -> String {
+    let mut bytes = Vec::with_capacity(part.len());
+    let mut rest = part.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex = rest
+                    .get(..2)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                match hex {
+                    Some(decoded) => {
+                        bytes.push(decoded);
+                        rest = &rest[2..];
+                    }
+                    None => bytes.push(byte),
+                }
+            }
+            byte => bytes.push(byte),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// An object of which only the name is known, like the albums and artists of
+/// [`LocalTrack`].
+#[derive(Serialize, Deserialize)]
+struct LocalObject {
+    #[serde(default)]
+    name: Option<String>,
+}
+
+/// The format of [`LocalTrack`] in the Web API. Spotify sends most of the
+/// fields of [`FullTrack`] too, but they're always empty, so they're ignored.
+#[derive(Serialize, Deserialize)]
+struct RawLocalTrack {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    artists: Vec<LocalObject>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    album: Option<LocalObject>,
+    #[serde(with = "duration_ms", rename = "duration_ms")]
+    duration: Duration,
+    #[serde(default)]
+    uri: Option<String>,
+    is_local: bool,
+}
+
+impl From<RawLocalTrack> for LocalTrack {
+    fn from(raw: RawLocalTrack) -> Self {
+        Self {
+            name: raw.name.unwrap_or_default(),
+            artists: raw
+                .artists
+                .into_iter()
+                .filter_map(|artist| artist.name)
+                .filter(|name| !name.is_empty())
+                .collect(),
+            album: raw
+                .album
+                .and_then(|album| album.name)
+                .filter(|name| !name.is_empty()),
+            duration: raw.duration,
+            uri: raw.uri.unwrap_or_default(),
+            is_local: true,
+        }
+        .with_uri_metadata()
+    }
+}
+
+impl From<LocalTrack> for RawLocalTrack {
+    fn from(track: LocalTrack) -> Self {
+        let object = |name| LocalObject { name: Some(name) };
+        Self {
+            name: Some(track.name),
+            artists: track.artists.into_iter().map(object).collect(),
+            album: track.album.map(object),
+            duration: track.duration,
+            uri: Some(track.uri),
+            is_local: true,
+        }
+    }
+}
+
 /// Track link object
 /// [track-relinking](https://developer.spotify.com/documentation/web-api/concepts/track-relinking)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -84,6 +257,37 @@ pub struct SimplifiedTrack {
     pub track_number: u32,
 }
 
+impl SimplifiedTrack {
+    /// Same as [`FullTrack::relinked_id`].
+    #[must_use]
+    pub fn relinked_id(&self) -> Option<TrackId<'_>> {
+        relinked_id(self.linked_from.as_ref())
+    }
+
+    /// Same as [`FullTrack::requested_id`].
+    #[must_use]
+    pub fn requested_id(&self) -> Option<TrackId<'_>> {
+        self.relinked_id()
+            .or_else(|| self.id.as_ref().map(|id| id.as_ref()))
+    }
+
+    /// Same as [`FullTrack::external_url`].
+    #[must_use]
+    pub fn external_url(&self) -> Option<&str> {
+        spotify_url(&self.external_urls)
+    }
+
+    /// Same as [`FullTrack::open_url`].
+    #[must_use]
+    pub fn open_url(&self) -> Option<String> {
+        self.id.as_ref().map(Id::url)
+    }
+}
+
+fn relinked_id(linked_from: Option<&TrackLink>) -> Option<TrackId<'_>> {
+    linked_from?.id.as_ref().map(|id| id.as_ref())
+}
+
 /// Saved track object
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct SavedTrack {
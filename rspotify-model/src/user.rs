@@ -2,7 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use std::collections::HashMap;
+use std::{cmp::Reverse, collections::HashMap};
 
 use crate::{Country, Followers, Image, SubscriptionLevel, UserId};
 
@@ -19,23 +19,72 @@ pub struct PublicUser {
 }
 
 /// Private user object
+///
+/// The managed and kids accounts may lack some of the fields, which are then
+/// `None` or empty.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct PrivateUser {
     pub country: Option<Country>,
     pub display_name: Option<String>,
     pub email: Option<String>,
+    #[serde(default)]
     pub external_urls: HashMap<String, String>,
-    pub explicit_content: Option<ExplicitContent>,
+    pub explicit_content: Option<ExplicitContentSettings>,
     pub followers: Option<Followers>,
+    #[serde(default)]
     pub href: String,
     pub id: UserId<'static>,
     pub images: Option<Vec<Image>>,
     pub product: Option<SubscriptionLevel>,
 }
 
-/// Explicit content setting object
+impl PublicUser {
+    /// The largest of the profile images, if any.
+    #[must_use]
+    pub fn profile_image(&self) -> Option<&Image> {
+        largest_image(&self.images)
+    }
+}
+
+impl PrivateUser {
+    /// The largest of the profile images, if any.
+    #[must_use]
+    pub fn profile_image(&self) -> Option<&Image> {
+        largest_image(self.images.as_deref().unwrap_or_default())
+    }
+
+    /// Whether explicit content is filtered out for this user, which is
+    /// `false` if the settings are missing, i.e., without the
+    /// `user-read-private` scope.
+    #[must_use]
+    pub fn filters_explicit_content(&self) -> bool {
+        matches!(&self.explicit_content, Some(settings) if settings.filter_enabled)
+    }
+}
+
+/// The image with the largest area, or the first one if their sizes are
+/// unknown.
+fn largest_image(images: &[Image]) -> Option<&Image> {
+    images.iter().min_by_key(|image| {
+        let area = u64::from(image.width.unwrap_or(0)) * u64::from(image.height.unwrap_or(0));
+        Reverse(area)
+    })
+}
+
+/// The explicit content settings of a user, only available with the
+/// `user-read-private` scope. The fields missing in the response, as with
+/// some account types, are `false`.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
-pub struct ExplicitContent {
+pub struct ExplicitContentSettings {
+    /// Whether explicit content is filtered out.
+    #[serde(default)]
     pub filter_enabled: bool,
+    /// Whether the setting is locked, so the user can't change it, e.g.,
+    /// because the account is managed by a parent.
+    #[serde(default)]
     pub filter_locked: bool,
 }
+
+/// The previous name of [`ExplicitContentSettings`].
+#[deprecated(since = "0.14.0", note = "renamed to `ExplicitContentSettings`")]
+pub type ExplicitContent = ExplicitContentSettings;
@@ -0,0 +1,228 @@
+//! Enums over the clients, to accept any of them as a single concrete type.
+//!
+//! [`BaseClient`] and [`OAuthClient`] aren't object safe, so libraries built
+//! on top of RSpotify can't take a `dyn BaseClient`. They may either be
+//! generic over the client, or take an [`AnyClient`], which implements
+//! [`BaseClient`] by delegating to the client of whichever flow it holds:
+//!
+//! ```
+//! use rspotify::{any_client::AnyClient, prelude::*, ClientCredsSpotify, Credentials};
+//!
+//! fn refreshes_token(spotify: &AnyClient) -> bool {
+//!     spotify.get_config().token_refreshing
+//! }
+//!
+//! let spotify = ClientCredsSpotify::new(Credentials::default());
+//! assert!(refreshes_token(&spotify.into()));
+//! ```
+//!
+//! The endpoints that require user authorization are only available with
+//! [`AnyOAuthClient`], which implements [`OAuthClient`] as well and excludes
+//! the client credentials flow. It's converted from an [`AnyClient`] with
+//! [`TryFrom`].
+
+use crate::{
+    clients::{BaseClient, CurrentUserCache, OAuthClient, SelectedRedirectUri},
+    http::HttpClient,
+    sync::Mutex,
+    AuthCodePkceSpotify, AuthCodeSpotify, ClientCredsSpotify, ClientResult, Config, Credentials,
+    OAuth, Token,
+};
+
+use std::{convert::TryFrom, sync::Arc};
+
+use maybe_async::maybe_async;
+
+/// The client of any of the authorization flows. See the [module-level
+/// documentation](self) for more information.
+#[derive(Clone, Debug)]
+pub enum AnyClient {
+    ClientCreds(ClientCredsSpotify),
+    AuthCode(AuthCodeSpotify),
+    AuthCodePkce(AuthCodePkceSpotify),
+}
+
+/// The client of any of the authorization flows with user authorization,
+/// i.e., all but the client credentials flow. See the [module-level
+/// documentation](self) for more information.
+#[derive(Clone, Debug)]
+pub enum AnyOAuthClient {
+    AuthCode(AuthCodeSpotify),
+    AuthCodePkce(AuthCodePkceSpotify),
+}
+
+/// Evaluates the expression with the client held by the enum. The async
+/// methods are matched by hand instead, since the attribute macros of
+/// `maybe_async` don't reach into macro invocations.
+macro_rules! delegate {
+    ($self:expr, $client:ident => $expr:expr) => {
+        match $self {
+            Self::ClientCreds($client) => $expr,
+            Self::AuthCode($client) => $expr,
+            Self::AuthCodePkce($client) => $expr,
+        }
+    };
+}
+
+/// Same as `delegate!`, for the clients with user authorization.
+macro_rules! delegate_oauth {
+    ($self:expr, $client:ident => $expr:expr) => {
+        match $self {
+            Self::AuthCode($client) => $expr,
+            Self::AuthCodePkce($client) => $expr,
+        }
+    };
+}
+
+/// The client credentials flow, like its default client.
+impl Default for AnyClient {
+    fn default() -> Self {
+        Self::ClientCreds(ClientCredsSpotify::default())
+    }
+}
+
+/// The authorization code flow, like its default client.
+impl Default for AnyOAuthClient {
+    fn default() -> Self {
+        Self::AuthCode(AuthCodeSpotify::default())
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", maybe_async(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), maybe_async)]
+impl BaseClient for AnyClient {
+    fn get_http(&self) -> &HttpClient {
+        delegate!(self, client => client.get_http())
+    }
+
+    fn get_token(&self) -> Arc<Mutex<Option<Token>>> {
+        delegate!(self, client => client.get_token())
+    }
+
+    fn get_creds(&self) -> &Credentials {
+        delegate!(self, client => client.get_creds())
+    }
+
+    fn get_config(&self) -> &Config {
+        delegate!(self, client => client.get_config())
+    }
+
+    fn can_request_token(&self) -> bool {
+        delegate!(self, client => client.can_request_token())
+    }
+
+    async fn refetch_token(&self) -> ClientResult<Option<Token>> {
+        match self {
+            Self::ClientCreds(client) => client.refetch_token().await,
+            Self::AuthCode(client) => client.refetch_token().await,
+            Self::AuthCodePkce(client) => client.refetch_token().await,
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", maybe_async(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), maybe_async)]
+impl BaseClient for AnyOAuthClient {
+    fn get_http(&self) -> &HttpClient {
+        delegate_oauth!(self, client => client.get_http())
+    }
+
+    fn get_token(&self) -> Arc<Mutex<Option<Token>>> {
+        delegate_oauth!(self, client => client.get_token())
+    }
+
+    fn get_creds(&self) -> &Credentials {
+        delegate_oauth!(self, client => client.get_creds())
+    }
+
+    fn get_config(&self) -> &Config {
+        delegate_oauth!(self, client => client.get_config())
+    }
+
+    async fn refetch_token(&self) -> ClientResult<Option<Token>> {
+        match self {
+            Self::AuthCode(client) => client.refetch_token().await,
+            Self::AuthCodePkce(client) => client.refetch_token().await,
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", maybe_async(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), maybe_async)]
+impl OAuthClient for AnyOAuthClient {
+    fn get_oauth(&self) -> &OAuth {
+        delegate_oauth!(self, client => client.get_oauth())
+    }
+
+    fn get_current_user_cache(&self) -> Option<&CurrentUserCache> {
+        delegate_oauth!(self, client => client.get_current_user_cache())
+    }
+
+    fn get_selected_redirect_uri(&self) -> Option<&SelectedRedirectUri> {
+        delegate_oauth!(self, client => client.get_selected_redirect_uri())
+    }
+
+    fn get_pkce_verifier_mut(&mut self) -> Option<&mut Option<String>> {
+        delegate_oauth!(self, client => client.get_pkce_verifier_mut())
+    }
+
+    async fn request_token(&self, code: &str) -> ClientResult<()> {
+        match self {
+            Self::AuthCode(client) => client.request_token(code).await,
+            Self::AuthCodePkce(client) => client.request_token(code).await,
+        }
+    }
+}
+
+impl From<ClientCredsSpotify> for AnyClient {
+    fn from(client: ClientCredsSpotify) -> Self {
+        Self::ClientCreds(client)
+    }
+}
+
+impl From<AuthCodeSpotify> for AnyClient {
+    fn from(client: AuthCodeSpotify) -> Self {
+        Self::AuthCode(client)
+    }
+}
+
+impl From<AuthCodePkceSpotify> for AnyClient {
+    fn from(client: AuthCodePkceSpotify) -> Self {
+        Self::AuthCodePkce(client)
+    }
+}
+
+impl From<AnyOAuthClient> for AnyClient {
+    fn from(client: AnyOAuthClient) -> Self {
+        match client {
+            AnyOAuthClient::AuthCode(client) => Self::AuthCode(client),
+            AnyOAuthClient::AuthCodePkce(client) => Self::AuthCodePkce(client),
+        }
+    }
+}
+
+impl From<AuthCodeSpotify> for AnyOAuthClient {
+    fn from(client: AuthCodeSpotify) -> Self {
+        Self::AuthCode(client)
+    }
+}
+
+impl From<AuthCodePkceSpotify> for AnyOAuthClient {
+    fn from(client: AuthCodePkceSpotify) -> Self {
+        Self::AuthCodePkce(client)
+    }
+}
+
+/// Fails with the client back if it's of the client credentials flow, which
+/// has no user authorization.
+impl TryFrom<AnyClient> for AnyOAuthClient {
+    type Error = ClientCredsSpotify;
+
+    fn try_from(client: AnyClient) -> Result<Self, Self::Error> {
+        match client {
+            AnyClient::ClientCreds(client) => Err(client),
+            AnyClient::AuthCode(client) => Ok(Self::AuthCode(client)),
+            AnyClient::AuthCodePkce(client) => Ok(Self::AuthCodePkce(client)),
+        }
+    }
+}
@@ -1,17 +1,15 @@
 use crate::{
-    auth_urls,
-    clients::{BaseClient, OAuthClient},
+    auth_urls, build_authorize_url,
+    clients::{BaseClient, CurrentUserCache, OAuthClient, SelectedRedirectUri},
     http::{Form, HttpClient},
     join_scopes, params,
     sync::Mutex,
     ClientError, ClientResult, Config, Credentials, OAuth, Token,
 };
 
-use std::collections::HashMap;
 use std::sync::Arc;
 
 use maybe_async::maybe_async;
-use url::Url;
 
 /// The [Authorization Code Flow][reference] client for the Spotify API.
 ///
@@ -69,6 +67,8 @@ pub struct AuthCodeSpotify {
     pub config: Config,
     pub token: Arc<Mutex<Option<Token>>>,
     pub(crate) http: HttpClient,
+    pub(crate) current_user: CurrentUserCache,
+    pub(crate) selected_redirect_uri: SelectedRedirectUri,
 }
 
 /// This client has access to the base methods.
@@ -134,16 +134,25 @@ impl OAuthClient for AuthCodeSpotify {
         &self.oauth
     }
 
+    fn get_current_user_cache(&self) -> Option<&CurrentUserCache> {
+        Some(&self.current_user)
+    }
+
+    fn get_selected_redirect_uri(&self) -> Option<&SelectedRedirectUri> {
+        Some(&self.selected_redirect_uri)
+    }
+
     /// Obtains a user access token given a code, as part of the OAuth
     /// authentication. The access token will be saved internally.
     async fn request_token(&self, code: &str) -> ClientResult<()> {
         log::info!("Requesting Auth Code token");
 
         let scopes = join_scopes(&self.oauth.scopes);
+        let redirect_uri = self.redirect_uri();
 
         let mut data = Form::new();
         data.insert(params::GRANT_TYPE, params::GRANT_TYPE_AUTH_CODE);
-        data.insert(params::REDIRECT_URI, &self.oauth.redirect_uri);
+        data.insert(params::REDIRECT_URI, &redirect_uri);
         data.insert(params::CODE, code);
         data.insert(params::SCOPE, &scopes);
         data.insert(params::STATE, &self.oauth.state);
@@ -190,18 +199,30 @@ impl AuthCodeSpotify {
 
     /// Same as [`Self::new`] but with an extra parameter to configure the
     /// client.
+    ///
+    /// # Panics
+    ///
+    /// If the HTTP client can't be built from the configuration, e.g.,
+    /// because of an invalid proxy. [`Self::try_with_config`] returns an
+    /// error instead.
     #[must_use]
     pub fn with_config(creds: Credentials, oauth: OAuth, config: Config) -> Self {
-        Self {
-            creds,
-            oauth,
-            config,
-            ..Default::default()
-        }
+        Self::try_with_config(creds, oauth, config).expect("Invalid HTTP client configuration")
+    }
+
+    /// Same as [`Self::with_config`], but returns an error if the HTTP client
+    /// can't be built from the configuration, e.g., because of an invalid
+    /// proxy or client certificate.
+    pub fn try_with_config(creds: Credentials, oauth: OAuth, config: Config) -> ClientResult<Self> {
+        Self::try_build(None, creds, oauth, config)
     }
 
     /// Build a new [`AuthCodeSpotify`] from an already generated token and
     /// config. Use this to be able to refresh a token.
+    ///
+    /// # Panics
+    ///
+    /// Same as [`Self::with_config`].
     #[must_use]
     pub fn from_token_with_config(
         token: Token,
@@ -209,35 +230,66 @@ impl AuthCodeSpotify {
         oauth: OAuth,
         config: Config,
     ) -> Self {
-        Self {
-            token: Arc::new(Mutex::new(Some(token))),
+        Self::try_from_token_with_config(token, creds, oauth, config)
+            .expect("Invalid HTTP client configuration")
+    }
+
+    /// Same as [`Self::from_token_with_config`], but returns an error if the
+    /// HTTP client can't be built from the configuration.
+    pub fn try_from_token_with_config(
+        token: Token,
+        creds: Credentials,
+        oauth: OAuth,
+        config: Config,
+    ) -> ClientResult<Self> {
+        Self::try_build(Some(token), creds, oauth, config)
+    }
+
+    /// Builds the client field by field, since [`Self::default`] would build
+    /// an HTTP client and generate an OAuth state only to discard them, and
+    /// panic if the latter fails.
+    fn try_build(
+        token: Option<Token>,
+        creds: Credentials,
+        oauth: OAuth,
+        config: Config,
+    ) -> ClientResult<Self> {
+        Ok(Self {
+            http: config.build_http_client()?,
+            token: Arc::new(Mutex::new(token)),
             creds,
             oauth,
             config,
-            ..Default::default()
-        }
+            current_user: CurrentUserCache::default(),
+            selected_redirect_uri: SelectedRedirectUri::default(),
+        })
     }
 
     /// Returns the URL needed to authorize the current client as the first step
     /// in the authorization flow.
+    ///
+    /// The redirect URI is chosen with [`OAuth::redirect_uri_selector`], if
+    /// set, and kept for the token request.
     pub fn get_authorize_url(&self, show_dialog: bool) -> ClientResult<String> {
         log::info!("Building auth URL");
 
         let scopes = join_scopes(&self.oauth.scopes);
+        let redirect_uri = self.oauth.select_redirect_uri();
+        *self.selected_redirect_uri.lock().unwrap() = Some(redirect_uri.clone());
 
-        let mut payload: HashMap<&str, &str> = HashMap::new();
-        payload.insert(params::CLIENT_ID, &self.creds.id);
-        payload.insert(params::RESPONSE_TYPE, params::RESPONSE_TYPE_CODE);
-        payload.insert(params::REDIRECT_URI, &self.oauth.redirect_uri);
-        payload.insert(params::SCOPE, &scopes);
-        payload.insert(params::STATE, &self.oauth.state);
+        let mut payload = vec![
+            (params::CLIENT_ID, self.creds.id.as_str()),
+            (params::RESPONSE_TYPE, params::RESPONSE_TYPE_CODE),
+            (params::REDIRECT_URI, redirect_uri.as_str()),
+            (params::SCOPE, scopes.as_str()),
+            (params::STATE, self.oauth.state.as_str()),
+        ];
 
         if show_dialog {
-            payload.insert(params::SHOW_DIALOG, "true");
+            payload.push((params::SHOW_DIALOG, "true"));
         }
 
         let request_url = self.auth_url(auth_urls::AUTHORIZE);
-        let parsed = Url::parse_with_params(&request_url, payload)?;
-        Ok(parsed.into())
+        build_authorize_url(&request_url, payload)
     }
 }
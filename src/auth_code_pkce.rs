@@ -1,21 +1,45 @@
 use crate::{
-    alphabets, auth_urls,
-    clients::{BaseClient, OAuthClient},
+    alphabets, auth_urls, build_authorize_url,
+    clients::{BaseClient, CurrentUserCache, OAuthClient, SelectedRedirectUri},
     generate_random_string,
     http::{Form, HttpClient},
     join_scopes, params,
     sync::Mutex,
-    ClientResult, Config, Credentials, OAuth, Token,
+    ClientError, ClientResult, Config, Credentials, OAuth, Token,
 };
 
 use base64::{engine::general_purpose, Engine as _};
 
-use std::collections::HashMap;
 use std::sync::Arc;
 
 use maybe_async::maybe_async;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use url::Url;
+
+/// The secrets generated for an authorization request with PKCE, which are
+/// needed again to request the token.
+///
+/// Web applications may have to handle the callback in a different process
+/// than the one that redirected the user, e.g., after a restart or in another
+/// server instance. This can be serialized and kept meanwhile, e.g., in the
+/// session of the user, and then passed to
+/// [`AuthCodePkceSpotify::request_token_with_verifier`] along with the code and
+/// state of the callback. The [`web`](crate::web) helpers keep it in the
+/// browser instead.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PkceVerifier {
+    /// The code verifier, which must be kept secret.
+    pub verifier: String,
+    /// The code challenge sent in the authorization URL, derived from the
+    /// verifier.
+    pub challenge: String,
+    /// The redirect URI sent in the authorization URL, which must be the
+    /// same in the token request.
+    pub redirect_uri: String,
+    /// The state sent in the authorization URL, which must match the one in
+    /// the callback.
+    pub state: String,
+}
 
 /// The [Authorization Code Flow with Proof Key for Code Exchange
 /// (PKCE)][reference] client for the Spotify API.
@@ -41,6 +65,8 @@ pub struct AuthCodePkceSpotify {
     /// The code verifier for the authentication process
     pub verifier: Option<String>,
     pub(crate) http: HttpClient,
+    pub(crate) current_user: CurrentUserCache,
+    pub(crate) selected_redirect_uri: SelectedRedirectUri,
 }
 
 /// This client has access to the base methods.
@@ -96,34 +122,30 @@ impl OAuthClient for AuthCodePkceSpotify {
         &self.oauth
     }
 
+    fn get_current_user_cache(&self) -> Option<&CurrentUserCache> {
+        Some(&self.current_user)
+    }
+
+    fn get_selected_redirect_uri(&self) -> Option<&SelectedRedirectUri> {
+        Some(&self.selected_redirect_uri)
+    }
+
+    fn get_pkce_verifier_mut(&mut self) -> Option<&mut Option<String>> {
+        Some(&mut self.verifier)
+    }
+
     /// Note that the code verifier must be set at this point, either manually
     /// or with [`Self::get_authorize_url`]. Otherwise, this function will
     /// panic.
     async fn request_token(&self, code: &str) -> ClientResult<()> {
-        log::info!("Requesting PKCE Auth Code token");
-
         let verifier = self.verifier.as_ref().expect(
             "Unknown code verifier. Try calling \
             `AuthCodePkceSpotify::get_authorize_url` first or setting it \
             yourself.",
         );
 
-        let mut data = Form::new();
-        data.insert(params::CLIENT_ID, &self.creds.id);
-        data.insert(params::GRANT_TYPE, params::GRANT_TYPE_AUTH_CODE);
-        data.insert(params::CODE, code);
-        data.insert(params::REDIRECT_URI, &self.oauth.redirect_uri);
-        data.insert(params::CODE_VERIFIER, verifier);
-
-        let token = self.fetch_access_token(&data, None).await?;
-
-        if let Some(callback_fn) = &*self.get_config().token_callback_fn.clone() {
-            callback_fn.0(token.clone())?;
-        }
-
-        *self.token.lock().await.unwrap() = Some(token);
-
-        self.write_token_cache().await
+        let redirect_uri = self.redirect_uri();
+        self.fetch_pkce_token(code, verifier, &redirect_uri).await
     }
 }
 
@@ -152,18 +174,30 @@ impl AuthCodePkceSpotify {
 
     /// Same as [`Self::new`] but with an extra parameter to configure the
     /// client.
+    ///
+    /// # Panics
+    ///
+    /// If the HTTP client can't be built from the configuration, e.g.,
+    /// because of an invalid proxy. [`Self::try_with_config`] returns an
+    /// error instead.
     #[must_use]
     pub fn with_config(creds: Credentials, oauth: OAuth, config: Config) -> Self {
-        Self {
-            creds,
-            oauth,
-            config,
-            ..Default::default()
-        }
+        Self::try_with_config(creds, oauth, config).expect("Invalid HTTP client configuration")
+    }
+
+    /// Same as [`Self::with_config`], but returns an error if the HTTP client
+    /// can't be built from the configuration, e.g., because of an invalid
+    /// proxy or client certificate.
+    pub fn try_with_config(creds: Credentials, oauth: OAuth, config: Config) -> ClientResult<Self> {
+        Self::try_build(None, creds, oauth, config)
     }
 
     /// Build a new [`AuthCodePkceSpotify`] from an already generated token and
     /// config. Use this to be able to refresh a token.
+    ///
+    /// # Panics
+    ///
+    /// Same as [`Self::with_config`].
     #[must_use]
     pub fn from_token_with_config(
         token: Token,
@@ -171,23 +205,50 @@ impl AuthCodePkceSpotify {
         oauth: OAuth,
         config: Config,
     ) -> Self {
-        Self {
-            token: Arc::new(Mutex::new(Some(token))),
+        Self::try_from_token_with_config(token, creds, oauth, config)
+            .expect("Invalid HTTP client configuration")
+    }
+
+    /// Same as [`Self::from_token_with_config`], but returns an error if the
+    /// HTTP client can't be built from the configuration.
+    pub fn try_from_token_with_config(
+        token: Token,
+        creds: Credentials,
+        oauth: OAuth,
+        config: Config,
+    ) -> ClientResult<Self> {
+        Self::try_build(Some(token), creds, oauth, config)
+    }
+
+    /// Builds the client field by field, since [`Self::default`] would build
+    /// an HTTP client and generate an OAuth state only to discard them, and
+    /// panic if the latter fails.
+    fn try_build(
+        token: Option<Token>,
+        creds: Credentials,
+        oauth: OAuth,
+        config: Config,
+    ) -> ClientResult<Self> {
+        Ok(Self {
+            http: config.build_http_client()?,
+            token: Arc::new(Mutex::new(token)),
             creds,
             oauth,
             config,
-            ..Default::default()
-        }
+            verifier: None,
+            current_user: CurrentUserCache::default(),
+            selected_redirect_uri: SelectedRedirectUri::default(),
+        })
     }
 
     /// Generate the verifier code and the challenge code.
-    fn generate_codes(verifier_bytes: usize) -> (String, String) {
+    fn generate_codes(verifier_bytes: usize) -> ClientResult<(String, String)> {
         log::info!("Generating PKCE codes");
 
         debug_assert!(verifier_bytes >= 43);
         debug_assert!(verifier_bytes <= 128);
         // The code verifier is just the randomly generated string.
-        let verifier = generate_random_string(verifier_bytes, alphabets::PKCE_CODE_VERIFIER);
+        let verifier = generate_random_string(verifier_bytes, alphabets::PKCE_CODE_VERIFIER)?;
         // The code challenge is the code verifier hashed with SHA256 and then
         // encoded with base64url.
         //
@@ -200,7 +261,7 @@ impl AuthCodePkceSpotify {
 
         let challenge = general_purpose::URL_SAFE_NO_PAD.encode(challenge);
 
-        (verifier, challenge)
+        Ok((verifier, challenge))
     }
 
     /// Returns the URL needed to authorize the current client as the first step
@@ -212,31 +273,109 @@ impl AuthCodePkceSpotify {
     /// docs][reference] or [PKCE's RFC][rfce] for more information about the
     /// code verifier.
     ///
+    /// The redirect URI is chosen with [`OAuth::redirect_uri_selector`], if
+    /// set, and kept for the token request along with the verifier.
+    ///
     /// [reference]: https://developer.spotify.com/documentation/general/guides/authorization/code-flow
     /// [rfce]: https://datatracker.ietf.org/doc/html/rfc7636#section-4.1
     pub fn get_authorize_url(&mut self, verifier_bytes: Option<usize>) -> ClientResult<String> {
+        let (url, verifier) = self.get_authorize_url_with_verifier(verifier_bytes)?;
+        *self.selected_redirect_uri.lock().unwrap() = Some(verifier.redirect_uri);
+        // The verifier will be needed later when requesting the token
+        self.verifier = Some(verifier.verifier);
+        Ok(url)
+    }
+
+    /// Same as [`Self::get_authorize_url`], but the generated verifier is
+    /// returned instead of being kept in the client, so that the token may be
+    /// requested from a different one with
+    /// [`Self::request_token_with_verifier`].
+    pub fn get_authorize_url_with_verifier(
+        &self,
+        verifier_bytes: Option<usize>,
+    ) -> ClientResult<(String, PkceVerifier)> {
         log::info!("Building auth URL");
 
         let scopes = join_scopes(&self.oauth.scopes);
+        let redirect_uri = self.oauth.select_redirect_uri();
         let verifier_bytes = verifier_bytes.unwrap_or(43);
-        let (verifier, challenge) = Self::generate_codes(verifier_bytes);
-        // The verifier will be needed later when requesting the token
-        self.verifier = Some(verifier);
-
-        let mut payload: HashMap<&str, &str> = HashMap::new();
-        payload.insert(params::CLIENT_ID, &self.creds.id);
-        payload.insert(params::RESPONSE_TYPE, params::RESPONSE_TYPE_CODE);
-        payload.insert(params::REDIRECT_URI, &self.oauth.redirect_uri);
-        payload.insert(
-            params::CODE_CHALLENGE_METHOD,
-            params::CODE_CHALLENGE_METHOD_S256,
-        );
-        payload.insert(params::CODE_CHALLENGE, &challenge);
-        payload.insert(params::STATE, &self.oauth.state);
-        payload.insert(params::SCOPE, &scopes);
+        let (verifier, challenge) = Self::generate_codes(verifier_bytes)?;
+
+        let payload = [
+            (params::CLIENT_ID, self.creds.id.as_str()),
+            (params::RESPONSE_TYPE, params::RESPONSE_TYPE_CODE),
+            (params::REDIRECT_URI, redirect_uri.as_str()),
+            (
+                params::CODE_CHALLENGE_METHOD,
+                params::CODE_CHALLENGE_METHOD_S256,
+            ),
+            (params::CODE_CHALLENGE, challenge.as_str()),
+            (params::STATE, self.oauth.state.as_str()),
+            (params::SCOPE, scopes.as_str()),
+        ];
 
         let request_url = self.auth_url(auth_urls::AUTHORIZE);
-        let parsed = Url::parse_with_params(&request_url, payload)?;
-        Ok(parsed.into())
+        let url = build_authorize_url(&request_url, payload)?;
+        let verifier = PkceVerifier {
+            verifier,
+            challenge,
+            redirect_uri,
+            state: self.oauth.state.clone(),
+        };
+        Ok((url, verifier))
+    }
+
+    /// Requests the token with the verifier returned by
+    /// [`Self::get_authorize_url_with_verifier`], possibly in another
+    /// process, instead of the one kept in [`Self::verifier`].
+    ///
+    /// The `code` and `state` are the parameters of the callback. This fails
+    /// with [`ClientError::Authorization`] if the state isn't the one the
+    /// authorization URL was built with, since the client's own
+    /// [`OAuth::state`] may have been generated by another process.
+    #[maybe_async]
+    pub async fn request_token_with_verifier(
+        &self,
+        code: &str,
+        state: &str,
+        verifier: &PkceVerifier,
+    ) -> ClientResult<()> {
+        if state != verifier.state {
+            log::error!("Request state doesn't match the callback state");
+            return Err(ClientError::Authorization(
+                "the state in the redirect doesn't match".to_owned(),
+            ));
+        }
+
+        *self.selected_redirect_uri.lock().unwrap() = Some(verifier.redirect_uri.clone());
+        self.fetch_pkce_token(code, &verifier.verifier, &verifier.redirect_uri)
+            .await
+    }
+
+    #[maybe_async]
+    async fn fetch_pkce_token(
+        &self,
+        code: &str,
+        verifier: &str,
+        redirect_uri: &str,
+    ) -> ClientResult<()> {
+        log::info!("Requesting PKCE Auth Code token");
+
+        let mut data = Form::new();
+        data.insert(params::CLIENT_ID, &self.creds.id);
+        data.insert(params::GRANT_TYPE, params::GRANT_TYPE_AUTH_CODE);
+        data.insert(params::CODE, code);
+        data.insert(params::REDIRECT_URI, redirect_uri);
+        data.insert(params::CODE_VERIFIER, verifier);
+
+        let token = self.fetch_access_token(&data, None).await?;
+
+        if let Some(callback_fn) = &*self.get_config().token_callback_fn.clone() {
+            callback_fn.0(token.clone())?;
+        }
+
+        *self.token.lock().await.unwrap() = Some(token);
+
+        self.write_token_cache().await
     }
 }
@@ -0,0 +1,615 @@
+//! Back up the library of an account into a single archive, and restore it
+//! onto another account.
+//!
+//! [`backup_account`] fetches the profile, the playlists with their items,
+//! the saved tracks, albums, shows and episodes, and the followed artists
+//! into an [`AccountBackup`], which can be saved as JSON with
+//! [`JsonModel`](crate::model::JsonModel). [`restore_account`] then replays
+//! it: the playlists owned by the account are created again with the same
+//! items, the rest are followed, and the library is saved in chunks.
+//!
+//! Both of them are resumable, since a large library takes a while and may
+//! run into rate limits or network errors. The progress is kept in a
+//! [`Checkpoint`], which is updated after every successful request, so when
+//! one fails, calling the function again with the same checkpoint continues
+//! from where it stopped. It's also serializable, so that it can be resumed
+//! after a restart as well:
+//!
+//! ```no_run
+//! # #[maybe_async::maybe_async]
+//! # async fn run(spotify: rspotify::AuthCodeSpotify) -> rspotify::ClientResult<()> {
+//! use rspotify::{backup::{backup_account, AccountBackup}, model::JsonModel};
+//!
+//! let path = "spotify-backup.json";
+//! let mut backup = match std::fs::read_to_string(path) {
+//!     // Resumes the previous backup, if it was interrupted
+//!     Ok(contents) => AccountBackup::from_json_str(&contents)?,
+//!     Err(_) => AccountBackup::default(),
+//! };
+//!
+//! let result = backup_account(&spotify, &mut backup, |progress| {
+//!     println!("{:?}: {}/{}", progress.section, progress.done, progress.total);
+//! })
+//! .await;
+//! std::fs::write(path, backup.to_json_string()?)?;
+//! result
+//! # }
+//! ```
+//!
+//! The library is listed by Spotify from the most recent item, so it's
+//! restored in the opposite order to keep the same order in the new account.
+//! Local files and unavailable items can't be restored, so they're skipped.
+
+use crate::{
+    clients::OAuthClient,
+    model::{
+        AlbumId, ArtistId, EpisodeId, Id, JsonModel, PlayableId, PlayableItem, PlaylistId,
+        PlaylistItem, PrivateUser, ShowId, TrackId,
+    },
+    ClientResult,
+};
+
+use maybe_async::maybe_async;
+use serde::{Deserialize, Serialize};
+
+/// The version of the format of [`AccountBackup`].
+pub const BACKUP_VERSION: u32 = 1;
+
+/// The number of items requested per page.
+const PAGE_LIMIT: u32 = 50;
+
+/// The number of playlist items requested per page, or added per request.
+const PLAYLIST_LIMIT: u32 = 100;
+
+/// The parts of an account, in the order they're backed up and restored.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Section {
+    /// The profile of the user, which is only backed up.
+    Profile,
+    /// The list of playlists, without their items.
+    Playlists,
+    /// The items of each playlist. When restoring, the playlists are created
+    /// or followed in this section too.
+    PlaylistItems,
+    SavedTracks,
+    SavedAlbums,
+    SavedShows,
+    SavedEpisodes,
+    FollowedArtists,
+    /// Everything has been backed up or restored.
+    Done,
+}
+
+impl Default for Section {
+    fn default() -> Self {
+        Self::Profile
+    }
+}
+
+/// Where a backup or restore stopped, to resume it later.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub section: Section,
+    /// The number of playlists done within [`Section::PlaylistItems`].
+    pub playlist: usize,
+    /// The number of items done within the section, or within the current
+    /// playlist.
+    pub offset: u32,
+    /// The cursor of the followed artists when backing up.
+    pub cursor: Option<String>,
+    /// The playlist being created when restoring, so that it isn't created
+    /// twice.
+    pub created_playlist: Option<PlaylistId<'static>>,
+}
+
+impl Checkpoint {
+    /// Moves on to the next section, or to the next playlist.
+    fn advance(&mut self, section: Section) {
+        if section == Section::PlaylistItems && self.section == Section::PlaylistItems {
+            self.playlist += 1;
+        } else {
+            self.playlist = 0;
+        }
+        self.section = section;
+        self.offset = 0;
+        self.cursor = None;
+        self.created_playlist = None;
+    }
+}
+
+/// The progress of a backup or restore, reported after every request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    pub section: Section,
+    /// The index of the current playlist, within [`Section::PlaylistItems`].
+    pub playlist: Option<usize>,
+    /// The number of items done within the section, or the playlist.
+    pub done: u32,
+    /// The total number of items within the section, or the playlist.
+    pub total: u32,
+}
+
+/// A playlist in the backup.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PlaylistBackup {
+    /// The ID of the original playlist.
+    pub id: PlaylistId<'static>,
+    pub name: String,
+    pub description: Option<String>,
+    pub public: Option<bool>,
+    pub collaborative: bool,
+    /// The ID of the owner, which decides whether the playlist is created
+    /// or followed when restoring.
+    pub owner_id: String,
+    /// The URIs of its tracks and episodes, in order.
+    pub items: Vec<String>,
+}
+
+/// The library of an account, built by [`backup_account`].
+///
+/// The items are saved in the same order Spotify lists them, i.e., from the
+/// most recent one.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AccountBackup {
+    /// The version of the format, i.e., [`BACKUP_VERSION`].
+    pub version: u32,
+    pub profile: Option<PrivateUser>,
+    pub playlists: Vec<PlaylistBackup>,
+    pub saved_tracks: Vec<TrackId<'static>>,
+    pub saved_albums: Vec<AlbumId<'static>>,
+    pub saved_shows: Vec<ShowId<'static>>,
+    pub saved_episodes: Vec<EpisodeId<'static>>,
+    pub followed_artists: Vec<ArtistId<'static>>,
+    /// Where the backup stopped, in case it's incomplete.
+    pub checkpoint: Checkpoint,
+}
+
+impl JsonModel for AccountBackup {}
+
+impl Default for AccountBackup {
+    fn default() -> Self {
+        Self {
+            version: BACKUP_VERSION,
+            profile: None,
+            playlists: Vec::new(),
+            saved_tracks: Vec::new(),
+            saved_albums: Vec::new(),
+            saved_shows: Vec::new(),
+            saved_episodes: Vec::new(),
+            followed_artists: Vec::new(),
+            checkpoint: Checkpoint::default(),
+        }
+    }
+}
+
+impl AccountBackup {
+    /// Whether all the sections have been backed up.
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.checkpoint.section == Section::Done
+    }
+
+    /// The ID of the user whose account was backed up.
+    #[must_use]
+    pub fn user_id(&self) -> Option<&str> {
+        self.profile.as_ref().map(|profile| profile.id.id())
+    }
+}
+
+fn playlist_item_uri(item: PlaylistItem) -> Option<String> {
+    match item.track? {
+        PlayableItem::Track(track) if item.is_local || track.is_local => None,
+        track => track.id().map(|id| id.uri()),
+    }
+}
+
+/// Backs up the library of the current user into `backup`, starting from its
+/// checkpoint. See the [module-level documentation](self) for more
+/// information.
+///
+/// If a request fails, its error is returned, and `backup` keeps what was
+/// fetched until then, along with the checkpoint to resume it.
+#[maybe_async]
+pub async fn backup_account<C: OAuthClient, F: FnMut(&Progress)>(
+    client: &C,
+    backup: &mut AccountBackup,
+    mut on_progress: F,
+) -> ClientResult<()> {
+    loop {
+        let checkpoint = &mut backup.checkpoint;
+        let offset = checkpoint.offset;
+        let limit = Some(PAGE_LIMIT);
+        let (section, done, total, last) = match checkpoint.section {
+            Section::Profile => {
+                backup.profile = Some(client.me().await?);
+                (Section::Profile, 1, 1, true)
+            }
+            Section::Playlists => {
+                let page = client
+                    .current_user_playlists_manual(limit, Some(offset))
+                    .await?;
+                let count = page.items.len() as u32;
+                backup
+                    .playlists
+                    .extend(page.items.into_iter().map(|playlist| PlaylistBackup {
+                        id: playlist.id,
+                        name: playlist.name,
+                        description: None,
+                        public: playlist.public,
+                        collaborative: playlist.collaborative,
+                        owner_id: playlist.owner.id.id().to_owned(),
+                        items: Vec::new(),
+                    }));
+                let done = offset + count;
+                (
+                    Section::Playlists,
+                    done,
+                    page.total,
+                    page.next.is_none() || count == 0,
+                )
+            }
+            Section::PlaylistItems => {
+                let index = checkpoint.playlist;
+                let playlist = match backup.playlists.get_mut(index) {
+                    Some(playlist) => playlist,
+                    None => {
+                        checkpoint.advance(Section::SavedTracks);
+                        continue;
+                    }
+                };
+                let id = playlist.id.as_ref();
+
+                // The first page comes along with the details of the playlist
+                let page = if offset == 0 {
+                    let full = client.playlist(id, None, None, None).await?;
+                    playlist.description = full.description.filter(|d| !d.is_empty());
+                    playlist.items.clear();
+                    full.tracks
+                } else {
+                    client
+                        .playlist_items_manual(
+                            id,
+                            None,
+                            None,
+                            None,
+                            Some(PLAYLIST_LIMIT),
+                            Some(offset),
+                        )
+                        .await?
+                };
+                let count = page.items.len() as u32;
+                playlist
+                    .items
+                    .extend(page.items.into_iter().filter_map(playlist_item_uri));
+                let done = offset + count;
+                checkpoint.offset = done;
+                on_progress(&Progress {
+                    section: Section::PlaylistItems,
+                    playlist: Some(index),
+                    done,
+                    total: page.total,
+                });
+                if page.next.is_none() || count == 0 {
+                    checkpoint.advance(Section::PlaylistItems);
+                }
+                continue;
+            }
+            Section::SavedTracks => {
+                let page = client
+                    .current_user_saved_tracks_manual(None, limit, Some(offset))
+                    .await?;
+                let count = page.items.len() as u32;
+                backup
+                    .saved_tracks
+                    .extend(page.items.into_iter().filter_map(|saved| saved.track.id));
+                (
+                    Section::SavedTracks,
+                    offset + count,
+                    page.total,
+                    page.next.is_none() || count == 0,
+                )
+            }
+            Section::SavedAlbums => {
+                let page = client
+                    .current_user_saved_albums_manual(None, limit, Some(offset))
+                    .await?;
+                let count = page.items.len() as u32;
+                backup
+                    .saved_albums
+                    .extend(page.items.into_iter().map(|saved| saved.album.id));
+                (
+                    Section::SavedAlbums,
+                    offset + count,
+                    page.total,
+                    page.next.is_none() || count == 0,
+                )
+            }
+            Section::SavedShows => {
+                let page = client
+                    .current_user_saved_shows_manual(limit, Some(offset))
+                    .await?;
+                let count = page.items.len() as u32;
+                backup
+                    .saved_shows
+                    .extend(page.items.into_iter().map(|saved| saved.show.id));
+                (
+                    Section::SavedShows,
+                    offset + count,
+                    page.total,
+                    page.next.is_none() || count == 0,
+                )
+            }
+            Section::SavedEpisodes => {
+                let page = client
+                    .current_user_saved_episodes_manual(None, limit, Some(offset))
+                    .await?;
+                let count = page.items.len() as u32;
+                backup
+                    .saved_episodes
+                    .extend(page.items.into_iter().map(|saved| saved.episode.id));
+                (
+                    Section::SavedEpisodes,
+                    offset + count,
+                    page.total,
+                    page.next.is_none() || count == 0,
+                )
+            }
+            Section::FollowedArtists => {
+                let page = client
+                    .current_user_followed_artists_manual(checkpoint.cursor.as_deref(), limit)
+                    .await?;
+                let count = page.items.len() as u32;
+                backup
+                    .followed_artists
+                    .extend(page.items.into_iter().map(|artist| artist.id));
+                checkpoint.cursor = page.cursors.and_then(|cursors| cursors.after);
+                let done = offset + count;
+                let total = page.total.unwrap_or(done);
+                let last = page.next.is_none() || checkpoint.cursor.is_none() || count == 0;
+                (Section::FollowedArtists, done, total, last)
+            }
+            Section::Done => return Ok(()),
+        };
+
+        checkpoint.offset = done;
+        on_progress(&Progress {
+            section,
+            playlist: None,
+            done,
+            total,
+        });
+        if last {
+            let next = match section {
+                Section::Profile => Section::Playlists,
+                Section::Playlists => Section::PlaylistItems,
+                Section::SavedTracks => Section::SavedAlbums,
+                Section::SavedAlbums => Section::SavedShows,
+                Section::SavedShows => Section::SavedEpisodes,
+                Section::SavedEpisodes => Section::FollowedArtists,
+                _ => Section::Done,
+            };
+            checkpoint.advance(next);
+        }
+    }
+}
+
+/// Restores `backup` onto the account of the current user, starting from
+/// `checkpoint`, which should be [`Checkpoint::default`] the first time. See
+/// the [module-level documentation](self) for more information.
+///
+/// If a request fails, its error is returned, and `checkpoint` points to the
+/// first request that hasn't been completed. Note that a request whose
+/// response got lost may have been applied anyway, in which case resuming
+/// adds its items twice to a playlist.
+#[maybe_async]
+pub async fn restore_account<C: OAuthClient, F: FnMut(&Progress)>(
+    client: &C,
+    backup: &AccountBackup,
+    checkpoint: &mut Checkpoint,
+    mut on_progress: F,
+) -> ClientResult<()> {
+    loop {
+        let offset = checkpoint.offset as usize;
+        let (section, total, count) = match checkpoint.section {
+            Section::Profile | Section::Playlists => {
+                checkpoint.advance(Section::PlaylistItems);
+                continue;
+            }
+            Section::PlaylistItems => {
+                // Created in reverse, so that they're listed in the same order
+                let index = checkpoint.playlist;
+                let playlist = match backup.playlists.iter().rev().nth(index) {
+                    Some(playlist) => playlist,
+                    None => {
+                        checkpoint.advance(Section::SavedTracks);
+                        continue;
+                    }
+                };
+
+                if Some(playlist.owner_id.as_str()) != backup.user_id() {
+                    client
+                        .playlist_follow(playlist.id.as_ref(), playlist.public)
+                        .await?;
+                    checkpoint.advance(Section::PlaylistItems);
+                    continue;
+                }
+
+                let id = match &checkpoint.created_playlist {
+                    Some(id) => id.clone(),
+                    None => {
+                        let user_id = client.current_user_id().await?;
+                        let created = client
+                            .user_playlist_create(
+                                user_id,
+                                &playlist.name,
+                                playlist.public,
+                                Some(playlist.collaborative),
+                                playlist.description.as_deref(),
+                            )
+                            .await?;
+                        checkpoint.created_playlist = Some(created.id.clone());
+                        created.id
+                    }
+                };
+
+                let uris = playlist
+                    .items
+                    .iter()
+                    .skip(offset)
+                    .take(PLAYLIST_LIMIT as usize)
+                    .collect::<Vec<_>>();
+                let items = uris
+                    .iter()
+                    .filter_map(|uri| playable_from_uri(uri))
+                    .collect::<Vec<_>>();
+                if !items.is_empty() {
+                    client.playlist_add_items(id, items, None).await?;
+                }
+
+                let done = offset + uris.len();
+                checkpoint.offset = done as u32;
+                on_progress(&Progress {
+                    section: Section::PlaylistItems,
+                    playlist: Some(index),
+                    done: done as u32,
+                    total: playlist.items.len() as u32,
+                });
+                if done >= playlist.items.len() {
+                    checkpoint.advance(Section::PlaylistItems);
+                }
+                continue;
+            }
+            // The library is saved from the oldest item for the same reason
+            Section::SavedTracks => {
+                let ids = chunk(&backup.saved_tracks, offset, PAGE_LIMIT);
+                let count = ids.len();
+                if count > 0 {
+                    client.current_user_saved_tracks_add(ids).await?;
+                }
+                (Section::SavedTracks, backup.saved_tracks.len(), count)
+            }
+            Section::SavedAlbums => {
+                // Only 20 albums are accepted at once
+                let ids = chunk(&backup.saved_albums, offset, 20);
+                let count = ids.len();
+                if count > 0 {
+                    client.current_user_saved_albums_add(ids).await?;
+                }
+                (Section::SavedAlbums, backup.saved_albums.len(), count)
+            }
+            Section::SavedShows => {
+                let ids = chunk(&backup.saved_shows, offset, PAGE_LIMIT);
+                let count = ids.len();
+                if count > 0 {
+                    client.current_user_saved_shows_add(ids).await?;
+                }
+                (Section::SavedShows, backup.saved_shows.len(), count)
+            }
+            Section::SavedEpisodes => {
+                let ids = chunk(&backup.saved_episodes, offset, PAGE_LIMIT);
+                let count = ids.len();
+                if count > 0 {
+                    client.current_user_saved_episodes_add(ids).await?;
+                }
+                (Section::SavedEpisodes, backup.saved_episodes.len(), count)
+            }
+            Section::FollowedArtists => {
+                let ids = chunk(&backup.followed_artists, offset, PAGE_LIMIT);
+                let count = ids.len();
+                if count > 0 {
+                    client.user_follow_artists(ids).await?;
+                }
+                (
+                    Section::FollowedArtists,
+                    backup.followed_artists.len(),
+                    count,
+                )
+            }
+            Section::Done => return Ok(()),
+        };
+
+        let done = offset + count;
+        checkpoint.offset = done as u32;
+        on_progress(&Progress {
+            section,
+            playlist: None,
+            done: done as u32,
+            total: total as u32,
+        });
+        if done >= total {
+            let next = match section {
+                Section::SavedTracks => Section::SavedAlbums,
+                Section::SavedAlbums => Section::SavedShows,
+                Section::SavedShows => Section::SavedEpisodes,
+                Section::SavedEpisodes => Section::FollowedArtists,
+                _ => Section::Done,
+            };
+            checkpoint.advance(next);
+        }
+    }
+}
+
+/// The next chunk of items to restore, starting from the oldest one.
+fn chunk<T: Clone>(items: &[T], offset: usize, size: u32) -> Vec<T> {
+    items
+        .iter()
+        .rev()
+        .skip(offset)
+        .take(size as usize)
+        .cloned()
+        .collect()
+}
+
+/// Parses the URI of either a track or an episode, skipping it if it's
+/// invalid.
+fn playable_from_uri(uri: &str) -> Option<PlayableId<'_>> {
+    let id = EpisodeId::from_uri(uri)
+        .map(PlayableId::Episode)
+        .or_else(|_| TrackId::from_uri(uri).map(PlayableId::Track));
+    match id {
+        Ok(id) => Some(id),
+        Err(err) => {
+            log::warn!("Skipping the invalid playlist item {uri}: {err}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_advance() {
+        let mut checkpoint = Checkpoint {
+            section: Section::PlaylistItems,
+            playlist: 2,
+            offset: 100,
+            cursor: Some("abc".to_owned()),
+            created_playlist: None,
+        };
+        checkpoint.advance(Section::PlaylistItems);
+        assert_eq!(checkpoint.playlist, 3);
+        assert_eq!(checkpoint.offset, 0);
+        assert_eq!(checkpoint.cursor, None);
+
+        checkpoint.advance(Section::SavedTracks);
+        assert_eq!(checkpoint.section, Section::SavedTracks);
+        assert_eq!(checkpoint.playlist, 0);
+    }
+
+    #[test]
+    fn test_playable_from_uri() {
+        let track = playable_from_uri("spotify:track:4iV5W9uYEdYUVa79Axb7Rh");
+        assert!(matches!(track, Some(PlayableId::Track(_))));
+        let episode = playable_from_uri("spotify:episode:512ojhOuo1ktJprKbVcKyQ");
+        assert!(matches!(episode, Some(PlayableId::Episode(_))));
+        assert_eq!(
+            playable_from_uri("spotify:album:4iV5W9uYEdYUVa79Axb7Rh"),
+            None
+        );
+
+        assert_eq!(chunk(&[1, 2, 3, 4, 5], 1, 3), vec![4, 3, 2]);
+    }
+}
@@ -0,0 +1,1124 @@
+//! Blocking clients that drive the async ones, available with the `blocking`
+//! feature.
+//!
+//! The sync clients require the `client-ureq` feature, which can't be enabled
+//! along with `client-reqwest`. An async application with a single
+//! synchronous code path, e.g., a callback of a C library, may instead use
+//! the blocking versions of the clients in this module, like those of
+//! `reqwest::blocking`. They wrap an async client, whose requests are driven
+//! by a runtime of their own, in a dedicated thread:
+//!
+//! ```no_run
+//! use rspotify::{blocking::ClientCredsSpotify, model::ArtistId, Credentials};
+//!
+//! let creds = Credentials::from_env().unwrap();
+//! let spotify = ClientCredsSpotify::new(creds).unwrap();
+//! spotify.request_token().unwrap();
+//!
+//! let id = ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+//! let artist = spotify.artist(id).unwrap();
+//! println!("{}", artist.name);
+//! ```
+//!
+//! The paginated endpoints return a [`Paginator`], which is an iterator. As
+//! with `reqwest::blocking`, the blocking clients panic if they're used from
+//! within an async runtime. In that case, [`BlockingRunner::run`] may be used
+//! instead, which passes a clone of the async client to the given closure and
+//! runs the returned future in the thread of the runtime:
+//!
+//! ```no_run
+//! use rspotify::{
+//!     blocking::BlockingRunner, model::ArtistId, prelude::*, ClientCredsSpotify, Credentials,
+//! };
+//!
+//! let creds = Credentials::from_env().unwrap();
+//! let spotify = BlockingRunner::new(ClientCredsSpotify::new(creds)).unwrap();
+//! spotify
+//!     .run(|spotify| async move { spotify.request_token().await })
+//!     .unwrap();
+//!
+//! let id = ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+//! let artist = spotify
+//!     .run(move |spotify| async move { spotify.artist(id).await })
+//!     .unwrap();
+//! println!("{}", artist.name);
+//! ```
+//!
+//! The async client of a blocking one, available with its `client` method,
+//! shares its token and configuration with the rest of its clones, so the
+//! requests made from both sides behave the same. The requests of concurrent
+//! callers run concurrently as well, instead of waiting for each other.
+
+use crate::{
+    clients::{pagination, BaseClient, OAuthClient, PlayerCommand},
+    http::Query,
+    model::*,
+    search::SearchQuery,
+    BulkEpisodes, ClientError, ClientResult, Config, Credentials, OAuth, OnSnapshotChange, Token,
+};
+
+use std::{collections::HashMap, future::Future, pin::Pin, sync::mpsc, thread};
+
+use futures::{
+    channel::mpsc::{unbounded, UnboundedSender},
+    StreamExt,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tokio::{runtime::Handle, task::LocalSet};
+
+/// A unit of work sent to the thread of the runtime, which builds the future
+/// to run there.
+type Job = Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()>>> + Send>;
+
+/// Runs the requests of an async client synchronously. See the
+/// [module-level documentation](self) for more information.
+///
+/// Its clones share the same runtime, whose thread is stopped once all of
+/// them are dropped.
+#[derive(Clone, Debug)]
+pub struct BlockingRunner<C> {
+    client: C,
+    jobs: UnboundedSender<Job>,
+    handle: Handle,
+}
+
+impl<C> BlockingRunner<C>
+where
+    C: Clone + Send + 'static,
+{
+    /// Wraps the async client, starting the thread and the runtime that
+    /// drive its requests.
+    pub fn new(client: C) -> ClientResult<Self> {
+        let (jobs, mut rx) = unbounded::<Job>();
+        let (ready_tx, ready_rx) = mpsc::channel();
+        thread::Builder::new()
+            .name("rspotify-blocking".to_owned())
+            .spawn(move || {
+                let runtime = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build();
+                let runtime = match runtime {
+                    Ok(runtime) => {
+                        let _ = ready_tx.send(Ok(runtime.handle().clone()));
+                        runtime
+                    }
+                    Err(err) => {
+                        let _ = ready_tx.send(Err(err));
+                        return;
+                    }
+                };
+                // Each request is a task of its own, so that they run
+                // concurrently, and a panicking one doesn't bring down the
+                // rest of them
+                LocalSet::new().block_on(&runtime, async move {
+                    while let Some(job) = rx.next().await {
+                        tokio::task::spawn_local(job());
+                    }
+                });
+            })?;
+
+        // The thread always reports whether the runtime was built unless it
+        // panicked, which is reported as an error as well.
+        let handle = ready_rx.recv().map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::Other, "the runtime thread panicked")
+        })??;
+
+        Ok(Self {
+            client,
+            jobs,
+            handle,
+        })
+    }
+
+    /// The wrapped async client.
+    #[must_use]
+    pub fn client(&self) -> &C {
+        &self.client
+    }
+
+    /// Runs the future in the current thread, blocking until it's done. Its
+    /// I/O and timers are driven by the thread of the runtime, which keeps
+    /// running it, so the future doesn't need to be `'static`.
+    ///
+    /// # Panics
+    ///
+    /// If it's called from within an async runtime.
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.handle.block_on(future)
+    }
+
+    /// Runs the future returned by `f` for a clone of the client, blocking
+    /// until it's done, and returns its output.
+    ///
+    /// # Panics
+    ///
+    /// If the future panics.
+    pub fn run<F, Fut, T>(&self, f: F) -> T
+    where
+        F: FnOnce(C) -> Fut + Send + 'static,
+        Fut: Future<Output = T> + 'static,
+        T: Send + 'static,
+    {
+        let client = self.client.clone();
+        let (tx, rx) = mpsc::channel();
+        let job: Job = Box::new(move || {
+            Box::pin(async move {
+                let _ = tx.send(f(client).await);
+            })
+        });
+
+        // The thread only stops once all the senders are dropped, and it
+        // survives the panics of the requests, so it's still running here.
+        self.jobs
+            .unbounded_send(job)
+            .expect("the runtime thread stopped unexpectedly");
+        rx.recv().expect("the blocking request panicked")
+    }
+}
+
+/// The paginated endpoints of the blocking clients, which iterate over the
+/// items of all the pages, requesting them as needed.
+pub struct Paginator<'a, T> {
+    stream: pagination::Paginator<'a, T>,
+    handle: &'a Handle,
+}
+
+impl<T> Iterator for Paginator<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.handle.block_on(self.stream.next())
+    }
+}
+
+/// Implements the blocking versions of the given endpoints of `$trait`, which
+/// run the async ones of the wrapped client.
+macro_rules! blocking_endpoints {
+    (
+        $trait:ident;
+        $(
+            $(#[$attr:meta])*
+            fn $name:ident $(<$($lt:lifetime),+>)? (
+                &$($self_lt:lifetime)? self $(, $arg:ident: $ty:ty)* $(,)?
+            ) -> $ret:ty;
+        )*
+    ) => {
+        $(
+            $(#[$attr])*
+            #[doc = concat!(
+                "Blocking version of [`", stringify!($trait), "::", stringify!($name), "`]."
+            )]
+            // The deprecated endpoints are forwarded along with their attribute
+            #[allow(deprecated)]
+            pub fn $name $(<$($lt),+>)? (&$($self_lt)? self $(, $arg: $ty)*) -> $ret {
+                self.runner.block_on(self.runner.client.$name($($arg),*))
+            }
+        )*
+    };
+}
+
+/// Same as `blocking_endpoints`, but for the paginated endpoints, which
+/// return a [`Paginator`] over the given item.
+macro_rules! blocking_paginators {
+    (
+        $trait:ident;
+        $(
+            fn $name:ident <$lt:lifetime> (
+                &$self_lt:lifetime self $(, $arg:ident: $ty:ty)* $(,)?
+            ) -> Paginator<$item:ty>;
+        )*
+    ) => {
+        $(
+            #[doc = concat!(
+                "Blocking version of [`", stringify!($trait), "::", stringify!($name), "`]."
+            )]
+            pub fn $name<$lt>(
+                &$self_lt self $(, $arg: $ty)*
+            ) -> Paginator<$lt, ClientResult<$item>> {
+                Paginator {
+                    stream: self.runner.client.$name($($arg),*),
+                    handle: &self.runner.handle,
+                }
+            }
+        )*
+    };
+}
+
+/// The blocking versions of the endpoints in [`BaseClient`].
+macro_rules! base_client_endpoints {
+    () => {
+        /// Blocking version of [`BaseClient::api_get_typed`].
+        pub fn api_get_typed<T: DeserializeOwned>(
+            &self,
+            url: &str,
+            params: &Query<'_>,
+        ) -> ClientResult<T> {
+            self.runner
+                .block_on(self.runner.client.api_get_typed(url, params))
+        }
+
+        /// Blocking version of [`BaseClient::next_page`].
+        pub fn next_page<T: DeserializeOwned + Sync>(
+            &self,
+            page: &Page<T>,
+        ) -> ClientResult<Option<Page<T>>> {
+            self.runner.block_on(self.runner.client.next_page(page))
+        }
+
+        /// Blocking version of [`BaseClient::previous_page`].
+        pub fn previous_page<T: DeserializeOwned + Sync>(
+            &self,
+            page: &Page<T>,
+        ) -> ClientResult<Option<Page<T>>> {
+            self.runner.block_on(self.runner.client.previous_page(page))
+        }
+
+        blocking_endpoints! {
+            BaseClient;
+            fn refresh_token(&self) -> ClientResult<()>;
+            fn api_get_raw(&self, url: &str, params: &Query<'_>) -> ClientResult<String>;
+            fn api_post_raw(&self, url: &str, payload: &Value) -> ClientResult<String>;
+            fn api_put_raw(&self, url: &str, payload: &Value) -> ClientResult<String>;
+            fn api_delete_raw(&self, url: &str, payload: &Value) -> ClientResult<String>;
+            fn write_token_cache(&self) -> ClientResult<()>;
+            fn track(
+                &self,
+                track_id: TrackId<'_>,
+                market: Option<Market>,
+            ) -> ClientResult<FullTrack>;
+            fn tracks<'a>(
+                &self,
+                track_ids: impl IntoIterator<Item = TrackId<'a>> + Send + 'a,
+                market: Option<Market>,
+            ) -> ClientResult<Vec<FullTrack>>;
+            fn artist(&self, artist_id: ArtistId<'_>) -> ClientResult<FullArtist>;
+            fn artists<'a>(
+                &self,
+                artist_ids: impl IntoIterator<Item = ArtistId<'a>> + Send + 'a,
+            ) -> ClientResult<Vec<FullArtist>>;
+            fn artist_albums_manual<'a>(
+                &self,
+                artist_id: ArtistId<'_>,
+                include_groups: impl IntoIterator<Item = AlbumType> + Send + 'a,
+                market: Option<Market>,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<Page<SimplifiedAlbum>>;
+            fn artist_top_tracks(
+                &self,
+                artist_id: ArtistId<'_>,
+                market: Option<Market>,
+            ) -> ClientResult<Vec<FullTrack>>;
+            #[deprecated(note = "Spotify has deprecated this endpoint")]
+            fn artist_related_artists(
+                &self,
+                artist_id: ArtistId<'_>,
+            ) -> ClientResult<Vec<FullArtist>>;
+            fn album(
+                &self,
+                album_id: AlbumId<'_>,
+                market: Option<Market>,
+            ) -> ClientResult<FullAlbum>;
+            fn albums<'a>(
+                &self,
+                album_ids: impl IntoIterator<Item = AlbumId<'a>> + Send + 'a,
+                market: Option<Market>,
+            ) -> ClientResult<Vec<FullAlbum>>;
+            fn search(
+                &self,
+                q: &str,
+                _type: SearchType,
+                market: Option<Market>,
+                include_external: Option<IncludeExternal>,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<SearchResult>;
+            fn search_query(
+                &self,
+                query: &SearchQuery,
+                _type: SearchType,
+                market: Option<Market>,
+                include_external: Option<IncludeExternal>,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<SearchResult>;
+            fn search_multiple(
+                &self,
+                q: &str,
+                r#type: impl IntoIterator<Item = SearchType> + Send,
+                market: Option<Market>,
+                include_external: Option<IncludeExternal>,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<SearchMultipleResult>;
+            fn album_track_manual(
+                &self,
+                album_id: AlbumId<'_>,
+                market: Option<Market>,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<Page<SimplifiedTrack>>;
+            fn user(&self, user_id: UserId<'_>) -> ClientResult<PublicUser>;
+            fn playlist(
+                &self,
+                playlist_id: PlaylistId<'_>,
+                fields: Option<&str>,
+                market: Option<Market>,
+                additional_types: Option<&[AdditionalType]>,
+            ) -> ClientResult<FullPlaylist>;
+            fn user_playlist(
+                &self,
+                user_id: UserId<'_>,
+                playlist_id: Option<PlaylistId<'_>>,
+                fields: Option<&str>,
+            ) -> ClientResult<FullPlaylist>;
+            fn playlist_check_follow(
+                &self,
+                playlist_id: PlaylistId<'_>,
+                user_ids: &[UserId<'_>],
+            ) -> ClientResult<Vec<bool>>;
+            fn get_a_show(&self, id: ShowId<'_>, market: Option<Market>) -> ClientResult<FullShow>;
+            fn get_several_shows<'a>(
+                &self,
+                ids: impl IntoIterator<Item = ShowId<'a>> + Send + 'a,
+                market: Option<Market>,
+            ) -> ClientResult<Vec<SimplifiedShow>>;
+            fn get_shows_episodes_manual(
+                &self,
+                id: ShowId<'_>,
+                market: Option<Market>,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<Page<SimplifiedEpisode>>;
+            fn episodes_by_show_bulk<'a>(
+                &self,
+                show_ids: impl IntoIterator<Item = ShowId<'a>> + Send + 'a,
+                latest: u32,
+                market: Option<Market>,
+            ) -> BulkEpisodes;
+            fn get_an_episode(
+                &self,
+                id: EpisodeId<'_>,
+                market: Option<Market>,
+            ) -> ClientResult<FullEpisode>;
+            fn get_several_episodes<'a>(
+                &self,
+                ids: impl IntoIterator<Item = EpisodeId<'a>> + Send + 'a,
+                market: Option<Market>,
+            ) -> ClientResult<Vec<FullEpisode>>;
+            fn get_an_audiobook(
+                &self,
+                id: AudiobookId<'_>,
+                market: Option<Market>,
+            ) -> ClientResult<FullAudiobook>;
+            fn get_several_audiobooks<'a>(
+                &self,
+                ids: impl IntoIterator<Item = AudiobookId<'a>> + Send + 'a,
+                market: Option<Market>,
+            ) -> ClientResult<Vec<Option<FullAudiobook>>>;
+            fn get_audiobook_chapters_manual(
+                &self,
+                id: AudiobookId<'_>,
+                market: Option<Market>,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<Page<SimplifiedChapter>>;
+            fn get_a_chapter(
+                &self,
+                id: ChapterId<'_>,
+                market: Option<Market>,
+            ) -> ClientResult<FullChapter>;
+            fn get_several_chapters<'a>(
+                &self,
+                ids: impl IntoIterator<Item = ChapterId<'a>> + Send + 'a,
+                market: Option<Market>,
+            ) -> ClientResult<Vec<Option<FullChapter>>>;
+            #[deprecated(note = "Spotify has deprecated this endpoint")]
+            fn track_features(&self, track_id: TrackId<'_>) -> ClientResult<AudioFeatures>;
+            #[deprecated(note = "Spotify has deprecated this endpoint")]
+            fn tracks_features<'a>(
+                &self,
+                track_ids: impl IntoIterator<Item = TrackId<'a>> + Send + 'a,
+            ) -> ClientResult<Option<Vec<AudioFeatures>>>;
+            #[deprecated(note = "Spotify has deprecated this endpoint")]
+            fn tracks_features_map<'a>(
+                &self,
+                track_ids: impl IntoIterator<Item = TrackId<'a>> + Send + 'a,
+            ) -> ClientResult<HashMap<TrackId<'static>, AudioFeatures>>;
+            #[deprecated(note = "Spotify has deprecated this endpoint")]
+            fn track_analysis(&self, track_id: TrackId<'_>) -> ClientResult<AudioAnalysis>;
+            #[deprecated(note = "Spotify has deprecated this endpoint")]
+            fn track_analysis_sections(
+                &self,
+                track_id: TrackId<'_>,
+            ) -> ClientResult<Vec<AudioAnalysisSection>>;
+            #[deprecated(note = "Spotify has deprecated this endpoint")]
+            fn track_analysis_segments(
+                &self,
+                track_id: TrackId<'_>,
+            ) -> ClientResult<Vec<AudioAnalysisSegment>>;
+            #[deprecated(note = "Spotify has deprecated this endpoint")]
+            fn track_analysis_beats(
+                &self,
+                track_id: TrackId<'_>,
+            ) -> ClientResult<Vec<TimeInterval>>;
+            fn category(
+                &self,
+                category_id: CategoryId<'_>,
+                locale: Option<&str>,
+                country: Option<Market>,
+            ) -> ClientResult<Category>;
+            fn categories_manual(
+                &self,
+                locale: Option<&str>,
+                country: Option<Market>,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<Page<Category>>;
+            fn category_playlists_manual(
+                &self,
+                category_id: CategoryId<'_>,
+                country: Option<Market>,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<Page<SimplifiedPlaylist>>;
+            fn featured_playlists(
+                &self,
+                locale: Option<&str>,
+                country: Option<Market>,
+                timestamp: Option<chrono::DateTime<chrono::Utc>>,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<FeaturedPlaylists>;
+            fn new_releases_manual(
+                &self,
+                country: Option<Market>,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<Page<SimplifiedAlbum>>;
+            fn recommendations<'a>(
+                &self,
+                attributes: impl IntoIterator<Item = RecommendationsAttribute> + Send + 'a,
+                seed_artists: Option<impl IntoIterator<Item = ArtistId<'a>> + Send + 'a>,
+                seed_genres: Option<impl IntoIterator<Item = &'a str> + Send + 'a>,
+                seed_tracks: Option<impl IntoIterator<Item = TrackId<'a>> + Send + 'a>,
+                market: Option<Market>,
+                limit: Option<u32>,
+            ) -> ClientResult<Recommendations>;
+            fn playlist_items_manual(
+                &self,
+                playlist_id: PlaylistId<'_>,
+                fields: Option<&str>,
+                market: Option<Market>,
+                additional_types: Option<&[AdditionalType]>,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<Page<PlaylistItem>>;
+            fn playlist_items_with_fields_manual(
+                &self,
+                playlist_id: PlaylistId<'_>,
+                fields: &str,
+                market: Option<Market>,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<Page<PartialPlaylistItem>>;
+            fn playlist_snapshot_id(
+                &self,
+                playlist_id: PlaylistId<'_>,
+            ) -> ClientResult<PlaylistSnapshotId>;
+            fn playlist_items_snapshot(
+                &self,
+                playlist_id: PlaylistId<'_>,
+                market: Option<Market>,
+                on_change: OnSnapshotChange,
+            ) -> ClientResult<(PlaylistSnapshotId, Vec<PlaylistItem>)>;
+            fn user_playlists_manual(
+                &self,
+                user_id: UserId<'_>,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<Page<SimplifiedPlaylist>>;
+        }
+
+        blocking_paginators! {
+            BaseClient;
+            fn artist_albums<'a>(
+                &'a self,
+                artist_id: ArtistId<'a>,
+                include_groups: impl IntoIterator<Item = AlbumType> + Send + Copy + 'a,
+                market: Option<Market>,
+            ) -> Paginator<SimplifiedAlbum>;
+            fn album_track<'a>(
+                &'a self,
+                album_id: AlbumId<'a>,
+                market: Option<Market>,
+            ) -> Paginator<SimplifiedTrack>;
+            fn get_shows_episodes<'a>(
+                &'a self,
+                id: ShowId<'a>,
+                market: Option<Market>,
+            ) -> Paginator<SimplifiedEpisode>;
+            fn get_audiobook_chapters<'a>(
+                &'a self,
+                id: AudiobookId<'a>,
+                market: Option<Market>,
+            ) -> Paginator<SimplifiedChapter>;
+            fn categories<'a>(
+                &'a self,
+                locale: Option<&'a str>,
+                country: Option<Market>,
+            ) -> Paginator<Category>;
+            fn category_playlists<'a>(
+                &'a self,
+                category_id: CategoryId<'a>,
+                country: Option<Market>,
+            ) -> Paginator<SimplifiedPlaylist>;
+            fn new_releases<'a>(&'a self, country: Option<Market>) -> Paginator<SimplifiedAlbum>;
+            fn playlist_items<'a>(
+                &'a self,
+                playlist_id: PlaylistId<'a>,
+                fields: Option<&'a str>,
+                market: Option<Market>,
+                additional_types: Option<&'a [AdditionalType]>,
+            ) -> Paginator<PlaylistItem>;
+            fn playlist_items_with_fields<'a>(
+                &'a self,
+                playlist_id: PlaylistId<'a>,
+                fields: &'a str,
+                market: Option<Market>,
+            ) -> Paginator<PartialPlaylistItem>;
+            fn user_playlists<'a>(&'a self, user_id: UserId<'a>) -> Paginator<SimplifiedPlaylist>;
+        }
+    };
+}
+
+/// The blocking versions of the endpoints in [`OAuthClient`].
+macro_rules! oauth_client_endpoints {
+    () => {
+        /// Blocking version of [`OAuthClient::sign_out`].
+        pub fn sign_out(&mut self) -> ClientResult<()> {
+            let runner = &mut self.runner;
+            runner.handle.block_on(runner.client.sign_out())
+        }
+
+        /// Blocking version of
+        /// [`OAuthClient::current_user_saved_tracks_borrowed`].
+        #[cfg(feature = "model-borrowed")]
+        pub fn current_user_saved_tracks_borrowed<F>(
+            &self,
+            market: Option<Market>,
+            f: F,
+        ) -> ClientResult<()>
+        where
+            F: FnMut(borrowed::BorrowedSavedTrack<'_>) + Send,
+        {
+            self.runner.block_on(
+                self.runner
+                    .client
+                    .current_user_saved_tracks_borrowed(market, f),
+            )
+        }
+
+        /// Blocking version of [`OAuthClient::current_user_top_items`].
+        pub fn current_user_top_items<T: TopItem + Send>(
+            &self,
+            time_range: Option<TimeRange>,
+        ) -> Paginator<'_, ClientResult<T>> {
+            Paginator {
+                stream: self.runner.client.current_user_top_items(time_range),
+                handle: &self.runner.handle,
+            }
+        }
+
+        /// Blocking version of [`OAuthClient::current_user_top_items_manual`].
+        pub fn current_user_top_items_manual<T: TopItem + Send>(
+            &self,
+            time_range: Option<TimeRange>,
+            limit: Option<u32>,
+            offset: Option<u32>,
+        ) -> ClientResult<Page<T>> {
+            self.runner.block_on(
+                self.runner
+                    .client
+                    .current_user_top_items_manual(time_range, limit, offset),
+            )
+        }
+
+        blocking_endpoints! {
+            OAuthClient;
+            #[cfg(feature = "cli")]
+            fn prompt_for_token(&self, url: &str) -> ClientResult<()>;
+            fn request_token(&self, code: &str) -> ClientResult<()>;
+            fn read_token_cache(&self, allow_expired: bool) -> ClientResult<Option<Token>>;
+            fn current_user_playlists_manual(
+                &self,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<Page<SimplifiedPlaylist>>;
+            fn user_playlist_create(
+                &self,
+                user_id: UserId<'_>,
+                name: &str,
+                public: Option<bool>,
+                collaborative: Option<bool>,
+                description: Option<&str>,
+            ) -> ClientResult<FullPlaylist>;
+            fn playlist_change_detail(
+                &self,
+                playlist_id: PlaylistId<'_>,
+                details: PlaylistDetails<'_>,
+            ) -> ClientResult<()>;
+            fn playlist_unfollow(&self, playlist_id: PlaylistId<'_>) -> ClientResult<()>;
+            fn unfollow_playlists<'a>(
+                &self,
+                playlist_ids: impl IntoIterator<Item = PlaylistId<'a>> + Send + 'a,
+            ) -> ClientResult<()>;
+            fn cleanup_empty_playlists(
+                &self,
+                predicate: impl for<'p> Fn(&'p SimplifiedPlaylist) -> bool + Send, dry_run: bool,
+            ) -> ClientResult<Vec<SimplifiedPlaylist>>;
+            fn playlist_add_items<'a>(
+                &self,
+                playlist_id: PlaylistId<'_>,
+                items: impl IntoIterator<Item = PlayableId<'a>> + Send + 'a,
+                position: Option<u32>,
+            ) -> ClientResult<PlaylistSnapshotId>;
+            fn playlist_replace_items<'a>(
+                &self,
+                playlist_id: PlaylistId<'_>,
+                items: impl IntoIterator<Item = PlayableId<'a>> + Send + 'a,
+            ) -> ClientResult<PlaylistSnapshotId>;
+            fn playlist_reorder_items(
+                &self,
+                playlist_id: PlaylistId<'_>,
+                range_start: Option<i32>,
+                insert_before: Option<i32>,
+                range_length: Option<u32>,
+                snapshot_id: Option<&str>,
+            ) -> ClientResult<PlaylistSnapshotId>;
+            fn playlist_remove_all_occurrences_of_items<'a>(
+                &self,
+                playlist_id: PlaylistId<'_>,
+                track_ids: impl IntoIterator<Item = PlayableId<'a>> + Send + 'a,
+                snapshot_id: Option<&str>,
+            ) -> ClientResult<PlaylistSnapshotId>;
+            fn playlist_remove_specific_occurrences_of_items<'a>(
+                &self,
+                playlist_id: PlaylistId<'_>,
+                items: impl IntoIterator<Item = ItemPositions<'a>> + Send + 'a,
+                snapshot_id: Option<&str>,
+            ) -> ClientResult<PlaylistSnapshotId>;
+            fn playlist_follow(
+                &self,
+                playlist_id: PlaylistId<'_>,
+                public: Option<bool>,
+            ) -> ClientResult<PlaylistSnapshotId>;
+            fn me(&self) -> ClientResult<PrivateUser>;
+            fn current_user(&self) -> ClientResult<PrivateUser>;
+            fn current_user_id(&self) -> ClientResult<UserId<'static>>;
+            fn playlist_check_current_user_follows(
+                &self,
+                playlist_id: PlaylistId<'_>,
+            ) -> ClientResult<bool>;
+            fn hydrate_added_by(&self, page: &mut Page<PlaylistItem>) -> ClientResult<()>;
+            fn current_user_playing_item(&self) -> ClientResult<Option<CurrentlyPlayingContext>>;
+            fn current_user_saved_albums_manual(
+                &self,
+                market: Option<Market>,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<Page<SavedAlbum>>;
+            fn current_user_saved_tracks_manual(
+                &self,
+                market: Option<Market>,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<Page<SavedTrack>>;
+            fn current_user_followed_artists_manual(
+                &self,
+                after: Option<&str>,
+                limit: Option<u32>,
+            ) -> ClientResult<CursorBasedPage<FullArtist>>;
+            fn current_user_saved_tracks_delete<'a>(
+                &self,
+                track_ids: impl IntoIterator<Item = TrackId<'a>> + Send + 'a,
+            ) -> ClientResult<()>;
+            fn current_user_saved_tracks_contains<'a>(
+                &self,
+                track_ids: impl IntoIterator<Item = TrackId<'a>> + Send + 'a,
+            ) -> ClientResult<Vec<bool>>;
+            fn current_user_saved_tracks_add<'a>(
+                &self,
+                track_ids: impl IntoIterator<Item = TrackId<'a>> + Send + 'a,
+            ) -> ClientResult<()>;
+            fn current_user_top_artists_manual(
+                &self,
+                time_range: Option<TimeRange>,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<Page<FullArtist>>;
+            fn current_user_top_tracks_manual(
+                &self,
+                time_range: Option<TimeRange>,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<Page<FullTrack>>;
+            fn current_user_recently_played_manual(
+                &self,
+                limit: Option<u32>,
+                time_limit: Option<TimeLimits>,
+            ) -> ClientResult<CursorBasedPage<PlayHistory>>;
+            fn current_user_recently_played_since(
+                &self,
+                after: Option<chrono::DateTime<chrono::Utc>>,
+            ) -> ClientResult<Vec<PlayHistory>>;
+            fn current_user_saved_albums_add<'a>(
+                &self,
+                album_ids: impl IntoIterator<Item = AlbumId<'a>> + Send + 'a,
+            ) -> ClientResult<()>;
+            fn current_user_saved_albums_delete<'a>(
+                &self,
+                album_ids: impl IntoIterator<Item = AlbumId<'a>> + Send + 'a,
+            ) -> ClientResult<()>;
+            fn current_user_saved_albums_contains<'a>(
+                &self,
+                album_ids: impl IntoIterator<Item = AlbumId<'a>> + Send + 'a,
+            ) -> ClientResult<Vec<bool>>;
+            fn user_follow_artists<'a>(
+                &self,
+                artist_ids: impl IntoIterator<Item = ArtistId<'a>> + Send + 'a,
+            ) -> ClientResult<()>;
+            fn user_unfollow_artists<'a>(
+                &self,
+                artist_ids: impl IntoIterator<Item = ArtistId<'a>> + Send + 'a,
+            ) -> ClientResult<()>;
+            fn user_artist_check_follow<'a>(
+                &self,
+                artist_ids: impl IntoIterator<Item = ArtistId<'a>> + Send + 'a,
+            ) -> ClientResult<Vec<bool>>;
+            fn user_follow_users<'a>(
+                &self,
+                user_ids: impl IntoIterator<Item = UserId<'a>> + Send + 'a,
+            ) -> ClientResult<()>;
+            fn user_unfollow_users<'a>(
+                &self,
+                user_ids: impl IntoIterator<Item = UserId<'a>> + Send + 'a,
+            ) -> ClientResult<()>;
+            fn user_check_follow_users<'a>(
+                &self,
+                user_ids: impl IntoIterator<Item = UserId<'a>> + Send + 'a,
+            ) -> ClientResult<Vec<bool>>;
+            fn device(&self) -> ClientResult<Vec<Device>>;
+            fn current_playback<'a>(
+                &self,
+                market: Option<Market>,
+                additional_types: Option<impl IntoIterator<Item = &'a AdditionalType> + Send + 'a>,
+            ) -> ClientResult<Option<CurrentPlaybackContext>>;
+            fn current_playing<'a>(
+                &'a self,
+                market: Option<Market>,
+                additional_types: Option<impl IntoIterator<Item = &'a AdditionalType> + Send + 'a>,
+            ) -> ClientResult<Option<CurrentlyPlayingContext>>;
+            fn current_user_queue(&self) -> ClientResult<CurrentUserQueue>;
+            fn player_command(
+                &self,
+                command: PlayerCommand<'_>,
+                device_id: Option<DeviceId<'_>>,
+            ) -> ClientResult<()>;
+            fn transfer_playback(
+                &self,
+                device_id: DeviceId<'_>,
+                play: Option<bool>,
+            ) -> ClientResult<()>;
+            fn start_context_playback(
+                &self,
+                context_uri: PlayContextId<'_>,
+                device_id: Option<DeviceId<'_>>,
+                offset: Option<Offset>,
+                position: Option<std::time::Duration>,
+            ) -> ClientResult<()>;
+            fn play_on_device(
+                &self,
+                device_name_or_id: &str,
+                context_uri: PlayContextId<'_>,
+                offset: Option<Offset>,
+            ) -> ClientResult<Device>;
+            fn start_uris_playback<'a>(
+                &self,
+                uris: impl IntoIterator<Item = PlayableId<'a>> + Send + 'a,
+                device_id: Option<DeviceId<'_>>,
+                offset: Option<crate::model::Offset>,
+                position: Option<std::time::Duration>,
+            ) -> ClientResult<()>;
+            fn pause_playback(&self, device_id: Option<DeviceId<'_>>) -> ClientResult<()>;
+            fn resume_playback(
+                &self,
+                device_id: Option<DeviceId<'_>>,
+                position: Option<std::time::Duration>,
+            ) -> ClientResult<()>;
+            fn next_track(&self, device_id: Option<DeviceId<'_>>) -> ClientResult<()>;
+            fn previous_track(&self, device_id: Option<DeviceId<'_>>) -> ClientResult<()>;
+            fn seek_track(
+                &self,
+                position: std::time::Duration,
+                device_id: Option<DeviceId<'_>>,
+            ) -> ClientResult<()>;
+            fn seek_track_clamped(
+                &self,
+                position: std::time::Duration,
+                device_id: Option<DeviceId<'_>>,
+            ) -> ClientResult<std::time::Duration>;
+            fn repeat(
+                &self,
+                state: RepeatState,
+                device_id: Option<DeviceId<'_>>,
+            ) -> ClientResult<()>;
+            fn volume(
+                &self,
+                volume_percent: u8,
+                device_id: Option<DeviceId<'_>>,
+            ) -> ClientResult<()>;
+            fn shuffle(&self, state: bool, device_id: Option<DeviceId<'_>>) -> ClientResult<()>;
+            fn add_item_to_queue(
+                &self,
+                item: PlayableId<'_>,
+                device_id: Option<DeviceId<'_>>,
+            ) -> ClientResult<()>;
+            fn add_items_to_queue<'a>(
+                &self,
+                items: impl IntoIterator<Item = PlayableId<'a>> + Send + 'a,
+                device_id: Option<DeviceId<'a>>,
+            ) -> Vec<(PlayableId<'static>, ClientError)>;
+            fn current_user_saved_shows_manual(
+                &self,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<Page<Show>>;
+            fn current_user_saved_shows_add<'a>(
+                &self,
+                show_ids: impl IntoIterator<Item = ShowId<'a>> + Send + 'a,
+            ) -> ClientResult<()>;
+            fn current_user_saved_shows_delete<'a>(
+                &self,
+                show_ids: impl IntoIterator<Item = ShowId<'a>> + Send + 'a,
+                market: Option<Market>,
+            ) -> ClientResult<()>;
+            fn current_user_saved_shows_contains<'a>(
+                &self,
+                show_ids: impl IntoIterator<Item = ShowId<'a>> + Send + 'a,
+            ) -> ClientResult<Vec<bool>>;
+            fn get_episodes_resume_points<'a>(
+                &self,
+                ids: impl IntoIterator<Item = EpisodeId<'a>> + Send + 'a,
+                market: Option<Market>,
+            ) -> ClientResult<HashMap<EpisodeId<'static>, ResumePoint>>;
+            fn current_user_saved_audiobooks_manual(
+                &self,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<Page<SimplifiedAudiobook>>;
+            fn current_user_saved_audiobooks_add<'a>(
+                &self,
+                audiobook_ids: impl IntoIterator<Item = AudiobookId<'a>> + Send + 'a,
+            ) -> ClientResult<()>;
+            fn current_user_saved_audiobooks_delete<'a>(
+                &self,
+                audiobook_ids: impl IntoIterator<Item = AudiobookId<'a>> + Send + 'a,
+            ) -> ClientResult<()>;
+            fn current_user_saved_audiobooks_contains<'a>(
+                &self,
+                audiobook_ids: impl IntoIterator<Item = AudiobookId<'a>> + Send + 'a,
+            ) -> ClientResult<Vec<bool>>;
+            fn current_user_saved_episodes_manual(
+                &self,
+                market: Option<Market>,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> ClientResult<Page<SavedEpisode>>;
+            fn current_user_saved_episodes_add<'a>(
+                &self,
+                episode_ids: impl IntoIterator<Item = EpisodeId<'a>> + Send + 'a,
+            ) -> ClientResult<()>;
+            fn current_user_saved_episodes_delete<'a>(
+                &self,
+                episode_ids: impl IntoIterator<Item = EpisodeId<'a>> + Send + 'a,
+            ) -> ClientResult<()>;
+            fn current_user_saved_episodes_contains<'a>(
+                &self,
+                episode_ids: impl IntoIterator<Item = EpisodeId<'a>> + Send + 'a,
+            ) -> ClientResult<Vec<bool>>;
+        }
+
+        blocking_paginators! {
+            OAuthClient;
+            fn current_user_playlists<'a>(&'a self) -> Paginator<SimplifiedPlaylist>;
+            fn current_user_saved_albums<'a>(
+                &'a self,
+                market: Option<Market>,
+            ) -> Paginator<SavedAlbum>;
+            fn current_user_saved_tracks<'a>(
+                &'a self,
+                market: Option<Market>,
+            ) -> Paginator<SavedTrack>;
+            fn current_user_followed_artists<'a>(&'a self) -> Paginator<FullArtist>;
+            fn current_user_top_artists<'a>(
+                &'a self,
+                time_range: Option<TimeRange>,
+            ) -> Paginator<FullArtist>;
+            fn current_user_top_tracks<'a>(
+                &'a self,
+                time_range: Option<TimeRange>,
+            ) -> Paginator<FullTrack>;
+            fn current_user_recently_played<'a>(
+                &'a self,
+                time_limit: Option<TimeLimits>,
+            ) -> Paginator<PlayHistory>;
+            fn current_user_saved_shows<'a>(&'a self) -> Paginator<Show>;
+            fn saved_shows_episodes<'a>(
+                &'a self,
+                market: Option<Market>,
+            ) -> Paginator<(ShowId<'static>, SimplifiedEpisode)>;
+            fn current_user_saved_audiobooks<'a>(&'a self) -> Paginator<SimplifiedAudiobook>;
+            fn current_user_saved_episodes<'a>(
+                &'a self,
+                market: Option<Market>,
+            ) -> Paginator<SavedEpisode>;
+        }
+    };
+}
+
+/// The blocking version of [`crate::ClientCredsSpotify`]. See the
+/// [module-level documentation](self) for more information.
+#[derive(Clone, Debug)]
+pub struct ClientCredsSpotify {
+    runner: BlockingRunner<crate::ClientCredsSpotify>,
+}
+
+impl ClientCredsSpotify {
+    /// Builds a new client with the default configuration, see
+    /// [`crate::ClientCredsSpotify::new`].
+    pub fn new(creds: Credentials) -> ClientResult<Self> {
+        Self::from_async(crate::ClientCredsSpotify::new(creds))
+    }
+
+    /// Builds a new client with a custom configuration, see
+    /// [`crate::ClientCredsSpotify::try_with_config`].
+    pub fn with_config(creds: Credentials, config: Config) -> ClientResult<Self> {
+        Self::from_async(crate::ClientCredsSpotify::try_with_config(creds, config)?)
+    }
+
+    /// Wraps the given async client.
+    pub fn from_async(client: crate::ClientCredsSpotify) -> ClientResult<Self> {
+        Ok(Self {
+            runner: BlockingRunner::new(client)?,
+        })
+    }
+
+    /// The wrapped async client.
+    #[must_use]
+    pub fn client(&self) -> &crate::ClientCredsSpotify {
+        &self.runner.client
+    }
+
+    /// Blocking version of [`crate::ClientCredsSpotify::read_token_cache`].
+    pub fn read_token_cache(&self) -> ClientResult<Option<Token>> {
+        self.runner.block_on(self.runner.client.read_token_cache())
+    }
+
+    /// Blocking version of [`crate::ClientCredsSpotify::request_token`].
+    pub fn request_token(&self) -> ClientResult<()> {
+        self.runner.block_on(self.runner.client.request_token())
+    }
+
+    base_client_endpoints!();
+}
+
+/// The blocking version of [`crate::AuthCodeSpotify`]. See the
+/// [module-level documentation](self) for more information.
+#[derive(Clone, Debug)]
+pub struct AuthCodeSpotify {
+    runner: BlockingRunner<crate::AuthCodeSpotify>,
+}
+
+impl AuthCodeSpotify {
+    /// Builds a new client with the default configuration, see
+    /// [`crate::AuthCodeSpotify::new`].
+    pub fn new(creds: Credentials, oauth: OAuth) -> ClientResult<Self> {
+        Self::from_async(crate::AuthCodeSpotify::new(creds, oauth))
+    }
+
+    /// Builds a new client with a custom configuration, see
+    /// [`crate::AuthCodeSpotify::try_with_config`].
+    pub fn with_config(creds: Credentials, oauth: OAuth, config: Config) -> ClientResult<Self> {
+        Self::from_async(crate::AuthCodeSpotify::try_with_config(
+            creds, oauth, config,
+        )?)
+    }
+
+    /// Wraps the given async client.
+    pub fn from_async(client: crate::AuthCodeSpotify) -> ClientResult<Self> {
+        Ok(Self {
+            runner: BlockingRunner::new(client)?,
+        })
+    }
+
+    /// The wrapped async client.
+    #[must_use]
+    pub fn client(&self) -> &crate::AuthCodeSpotify {
+        &self.runner.client
+    }
+
+    /// See [`crate::AuthCodeSpotify::get_authorize_url`].
+    pub fn get_authorize_url(&self, show_dialog: bool) -> ClientResult<String> {
+        self.runner.client.get_authorize_url(show_dialog)
+    }
+
+    base_client_endpoints!();
+    oauth_client_endpoints!();
+}
+
+/// The blocking version of [`crate::AuthCodePkceSpotify`]. See the
+/// [module-level documentation](self) for more information.
+#[derive(Clone, Debug)]
+pub struct AuthCodePkceSpotify {
+    runner: BlockingRunner<crate::AuthCodePkceSpotify>,
+}
+
+impl AuthCodePkceSpotify {
+    /// Builds a new client with the default configuration, see
+    /// [`crate::AuthCodePkceSpotify::new`].
+    pub fn new(creds: Credentials, oauth: OAuth) -> ClientResult<Self> {
+        Self::from_async(crate::AuthCodePkceSpotify::new(creds, oauth))
+    }
+
+    /// Builds a new client with a custom configuration, see
+    /// [`crate::AuthCodePkceSpotify::try_with_config`].
+    pub fn with_config(creds: Credentials, oauth: OAuth, config: Config) -> ClientResult<Self> {
+        Self::from_async(crate::AuthCodePkceSpotify::try_with_config(
+            creds, oauth, config,
+        )?)
+    }
+
+    /// Wraps the given async client.
+    pub fn from_async(client: crate::AuthCodePkceSpotify) -> ClientResult<Self> {
+        Ok(Self {
+            runner: BlockingRunner::new(client)?,
+        })
+    }
+
+    /// The wrapped async client.
+    #[must_use]
+    pub fn client(&self) -> &crate::AuthCodePkceSpotify {
+        &self.runner.client
+    }
+
+    /// See [`crate::AuthCodePkceSpotify::get_authorize_url`], which generates
+    /// the code verifier kept by this client.
+    pub fn get_authorize_url(&mut self, verifier_bytes: Option<usize>) -> ClientResult<String> {
+        self.runner.client.get_authorize_url(verifier_bytes)
+    }
+
+    base_client_endpoints!();
+    oauth_client_endpoints!();
+}
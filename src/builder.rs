@@ -0,0 +1,213 @@
+//! A single builder for all the clients, as an alternative to their
+//! constructors.
+//!
+//! Each client has several constructors for the combinations of credentials,
+//! OAuth information, configuration and token. [`SpotifyBuilder`] takes them
+//! one at a time instead, and then builds the client of the chosen flow:
+//!
+//! ```
+//! use rspotify::{scopes, Config, Credentials, OAuth, SpotifyBuilder};
+//!
+//! let spotify = SpotifyBuilder::new()
+//!     .credentials(Credentials::new("my-client-id", "my-client-secret"))
+//!     .oauth(OAuth {
+//!         redirect_uri: "http://localhost:8888/callback".to_owned(),
+//!         scopes: scopes!("user-read-recently-played"),
+//!         ..Default::default()
+//!     })
+//!     .config(Config {
+//!         token_refreshing: true,
+//!         ..Default::default()
+//!     })
+//!     .auth_code();
+//! ```
+//!
+//! Either the credentials or a token are required, which is checked at
+//! compile time:
+//!
+//! ```compile_fail
+//! use rspotify::SpotifyBuilder;
+//!
+//! let spotify = SpotifyBuilder::new().client_credentials();
+//! ```
+
+use crate::{
+    sync::Mutex, AuthCodePkceSpotify, AuthCodeSpotify, ClientCredsSpotify, ClientResult, Config,
+    Credentials, OAuth, Token,
+};
+
+use std::{marker::PhantomData, sync::Arc};
+
+/// The state of a [`SpotifyBuilder`] without credentials nor token yet,
+/// which can't build a client.
+#[derive(Clone, Copy, Debug)]
+pub struct Incomplete;
+
+/// The state of a [`SpotifyBuilder`] with credentials or a token, which can
+/// build a client.
+#[derive(Clone, Copy, Debug)]
+pub struct Ready;
+
+/// Builds any of the clients. See the [module-level documentation](self) for
+/// more information.
+///
+/// The OAuth information and the configuration are optional, and default to
+/// [`OAuth::default`] and [`Config::default`]. The OAuth information is
+/// ignored by [`Self::client_credentials`].
+#[derive(Debug)]
+#[must_use]
+pub struct SpotifyBuilder<State = Incomplete> {
+    creds: Credentials,
+    oauth: OAuth,
+    config: Config,
+    token: Option<Token>,
+    state: PhantomData<State>,
+}
+
+impl Default for SpotifyBuilder<Incomplete> {
+    fn default() -> Self {
+        Self {
+            creds: Credentials::default(),
+            oauth: OAuth::default(),
+            config: Config::default(),
+            token: None,
+            state: PhantomData,
+        }
+    }
+}
+
+impl SpotifyBuilder<Incomplete> {
+    /// Starts building a client, which requires [`Self::credentials`] or
+    /// [`Self::token`].
+    ///
+    /// # Panics
+    ///
+    /// The same as [`OAuth::default`], if the state can't be generated. See
+    /// [`Self::try_new`] to handle it instead.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The same as [`Self::new`], but it fails with
+    /// [`ClientError::Random`](crate::ClientError::Random) if the state of the
+    /// OAuth information can't be generated.
+    pub fn try_new() -> ClientResult<Self> {
+        Ok(Self {
+            creds: Credentials::default(),
+            oauth: OAuth::try_default()?,
+            config: Config::default(),
+            token: None,
+            state: PhantomData,
+        })
+    }
+}
+
+impl<State> SpotifyBuilder<State> {
+    /// Sets the credentials of the application.
+    pub fn credentials(self, creds: Credentials) -> SpotifyBuilder<Ready> {
+        SpotifyBuilder {
+            creds,
+            oauth: self.oauth,
+            config: self.config,
+            token: self.token,
+            state: PhantomData,
+        }
+    }
+
+    /// Sets an already generated token. Without the credentials, the client
+    /// stops working once it expires, since it can't be refreshed.
+    pub fn token(self, token: Token) -> SpotifyBuilder<Ready> {
+        SpotifyBuilder {
+            creds: self.creds,
+            oauth: self.oauth,
+            config: self.config,
+            token: Some(token),
+            state: PhantomData,
+        }
+    }
+
+    /// Sets the OAuth information of the authorization code flows.
+    pub fn oauth(mut self, oauth: OAuth) -> Self {
+        self.oauth = oauth;
+        self
+    }
+
+    /// Sets the configuration of the client.
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+}
+
+impl SpotifyBuilder<Ready> {
+    /// Builds a client for the client credentials flow.
+    ///
+    /// # Panics
+    ///
+    /// If the HTTP client can't be built from the configuration, e.g.,
+    /// because of an invalid proxy. [`Self::try_client_credentials`] returns
+    /// an error instead.
+    pub fn client_credentials(self) -> ClientCredsSpotify {
+        self.try_client_credentials()
+            .expect("Invalid HTTP client configuration")
+    }
+
+    /// Same as [`Self::client_credentials`], but returns an error if the HTTP
+    /// client can't be built from the configuration.
+    pub fn try_client_credentials(self) -> ClientResult<ClientCredsSpotify> {
+        let mut spotify = ClientCredsSpotify::try_with_config(self.creds, self.config)?;
+        if let Some(token) = self.token {
+            spotify.token = Arc::new(Mutex::new(Some(token)));
+        }
+        Ok(spotify)
+    }
+
+    /// Builds a client for the authorization code flow.
+    ///
+    /// # Panics
+    ///
+    /// The same as [`Self::client_credentials`]. [`Self::try_auth_code`]
+    /// returns an error instead.
+    pub fn auth_code(self) -> AuthCodeSpotify {
+        self.try_auth_code()
+            .expect("Invalid HTTP client configuration")
+    }
+
+    /// Same as [`Self::auth_code`], but returns an error if the HTTP client
+    /// can't be built from the configuration.
+    pub fn try_auth_code(self) -> ClientResult<AuthCodeSpotify> {
+        match self.token {
+            Some(token) => AuthCodeSpotify::try_from_token_with_config(
+                token,
+                self.creds,
+                self.oauth,
+                self.config,
+            ),
+            None => AuthCodeSpotify::try_with_config(self.creds, self.oauth, self.config),
+        }
+    }
+
+    /// Builds a client for the authorization code flow with PKCE.
+    ///
+    /// # Panics
+    ///
+    /// The same as [`Self::client_credentials`]. [`Self::try_pkce`] returns
+    /// an error instead.
+    pub fn pkce(self) -> AuthCodePkceSpotify {
+        self.try_pkce().expect("Invalid HTTP client configuration")
+    }
+
+    /// Same as [`Self::pkce`], but returns an error if the HTTP client can't
+    /// be built from the configuration.
+    pub fn try_pkce(self) -> ClientResult<AuthCodePkceSpotify> {
+        match self.token {
+            Some(token) => AuthCodePkceSpotify::try_from_token_with_config(
+                token,
+                self.creds,
+                self.oauth,
+                self.config,
+            ),
+            None => AuthCodePkceSpotify::try_with_config(self.creds, self.oauth, self.config),
+        }
+    }
+}
@@ -0,0 +1,290 @@
+//! Utilities for command line applications, available with the `cli` feature.
+//!
+//! [`doctor`] diagnoses the most common setup problems: missing environment
+//! variables, an unreadable token cache, an expired token, a malformed
+//! redirect URI or no connectivity to Spotify. Running it is a good first step
+//! before opening an issue.
+
+use crate::{
+    clients::{BaseClient, OAuthClient},
+    http::{BaseHttpClient, Query},
+    Token,
+};
+
+use std::{env, fmt, fs, path::Path};
+
+use maybe_async::maybe_async;
+use url::Url;
+
+/// The outcome of a single check.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// A single check made by [`doctor`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub details: String,
+}
+
+/// The human-readable report generated by [`doctor`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    /// Whether none of the checks failed. Warnings are allowed.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|check| check.status != Status::Error)
+    }
+
+    fn push(&mut self, name: &'static str, status: Status, details: impl Into<String>) {
+        self.checks.push(Check {
+            name,
+            status,
+            details: details.into(),
+        });
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            let status = match check.status {
+                Status::Ok => "ok",
+                Status::Warning => "warning",
+                Status::Error => "error",
+            };
+            writeln!(f, "[{status:^7}] {}: {}", check.name, check.details)?;
+        }
+        Ok(())
+    }
+}
+
+/// Diagnoses the setup of the given client, printing a report to the standard
+/// output and returning it as well.
+///
+/// See [`doctor_oauth`] to also check the redirect URI configured in an
+/// [`OAuthClient`].
+#[maybe_async]
+pub async fn doctor<C: BaseClient>(client: &C) -> Report {
+    let report = diagnose(client).await;
+    println!("{report}");
+    report
+}
+
+/// Same as [`doctor`], but it also checks the redirect URI of the client.
+#[maybe_async]
+pub async fn doctor_oauth<C: OAuthClient>(client: &C) -> Report {
+    let mut report = diagnose(client).await;
+    check_redirect_uri(&mut report, &client.get_oauth().redirect_uri);
+    println!("{report}");
+    report
+}
+
+#[maybe_async]
+async fn diagnose<C: BaseClient>(client: &C) -> Report {
+    let mut report = Report::default();
+    check_env(&mut report, client);
+    if let Ok(uri) = env::var("RSPOTIFY_REDIRECT_URI") {
+        check_redirect_uri(&mut report, &uri);
+    }
+    check_cache(&mut report, client);
+    check_token(&mut report, client).await;
+
+    let config = client.get_config();
+    check_host(&mut report, client, "api host", &config.api_base_url).await;
+    check_host(&mut report, client, "auth host", &config.auth_base_url).await;
+    report
+}
+
+fn check_env<C: BaseClient>(report: &mut Report, client: &C) {
+    for var in [
+        "RSPOTIFY_CLIENT_ID",
+        "RSPOTIFY_CLIENT_SECRET",
+        "RSPOTIFY_REDIRECT_URI",
+    ] {
+        match env::var(var) {
+            Ok(value) if !value.trim().is_empty() => report.push(var, Status::Ok, "set"),
+            Ok(_) => report.push(var, Status::Warning, "set, but empty"),
+            Err(_) => report.push(
+                var,
+                Status::Warning,
+                "not set; make sure it's configured in the code instead",
+            ),
+        }
+    }
+
+    if client.get_creds().id.is_empty() {
+        report.push("client id", Status::Error, "the client ID is empty");
+    }
+}
+
+fn check_redirect_uri(report: &mut Report, uri: &str) {
+    let name = "redirect uri";
+    let url = match Url::parse(uri) {
+        Ok(url) => url,
+        Err(err) => {
+            report.push(name, Status::Error, format!("`{uri}` is invalid: {err}"));
+            return;
+        }
+    };
+
+    match (url.scheme(), url.host_str()) {
+        (_, Some("localhost")) => report.push(
+            name,
+            Status::Warning,
+            format!("`{uri}` uses `localhost`, which Spotify rejects; use `127.0.0.1` instead"),
+        ),
+        ("http", Some("127.0.0.1" | "[::1]")) | ("https", _) => {
+            report.push(name, Status::Ok, format!("`{uri}`"));
+        }
+        ("http", _) => report.push(
+            name,
+            Status::Warning,
+            format!("`{uri}` uses plain HTTP, which Spotify only allows for loopback addresses"),
+        ),
+        _ => report.push(name, Status::Ok, format!("`{uri}` (custom scheme)")),
+    }
+}
+
+fn check_cache<C: BaseClient>(report: &mut Report, client: &C) {
+    let name = "token cache";
+    let config = client.get_config();
+    if !config.token_cached {
+        report.push(name, Status::Ok, "disabled");
+        return;
+    }
+    if config.token_store.is_some() {
+        report.push(name, Status::Ok, "using a custom token store");
+        return;
+    }
+
+    let path = &config.cache_path;
+    if !path.exists() {
+        report.push(
+            name,
+            Status::Ok,
+            format!("{} doesn't exist yet", path.display()),
+        );
+        return;
+    }
+
+    if let Err(err) = Token::from_cache(path) {
+        report.push(
+            name,
+            Status::Error,
+            format!("{} couldn't be read: {err}", path.display()),
+        );
+        return;
+    }
+
+    match cache_permissions_warning(path) {
+        Some(warning) => report.push(name, Status::Warning, warning),
+        None => report.push(name, Status::Ok, format!("{} is readable", path.display())),
+    }
+}
+
+#[cfg(unix)]
+fn cache_permissions_warning(path: &Path) -> Option<String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = fs::metadata(path).ok()?.permissions().mode();
+    (mode & 0o077 != 0).then(|| {
+        format!(
+            "{} can be read by other users (mode {:o}); consider `chmod 600`",
+            path.display(),
+            mode & 0o777
+        )
+    })
+}
+
+#[cfg(not(unix))]
+fn cache_permissions_warning(path: &Path) -> Option<String> {
+    fs::metadata(path)
+        .ok()?
+        .permissions()
+        .readonly()
+        .then(|| format!("{} is read-only", path.display()))
+}
+
+#[maybe_async]
+async fn check_token<C: BaseClient>(report: &mut Report, client: &C) {
+    let name = "token";
+    let token = client.get_token();
+    let token = token.lock().await.unwrap();
+    let token = match token.as_ref() {
+        Some(token) => token,
+        None => {
+            report.push(name, Status::Warning, "not authenticated yet");
+            return;
+        }
+    };
+
+    let scopes = if token.scopes.is_empty() {
+        "no scopes".to_owned()
+    } else {
+        let mut scopes = token.scopes.iter().map(String::as_str).collect::<Vec<_>>();
+        scopes.sort_unstable();
+        format!("scopes: {}", scopes.join(" "))
+    };
+
+    if !token.is_expired_within(client.get_config().token_expiry_margin) {
+        report.push(name, Status::Ok, format!("valid; {scopes}"));
+    } else if client.get_config().token_refreshing {
+        report.push(
+            name,
+            Status::Ok,
+            format!("expired, it will be refreshed automatically; {scopes}"),
+        );
+    } else {
+        report.push(
+            name,
+            Status::Error,
+            format!("expired and automatic refreshing is disabled; {scopes}"),
+        );
+    }
+}
+
+/// Any HTTP response, even an unsuccessful one, means that the host is
+/// reachable.
+#[maybe_async]
+async fn check_host<C: BaseClient>(report: &mut Report, client: &C, name: &'static str, url: &str) {
+    match client.get_http().get(url, None, &Query::new()).await {
+        Ok(_) => report.push(name, Status::Ok, format!("{url} is reachable")),
+        Err(err) if err.status_code().is_some() => {
+            report.push(name, Status::Ok, format!("{url} is reachable"));
+        }
+        Err(err) => report.push(name, Status::Error, format!("{url} is unreachable: {err}")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_check_redirect_uri() {
+        let status = |uri| {
+            let mut report = Report::default();
+            check_redirect_uri(&mut report, uri);
+            report.checks[0].status
+        };
+
+        assert_eq!(status("http://127.0.0.1:8888/callback"), Status::Ok);
+        assert_eq!(status("https://example.com/callback"), Status::Ok);
+        assert_eq!(status("myapp://callback"), Status::Ok);
+        assert_eq!(status("http://localhost:8888/callback"), Status::Warning);
+        assert_eq!(status("http://example.com/callback"), Status::Warning);
+        assert_eq!(status("127.0.0.1:8888"), Status::Error);
+    }
+}
@@ -13,7 +13,8 @@ use std::sync::Arc;
 ///
 /// This is the most basic flow. It requests a token to Spotify given some
 /// client credentials, without user authorization. The only step to take is to
-/// call [`Self::request_token`]. See [this example][example-main].
+/// call [`Self::request_token`], which otherwise happens on the first request
+/// if [`Config::token_fetching`] is enabled. See [this example][example-main].
 ///
 /// Note: This flow does not include authorization and therefore cannot be used
 /// to access or to manage the endpoints related to user private data in
@@ -49,6 +50,10 @@ impl BaseClient for ClientCredsSpotify {
         &self.config
     }
 
+    fn can_request_token(&self) -> bool {
+        true
+    }
+
     /// Note that refetching a token in the Client Credentials flow is
     /// equivalent to requesting a token from scratch, since there's no refresh
     /// token available.
@@ -82,20 +87,36 @@ impl ClientCredsSpotify {
 
     /// Same as [`Self::new`] but with an extra parameter to configure the
     /// client.
+    ///
+    /// # Panics
+    ///
+    /// If the HTTP client can't be built from the configuration, e.g.,
+    /// because of an invalid proxy. [`Self::try_with_config`] returns an
+    /// error instead.
     #[must_use]
     pub fn with_config(creds: Credentials, config: Config) -> Self {
-        Self {
+        Self::try_with_config(creds, config).expect("Invalid HTTP client configuration")
+    }
+
+    /// Same as [`Self::with_config`], but returns an error if the HTTP client
+    /// can't be built from the configuration, e.g., because of an invalid
+    /// proxy or client certificate.
+    pub fn try_with_config(creds: Credentials, config: Config) -> ClientResult<Self> {
+        Ok(Self {
+            http: config.build_http_client()?,
             config,
             creds,
-            ..Default::default()
-        }
+            token: Arc::default(),
+        })
     }
 
-    /// Tries to read the cache file's token.
+    /// Tries to read the cached token from the configured
+    /// [`TokenStore`](crate::TokenStore).
     ///
-    /// This will return an error if the token couldn't be read (e.g. it's not
-    /// available or the JSON is malformed). It may return `Ok(None)` if:
+    /// This will return an error if the token couldn't be read (e.g. the JSON
+    /// is malformed). It may return `Ok(None)` if:
     ///
+    /// * There's no token cached yet
     /// * The read token is expired
     /// * The cached token is disabled in the config
     #[maybe_async]
@@ -106,8 +127,11 @@ impl ClientCredsSpotify {
         }
 
         log::info!("Reading token cache");
-        let token = Token::from_cache(&self.get_config().cache_path)?;
-        if token.is_expired() {
+        let token = match self.get_config().get_token_store().load().await? {
+            Some(token) => token,
+            None => return Ok(None),
+        };
+        if token.is_expired_within(self.get_config().token_expiry_margin) {
             // Invalid token, since it's expired.
             Ok(None)
         } else {
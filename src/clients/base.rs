@@ -1,22 +1,54 @@
 use crate::{
     auth_urls,
     clients::{
-        convert_result,
+        chunk_items, convert_api_response, convert_result, log_response,
         pagination::{paginate, paginate_with_ctx, Paginator},
+        route::Route,
+        MaybeSend, MaybeSync,
     },
-    http::{BaseHttpClient, Form, Headers, HttpClient, Query},
+    http::{BaseHttpClient, Form, Headers, HttpClient, HttpError, Query},
     join_ids,
     model::*,
+    scope_audit,
+    search::SearchQuery,
     sync::Mutex,
-    util::build_map,
-    ClientError, ClientResult, Config, Credentials, Token,
+    util::{additional_types_param, build_map},
+    BulkEpisodes, ClientError, ClientResult, Config, Credentials, OnSnapshotChange, Token,
 };
 
-use std::{collections::HashMap, fmt, ops::Not, sync::Arc};
+#[cfg(feature = "journal")]
+use crate::journal;
 
-use chrono::Utc;
+use std::{cmp::Reverse, collections::HashMap, fmt, ops::Not, sync::Arc};
+
+use chrono::{NaiveDate, Utc};
+use http::{HeaderName, HeaderValue};
 use maybe_async::maybe_async;
+use serde::{de::DeserializeOwned, Deserialize};
 use serde_json::Value;
+use url::Url;
+
+/// A request sent by [`BaseClient::send_with_retry`], with its payload: the
+/// query parameters of a `GET`, or the JSON body of the other methods.
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug)]
+pub enum ApiRequest<'a> {
+    Get(&'a Query<'a>),
+    Post(&'a Value),
+    Put(&'a Value),
+    Delete(&'a Value),
+}
+
+impl ApiRequest<'_> {
+    fn method(&self) -> &'static str {
+        match self {
+            Self::Get(_) => "GET",
+            Self::Post(_) => "POST",
+            Self::Put(_) => "PUT",
+            Self::Delete(_) => "DELETE",
+        }
+    }
+}
 
 /// This trait implements the basic endpoints from the Spotify API that may be
 /// accessed without user authorization, including parts of the authentication
@@ -25,7 +57,7 @@ use serde_json::Value;
 #[cfg_attr(not(target_arch = "wasm32"), maybe_async)]
 pub trait BaseClient
 where
-    Self: Send + Sync + Default + Clone + fmt::Debug,
+    Self: MaybeSend + MaybeSync + Default + Clone + fmt::Debug,
 {
     fn get_config(&self) -> &Config;
     fn get_http(&self) -> &HttpClient;
@@ -54,25 +86,61 @@ where
         base + url
     }
 
+    /// The given market, or [`Config::default_market`] if it's `None`.
+    #[doc(hidden)]
+    fn market_or_default(&self, market: Option<Market>) -> Option<&'static str> {
+        market.or(self.get_config().default_market).map(Into::into)
+    }
+
+    /// Turns the `404 Not Found` of a request for a show, an episode, an
+    /// audiobook or a chapter into [`ClientError::MarketRequired`] if it was
+    /// sent without a market by the client credentials flow. Spotify
+    /// considers them unavailable in that case, since there's no user to
+    /// take the country from.
+    #[doc(hidden)]
+    fn check_market_required(
+        &self,
+        endpoint: &str,
+        market: Option<&str>,
+        result: ClientResult<String>,
+    ) -> ClientResult<String> {
+        match result {
+            // Only the client credentials flow can request its own token
+            Err(ClientError::Api { status: 404, .. })
+                if market.is_none() && self.can_request_token() =>
+            {
+                Err(ClientError::MarketRequired(endpoint.to_owned()))
+            }
+            result => result,
+        }
+    }
+
     /// Refetch the current access token given a refresh token.
     async fn refetch_token(&self) -> ClientResult<Option<Token>>;
 
+    /// Whether the flow can obtain a token from scratch without the user's
+    /// intervention, so that it's requested on the first request if it's
+    /// missing. Only true for the client credentials flow.
+    #[doc(hidden)]
+    fn can_request_token(&self) -> bool {
+        false
+    }
+
     /// Re-authenticate the client automatically if it's configured to do so,
-    /// which uses the refresh token to obtain a new access token.
+    /// which uses the refresh token to obtain a new access token. If there's
+    /// no token yet and the flow allows it, it's requested as well, according
+    /// to [`Config::token_fetching`].
     async fn auto_reauth(&self) -> ClientResult<()> {
-        if !self.get_config().token_refreshing {
-            return Ok(());
-        }
+        let config = self.get_config();
 
         // NOTE: It's important to not leave the token locked, or else a
         // deadlock when calling `refresh_token` will occur.
-        let should_reauth = self
-            .get_token()
-            .lock()
-            .await
-            .unwrap()
-            .as_ref()
-            .map_or(false, Token::is_expired);
+        let should_reauth = match self.get_token().lock().await.unwrap().as_ref() {
+            Some(token) => {
+                config.token_refreshing && token.is_expired_within(config.token_expiry_margin)
+            }
+            None => config.token_fetching && self.can_request_token(),
+        };
 
         if should_reauth {
             self.refresh_token().await
@@ -84,11 +152,37 @@ where
     /// Refreshes the current access token given a refresh token. The obtained
     /// token will be saved internally.
     async fn refresh_token(&self) -> ClientResult<()> {
-        let token = self.refetch_token().await?;
+        let token = self.refetch_token_with_hooks().await?;
         *self.get_token().lock().await.unwrap() = token;
         self.write_token_cache().await
     }
 
+    /// Same as [`Self::refetch_token`], but also invokes the configured
+    /// [`TokenRefreshHooks`](crate::TokenRefreshHooks).
+    #[doc(hidden)]
+    async fn refetch_token_with_hooks(&self) -> ClientResult<Option<Token>> {
+        let hooks = &self.get_config().token_refresh_hooks;
+        if let Some(hook) = &hooks.on_token_refresh_start {
+            hook();
+        }
+
+        let result = self.refetch_token().await;
+        match &result {
+            Ok(Some(token)) => {
+                if let Some(hook) = &hooks.on_token_refresh_success {
+                    hook(token);
+                }
+            }
+            Ok(None) => {}
+            Err(err) => {
+                if let Some(hook) = &hooks.on_token_refresh_failure {
+                    hook(err);
+                }
+            }
+        }
+        result
+    }
+
     /// The headers required for authenticated requests to the API.
     ///
     /// Since this is accessed by authenticated requests always, it's where the
@@ -107,6 +201,121 @@ where
             .auth_headers())
     }
 
+    /// Checks the result of a request to the API. If it was rejected with a
+    /// `401 Unauthorized` status code, the token may have been revoked or it
+    /// may have expired earlier than expected (e.g., because of clock skew),
+    /// so it's refreshed once and the new headers are returned for the request
+    /// to be replayed. Otherwise, or if the token couldn't be refreshed,
+    /// `None` is returned.
+    ///
+    /// This respects [`Config::token_refreshing`].
+    #[doc(hidden)]
+    async fn reauth_on_unauthorized(&self, result: &Result<String, HttpError>) -> Option<Headers> {
+        match result {
+            Err(err) if self.get_config().token_refreshing && err.status_code() == Some(401) => {}
+            _ => return None,
+        }
+
+        log::info!("Request unauthorized, refreshing the token and retrying");
+        let token = match self.refetch_token_with_hooks().await {
+            Ok(Some(token)) => token,
+            Ok(None) => return None,
+            Err(err) => {
+                log::warn!("Unable to refresh the token: {err}");
+                return None;
+            }
+        };
+
+        let headers = token.auth_headers();
+        *self.get_token().lock().await.unwrap() = Some(token);
+        if let Err(err) = self.write_token_cache().await {
+            log::warn!("Unable to cache the refreshed token: {err}");
+        }
+        Some(headers)
+    }
+
+    /// Fails with [`ClientError::MissingScope`] if the token lacks a scope
+    /// required by the request, when [`Config::validate_scopes`] is enabled.
+    /// Without a token, the request is left to fail on its own.
+    #[doc(hidden)]
+    async fn check_scopes(&self, method: &str, url: &str) -> ClientResult<()> {
+        if !self.get_config().validate_scopes {
+            return Ok(());
+        }
+
+        let token = self.get_token();
+        let token = token.lock().await.unwrap();
+        let missing = token
+            .as_ref()
+            .and_then(|token| scope_audit::missing_scope(method, url, &token.scopes));
+        match missing {
+            Some(scope) => Err(ClientError::MissingScope(scope.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    /// Waits before retrying a failed request, if the
+    /// [`Config::retry_policy`] allows it. Returns whether the request should
+    /// be sent again, where `retry` is the number of the retry, starting at
+    /// 1.
+    #[doc(hidden)]
+    async fn wait_for_retry(
+        &self,
+        method: &str,
+        retry: u32,
+        result: &Result<String, HttpError>,
+    ) -> bool {
+        let (policy, err) = match (&self.get_config().retry_policy, result) {
+            (Some(policy), Err(err)) => (policy, err),
+            _ => return false,
+        };
+        let delay = match policy.delay(retry, method, err) {
+            Some(delay) => delay,
+            None => return false,
+        };
+
+        log::info!("{method} request failed ({err}), retrying in {delay:?}");
+        policy.wait(delay).await;
+        true
+    }
+
+    /// Sends a request to the API with the authentication headers. It's
+    /// retried as allowed by [`Config::retry_policy`], and once more with a
+    /// refreshed token if it was unauthorized.
+    #[doc(hidden)]
+    async fn send_with_retry(&self, url: &str, request: ApiRequest<'_>) -> ClientResult<String> {
+        let headers = self.auth_headers().await?;
+        let mut retry = 0;
+        let mut result = loop {
+            let result = self.send_request(url, &headers, request).await;
+            retry += 1;
+            if !self.wait_for_retry(request.method(), retry, &result).await {
+                break result;
+            }
+        };
+        if let Some(headers) = self.reauth_on_unauthorized(&result).await {
+            result = self.send_request(url, &headers, request).await;
+        }
+        convert_api_response(result).await
+    }
+
+    /// Sends a request to the API once, with the given headers.
+    #[doc(hidden)]
+    async fn send_request(
+        &self,
+        url: &str,
+        headers: &Headers,
+        request: ApiRequest<'_>,
+    ) -> Result<String, HttpError> {
+        let http = self.get_http();
+        match request {
+            ApiRequest::Get(payload) => http.get(url, Some(headers), payload).await,
+            ApiRequest::Post(payload) => http.post(url, Some(headers), payload).await,
+            ApiRequest::Put(payload) => http.put(url, Some(headers), payload).await,
+            ApiRequest::Delete(payload) => http.delete(url, Some(headers), payload).await,
+        }
+    }
+
     // HTTP-related methods for the Spotify client. They wrap up the basic HTTP
     // client with its specific usage for endpoints or authentication.
 
@@ -114,49 +323,218 @@ where
     /// API.
     #[doc(hidden)]
     #[inline]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "spotify_api", skip_all, fields(method = "GET", endpoint = url))
+    )]
     async fn api_get(&self, url: &str, payload: &Query<'_>) -> ClientResult<String> {
-        let url = self.api_url(url);
-        let headers = self.auth_headers().await?;
-        Ok(self.get_http().get(&url, Some(&headers), payload).await?)
+        scope_audit::record(self.get_config(), "GET", url);
+        self.check_scopes("GET", url).await?;
+        // The cached responses are only reused with the same access token
+        let cache = match self.get_config().response_cache.as_deref() {
+            Some(cache) => {
+                let token = self.get_token();
+                let token = token.lock().await.unwrap();
+                token
+                    .as_ref()
+                    .map(|token| (cache, token.access_token.clone()))
+            }
+            None => None,
+        };
+        if let Some((cache, token)) = &cache {
+            if let Some(body) = cache.get(token, url, payload) {
+                return Ok(body);
+            }
+        }
+
+        let endpoint = url;
+        let url = self.api_url(endpoint);
+        let result = self.send_with_retry(&url, ApiRequest::Get(payload)).await;
+        log_response(self.get_config(), "GET", &url, &result);
+        if let (Some((cache, token)), Ok(body)) = (&cache, &result) {
+            cache.insert(token, endpoint, payload, body);
+        }
+        result
     }
 
     /// Convenience method to send POST requests related to an endpoint in the
     /// API.
     #[doc(hidden)]
     #[inline]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "spotify_api", skip_all, fields(method = "POST", endpoint = url))
+    )]
     async fn api_post(&self, url: &str, payload: &Value) -> ClientResult<String> {
+        scope_audit::record(self.get_config(), "POST", url);
+        self.check_scopes("POST", url).await?;
         let url = self.api_url(url);
-        let headers = self.auth_headers().await?;
-        Ok(self.get_http().post(&url, Some(&headers), payload).await?)
+        #[cfg(feature = "journal")]
+        let entry = journal::begin(self.get_config(), "POST", &url, payload).await?;
+
+        let result = self.send_with_retry(&url, ApiRequest::Post(payload)).await;
+        log_response(self.get_config(), "POST", &url, &result);
+
+        #[cfg(feature = "journal")]
+        journal::finish(self.get_config(), entry, &result).await;
+        result
     }
 
     /// Convenience method to send PUT requests related to an endpoint in the
     /// API.
     #[doc(hidden)]
     #[inline]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "spotify_api", skip_all, fields(method = "PUT", endpoint = url))
+    )]
     async fn api_put(&self, url: &str, payload: &Value) -> ClientResult<String> {
+        scope_audit::record(self.get_config(), "PUT", url);
+        self.check_scopes("PUT", url).await?;
         let url = self.api_url(url);
-        let headers = self.auth_headers().await?;
-        Ok(self.get_http().put(&url, Some(&headers), payload).await?)
+        #[cfg(feature = "journal")]
+        let entry = journal::begin(self.get_config(), "PUT", &url, payload).await?;
+
+        let result = self.send_with_retry(&url, ApiRequest::Put(payload)).await;
+        log_response(self.get_config(), "PUT", &url, &result);
+
+        #[cfg(feature = "journal")]
+        journal::finish(self.get_config(), entry, &result).await;
+        result
     }
 
     /// Convenience method to send DELETE requests related to an endpoint in the
     /// API.
     #[doc(hidden)]
     #[inline]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "spotify_api", skip_all, fields(method = "DELETE", endpoint = url))
+    )]
     async fn api_delete(&self, url: &str, payload: &Value) -> ClientResult<String> {
+        scope_audit::record(self.get_config(), "DELETE", url);
+        self.check_scopes("DELETE", url).await?;
         let url = self.api_url(url);
-        let headers = self.auth_headers().await?;
-        Ok(self
-            .get_http()
-            .delete(&url, Some(&headers), payload)
-            .await?)
+        #[cfg(feature = "journal")]
+        let entry = journal::begin(self.get_config(), "DELETE", &url, payload).await?;
+
+        let result = self
+            .send_with_retry(&url, ApiRequest::Delete(payload))
+            .await;
+        log_response(self.get_config(), "DELETE", &url, &result);
+
+        #[cfg(feature = "journal")]
+        journal::finish(self.get_config(), entry, &result).await;
+        result
+    }
+
+    /// Sends a GET request to an arbitrary endpoint in the Web API, which is
+    /// useful for endpoints that aren't wrapped by RSpotify yet. The request
+    /// uses the client's authentication, base URL and token refreshing, and
+    /// the body of the response is returned as is.
+    ///
+    /// Parameters:
+    /// - url - the endpoint, relative to [`Config::api_base_url`], e.g.
+    ///   `"me/player/queue"`
+    /// - params - the query parameters
+    ///
+    /// See [`Self::api_get_typed`] to deserialize the response into a model.
+    async fn api_get_raw(&self, url: &str, params: &Query<'_>) -> ClientResult<String> {
+        self.api_get(url, params).await
+    }
+
+    /// The same as [`Self::api_get_raw`], but the response is deserialized
+    /// into the given type.
+    async fn api_get_typed<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        params: &Query<'_>,
+    ) -> ClientResult<T> {
+        let result = self.api_get(url, params).await?;
+        convert_result(self.get_config(), url, &result)
+    }
+
+    /// Sends a POST request with a JSON body to an arbitrary endpoint in the
+    /// Web API. See [`Self::api_get_raw`] for more information.
+    async fn api_post_raw(&self, url: &str, payload: &Value) -> ClientResult<String> {
+        self.api_post(url, payload).await
+    }
+
+    /// Sends a PUT request with a JSON body to an arbitrary endpoint in the
+    /// Web API. See [`Self::api_get_raw`] for more information.
+    async fn api_put_raw(&self, url: &str, payload: &Value) -> ClientResult<String> {
+        self.api_put(url, payload).await
+    }
+
+    /// Sends a DELETE request with a JSON body to an arbitrary endpoint in the
+    /// Web API. See [`Self::api_get_raw`] for more information.
+    async fn api_delete_raw(&self, url: &str, payload: &Value) -> ClientResult<String> {
+        self.api_delete(url, payload).await
+    }
+
+    /// Fetches the page after the given one by following its `next` URL, or
+    /// returns `None` if it's the last one. This is useful to navigate the
+    /// results of the `_manual` endpoints without computing the offsets.
+    ///
+    /// Only the path and query of the URL are used, so the request is still
+    /// sent to [`Config::api_base_url`] with the client's authentication.
+    async fn next_page<T: DeserializeOwned + MaybeSync>(
+        &self,
+        page: &Page<T>,
+    ) -> ClientResult<Option<Page<T>>> {
+        match &page.next {
+            Some(url) => self.page_from_url(url).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Same as [`Self::next_page`], but fetches the page before the given
+    /// one by following its `previous` URL.
+    async fn previous_page<T: DeserializeOwned + MaybeSync>(
+        &self,
+        page: &Page<T>,
+    ) -> ClientResult<Option<Page<T>>> {
+        match &page.previous {
+            Some(url) => self.page_from_url(url).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetches the page at an absolute URL returned by Spotify, see
+    /// [`Self::next_page`].
+    #[doc(hidden)]
+    async fn page_from_url<T: DeserializeOwned>(&self, url: &str) -> ClientResult<Page<T>> {
+        let mut url = Url::parse(url)?;
+        let query = url.query_pairs().into_owned().collect::<Vec<_>>();
+        url.set_query(None);
+
+        // The path of the base URL, like `/v1/`, is added back by `api_get`
+        let base_path = Url::parse(&self.get_config().api_base_url)
+            .map(|base| base.path().to_owned())
+            .unwrap_or_default();
+        let path = url.path();
+        let endpoint = path
+            .strip_prefix(base_path.as_str())
+            .unwrap_or(path)
+            .trim_start_matches('/')
+            .to_owned();
+
+        let params = query
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+        let result = self.api_get(&endpoint, &params).await?;
+        convert_result(self.get_config(), &endpoint, &result)
     }
 
     /// Convenience method to send POST requests related to the authentication
     /// process.
     #[doc(hidden)]
     #[inline]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "spotify_auth", skip_all, fields(endpoint = url))
+    )]
     async fn auth_post(
         &self,
         url: &str,
@@ -164,10 +542,19 @@ where
         payload: &Form<'_>,
     ) -> ClientResult<String> {
         let url = self.auth_url(url);
-        Ok(self.get_http().post_form(&url, headers, payload).await?)
+        let result = self
+            .get_http()
+            .post_form(&url, headers, payload)
+            .await
+            .map_err(Into::into);
+
+        log_response(self.get_config(), "POST", &url, &result);
+        result
     }
 
-    /// Updates the cache file at the internal cache path.
+    /// Saves the current token with the configured
+    /// [`TokenStore`](crate::TokenStore), which is the cache file at the
+    /// internal cache path by default.
     ///
     /// This should be used whenever it's possible to, even if the cached token
     /// isn't configured, because this will already check `Config::token_cached`
@@ -179,20 +566,46 @@ where
         }
 
         log::info!("Writing token cache");
-        if let Some(tok) = self.get_token().lock().await.unwrap().as_ref() {
-            tok.write_cache(&self.get_config().cache_path)?;
+        // The lock isn't held while saving it, which may take a while
+        let token = self.get_token().lock().await.unwrap().clone();
+        if let Some(tok) = token {
+            self.get_config().get_token_store().save(&tok).await?;
         }
 
         Ok(())
     }
 
-    /// Sends a request to Spotify for an access token.
+    /// Sends a request to Spotify for an access token, along with the
+    /// [`Credentials::extra_token_params`] and
+    /// [`Credentials::extra_token_headers`].
     async fn fetch_access_token(
         &self,
         payload: &Form<'_>,
         headers: Option<&Headers>,
     ) -> ClientResult<Token> {
-        let response = self.auth_post(auth_urls::TOKEN, headers, payload).await?;
+        let creds = self.get_creds();
+        let mut payload = payload.clone();
+        for (name, value) in &creds.extra_token_params {
+            payload.entry(name).or_insert(value);
+        }
+        let mut headers = headers.cloned().unwrap_or_default();
+        for (name, value) in &creds.extra_token_headers {
+            // Header names are case-insensitive, so `authorization` mustn't
+            // be sent along with the one by RSpotify.
+            if headers.keys().any(|key| key.eq_ignore_ascii_case(name)) {
+                continue;
+            }
+            if HeaderName::from_bytes(name.as_bytes()).is_err()
+                || HeaderValue::from_str(value).is_err()
+            {
+                return Err(ClientError::InvalidHeader(name.clone()));
+            }
+            headers.insert(name.clone(), value.clone());
+        }
+
+        let response = self
+            .auth_post(auth_urls::TOKEN, Some(&headers), &payload)
+            .await?;
 
         let mut tok = serde_json::from_str::<Token>(&response)?;
         tok.expires_at = Utc::now().checked_add_signed(tok.expires_in);
@@ -210,11 +623,11 @@ where
         track_id: TrackId<'_>,
         market: Option<Market>,
     ) -> ClientResult<FullTrack> {
-        let params = build_map([("market", market.map(Into::into))]);
+        let params = build_map([("market", self.market_or_default(market))]);
 
-        let url = format!("tracks/{}", track_id.id());
+        let url = Route::Track(track_id.as_ref()).to_string();
         let result = self.api_get(&url, &params).await?;
-        convert_result(&result)
+        convert_result(self.get_config(), &url, &result)
     }
 
     /// Returns a list of tracks given a list of track IDs, URIs, or URLs.
@@ -226,15 +639,18 @@ where
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-several-tracks)
     async fn tracks<'a>(
         &self,
-        track_ids: impl IntoIterator<Item = TrackId<'a>> + Send + 'a,
+        track_ids: impl IntoIterator<Item = TrackId<'a>> + MaybeSend + 'a,
         market: Option<Market>,
     ) -> ClientResult<Vec<FullTrack>> {
-        let ids = join_ids(track_ids);
-        let params = build_map([("market", market.map(Into::into))]);
+        let params = build_map([("market", self.market_or_default(market))]);
 
-        let url = format!("tracks/?ids={ids}");
-        let result = self.api_get(&url, &params).await?;
-        convert_result::<FullTracks>(&result).map(|x| x.tracks)
+        let mut tracks = Vec::new();
+        for ids in chunk_items(self.get_config(), track_ids, 50) {
+            let url = Route::Tracks(&join_ids(ids)).to_string();
+            let result = self.api_get(&url, &params).await?;
+            tracks.extend(convert_result::<FullTracks>(self.get_config(), &url, &result)?.tracks);
+        }
+        Ok(tracks)
     }
 
     /// Returns a single artist given the artist's ID, URI or URL.
@@ -244,9 +660,9 @@ where
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-an-artist)
     async fn artist(&self, artist_id: ArtistId<'_>) -> ClientResult<FullArtist> {
-        let url = format!("artists/{}", artist_id.id());
+        let url = Route::Artist(artist_id.as_ref()).to_string();
         let result = self.api_get(&url, &Query::new()).await?;
-        convert_result(&result)
+        convert_result(self.get_config(), &url, &result)
     }
 
     /// Returns a list of artists given the artist IDs, URIs, or URLs.
@@ -257,13 +673,16 @@ where
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-multiple-artists)
     async fn artists<'a>(
         &self,
-        artist_ids: impl IntoIterator<Item = ArtistId<'a>> + Send + 'a,
+        artist_ids: impl IntoIterator<Item = ArtistId<'a>> + MaybeSend + 'a,
     ) -> ClientResult<Vec<FullArtist>> {
-        let ids = join_ids(artist_ids);
-        let url = format!("artists/?ids={ids}");
-        let result = self.api_get(&url, &Query::new()).await?;
-
-        convert_result::<FullArtists>(&result).map(|x| x.artists)
+        let mut artists = Vec::new();
+        for ids in chunk_items(self.get_config(), artist_ids, 50) {
+            let url = Route::Artists(&join_ids(ids)).to_string();
+            let result = self.api_get(&url, &Query::new()).await?;
+            artists
+                .extend(convert_result::<FullArtists>(self.get_config(), &url, &result)?.artists);
+        }
+        Ok(artists)
     }
 
     /// Get Spotify catalog information about an artist's albums.
@@ -282,7 +701,7 @@ where
     fn artist_albums<'b, 'a: 'b>(
         &'a self,
         artist_id: ArtistId<'a>,
-        include_groups: impl IntoIterator<Item = AlbumType> + Send + Copy + 'a,
+        include_groups: impl IntoIterator<Item = AlbumType> + MaybeSend + Copy + 'a,
         market: Option<Market>,
     ) -> Paginator<'b, ClientResult<SimplifiedAlbum>> {
         paginate_with_ctx(
@@ -304,7 +723,7 @@ where
     async fn artist_albums_manual<'a>(
         &self,
         artist_id: ArtistId<'_>,
-        include_groups: impl IntoIterator<Item = AlbumType> + Send + 'a,
+        include_groups: impl IntoIterator<Item = AlbumType> + MaybeSend + 'a,
         market: Option<Market>,
         limit: Option<u32>,
         offset: Option<u32>,
@@ -323,14 +742,14 @@ where
 
         let params = build_map([
             ("include_groups", include_groups_opt.as_deref()),
-            ("market", market.map(Into::into)),
+            ("market", self.market_or_default(market)),
             ("limit", limit.as_deref()),
             ("offset", offset.as_deref()),
         ]);
 
-        let url = format!("artists/{}/albums", artist_id.id());
+        let url = Route::ArtistAlbums(artist_id.as_ref()).to_string();
         let result = self.api_get(&url, &params).await?;
-        convert_result(&result)
+        convert_result(self.get_config(), &url, &result)
     }
 
     /// Get Spotify catalog information about an artist's top 10 tracks by
@@ -346,11 +765,11 @@ where
         artist_id: ArtistId<'_>,
         market: Option<Market>,
     ) -> ClientResult<Vec<FullTrack>> {
-        let params = build_map([("market", market.map(Into::into))]);
+        let params = build_map([("market", self.market_or_default(market))]);
 
-        let url = format!("artists/{}/top-tracks", artist_id.id());
+        let url = Route::ArtistTopTracks(artist_id.as_ref()).to_string();
         let result = self.api_get(&url, &params).await?;
-        convert_result::<FullTracks>(&result).map(|x| x.tracks)
+        convert_result::<FullTracks>(self.get_config(), &url, &result).map(|x| x.tracks)
     }
 
     /// Get Spotify catalog information about artists similar to an identified
@@ -369,9 +788,9 @@ where
         &self,
         artist_id: ArtistId<'_>,
     ) -> ClientResult<Vec<FullArtist>> {
-        let url = format!("artists/{}/related-artists", artist_id.id());
+        let url = Route::ArtistRelatedArtists(artist_id.as_ref()).to_string();
         let result = self.api_get(&url, &Query::new()).await?;
-        convert_result::<FullArtists>(&result).map(|x| x.artists)
+        convert_result::<FullArtists>(self.get_config(), &url, &result).map(|x| x.artists)
     }
 
     /// Returns a single album given the album's ID, URIs or URL.
@@ -385,11 +804,11 @@ where
         album_id: AlbumId<'_>,
         market: Option<Market>,
     ) -> ClientResult<FullAlbum> {
-        let params = build_map([("market", market.map(Into::into))]);
+        let params = build_map([("market", self.market_or_default(market))]);
 
-        let url = format!("albums/{}", album_id.id());
+        let url = Route::Album(album_id.as_ref()).to_string();
         let result = self.api_get(&url, &params).await?;
-        convert_result(&result)
+        convert_result(self.get_config(), &url, &result)
     }
 
     /// Returns a list of albums given the album IDs, URIs, or URLs.
@@ -400,15 +819,18 @@ where
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-multiple-albums)
     async fn albums<'a>(
         &self,
-        album_ids: impl IntoIterator<Item = AlbumId<'a>> + Send + 'a,
+        album_ids: impl IntoIterator<Item = AlbumId<'a>> + MaybeSend + 'a,
         market: Option<Market>,
     ) -> ClientResult<Vec<FullAlbum>> {
-        let params = build_map([("market", market.map(Into::into))]);
+        let params = build_map([("market", self.market_or_default(market))]);
 
-        let ids = join_ids(album_ids);
-        let url = format!("albums/?ids={ids}");
-        let result = self.api_get(&url, &params).await?;
-        convert_result::<FullAlbums>(&result).map(|x| x.albums)
+        let mut albums = Vec::new();
+        for ids in chunk_items(self.get_config(), album_ids, 20) {
+            let url = Route::Albums(&join_ids(ids)).to_string();
+            let result = self.api_get(&url, &params).await?;
+            albums.extend(convert_result::<FullAlbums>(self.get_config(), &url, &result)?.albums);
+        }
+        Ok(albums)
     }
 
     /// Search for an Item. Get Spotify catalog information about artists,
@@ -448,14 +870,43 @@ where
         let params = build_map([
             ("q", Some(q)),
             ("type", Some(_type.into())),
-            ("market", market.map(Into::into)),
+            ("market", self.market_or_default(market)),
             ("include_external", include_external.map(Into::into)),
             ("limit", limit.as_deref()),
             ("offset", offset.as_deref()),
         ]);
 
-        let result = self.api_get("search", &params).await?;
-        convert_result(&result)
+        let url = Route::Search.to_string();
+        let result = self.api_get(&url, &params).await?;
+        convert_result(self.get_config(), &url, &result)
+    }
+
+    /// Search for an Item with a query built with [`SearchQuery`], which
+    /// takes care of the syntax of the filters.
+    ///
+    /// Parameters:
+    /// - query - the search query
+    /// - type - the type of item to return
+    ///
+    /// The rest of the parameters are the same as in [`Self::search`].
+    async fn search_query(
+        &self,
+        query: &SearchQuery,
+        _type: SearchType,
+        market: Option<Market>,
+        include_external: Option<IncludeExternal>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> ClientResult<SearchResult> {
+        self.search(
+            &query.to_string(),
+            _type,
+            market,
+            include_external,
+            limit,
+            offset,
+        )
+        .await
     }
 
     /// Search for multiple an Item. Get Spotify catalog information about artists,
@@ -484,7 +935,7 @@ where
     async fn search_multiple(
         &self,
         q: &str,
-        r#type: impl IntoIterator<Item = SearchType> + Send,
+        r#type: impl IntoIterator<Item = SearchType> + MaybeSend,
         market: Option<Market>,
         include_external: Option<IncludeExternal>,
         limit: Option<u32>,
@@ -499,14 +950,15 @@ where
         let params = build_map([
             ("q", Some(q)),
             ("type", Some(_type.trim_end_matches(","))),
-            ("market", market.map(Into::into)),
+            ("market", self.market_or_default(market)),
             ("include_external", include_external.map(Into::into)),
             ("limit", limit.as_deref()),
             ("offset", offset.as_deref()),
         ]);
 
-        let result = self.api_get("search", &params).await?;
-        convert_result(&result)
+        let url = Route::Search.to_string();
+        let result = self.api_get(&url, &params).await?;
+        convert_result(self.get_config(), &url, &result)
     }
 
     /// Get Spotify catalog information about an album's tracks.
@@ -547,12 +999,12 @@ where
         let params = build_map([
             ("limit", limit.as_deref()),
             ("offset", offset.as_deref()),
-            ("market", market.map(Into::into)),
+            ("market", self.market_or_default(market)),
         ]);
 
-        let url = format!("albums/{}/tracks", album_id.id());
+        let url = Route::AlbumTracks(album_id.as_ref()).to_string();
         let result = self.api_get(&url, &params).await?;
-        convert_result(&result)
+        convert_result(self.get_config(), &url, &result)
     }
 
     /// Gets basic profile information about a Spotify User.
@@ -562,9 +1014,9 @@ where
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-users-profile)
     async fn user(&self, user_id: UserId<'_>) -> ClientResult<PublicUser> {
-        let url = format!("users/{}", user_id.id());
+        let url = Route::User(user_id.as_ref()).to_string();
         let result = self.api_get(&url, &Query::new()).await?;
-        convert_result(&result)
+        convert_result(self.get_config(), &url, &result)
     }
 
     /// Get full details about Spotify playlist.
@@ -572,6 +1024,9 @@ where
     /// Parameters:
     /// - playlist_id - the id of the playlist
     /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
+    /// - additional_types - the types of items supported besides tracks. If
+    ///   `None`, both tracks and episodes are requested, so that the episodes
+    ///   are returned as [`PlayableItem::Episode`].
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-playlist)
     async fn playlist(
@@ -579,12 +1034,18 @@ where
         playlist_id: PlaylistId<'_>,
         fields: Option<&str>,
         market: Option<Market>,
+        additional_types: Option<&[AdditionalType]>,
     ) -> ClientResult<FullPlaylist> {
-        let params = build_map([("fields", fields), ("market", market.map(Into::into))]);
+        let additional_types = additional_types_param(additional_types);
+        let params = build_map([
+            ("fields", fields),
+            ("market", self.market_or_default(market)),
+            ("additional_types", Some(&additional_types)),
+        ]);
 
-        let url = format!("playlists/{}", playlist_id.id());
+        let url = Route::Playlist(playlist_id.as_ref()).to_string();
         let result = self.api_get(&url, &params).await?;
-        convert_result(&result)
+        convert_result(self.get_config(), &url, &result)
     }
 
     /// Gets playlist of a user.
@@ -604,11 +1065,15 @@ where
         let params = build_map([("fields", fields)]);
 
         let url = match playlist_id {
-            Some(playlist_id) => format!("users/{}/playlists/{}", user_id.id(), playlist_id.id()),
-            None => format!("users/{}/starred", user_id.id()),
+            Some(playlist_id) => Route::UserPlaylist {
+                user: user_id.as_ref(),
+                playlist: playlist_id,
+            }
+            .to_string(),
+            None => Route::UserStarred(user_id.as_ref()).to_string(),
         };
         let result = self.api_get(&url, &params).await?;
-        convert_result(&result)
+        convert_result(self.get_config(), &url, &result)
     }
 
     /// Check to see if the given users are following the given playlist.
@@ -628,13 +1093,13 @@ where
             user_ids.len() <= 5,
             "The maximum length of user ids is limited to 5 :-)"
         );
-        let url = format!(
-            "playlists/{}/followers/contains?ids={}",
-            playlist_id.id(),
-            user_ids.iter().map(Id::id).collect::<Vec<_>>().join(","),
-        );
+        let url = Route::PlaylistFollowersContains {
+            playlist: playlist_id,
+            users: &user_ids.iter().map(Id::id).collect::<Vec<_>>().join(","),
+        }
+        .to_string();
         let result = self.api_get(&url, &Query::new()).await?;
-        convert_result(&result)
+        convert_result(self.get_config(), &url, &result)
     }
 
     /// Get Spotify catalog information for a single show identified by its unique Spotify ID.
@@ -644,14 +1109,21 @@ where
     ///
     /// Query Parameters
     /// - market(Optional): An ISO 3166-1 alpha-2 country code or the string from_token.
+    ///   Required by the client credentials flow, which otherwise fails with
+    ///   [`ClientError::MarketRequired`]. The same applies to
+    ///   [`Self::get_an_episode`], [`Self::get_an_audiobook`],
+    ///   [`Self::get_a_chapter`] and the episodes and chapters of a show or
+    ///   audiobook.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-a-show)
     async fn get_a_show(&self, id: ShowId<'_>, market: Option<Market>) -> ClientResult<FullShow> {
-        let params = build_map([("market", market.map(Into::into))]);
+        let market = self.market_or_default(market);
+        let params = build_map([("market", market)]);
 
-        let url = format!("shows/{}", id.id());
-        let result = self.api_get(&url, &params).await?;
-        convert_result(&result)
+        let url = Route::Show(id.as_ref()).to_string();
+        let result = self.api_get(&url, &params).await;
+        let result = self.check_market_required(&url, market, result)?;
+        convert_result(self.get_config(), &url, &result)
     }
 
     /// Get Spotify catalog information for multiple shows based on their
@@ -664,14 +1136,24 @@ where
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-multiple-shows)
     async fn get_several_shows<'a>(
         &self,
-        ids: impl IntoIterator<Item = ShowId<'a>> + Send + 'a,
+        ids: impl IntoIterator<Item = ShowId<'a>> + MaybeSend + 'a,
         market: Option<Market>,
     ) -> ClientResult<Vec<SimplifiedShow>> {
-        let ids = join_ids(ids);
-        let params = build_map([("ids", Some(&ids)), ("market", market.map(Into::into))]);
-
-        let result = self.api_get("shows", &params).await?;
-        convert_result::<SeversalSimplifiedShows>(&result).map(|x| x.shows)
+        let mut shows = Vec::new();
+        for ids in chunk_items(self.get_config(), ids, 50) {
+            let ids = join_ids(ids);
+            let params = build_map([
+                ("ids", Some(&ids)),
+                ("market", self.market_or_default(market)),
+            ]);
+
+            let url = Route::Shows.to_string();
+            let result = self.api_get(&url, &params).await?;
+            shows.extend(
+                convert_result::<SeversalSimplifiedShows>(self.get_config(), &url, &result)?.shows,
+            );
+        }
+        Ok(shows)
     }
 
     /// Get Spotify catalog information about an show’s episodes. Optional
@@ -713,15 +1195,65 @@ where
     ) -> ClientResult<Page<SimplifiedEpisode>> {
         let limit = limit.map(|x| x.to_string());
         let offset = offset.map(|x| x.to_string());
+        let market = self.market_or_default(market);
         let params = build_map([
-            ("market", market.map(Into::into)),
+            ("market", market),
             ("limit", limit.as_deref()),
             ("offset", offset.as_deref()),
         ]);
 
-        let url = format!("shows/{}/episodes", id.id());
-        let result = self.api_get(&url, &params).await?;
-        convert_result(&result)
+        let url = Route::ShowEpisodes(id.as_ref()).to_string();
+        let result = self.api_get(&url, &params).await;
+        let result = self.check_market_required(&url, market, result)?;
+        convert_result(self.get_config(), &url, &result)
+    }
+
+    /// Fetches the latest episodes of several shows concurrently, merging
+    /// them into a single list with the newest episodes first.
+    ///
+    /// A failure in one of the shows doesn't affect the rest; it's returned
+    /// in [`BulkEpisodes::failed`] instead.
+    ///
+    /// Parameters:
+    /// - show_ids - the shows to fetch the episodes from
+    /// - latest - the number of episodes to fetch from each show
+    /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
+    async fn episodes_by_show_bulk<'a>(
+        &self,
+        show_ids: impl IntoIterator<Item = ShowId<'a>> + MaybeSend + 'a,
+        latest: u32,
+        market: Option<Market>,
+    ) -> BulkEpisodes {
+        let show_ids = show_ids
+            .into_iter()
+            .map(ShowId::into_static)
+            .collect::<Vec<_>>();
+
+        #[cfg(feature = "__async")]
+        let results = futures::future::join_all(
+            show_ids
+                .iter()
+                .map(|id| latest_episodes(self, id.as_ref(), latest, market)),
+        )
+        .await;
+        #[cfg(feature = "__sync")]
+        let results = show_ids
+            .iter()
+            .map(|id| latest_episodes(self, id.as_ref(), latest, market))
+            .collect::<Vec<_>>();
+
+        let mut bulk = BulkEpisodes::default();
+        for (id, result) in show_ids.into_iter().zip(results) {
+            match result {
+                Ok(episodes) => bulk
+                    .episodes
+                    .extend(episodes.into_iter().map(|episode| (id.clone(), episode))),
+                Err(err) => bulk.failed.push((id, err)),
+            }
+        }
+        bulk.episodes
+            .sort_by_key(|(_, episode)| Reverse(release_date(episode)));
+        bulk
     }
 
     /// Get Spotify catalog information for a single episode identified by its unique Spotify ID.
@@ -738,11 +1270,13 @@ where
         id: EpisodeId<'_>,
         market: Option<Market>,
     ) -> ClientResult<FullEpisode> {
-        let url = format!("episodes/{}", id.id());
-        let params = build_map([("market", market.map(Into::into))]);
+        let url = Route::Episode(id.as_ref()).to_string();
+        let market = self.market_or_default(market);
+        let params = build_map([("market", market)]);
 
-        let result = self.api_get(&url, &params).await?;
-        convert_result(&result)
+        let result = self.api_get(&url, &params).await;
+        let result = self.check_market_required(&url, market, result)?;
+        convert_result(self.get_config(), &url, &result)
     }
 
     /// Get Spotify catalog information for multiple episodes based on their Spotify IDs.
@@ -754,14 +1288,185 @@ where
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-multiple-episodes)
     async fn get_several_episodes<'a>(
         &self,
-        ids: impl IntoIterator<Item = EpisodeId<'a>> + Send + 'a,
+        ids: impl IntoIterator<Item = EpisodeId<'a>> + MaybeSend + 'a,
         market: Option<Market>,
     ) -> ClientResult<Vec<FullEpisode>> {
-        let ids = join_ids(ids);
-        let params = build_map([("ids", Some(&ids)), ("market", market.map(Into::into))]);
+        let mut episodes = Vec::new();
+        for ids in chunk_items(self.get_config(), ids, 50) {
+            let ids = join_ids(ids);
+            let params = build_map([
+                ("ids", Some(&ids)),
+                ("market", self.market_or_default(market)),
+            ]);
+
+            let url = Route::Episodes.to_string();
+            let result = self.api_get(&url, &params).await?;
+            episodes.extend(
+                convert_result::<EpisodesPayload>(self.get_config(), &url, &result)?.episodes,
+            );
+        }
+        Ok(episodes)
+    }
 
-        let result = self.api_get("episodes", &params).await?;
-        convert_result::<EpisodesPayload>(&result).map(|x| x.episodes)
+    /// Get Spotify catalog information for a single audiobook. Audiobooks are
+    /// only available within the US, UK, Canada, Ireland, New Zealand and
+    /// Australia markets.
+    ///
+    /// Path Parameters
+    /// - id: The Spotify ID for the audiobook.
+    ///
+    /// Query Parameters
+    /// - market: Optional. An ISO 3166-1 alpha-2 country code or the string from_token.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-an-audiobook)
+    async fn get_an_audiobook(
+        &self,
+        id: AudiobookId<'_>,
+        market: Option<Market>,
+    ) -> ClientResult<FullAudiobook> {
+        let url = Route::Audiobook(id.as_ref()).to_string();
+        let market = self.market_or_default(market);
+        let params = build_map([("market", market)]);
+
+        let result = self.api_get(&url, &params).await;
+        let result = self.check_market_required(&url, market, result)?;
+        convert_result(self.get_config(), &url, &result)
+    }
+
+    /// Get Spotify catalog information for several audiobooks identified by
+    /// their Spotify IDs. The audiobooks that couldn't be found are returned
+    /// as `None`.
+    ///
+    /// Query Parameters
+    /// - ids: Required. A comma-separated list of the Spotify IDs for the audiobooks. Maximum: 50 IDs.
+    /// - market: Optional. An ISO 3166-1 alpha-2 country code or the string from_token.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-multiple-audiobooks)
+    async fn get_several_audiobooks<'a>(
+        &self,
+        ids: impl IntoIterator<Item = AudiobookId<'a>> + MaybeSend + 'a,
+        market: Option<Market>,
+    ) -> ClientResult<Vec<Option<FullAudiobook>>> {
+        let mut audiobooks = Vec::new();
+        for ids in chunk_items(self.get_config(), ids, 50) {
+            let ids = join_ids(ids);
+            let params = build_map([
+                ("ids", Some(&ids)),
+                ("market", self.market_or_default(market)),
+            ]);
+
+            let url = Route::Audiobooks.to_string();
+            let result = self.api_get(&url, &params).await?;
+            audiobooks.extend(
+                convert_result::<AudiobooksPayload>(self.get_config(), &url, &result)?.audiobooks,
+            );
+        }
+        Ok(audiobooks)
+    }
+
+    /// Get Spotify catalog information about an audiobook's chapters.
+    ///
+    /// Path Parameters
+    /// - id: The Spotify ID for the audiobook.
+    ///
+    /// Query Parameters
+    /// - limit: Optional. The maximum number of chapters to return. Default: 20. Minimum: 1. Maximum: 50.
+    /// - offset: Optional. The index of the first chapter to return. Default: 0 (the first object). Use with limit to get the next set of chapters.
+    /// - market: Optional. An ISO 3166-1 alpha-2 country code or the string from_token.
+    ///
+    /// See [`Self::get_audiobook_chapters_manual`] for a manually paginated
+    /// version of this.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-audiobook-chapters)
+    fn get_audiobook_chapters<'b, 'a: 'b>(
+        &'a self,
+        id: AudiobookId<'a>,
+        market: Option<Market>,
+    ) -> Paginator<'b, ClientResult<SimplifiedChapter>> {
+        paginate_with_ctx(
+            (self, id),
+            move |(slf, id), limit, offset| {
+                slf.get_audiobook_chapters_manual(id.as_ref(), market, Some(limit), Some(offset))
+            },
+            self.get_config().pagination_chunks,
+        )
+    }
+
+    /// The manually paginated version of [`Self::get_audiobook_chapters`].
+    async fn get_audiobook_chapters_manual(
+        &self,
+        id: AudiobookId<'_>,
+        market: Option<Market>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> ClientResult<Page<SimplifiedChapter>> {
+        let limit = limit.map(|x| x.to_string());
+        let offset = offset.map(|x| x.to_string());
+        let market = self.market_or_default(market);
+        let params = build_map([
+            ("market", market),
+            ("limit", limit.as_deref()),
+            ("offset", offset.as_deref()),
+        ]);
+
+        let url = Route::AudiobookChapters(id.as_ref()).to_string();
+        let result = self.api_get(&url, &params).await;
+        let result = self.check_market_required(&url, market, result)?;
+        convert_result(self.get_config(), &url, &result)
+    }
+
+    /// Get Spotify catalog information for a single audiobook chapter.
+    ///
+    /// Path Parameters
+    /// - id: The Spotify ID for the chapter.
+    ///
+    /// Query Parameters
+    /// - market: Optional. An ISO 3166-1 alpha-2 country code or the string from_token.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-a-chapter)
+    async fn get_a_chapter(
+        &self,
+        id: ChapterId<'_>,
+        market: Option<Market>,
+    ) -> ClientResult<FullChapter> {
+        let url = Route::Chapter(id.as_ref()).to_string();
+        let market = self.market_or_default(market);
+        let params = build_map([("market", market)]);
+
+        let result = self.api_get(&url, &params).await;
+        let result = self.check_market_required(&url, market, result)?;
+        convert_result(self.get_config(), &url, &result)
+    }
+
+    /// Get Spotify catalog information for several audiobook chapters
+    /// identified by their Spotify IDs. The chapters that couldn't be found
+    /// are returned as `None`.
+    ///
+    /// Query Parameters
+    /// - ids: Required. A comma-separated list of the Spotify IDs for the chapters. Maximum: 50 IDs.
+    /// - market: Optional. An ISO 3166-1 alpha-2 country code or the string from_token.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-several-chapters)
+    async fn get_several_chapters<'a>(
+        &self,
+        ids: impl IntoIterator<Item = ChapterId<'a>> + MaybeSend + 'a,
+        market: Option<Market>,
+    ) -> ClientResult<Vec<Option<FullChapter>>> {
+        let mut chapters = Vec::new();
+        for ids in chunk_items(self.get_config(), ids, 50) {
+            let ids = join_ids(ids);
+            let params = build_map([
+                ("ids", Some(&ids)),
+                ("market", self.market_or_default(market)),
+            ]);
+
+            let url = Route::Chapters.to_string();
+            let result = self.api_get(&url, &params).await?;
+            chapters.extend(
+                convert_result::<ChaptersPayload>(self.get_config(), &url, &result)?.chapters,
+            );
+        }
+        Ok(chapters)
     }
 
     /// Get audio features for a track
@@ -775,9 +1480,9 @@ where
         note = "Spotify has deprecated this endpoint, check documentation for more information"
     )]
     async fn track_features(&self, track_id: TrackId<'_>) -> ClientResult<AudioFeatures> {
-        let url = format!("audio-features/{}", track_id.id());
+        let url = Route::AudioFeature(track_id.as_ref()).to_string();
         let result = self.api_get(&url, &Query::new()).await?;
-        convert_result(&result)
+        convert_result(self.get_config(), &url, &result)
     }
 
     /// Get Audio Features for Several Tracks
@@ -785,6 +1490,10 @@ where
     /// Parameters:
     /// - tracks a list of track URIs, URLs or IDs
     ///
+    /// The tracks without audio features are skipped, so the result doesn't
+    /// necessarily line up with the IDs. See [`Self::tracks_features_map`] to
+    /// look them up by ID instead.
+    ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-several-audio-features)
     #[deprecated(
         since = "0.14.0",
@@ -792,19 +1501,45 @@ where
     )]
     async fn tracks_features<'a>(
         &self,
-        track_ids: impl IntoIterator<Item = TrackId<'a>> + Send + 'a,
+        track_ids: impl IntoIterator<Item = TrackId<'a>> + MaybeSend + 'a,
     ) -> ClientResult<Option<Vec<AudioFeatures>>> {
-        let url = format!("audio-features/?ids={}", join_ids(track_ids));
-
-        let result = self.api_get(&url, &Query::new()).await?;
-        if result.is_empty() {
-            Ok(None)
-        } else if let Some(payload) = convert_result::<Option<AudioFeaturesPayload>>(&result)? {
-            let audio_features = payload.audio_features.into_iter().flatten().collect();
-            Ok(Some(audio_features))
-        } else {
-            Ok(None)
+        let mut audio_features: Option<Vec<AudioFeatures>> = None;
+        for ids in chunk_items(self.get_config(), track_ids, 100) {
+            let url = Route::AudioFeatures(&join_ids(ids)).to_string();
+
+            let result = self.api_get(&url, &Query::new()).await?;
+            if result.is_empty() {
+                continue;
+            }
+            if let Some(payload) =
+                convert_result::<Option<AudioFeaturesPayload>>(self.get_config(), &url, &result)?
+            {
+                audio_features
+                    .get_or_insert_with(Vec::new)
+                    .extend(payload.audio_features.into_iter().flatten());
+            }
         }
+        Ok(audio_features)
+    }
+
+    /// The same as [`Self::tracks_features`], but the audio features are
+    /// returned by the ID of their track. The tracks without audio features
+    /// are missing from the map.
+    #[deprecated(
+        since = "0.14.0",
+        note = "Spotify has deprecated this endpoint, check documentation for more information"
+    )]
+    async fn tracks_features_map<'a>(
+        &self,
+        track_ids: impl IntoIterator<Item = TrackId<'a>> + MaybeSend + 'a,
+    ) -> ClientResult<HashMap<TrackId<'static>, AudioFeatures>> {
+        #[allow(deprecated)]
+        let audio_features = self.tracks_features(track_ids).await?;
+        Ok(audio_features
+            .unwrap_or_default()
+            .into_iter()
+            .map(|features| (features.id.clone(), features))
+            .collect())
     }
 
     /// Get Audio Analysis for a Track
@@ -818,9 +1553,90 @@ where
         note = "Spotify has deprecated this endpoint, check documentation for more information"
     )]
     async fn track_analysis(&self, track_id: TrackId<'_>) -> ClientResult<AudioAnalysis> {
-        let url = format!("audio-analysis/{}", track_id.id());
+        let url = Route::AudioAnalysis(track_id.as_ref()).to_string();
         let result = self.api_get(&url, &Query::new()).await?;
-        convert_result(&result)
+        convert_result(self.get_config(), &url, &result)
+    }
+
+    /// Get only the sections of the Audio Analysis for a Track. The rest of
+    /// the analysis, which may be large, is skipped when parsing it.
+    ///
+    /// Parameters:
+    /// - track_id - a track URI, URL or ID
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-audio-analysis)
+    #[deprecated(
+        since = "0.14.0",
+        note = "Spotify has deprecated this endpoint, check documentation for more information"
+    )]
+    async fn track_analysis_sections(
+        &self,
+        track_id: TrackId<'_>,
+    ) -> ClientResult<Vec<AudioAnalysisSection>> {
+        let url = Route::AudioAnalysis(track_id.as_ref()).to_string();
+        let result = self.api_get(&url, &Query::new()).await?;
+        convert_result::<AudioAnalysisSectionsPayload>(self.get_config(), &url, &result)
+            .map(|x| x.sections)
+    }
+
+    /// Get only the segments of the Audio Analysis for a Track.
+    ///
+    /// Parameters:
+    /// - track_id - a track URI, URL or ID
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-audio-analysis)
+    #[deprecated(
+        since = "0.14.0",
+        note = "Spotify has deprecated this endpoint, check documentation for more information"
+    )]
+    async fn track_analysis_segments(
+        &self,
+        track_id: TrackId<'_>,
+    ) -> ClientResult<Vec<AudioAnalysisSegment>> {
+        let url = Route::AudioAnalysis(track_id.as_ref()).to_string();
+        let result = self.api_get(&url, &Query::new()).await?;
+        convert_result::<AudioAnalysisSegmentsPayload>(self.get_config(), &url, &result)
+            .map(|x| x.segments)
+    }
+
+    /// Get only the beats of the Audio Analysis for a Track.
+    ///
+    /// Parameters:
+    /// - track_id - a track URI, URL or ID
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-audio-analysis)
+    #[deprecated(
+        since = "0.14.0",
+        note = "Spotify has deprecated this endpoint, check documentation for more information"
+    )]
+    async fn track_analysis_beats(&self, track_id: TrackId<'_>) -> ClientResult<Vec<TimeInterval>> {
+        let url = Route::AudioAnalysis(track_id.as_ref()).to_string();
+        let result = self.api_get(&url, &Query::new()).await?;
+        convert_result::<AudioAnalysisBeatsPayload>(self.get_config(), &url, &result)
+            .map(|x| x.beats)
+    }
+
+    /// Get a single category used to tag items in Spotify
+    ///
+    /// Parameters:
+    /// - category_id - The category id.
+    /// - locale - The desired language of the category's name and icons,
+    ///   consisting of an ISO 639 language code and an ISO 3166-1 alpha-2
+    ///   country code, joined by an underscore.
+    /// - country - An ISO 3166-1 alpha-2 country code or string from_token.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-a-category)
+    async fn category(
+        &self,
+        category_id: CategoryId<'_>,
+        locale: Option<&str>,
+        country: Option<Market>,
+    ) -> ClientResult<Category> {
+        let params = build_map([("locale", locale), ("country", country.map(Into::into))]);
+
+        let url = Route::Category(category_id.as_ref()).to_string();
+        let result = self.api_get(&url, &params).await?;
+        convert_result(self.get_config(), &url, &result)
     }
 
     /// Get a list of new album releases featured in Spotify
@@ -865,8 +1681,9 @@ where
             ("limit", limit.as_deref()),
             ("offset", offset.as_deref()),
         ]);
-        let result = self.api_get("browse/categories", &params).await?;
-        convert_result::<PageCategory>(&result).map(|x| x.categories)
+        let url = Route::Categories.to_string();
+        let result = self.api_get(&url, &params).await?;
+        convert_result::<PageCategory>(self.get_config(), &url, &result).map(|x| x.categories)
     }
 
     /// Get a list of playlists in a category in Spotify
@@ -885,12 +1702,18 @@ where
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-a-categories-playlists)
     fn category_playlists<'b, 'a: 'b>(
         &'a self,
-        category_id: &'a str,
+        category_id: CategoryId<'a>,
         country: Option<Market>,
     ) -> Paginator<'b, ClientResult<SimplifiedPlaylist>> {
-        paginate(
-            move |limit, offset| {
-                self.category_playlists_manual(category_id, country, Some(limit), Some(offset))
+        paginate_with_ctx(
+            (self, category_id),
+            move |(slf, category_id), limit, offset| {
+                slf.category_playlists_manual(
+                    category_id.as_ref(),
+                    country,
+                    Some(limit),
+                    Some(offset),
+                )
             },
             self.get_config().pagination_chunks,
         )
@@ -899,7 +1722,7 @@ where
     /// The manually paginated version of [`Self::category_playlists`].
     async fn category_playlists_manual(
         &self,
-        category_id: &str,
+        category_id: CategoryId<'_>,
         country: Option<Market>,
         limit: Option<u32>,
         offset: Option<u32>,
@@ -912,9 +1735,9 @@ where
             ("offset", offset.as_deref()),
         ]);
 
-        let url = format!("browse/categories/{category_id}/playlists");
+        let url = Route::CategoryPlaylists(category_id.as_ref()).to_string();
         let result = self.api_get(&url, &params).await?;
-        convert_result::<CategoryPlaylists>(&result).map(|x| x.playlists)
+        convert_result::<CategoryPlaylists>(self.get_config(), &url, &result).map(|x| x.playlists)
     }
 
     /// Get a list of Spotify featured playlists.
@@ -953,8 +1776,9 @@ where
             ("offset", offset.as_deref()),
         ]);
 
-        let result = self.api_get("browse/featured-playlists", &params).await?;
-        convert_result(&result)
+        let url = Route::FeaturedPlaylists.to_string();
+        let result = self.api_get(&url, &params).await?;
+        convert_result(self.get_config(), &url, &result)
     }
 
     /// Get a list of new album releases featured in Spotify.
@@ -995,8 +1819,9 @@ where
             ("offset", offset.as_deref()),
         ]);
 
-        let result = self.api_get("browse/new-releases", &params).await?;
-        convert_result::<PageSimplifiedAlbums>(&result).map(|x| x.albums)
+        let url = Route::NewReleases.to_string();
+        let result = self.api_get(&url, &params).await?;
+        convert_result::<PageSimplifiedAlbums>(self.get_config(), &url, &result).map(|x| x.albums)
     }
 
     /// Get Recommendations Based on Seeds
@@ -1018,10 +1843,10 @@ where
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-recommendations)
     async fn recommendations<'a>(
         &self,
-        attributes: impl IntoIterator<Item = RecommendationsAttribute> + Send + 'a,
-        seed_artists: Option<impl IntoIterator<Item = ArtistId<'a>> + Send + 'a>,
-        seed_genres: Option<impl IntoIterator<Item = &'a str> + Send + 'a>,
-        seed_tracks: Option<impl IntoIterator<Item = TrackId<'a>> + Send + 'a>,
+        attributes: impl IntoIterator<Item = RecommendationsAttribute> + MaybeSend + 'a,
+        seed_artists: Option<impl IntoIterator<Item = ArtistId<'a>> + MaybeSend + 'a>,
+        seed_genres: Option<impl IntoIterator<Item = &'a str> + MaybeSend + 'a>,
+        seed_tracks: Option<impl IntoIterator<Item = TrackId<'a>> + MaybeSend + 'a>,
         market: Option<Market>,
         limit: Option<u32>,
     ) -> ClientResult<Recommendations> {
@@ -1033,7 +1858,7 @@ where
             ("seed_artists", seed_artists.as_deref()),
             ("seed_genres", seed_genres.as_deref()),
             ("seed_tracks", seed_tracks.as_deref()),
-            ("market", market.map(Into::into)),
+            ("market", self.market_or_default(market)),
             ("limit", limit.as_deref()),
         ]);
 
@@ -1050,8 +1875,9 @@ where
         // And finally adding all of them to the payload
         params.extend(borrowed_attributes);
 
-        let result = self.api_get("recommendations", &params).await?;
-        convert_result(&result)
+        let url = Route::Recommendations.to_string();
+        let result = self.api_get(&url, &params).await?;
+        convert_result(self.get_config(), &url, &result)
     }
 
     /// Get full details of the items of a playlist owned by a user.
@@ -1062,6 +1888,9 @@ where
     /// - limit - the maximum number of tracks to return
     /// - offset - the index of the first track to return
     /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
+    /// - additional_types - the types of items supported besides tracks. If
+    ///   `None`, both tracks and episodes are requested, so that the episodes
+    ///   are returned as [`PlayableItem::Episode`].
     ///
     /// See [`Self::playlist_items_manual`] for a manually paginated version of
     /// this.
@@ -1072,6 +1901,7 @@ where
         playlist_id: PlaylistId<'a>,
         fields: Option<&'a str>,
         market: Option<Market>,
+        additional_types: Option<&'a [AdditionalType]>,
     ) -> Paginator<'b, ClientResult<PlaylistItem>> {
         paginate_with_ctx(
             (self, playlist_id, fields),
@@ -1080,6 +1910,7 @@ where
                     playlist_id.as_ref(),
                     *fields,
                     market,
+                    additional_types,
                     Some(limit),
                     Some(offset),
                 )
@@ -1094,21 +1925,166 @@ where
         playlist_id: PlaylistId<'_>,
         fields: Option<&str>,
         market: Option<Market>,
+        additional_types: Option<&[AdditionalType]>,
         limit: Option<u32>,
         offset: Option<u32>,
     ) -> ClientResult<Page<PlaylistItem>> {
         let limit = limit.map(|s| s.to_string());
         let offset = offset.map(|s| s.to_string());
+        let additional_types = additional_types_param(additional_types);
         let params = build_map([
             ("fields", fields),
-            ("market", market.map(Into::into)),
+            ("market", self.market_or_default(market)),
+            ("additional_types", Some(&additional_types)),
+            ("limit", limit.as_deref()),
+            ("offset", offset.as_deref()),
+        ]);
+
+        let url = Route::PlaylistItems(playlist_id.as_ref()).to_string();
+        let result = self.api_get(&url, &params).await?;
+        convert_result(self.get_config(), &url, &result)
+    }
+
+    /// Get the items of a playlist with only the given fields, which can't be
+    /// deserialized into [`PlaylistItem`] with [`Self::playlist_items`]. The
+    /// fields needed for the pagination are requested as well.
+    ///
+    /// Parameters:
+    /// - playlist_id - the id of the playlist
+    /// - fields - which fields to return, e.g., `"items(added_at,track(name))"`
+    /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
+    ///
+    /// See [`Self::playlist_items_with_fields_manual`] for a manually paginated
+    /// version of this.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-playlists-tracks)
+    fn playlist_items_with_fields<'b, 'a: 'b>(
+        &'a self,
+        playlist_id: PlaylistId<'a>,
+        fields: &'a str,
+        market: Option<Market>,
+    ) -> Paginator<'b, ClientResult<PartialPlaylistItem>> {
+        paginate_with_ctx(
+            (self, playlist_id, fields),
+            move |(slf, playlist_id, fields), limit, offset| {
+                slf.playlist_items_with_fields_manual(
+                    playlist_id.as_ref(),
+                    fields,
+                    market,
+                    Some(limit),
+                    Some(offset),
+                )
+            },
+            self.get_config().pagination_chunks,
+        )
+    }
+
+    /// The manually paginated version of [`Self::playlist_items_with_fields`].
+    /// The page fields that weren't requested are left empty.
+    async fn playlist_items_with_fields_manual(
+        &self,
+        playlist_id: PlaylistId<'_>,
+        fields: &str,
+        market: Option<Market>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> ClientResult<Page<PartialPlaylistItem>> {
+        let fields = with_page_fields(fields);
+        let limit = limit.map(|s| s.to_string());
+        let offset = offset.map(|s| s.to_string());
+        let params = build_map([
+            ("fields", Some(fields.as_str())),
+            ("market", self.market_or_default(market)),
             ("limit", limit.as_deref()),
             ("offset", offset.as_deref()),
         ]);
 
-        let url = format!("playlists/{}/tracks", playlist_id.id());
+        let url = Route::PlaylistItems(playlist_id.as_ref()).to_string();
         let result = self.api_get(&url, &params).await?;
-        convert_result(&result)
+        convert_result::<PartialPage>(self.get_config(), &url, &result).map(Into::into)
+    }
+
+    /// Gets the current snapshot ID of a playlist, without the rest of its
+    /// fields.
+    ///
+    /// Parameters:
+    /// - playlist_id - the id of the playlist
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-playlist)
+    async fn playlist_snapshot_id(
+        &self,
+        playlist_id: PlaylistId<'_>,
+    ) -> ClientResult<PlaylistSnapshotId> {
+        let url = Route::Playlist(playlist_id.as_ref()).to_string();
+        let params = build_map([("fields", Some("snapshot_id"))]);
+
+        let result = self.api_get(&url, &params).await?;
+        convert_result(self.get_config(), &url, &result)
+    }
+
+    /// Reads all the items of a playlist from the same snapshot, which
+    /// [`Self::playlist_items`] can't guarantee for large playlists if they're
+    /// modified in the meantime. Returns the snapshot ID along with the items.
+    ///
+    /// The snapshot ID is requested before reading the first page and again
+    /// after the last one. If it changed, [`ClientError::SnapshotChanged`] is
+    /// returned or the read is restarted, depending on `on_change`. Each read
+    /// thus takes two more requests than the pages of the playlist, which
+    /// count towards the rate limits as well.
+    ///
+    /// Parameters:
+    /// - playlist_id - the id of the playlist
+    /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
+    /// - on_change - what to do when the playlist is modified during the read
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-playlists-tracks)
+    async fn playlist_items_snapshot(
+        &self,
+        playlist_id: PlaylistId<'_>,
+        market: Option<Market>,
+        on_change: OnSnapshotChange,
+    ) -> ClientResult<(PlaylistSnapshotId, Vec<PlaylistItem>)> {
+        let mut restarts = 0;
+        loop {
+            let snapshot_id = self.playlist_snapshot_id(playlist_id.as_ref()).await?;
+
+            let mut items = Vec::new();
+            loop {
+                let page = self
+                    .playlist_items_manual(
+                        playlist_id.as_ref(),
+                        None,
+                        market,
+                        None,
+                        Some(100),
+                        Some(items.len() as u32),
+                    )
+                    .await?;
+
+                let done = page.next.is_none() || page.items.is_empty();
+                items.extend(page.items);
+                if done {
+                    break;
+                }
+            }
+
+            let current = self.playlist_snapshot_id(playlist_id.as_ref()).await?;
+            if current == snapshot_id {
+                return Ok((snapshot_id, items));
+            }
+            match on_change {
+                OnSnapshotChange::Restart { max_restarts } if restarts < max_restarts => {
+                    log::info!("Playlist modified while reading it, restarting");
+                    restarts += 1;
+                }
+                _ => {
+                    return Err(ClientError::SnapshotChanged {
+                        expected: snapshot_id.into_string(),
+                        found: current.into_string(),
+                    })
+                }
+            }
+        }
     }
 
     /// Gets playlists of a user.
@@ -1146,8 +2122,122 @@ where
         let offset = offset.map(|s| s.to_string());
         let params = build_map([("limit", limit.as_deref()), ("offset", offset.as_deref())]);
 
-        let url = format!("users/{}/playlists", user_id.id());
+        let url = Route::UserPlaylists(user_id.as_ref()).to_string();
         let result = self.api_get(&url, &params).await?;
-        convert_result(&result)
+        convert_result(self.get_config(), &url, &result)
+    }
+}
+
+/// A page requested with a `fields` filter, where any of its fields may be
+/// missing.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct PartialPage {
+    href: String,
+    items: Vec<PartialPlaylistItem>,
+    limit: u32,
+    next: Option<String>,
+    offset: u32,
+    previous: Option<String>,
+    total: u32,
+}
+
+impl From<PartialPage> for Page<PartialPlaylistItem> {
+    fn from(page: PartialPage) -> Self {
+        Self {
+            href: page.href,
+            items: page.items,
+            limit: page.limit,
+            next: page.next,
+            offset: page.offset,
+            previous: page.previous,
+            total: page.total,
+        }
+    }
+}
+
+/// Adds the fields needed for the pagination to a `fields` filter, unless
+/// they're already requested at the top level.
+fn with_page_fields(fields: &str) -> String {
+    let mut depth = 0;
+    let mut top_level = Vec::new();
+    let mut start = 0;
+    for (i, c) in fields.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                top_level.push(fields[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    top_level.push(fields[start..].trim());
+
+    let mut fields = fields.trim().to_owned();
+    for required in ["next", "total"] {
+        if !top_level.contains(&required) {
+            if !fields.is_empty() {
+                fields.push(',');
+            }
+            fields.push_str(required);
+        }
+    }
+    fields
+}
+
+/// The release date of the episode, with the parts missing from its precision
+/// defaulted to the first month or day, so that the dates of different
+/// precisions can be compared. It's `None` if the date is malformed.
+fn release_date(episode: &SimplifiedEpisode) -> Option<NaiveDate> {
+    let date = &episode.release_date;
+    match episode.release_date_precision {
+        DatePrecision::Year => NaiveDate::from_ymd_opt(date.parse().ok()?, 1, 1),
+        DatePrecision::Month => NaiveDate::parse_from_str(&format!("{date}-01"), "%Y-%m-%d").ok(),
+        DatePrecision::Day => NaiveDate::parse_from_str(date, "%Y-%m-%d").ok(),
+    }
+}
+
+/// Fetches the first `latest` episodes of a show, which are the newest ones.
+#[maybe_async]
+async fn latest_episodes<C: BaseClient>(
+    client: &C,
+    id: ShowId<'_>,
+    latest: u32,
+    market: Option<Market>,
+) -> ClientResult<Vec<SimplifiedEpisode>> {
+    let mut episodes = Vec::new();
+    while (episodes.len() as u32) < latest {
+        let offset = episodes.len() as u32;
+        let limit = (latest - offset).min(50);
+        let page = client
+            .get_shows_episodes_manual(id.as_ref(), market, Some(limit), Some(offset))
+            .await?;
+
+        let done = page.next.is_none() || page.items.is_empty();
+        episodes.extend(page.items);
+        if done {
+            break;
+        }
+    }
+    Ok(episodes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_with_page_fields() {
+        assert_eq!(
+            with_page_fields("items(added_at,track(name,next))"),
+            "items(added_at,track(name,next)),next,total"
+        );
+        assert_eq!(
+            with_page_fields("total, items(track(id)),next"),
+            "total, items(track(id)),next"
+        );
+        assert_eq!(with_page_fields(""), "next,total");
     }
 }
@@ -1,56 +1,425 @@
 mod base;
 mod oauth;
 pub mod pagination;
+mod player;
+pub(crate) mod route;
 
 pub use base::BaseClient;
-pub use oauth::OAuthClient;
+pub use oauth::{CurrentUserCache, OAuthClient, SelectedRedirectUri};
+pub use player::PlayerCommand;
 
-use crate::ClientResult;
+use crate::{
+    http::HttpError,
+    model::{ApiError, ApiErrorResponse},
+    ClientError, ClientResult, Config, ParseJsonError,
+};
 
-use std::fmt::Write as _;
+use std::{fmt::Write as _, time::Duration};
 
-use serde::Deserialize;
+use maybe_async::maybe_async;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Marker for the values that the endpoints may hold across `.await` points,
+/// which is equivalent to [`Send`]. On WebAssembly, where futures don't need
+/// to be sent between threads, the `wasm-single-thread` feature implements it
+/// for every type, so that e.g. iterators holding `Rc`s may be passed.
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm-single-thread")))]
+pub trait MaybeSend: Send {}
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm-single-thread")))]
+impl<T: Send + ?Sized> MaybeSend for T {}
+#[cfg(all(target_arch = "wasm32", feature = "wasm-single-thread"))]
+pub trait MaybeSend {}
+#[cfg(all(target_arch = "wasm32", feature = "wasm-single-thread"))]
+impl<T: ?Sized> MaybeSend for T {}
+
+/// Same as [`MaybeSend`], but for [`Sync`].
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm-single-thread")))]
+pub trait MaybeSync: Sync {}
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm-single-thread")))]
+impl<T: Sync + ?Sized> MaybeSync for T {}
+#[cfg(all(target_arch = "wasm32", feature = "wasm-single-thread"))]
+pub trait MaybeSync {}
+#[cfg(all(target_arch = "wasm32", feature = "wasm-single-thread"))]
+impl<T: ?Sized> MaybeSync for T {}
 
 /// Converts a JSON response from Spotify into its model.
-pub(crate) fn convert_result<'a, T: Deserialize<'a>>(input: &'a str) -> ClientResult<T> {
-    serde_json::from_str::<T>(input).map_err(Into::into)
+///
+/// If it can't be parsed and [`Config::on_parse_error`] is set, the JSON
+/// patched by the handler is parsed instead. The original error is returned
+/// when it declines to patch it, or when the patched JSON can't be parsed
+/// either, along with the `endpoint` and the JSON around the failure.
+pub(crate) fn convert_result<T: DeserializeOwned>(
+    config: &Config,
+    endpoint: &str,
+    input: &str,
+) -> ClientResult<T> {
+    let err = match serde_json::from_str::<T>(input) {
+        Ok(result) => return Ok(result),
+        Err(err) => err,
+    };
+
+    let type_name = std::any::type_name::<T>();
+    let patched = match &config.on_parse_error {
+        Some(handler) => (handler.0)(input, type_name),
+        None => None,
+    };
+    match patched.map(serde_json::from_value::<T>) {
+        Some(Ok(result)) => {
+            log::warn!("Recovered from a parse error for {type_name}: {err}");
+            return Ok(result);
+        }
+        Some(Err(patched_err)) => {
+            log::warn!("The patched JSON for {type_name} couldn't be parsed: {patched_err}");
+        }
+        None => {}
+    }
+
+    Err(parse_error(config, endpoint, input, err))
 }
 
-/// Append device ID to an API path.
-pub(crate) fn append_device_id(path: &str, device_id: Option<&str>) -> String {
-    let mut new_path = path.to_string();
-    if let Some(device_id) = device_id {
-        if path.contains('?') {
-            let _ = write!(new_path, "&device_id={device_id}");
-        } else {
-            let _ = write!(new_path, "?device_id={device_id}");
+/// Converts a JSON response from Spotify into a model that borrows from it,
+/// like the ones in [`model::borrowed`](crate::model::borrowed). Unlike
+/// [`convert_result`], [`Config::on_parse_error`] isn't supported, since the
+/// patched JSON wouldn't outlive the model.
+#[cfg(feature = "model-borrowed")]
+pub(crate) fn convert_result_borrowed<'a, T: serde::Deserialize<'a>>(
+    config: &Config,
+    endpoint: &str,
+    input: &'a str,
+) -> ClientResult<T> {
+    serde_json::from_str::<T>(input).map_err(|err| parse_error(config, endpoint, input, err))
+}
+
+/// The error for a response of the `endpoint` that couldn't be parsed.
+fn parse_error(
+    config: &Config,
+    endpoint: &str,
+    input: &str,
+    err: serde_json::Error,
+) -> ClientError {
+    ClientError::ParseJson(Box::new(ParseJsonError {
+        endpoint: Some(endpoint.to_owned()),
+        snippet: json_snippet(input, &err),
+        body: config
+            .parse_error_body
+            .then(|| redact_body(input, usize::MAX)),
+        source: err,
+    }))
+}
+
+/// The JSON around the position of a parse error, at most `SNIPPET_RADIUS`
+/// bytes before and after it, or `None` if the error has no position.
+fn json_snippet(input: &str, err: &serde_json::Error) -> Option<String> {
+    const SNIPPET_RADIUS: usize = 60;
+
+    if err.line() == 0 {
+        return None;
+    }
+    let line_start = input
+        .split_inclusive('\n')
+        .take(err.line() - 1)
+        .map(str::len)
+        .sum::<usize>();
+    let position = (line_start + err.column()).min(input.len());
+
+    let mut start = position.saturating_sub(SNIPPET_RADIUS);
+    while !input.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (position + SNIPPET_RADIUS).min(input.len());
+    while !input.is_char_boundary(end) {
+        end += 1;
+    }
+
+    let mut snippet = input[start..end]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if start > 0 {
+        snippet.insert_str(0, "...");
+    }
+    if end < input.len() {
+        snippet.push_str("...");
+    }
+    Some(snippet)
+}
+
+/// Builds a [`ClientError::Api`] from an unsuccessful response.
+pub(crate) fn api_error(status: u16, body: &str, retry_after: Option<Duration>) -> ClientError {
+    let (status, message, reason) = match serde_json::from_str::<ApiErrorResponse>(body) {
+        Ok(ApiErrorResponse {
+            error: ApiError::Regular { status, message },
+        }) => (status, message, None),
+        Ok(ApiErrorResponse {
+            error:
+                ApiError::Player {
+                    status,
+                    message,
+                    reason,
+                },
+        }) => (status, message, Some(reason)),
+        Err(_) => (status, body.trim().to_owned(), None),
+    };
+    ClientError::Api {
+        status,
+        message,
+        reason,
+        retry_after,
+    }
+}
+
+/// Converts the result of a request to the Web API, reading the body of the
+/// response to build a [`ClientError::Api`] if it was unsuccessful.
+#[maybe_async]
+pub(crate) async fn convert_api_response(
+    result: Result<String, HttpError>,
+) -> ClientResult<String> {
+    match result {
+        Ok(response) => Ok(response),
+        Err(err) => {
+            let retry_after = err.retry_after();
+            match err.into_status_and_body().await {
+                Ok((status, body)) => Err(api_error(status, &body, retry_after)),
+                Err(err) => Err(err.into()),
+            }
         }
     }
-    new_path
+}
+
+/// Logs the response to a request if [`Config::log_response_bodies`] is
+/// enabled.
+pub(crate) fn log_response(
+    config: &Config,
+    method: &str,
+    url: &str,
+    result: &ClientResult<String>,
+) {
+    let logging = match &config.log_response_bodies {
+        Some(logging) if logging.sample() => logging,
+        _ => return,
+    };
+
+    match result {
+        Ok(body) => log::info!(
+            "Response to {method} {url}: {}",
+            redact_body(body, logging.max_len)
+        ),
+        Err(err) => log::info!("Response to {method} {url} failed: {err}"),
+    }
+}
+
+/// Removes the tokens from a response body and truncates it to `max_len`
+/// bytes.
+fn redact_body(body: &str, max_len: usize) -> String {
+    fn redact(value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    if key.ends_with("token") && value.is_string() {
+                        *value = Value::from("<redacted>");
+                    } else {
+                        redact(value);
+                    }
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(redact),
+            _ => {}
+        }
+    }
+
+    let mut body = match serde_json::from_str::<Value>(body) {
+        Ok(mut value) => {
+            redact(&mut value);
+            value.to_string()
+        }
+        Err(_) => body.to_owned(),
+    };
+
+    if body.len() > max_len {
+        let mut end = max_len;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        let len = body.len();
+        body.truncate(end);
+        let _ = write!(body, "... ({len} bytes)");
+    }
+    body
+}
+
+/// Splits the items into batches of at most `max` elements when
+/// [`Config::auto_chunk_requests`] is enabled, so that each one can be sent in
+/// a separate request. Otherwise, all of them are returned in a single batch.
+///
+/// There's always at least one batch, so that the request is still made when
+/// there are no items.
+pub(crate) fn chunk_items<T>(
+    config: &Config,
+    items: impl IntoIterator<Item = T>,
+    max: usize,
+) -> Vec<Vec<T>> {
+    let mut items = items.into_iter().collect::<Vec<_>>();
+    if !config.auto_chunk_requests || items.len() <= max {
+        return vec![items];
+    }
+
+    let mut chunks = Vec::with_capacity((items.len() + max - 1) / max);
+    while items.len() > max {
+        let rest = items.split_off(max);
+        chunks.push(items);
+        items = rest;
+    }
+    chunks.push(items);
+    chunks
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{model::Token, scopes, ClientCredsSpotify, Config};
+    use crate::{
+        model::{PlayerErrorReason, Token},
+        scopes, ClientCredsSpotify, Config,
+    };
     use chrono::{prelude::*, Duration};
 
     #[test]
-    fn test_append_device_id_without_question_mark() {
-        let path = "me/player/play";
-        let device_id = Some("fdafdsadfa");
-        let new_path = append_device_id(path, device_id);
-        assert_eq!(new_path, "me/player/play?device_id=fdafdsadfa");
+    fn test_api_error() {
+        let body = r#"{"error": {"status": 404, "message": "Resource not found"}}"#;
+        match api_error(404, body, None) {
+            ClientError::Api {
+                status,
+                message,
+                reason,
+                retry_after,
+            } => {
+                assert_eq!(status, 404);
+                assert_eq!(message, "Resource not found");
+                assert_eq!(reason, None);
+                assert_eq!(retry_after, None);
+            }
+            err => panic!("unexpected error: {err}"),
+        }
+
+        let body = r#"{
+            "error": {
+                "status": 403,
+                "message": "Player command failed: Premium required",
+                "reason": "PREMIUM_REQUIRED"
+            }
+        }"#;
+        let err = api_error(403, body, None);
+        assert_eq!(
+            err.to_string(),
+            "api error 403 (PREMIUM_REQUIRED): Player command failed: Premium required"
+        );
+        assert!(matches!(
+            err,
+            ClientError::Api {
+                reason: Some(PlayerErrorReason::PremiumRequired),
+                ..
+            }
+        ));
+
+        // The reasons that aren't known yet are still recognized as such
+        let body = r#"{"error": {"status": 403, "message": "", "reason": "SOMETHING_NEW"}}"#;
+        assert!(matches!(
+            api_error(403, body, None),
+            ClientError::Api {
+                reason: Some(PlayerErrorReason::Unknown),
+                ..
+            }
+        ));
     }
 
     #[test]
-    fn test_append_device_id_with_question_mark() {
-        let path = "me/player/shuffle?state=true";
-        let device_id = Some("fdafdsadfa");
-        let new_path = append_device_id(path, device_id);
+    fn test_api_error_retry_after() {
+        let body = r#"{"error": {"status": 429, "message": "API rate limit exceeded"}}"#;
+        let err = api_error(429, body, Some(std::time::Duration::from_secs(3)));
+        assert!(matches!(
+            err,
+            ClientError::Api {
+                status: 429,
+                retry_after: Some(delay),
+                ..
+            } if delay == std::time::Duration::from_secs(3)
+        ));
+    }
+
+    #[test]
+    fn test_api_error_unparseable() {
+        match api_error(502, "Bad Gateway\n", None) {
+            ClientError::Api {
+                status,
+                message,
+                reason,
+                ..
+            } => {
+                assert_eq!(status, 502);
+                assert_eq!(message, "Bad Gateway");
+                assert_eq!(reason, None);
+            }
+            err => panic!("unexpected error: {err}"),
+        }
+    }
+
+    #[test]
+    fn test_redact_body() {
+        let body = r#"{"access_token": "secret", "expires_in": 3600, "nested": [{"refresh_token": "secret"}]}"#;
+        let redacted = redact_body(body, 1000);
+        assert!(!redacted.contains("secret"));
+        assert!(redacted.contains(r#""access_token":"<redacted>""#));
+        assert!(redacted.contains(r#""expires_in":3600"#));
+
+        assert_eq!(redact_body("Bad Gateway", 1000), "Bad Gateway");
+        assert_eq!(redact_body("añb", 2), "a... (4 bytes)");
+    }
+
+    #[test]
+    fn test_json_snippet() {
+        let input = "{\n  \"name\": \"Band of Horses\",\n  \"followers\": {\"total\": 1.5}\n}";
+        let err = serde_json::from_str::<crate::model::FullArtist>(input).unwrap_err();
+        let snippet = json_snippet(input, &err).unwrap();
+        assert!(snippet.contains(r#""followers": {"total": 1.5}"#));
+        assert!(!snippet.contains('\n'));
+
+        let input = format!(
+            r#"{{"padding": "{}", "total": 1.5, "rest": "{}"}}"#,
+            "a".repeat(100),
+            "b".repeat(100)
+        );
+        let err = serde_json::from_str::<crate::model::Followers>(&input).unwrap_err();
+        let snippet = json_snippet(&input, &err).unwrap();
+        assert!(snippet.starts_with("...") && snippet.ends_with("..."));
+        assert!(snippet.contains(r#""total": 1.5"#));
+    }
+
+    #[test]
+    fn test_chunk_items() {
+        let mut config = Config::default();
+        assert_eq!(chunk_items(&config, 0..5, 2), vec![vec![0, 1, 2, 3, 4]]);
+
+        config.auto_chunk_requests = true;
+        assert_eq!(
+            chunk_items(&config, 0..5, 2),
+            vec![vec![0, 1], vec![2, 3], vec![4]]
+        );
+        assert_eq!(chunk_items(&config, 0..4, 2), vec![vec![0, 1], vec![2, 3]]);
+        assert_eq!(chunk_items(&config, 0..0, 2), vec![Vec::<i32>::new()]);
+    }
+
+    #[test]
+    fn test_market_or_default() {
+        use crate::model::{Country, Market};
+
+        let mut spotify = ClientCredsSpotify::default();
+        assert_eq!(spotify.market_or_default(None), None);
+
+        spotify.config.default_market = Some(Market::FromToken);
+        assert_eq!(spotify.market_or_default(None), Some("from_token"));
         assert_eq!(
-            new_path,
-            "me/player/shuffle?state=true&device_id=fdafdsadfa"
+            spotify.market_or_default(Some(Market::Country(Country::Spain))),
+            Some("ES")
         );
     }
 
@@ -110,6 +479,126 @@ mod test {
         );
     }
 
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_token_refresh_hooks() {
+        use crate::{Credentials, TokenRefreshHooks};
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+
+        let started = Arc::new(AtomicUsize::new(0));
+        let failed = Arc::new(AtomicUsize::new(0));
+        let hooks = TokenRefreshHooks {
+            on_token_refresh_start: Some(Box::new({
+                let started = Arc::clone(&started);
+                move || {
+                    started.fetch_add(1, Ordering::SeqCst);
+                }
+            })),
+            on_token_refresh_success: Some(Box::new(|_| panic!("the refresh can't succeed"))),
+            on_token_refresh_failure: Some(Box::new({
+                let failed = Arc::clone(&failed);
+                move |_| {
+                    failed.fetch_add(1, Ordering::SeqCst);
+                }
+            })),
+        };
+        // Nothing listens on the discard port, so the refresh fails right away
+        let config = Config {
+            auth_base_url: String::from("http://127.0.0.1:9/"),
+            token_refresh_hooks: Arc::new(hooks),
+            ..Default::default()
+        };
+        let spotify = ClientCredsSpotify::with_config(Credentials::new("id", "secret"), config);
+
+        let result = spotify.refresh_token().await;
+        assert!(result.is_err());
+        assert_eq!(started.load(Ordering::SeqCst), 1);
+        assert_eq!(failed.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_try_with_config() {
+        use crate::{
+            http::ProxyConfig, AuthCodeSpotify, Credentials, OAuth, SpotifyBuilder, Token,
+        };
+
+        let config = Config {
+            proxy: Some(ProxyConfig::new("ftp://[")),
+            ..Default::default()
+        };
+        let result =
+            ClientCredsSpotify::try_with_config(Credentials::new("id", "secret"), config.clone());
+        assert!(matches!(result, Err(ClientError::Http(_))));
+
+        // Nor do the builder nor the constructors from a token panic
+        let builder = || {
+            SpotifyBuilder::try_new()
+                .unwrap()
+                .credentials(Credentials::new("id", "secret"))
+                .config(config.clone())
+        };
+        assert!(matches!(
+            builder().try_client_credentials(),
+            Err(ClientError::Http(_))
+        ));
+        assert!(matches!(
+            builder().try_auth_code(),
+            Err(ClientError::Http(_))
+        ));
+        assert!(matches!(builder().try_pkce(), Err(ClientError::Http(_))));
+        let result = AuthCodeSpotify::try_from_token_with_config(
+            Token::default(),
+            Credentials::new("id", "secret"),
+            OAuth::try_default().unwrap(),
+            config,
+        );
+        assert!(matches!(result, Err(ClientError::Http(_))));
+    }
+
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_interceptors() {
+        use crate::{
+            http::{Headers, InterceptedResponse, RequestInterceptor},
+            Credentials,
+        };
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Debug, Default)]
+        struct Recorder(Mutex<Vec<String>>);
+
+        impl RequestInterceptor for Recorder {
+            fn before_request(&self, method: &str, url: &str, headers: &mut Headers) {
+                headers.insert("x-request-id".to_owned(), "1234".to_owned());
+                self.0.lock().unwrap().push(format!("{method} {url}"));
+            }
+
+            fn after_response(&self, _method: &str, _url: &str, response: &InterceptedResponse) {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push(format!("{:?}", response.status));
+            }
+        }
+
+        let recorder = Arc::new(Recorder::default());
+        // Nothing listens on the discard port, so the request fails right away
+        let config = Config {
+            auth_base_url: String::from("http://127.0.0.1:9/"),
+            interceptors: vec![Arc::clone(&recorder) as Arc<dyn RequestInterceptor>],
+            ..Default::default()
+        };
+        let spotify = ClientCredsSpotify::with_config(Credentials::new("id", "secret"), config);
+
+        let result = spotify.request_token().await;
+        assert!(result.is_err());
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            ["POST http://127.0.0.1:9/api/token", "None"]
+        );
+    }
+
     #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
     async fn test_auth_headers() {
         let tok = Token {
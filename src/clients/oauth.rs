@@ -1,27 +1,45 @@
 use crate::{
     clients::{
-        append_device_id, convert_result,
-        pagination::{paginate, Paginator},
-        BaseClient,
+        chunk_items, convert_result,
+        pagination::{flat_paginate, paginate, paginate_cursor_with_ctx, Paginator},
+        route::Route,
+        BaseClient, MaybeSend, PlayerCommand,
     },
     http::Query,
     join_ids,
+    library::Library,
     model::*,
+    sync::Mutex,
     util::{build_map, JsonBuilder},
     ClientError, ClientResult, OAuth, Token,
 };
 
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::{
     io::{BufRead, BufReader, Write},
     net::{IpAddr, SocketAddr, TcpListener},
 };
 
+#[cfg(feature = "model-borrowed")]
+use crate::{
+    clients::convert_result_borrowed,
+    model::borrowed::{BorrowedPage, BorrowedSavedTrack},
+};
 use maybe_async::maybe_async;
 use rspotify_model::idtypes::{PlayContextId, PlayableId};
 use serde_json::{json, Map};
 use url::Url;
 
+/// The ID of the current user, along with the access token it was requested
+/// with, so that it's requested again after switching accounts.
+pub type CurrentUserCache = Arc<Mutex<Option<(String, UserId<'static>)>>>;
+
+/// The redirect URI of the authorization in progress, see
+/// [`OAuthClient::redirect_uri`].
+#[doc(hidden)]
+pub type SelectedRedirectUri = Arc<std::sync::Mutex<Option<String>>>;
+
 /// This trait implements the methods available strictly to clients with user
 /// authorization, including some parts of the authentication flow that are
 /// shared, and the endpoints.
@@ -36,15 +54,75 @@ use url::Url;
 pub trait OAuthClient: BaseClient {
     fn get_oauth(&self) -> &OAuth;
 
+    /// The cache used by [`Self::current_user_id`]. Without one, the ID is
+    /// requested every time.
+    #[doc(hidden)]
+    fn get_current_user_cache(&self) -> Option<&CurrentUserCache> {
+        None
+    }
+
+    /// Where the redirect URI chosen for the authorization in progress is
+    /// kept. Without one, [`OAuth::redirect_uri`] is always used.
+    #[doc(hidden)]
+    fn get_selected_redirect_uri(&self) -> Option<&SelectedRedirectUri> {
+        None
+    }
+
+    /// Where the code verifier of the PKCE flow is kept, so that it's
+    /// forgotten by [`Self::sign_out`]. The other flows have none.
+    #[doc(hidden)]
+    fn get_pkce_verifier_mut(&mut self) -> Option<&mut Option<String>> {
+        None
+    }
+
+    /// The redirect URI of the authorization in progress, which is sent again
+    /// when requesting the token. It's the one picked by
+    /// [`OAuth::redirect_uri_selector`] when building the authorization URL,
+    /// or the one the user was redirected to according to
+    /// [`Self::parse_response_code`], falling back to [`OAuth::redirect_uri`].
+    fn redirect_uri(&self) -> String {
+        self.get_selected_redirect_uri()
+            .and_then(|selected| selected.lock().unwrap().clone())
+            .unwrap_or_else(|| self.get_oauth().redirect_uri.clone())
+    }
+
+    /// Returns the scopes in [`OAuth::scopes`] that none of the requests sent
+    /// so far have needed, logging a warning for each of them. These are
+    /// candidates to be removed from the consent screen.
+    ///
+    /// This is `None` if [`Config::scope_audit`](crate::Config::scope_audit)
+    /// isn't set. See the [`scope_audit`](crate::scope_audit) module for more
+    /// information.
+    fn unused_scopes(&self) -> Option<Vec<String>> {
+        let audit = self.get_config().scope_audit.as_ref()?;
+        let unused = audit.unused_scopes(&self.get_oauth().scopes);
+        for scope in &unused {
+            log::warn!("The scope `{scope}` was requested but none of the requests needed it");
+        }
+        Some(unused)
+    }
+
+    /// The saved tracks, albums, shows, episodes and audiobooks of the
+    /// current user, grouped by kind with the same operations for all of
+    /// them. See the [`library`](crate::library) module for more information.
+    fn library(&self) -> Library<'_, Self>
+    where
+        Self: Sized,
+    {
+        Library::new(self)
+    }
+
     /// Obtains a user access token given a code, as part of the OAuth
     /// authentication. The access token will be saved internally.
     async fn request_token(&self, code: &str) -> ClientResult<()>;
 
-    /// Tries to read the cache file's token.
+    /// Tries to read the cached token from the configured
+    /// [`TokenStore`](crate::TokenStore).
     ///
-    /// This will return an error if the token couldn't be read (e.g. it's not
-    /// available or the JSON is malformed). It may return `Ok(None)` if:
+    /// This will return an error if the token couldn't be read (e.g. the JSON
+    /// is malformed). It may return `Ok(None)` if:
     ///
+    /// * There's no token cached yet
     /// * The read token is expired and `allow_expired` is false
     /// * Its scopes don't match with the current client (you will need to
     ///   re-authenticate to gain access to more scopes)
@@ -68,9 +146,12 @@ pub trait OAuthClient: BaseClient {
         }
 
         log::info!("Reading auth token cache");
-        let token = Token::from_cache(&self.get_config().cache_path)?;
+        let token = match self.get_config().get_token_store().load().await? {
+            Some(token) => token,
+            None => return Ok(None),
+        };
         if !self.get_oauth().scopes.is_subset(&token.scopes)
-            || (!allow_expired && token.is_expired())
+            || (!allow_expired && token.is_expired_within(self.get_config().token_expiry_margin))
         {
             // Invalid token, since it doesn't have at least the currently
             // required scopes or it's expired.
@@ -80,13 +161,55 @@ pub trait OAuthClient: BaseClient {
         }
     }
 
+    /// Signs the user out of this client: its token is forgotten, along with
+    /// the ID of the current user, the redirect URI of the authorization in
+    /// progress and the PKCE code verifier, and the cached token is removed
+    /// from the [`TokenStore`](crate::TokenStore) if
+    /// [`Config::token_cached`](crate::Config::token_cached) is enabled. A new
+    /// PKCE authorization thus has to start from
+    /// [`AuthCodePkceSpotify::get_authorize_url`].
+    ///
+    /// The token is shared with the clones of the client, so they're signed
+    /// out as well, but each of them keeps its own code verifier.
+    ///
+    /// Spotify has no endpoint to revoke a token, so the refresh token stays
+    /// valid until the user removes the application from their account.
+    ///
+    /// [`AuthCodePkceSpotify::get_authorize_url`]: crate::AuthCodePkceSpotify::get_authorize_url
+    async fn sign_out(&mut self) -> ClientResult<()> {
+        *self.get_token().lock().await.unwrap() = None;
+        if let Some(cache) = self.get_current_user_cache() {
+            *cache.lock().await.unwrap() = None;
+        }
+        if let Some(selected) = self.get_selected_redirect_uri() {
+            *selected.lock().unwrap() = None;
+        }
+        if let Some(verifier) = self.get_pkce_verifier_mut() {
+            *verifier = None;
+        }
+
+        let config = self.get_config();
+        if config.token_cached {
+            config.get_token_store().clear().await?;
+        }
+        Ok(())
+    }
+
     /// Parse the response code in the given response url. If the URL cannot be
     /// parsed or the `code` parameter is not present, this will return `None`.
     ///
-    // As the [RFC
-    // indicates](https://datatracker.ietf.org/doc/html/rfc6749#section-4.1),
-    // the state should be the same between the request and the callback. This
-    // will also return `None` if this is not true.
+    /// The URL may be any of [`OAuth::redirect_uri`] and
+    /// [`OAuth::redirect_uris`], and the one it matches is then used to
+    /// request the token.
+    ///
+    /// As the [RFC
+    /// indicates](https://datatracker.ietf.org/doc/html/rfc6749#section-4.1),
+    /// the state should be the same between the request and the callback, so
+    /// this will also return `None` if it doesn't match [`OAuth::state`]; see
+    /// [`OAuth::validate_state`]. The callback may thus be parsed by another
+    /// client than the one that built the authorization URL only if its
+    /// [`OAuth`] has the same state, e.g., with [`OAuth::with_state`], which
+    /// is required by servers with multiple instances.
     fn parse_response_code(&self, url: &str) -> Option<String> {
         let url = Url::parse(url).ok()?;
         let params = url.query_pairs().collect::<HashMap<_, _>>();
@@ -94,17 +217,27 @@ pub trait OAuthClient: BaseClient {
         let code = params.get("code")?;
 
         // Making sure the state is the same
-        let expected_state = &self.get_oauth().state;
-        let state = params.get("state").map(AsRef::as_ref);
-        if state != Some(expected_state) {
+        if !self.get_oauth().state_matches(&url) {
             log::error!("Request state doesn't match the callback state");
             return None;
         }
 
+        // Any of the registered redirect URIs may have been used
+        if let (Some(uri), Some(selected)) = (
+            self.get_oauth().matching_redirect_uri(&url),
+            self.get_selected_redirect_uri(),
+        ) {
+            *selected.lock().unwrap() = Some(uri.to_owned());
+        }
+
         Some(code.to_string())
     }
 
     /// Spawn HTTP server at provided socket address to accept OAuth callback and return auth code.
+    ///
+    /// Requests to other paths than the redirect URI's, like the browser's
+    /// `/favicon.ico`, are answered with `404 Not Found` and ignored. If the
+    /// user denies access, [`ClientError::Authorization`] is returned.
     fn get_authcode_listener(&self, socket_address: SocketAddr) -> ClientResult<String> {
         let listener =
             TcpListener::bind(socket_address).map_err(|e| ClientError::AuthCodeListenerBind {
@@ -113,40 +246,57 @@ pub trait OAuthClient: BaseClient {
             })?;
         log::info!("OAuth server listening on {:?}", socket_address);
 
+        let redirect_uri = Url::parse(&self.redirect_uri())?;
+
         // The server will terminate itself after collecting the first code.
-        let mut stream = listener
-            .incoming()
-            .flatten()
-            .next()
-            .ok_or(ClientError::AuthCodeListenerTerminated)?;
-        let mut reader = BufReader::new(&stream);
-        let mut request_line = String::new();
-        reader
-            .read_line(&mut request_line)
-            .map_err(|_| ClientError::AuthCodeListenerRead)?;
-
-        let redirect_url = request_line
-            .split_whitespace()
-            .nth(1)
-            .ok_or(ClientError::AuthCodeListenerRead)?;
-        let redirect_full_url = format!("{}{}", self.get_oauth().redirect_uri, redirect_url);
-        log::info!("redirect_full_url {}", redirect_full_url);
-
-        let code = self
-            .parse_response_code(&redirect_full_url)
-            .ok_or_else(|| ClientError::AuthCodeListenerParse(redirect_full_url))?;
-
-        let message = "Go back to your terminal :)";
-        let response = format!(
-            "HTTP/1.1 200 OK\r\ncontent-length: {}\r\n\r\n{}",
-            message.len(),
-            message
-        );
-        stream
-            .write_all(response.as_bytes())
-            .map_err(|_| ClientError::AuthCodeListenerWrite)?;
+        for mut stream in listener.incoming().flatten() {
+            let mut reader = BufReader::new(&stream);
+            let mut request_line = String::new();
+            reader
+                .read_line(&mut request_line)
+                .map_err(|_| ClientError::AuthCodeListenerRead)?;
+
+            let target = match request_line.split_whitespace().nth(1) {
+                Some(target) => target,
+                // Not an HTTP request; e.g., a connection closed right away
+                None => continue,
+            };
+            let redirect_full_url = redirect_uri.join(target)?;
+            log::info!("redirect_full_url {}", redirect_full_url);
+
+            if redirect_full_url.path() != redirect_uri.path() {
+                respond(&mut stream, "404 Not Found", "Not found")?;
+                continue;
+            }
+
+            let error = redirect_full_url
+                .query_pairs()
+                .find(|(key, _)| key == "error")
+                .map(|(_, error)| error.into_owned());
+            if let Some(error) = error {
+                respond(
+                    &mut stream,
+                    "200 OK",
+                    "Authorization denied, go back to your terminal",
+                )?;
+                return Err(ClientError::Authorization(format!(
+                    "access wasn't granted: {error}"
+                )));
+            }
+
+            return match self.parse_response_code(redirect_full_url.as_str()) {
+                Some(code) => {
+                    respond(&mut stream, "200 OK", "Go back to your terminal :)")?;
+                    Ok(code)
+                }
+                None => {
+                    respond(&mut stream, "400 Bad Request", "Invalid OAuth callback")?;
+                    Err(ClientError::AuthCodeListenerParse(redirect_full_url.into()))
+                }
+            };
+        }
 
-        Ok(code)
+        Err(ClientError::AuthCodeListenerTerminated)
     }
 
     // If the specified `redirect_url` is HTTP, loopback, and contains a port,
@@ -200,7 +350,7 @@ pub trait OAuthClient: BaseClient {
             ),
         }
 
-        match self.get_socket_address(&self.get_oauth().redirect_uri) {
+        match self.get_socket_address(&self.redirect_uri()) {
             Some(addr) => self.get_authcode_listener(addr),
             _ => {
                 log::info!("Prompting user for code");
@@ -233,7 +383,7 @@ pub trait OAuthClient: BaseClient {
     async fn prompt_for_token(&self, url: &str) -> ClientResult<()> {
         match self.read_token_cache(true).await {
             Ok(Some(new_token)) => {
-                let expired = new_token.is_expired();
+                let expired = new_token.is_expired_within(self.get_config().token_expiry_margin);
 
                 // Load token into client regardless of whether it's expired o
                 // not, since it will be refreshed later anyway.
@@ -241,7 +391,7 @@ pub trait OAuthClient: BaseClient {
 
                 if expired {
                     // Ensure that we actually got a token from the refetch
-                    match self.refetch_token().await? {
+                    match self.refetch_token_with_hooks().await? {
                         Some(refreshed_token) => {
                             log::info!("Successfully refreshed expired token from token cache");
                             *self.get_token().lock().await.unwrap() = Some(refreshed_token)
@@ -292,8 +442,9 @@ pub trait OAuthClient: BaseClient {
         let offset = offset.map(|s| s.to_string());
         let params = build_map([("limit", limit.as_deref()), ("offset", offset.as_deref())]);
 
-        let result = self.api_get("me/playlists", &params).await?;
-        convert_result(&result)
+        let url = Route::MyPlaylists.to_string();
+        let result = self.api_get(&url, &params).await?;
+        convert_result(self.get_config(), &url, &result)
     }
 
     /// Creates a playlist for a user.
@@ -328,38 +479,40 @@ pub trait OAuthClient: BaseClient {
             .optional("description", description)
             .build();
 
-        let url = format!("users/{}/playlists", user_id.id());
+        let url = Route::UserPlaylists(user_id.as_ref()).to_string();
         let result = self.api_post(&url, &params).await?;
-        convert_result(&result)
+        convert_result(self.get_config(), &url, &result)
     }
 
-    /// Changes a playlist's name and/or public/private state.
+    /// Changes a playlist's name, public/private state, collaborative state
+    /// and/or description.
+    ///
+    /// Spotify doesn't respond with the snapshot ID here. It may be fetched
+    /// afterwards with
+    /// [`playlist_snapshot_id`](BaseClient::playlist_snapshot_id) if needed.
     ///
     /// Parameters:
     /// - playlist_id - the id of the playlist
-    /// - name - optional name of the playlist
-    /// - public - optional is the playlist public
-    /// - collaborative - optional is the playlist collaborative
-    /// - description - optional description of the playlist
+    /// - details - the details to change, e.g.,
+    ///   `PlaylistDetails::new().name("Road trip").public(false)`
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/change-playlist-details)
     async fn playlist_change_detail(
         &self,
         playlist_id: PlaylistId<'_>,
-        name: Option<&str>,
-        public: Option<bool>,
-        description: Option<&str>,
-        collaborative: Option<bool>,
-    ) -> ClientResult<String> {
+        details: PlaylistDetails<'_>,
+    ) -> ClientResult<()> {
         let params = JsonBuilder::new()
-            .optional("name", name)
-            .optional("public", public)
-            .optional("collaborative", collaborative)
-            .optional("description", description)
+            .optional("name", details.name)
+            .optional("public", details.public)
+            .optional("collaborative", details.collaborative)
+            .optional("description", details.description)
             .build();
 
-        let url = format!("playlists/{}", playlist_id.id());
-        self.api_put(&url, &params).await
+        let url = Route::Playlist(playlist_id.as_ref()).to_string();
+        self.api_put(&url, &params).await?;
+
+        Ok(())
     }
 
     /// Unfollows (deletes) a playlist for a user.
@@ -369,12 +522,71 @@ pub trait OAuthClient: BaseClient {
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/unfollow-playlist)
     async fn playlist_unfollow(&self, playlist_id: PlaylistId<'_>) -> ClientResult<()> {
-        let url = format!("playlists/{}/followers", playlist_id.id());
+        let url = Route::PlaylistFollowers(playlist_id.as_ref()).to_string();
         self.api_delete(&url, &json!({})).await?;
 
         Ok(())
     }
 
+    /// Unfollows (deletes) several playlists, with a request for each of
+    /// them. It stops at the first one that fails, leaving the previous ones
+    /// unfollowed.
+    ///
+    /// Parameters:
+    /// - playlist_ids - the ids of the playlists
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/unfollow-playlist)
+    async fn unfollow_playlists<'a>(
+        &self,
+        playlist_ids: impl IntoIterator<Item = PlaylistId<'a>> + MaybeSend + 'a,
+    ) -> ClientResult<()> {
+        let playlist_ids = playlist_ids.into_iter().collect::<Vec<_>>();
+        for playlist_id in playlist_ids {
+            self.playlist_unfollow(playlist_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Finds the empty playlists owned by the current user for which
+    /// `predicate` returns true, and unfollows (deletes) them unless
+    /// `dry_run` is set. Returns the playlists that were found, so that they
+    /// can be reviewed first with `dry_run`.
+    ///
+    /// Parameters:
+    /// - predicate - whether to clean up the given playlist, e.g., to only
+    ///   match the ones with a given name
+    /// - dry_run - if true, the playlists are only listed
+    async fn cleanup_empty_playlists(
+        &self,
+        predicate: impl for<'p> Fn(&'p SimplifiedPlaylist) -> bool + MaybeSend,
+        dry_run: bool,
+    ) -> ClientResult<Vec<SimplifiedPlaylist>> {
+        let user_id = self.current_user_id().await?;
+
+        let mut empty = Vec::new();
+        let mut offset = 0;
+        loop {
+            let page = self
+                .current_user_playlists_manual(Some(50), Some(offset))
+                .await?;
+            offset += page.items.len() as u32;
+            let done = page.next.is_none() || page.items.is_empty();
+
+            empty.extend(page.items.into_iter().filter(|playlist| {
+                playlist.owner.id == user_id && playlist.tracks.total == 0 && predicate(playlist)
+            }));
+            if done {
+                break;
+            }
+        }
+
+        if !dry_run {
+            self.unfollow_playlists(empty.iter().map(|playlist| playlist.id.as_ref()))
+                .await?;
+        }
+        Ok(empty)
+    }
+
     /// Adds items to a playlist.
     ///
     /// Parameters:
@@ -386,21 +598,30 @@ pub trait OAuthClient: BaseClient {
     async fn playlist_add_items<'a>(
         &self,
         playlist_id: PlaylistId<'_>,
-        items: impl IntoIterator<Item = PlayableId<'a>> + Send + 'a,
+        items: impl IntoIterator<Item = PlayableId<'a>> + MaybeSend + 'a,
         position: Option<u32>,
-    ) -> ClientResult<PlaylistResult> {
-        let uris = items.into_iter().map(|id| id.uri()).collect::<Vec<_>>();
-        let params = JsonBuilder::new()
-            .required("uris", uris)
-            .optional("position", position)
-            .build();
+    ) -> ClientResult<PlaylistSnapshotId> {
+        let url = Route::PlaylistItems(playlist_id.as_ref()).to_string();
+        let mut result: Option<PlaylistSnapshotId> = None;
+        let mut added = 0;
+        for chunk in chunk_items(self.get_config(), items, 100) {
+            let len = chunk.len() as u32;
+            let uris = chunk.into_iter().map(|id| id.uri()).collect::<Vec<_>>();
+            let params = JsonBuilder::new()
+                .required("uris", uris)
+                .optional("position", position.map(|position| position + added))
+                .build();
+
+            let response = self.api_post(&url, &params).await?;
+            result = Some(convert_result(self.get_config(), &url, &response)?);
+            added += len;
+        }
 
-        let url = format!("playlists/{}/tracks", playlist_id.id());
-        let result = self.api_post(&url, &params).await?;
-        convert_result(&result)
+        // There's always at least one chunk
+        Ok(result.unwrap())
     }
 
-    /// Replace all items in a playlist
+    /// Replace all items in a playlist, and returns its new snapshot ID.
     ///
     /// Parameters:
     /// - user - the id of the user
@@ -411,15 +632,26 @@ pub trait OAuthClient: BaseClient {
     async fn playlist_replace_items<'a>(
         &self,
         playlist_id: PlaylistId<'_>,
-        items: impl IntoIterator<Item = PlayableId<'a>> + Send + 'a,
-    ) -> ClientResult<()> {
-        let uris = items.into_iter().map(|id| id.uri()).collect::<Vec<_>>();
-        let params = JsonBuilder::new().required("uris", uris).build();
-
-        let url = format!("playlists/{}/tracks", playlist_id.id());
-        self.api_put(&url, &params).await?;
+        items: impl IntoIterator<Item = PlayableId<'a>> + MaybeSend + 'a,
+    ) -> ClientResult<PlaylistSnapshotId> {
+        let url = Route::PlaylistItems(playlist_id.as_ref()).to_string();
+        let mut result: Option<PlaylistSnapshotId> = None;
+        let chunks = chunk_items(self.get_config(), items, 100);
+        for (i, chunk) in chunks.into_iter().enumerate() {
+            let uris = chunk.into_iter().map(|id| id.uri()).collect::<Vec<_>>();
+            let params = JsonBuilder::new().required("uris", uris).build();
+
+            // Only the first chunk replaces the items; the rest are appended
+            let response = if i == 0 {
+                self.api_put(&url, &params).await?
+            } else {
+                self.api_post(&url, &params).await?
+            };
+            result = Some(convert_result(self.get_config(), &url, &response)?);
+        }
 
-        Ok(())
+        // There's always at least one chunk
+        Ok(result.unwrap())
     }
 
     /// Reorder items in a playlist.
@@ -441,7 +673,7 @@ pub trait OAuthClient: BaseClient {
         insert_before: Option<i32>,
         range_length: Option<u32>,
         snapshot_id: Option<&str>,
-    ) -> ClientResult<PlaylistResult> {
+    ) -> ClientResult<PlaylistSnapshotId> {
         let params = JsonBuilder::new()
             .optional("range_start", range_start)
             .optional("insert_before", insert_before)
@@ -449,9 +681,9 @@ pub trait OAuthClient: BaseClient {
             .optional("snapshot_id", snapshot_id)
             .build();
 
-        let url = format!("playlists/{}/tracks", playlist_id.id());
+        let url = Route::PlaylistItems(playlist_id.as_ref()).to_string();
         let result = self.api_put(&url, &params).await?;
-        convert_result(&result)
+        convert_result(self.get_config(), &url, &result)
     }
 
     /// Removes all occurrences of the given items from the given playlist.
@@ -465,26 +697,38 @@ pub trait OAuthClient: BaseClient {
     async fn playlist_remove_all_occurrences_of_items<'a>(
         &self,
         playlist_id: PlaylistId<'_>,
-        track_ids: impl IntoIterator<Item = PlayableId<'a>> + Send + 'a,
+        track_ids: impl IntoIterator<Item = PlayableId<'a>> + MaybeSend + 'a,
         snapshot_id: Option<&str>,
-    ) -> ClientResult<PlaylistResult> {
-        let tracks = track_ids
-            .into_iter()
-            .map(|id| {
-                let mut map = Map::with_capacity(1);
-                map.insert("uri".to_owned(), id.uri().into());
-                map
-            })
-            .collect::<Vec<_>>();
-
-        let params = JsonBuilder::new()
-            .required("tracks", tracks)
-            .optional("snapshot_id", snapshot_id)
-            .build();
+    ) -> ClientResult<PlaylistSnapshotId> {
+        let url = Route::PlaylistItems(playlist_id.as_ref()).to_string();
+        let mut result: Option<PlaylistSnapshotId> = None;
+        for chunk in chunk_items(self.get_config(), track_ids, 100) {
+            let tracks = chunk
+                .into_iter()
+                .map(|id| {
+                    let mut map = Map::with_capacity(1);
+                    map.insert("uri".to_owned(), id.uri().into());
+                    map
+                })
+                .collect::<Vec<_>>();
+
+            // Each chunk is applied on top of the snapshot left by the
+            // previous one
+            let snapshot_id = match &result {
+                Some(result) => Some(result.snapshot_id.as_str()),
+                None => snapshot_id,
+            };
+            let params = JsonBuilder::new()
+                .required("tracks", tracks)
+                .optional("snapshot_id", snapshot_id)
+                .build();
+
+            let response = self.api_delete(&url, &params).await?;
+            result = Some(convert_result(self.get_config(), &url, &response)?);
+        }
 
-        let url = format!("playlists/{}/tracks", playlist_id.id());
-        let result = self.api_delete(&url, &params).await?;
-        convert_result(&result)
+        // There's always at least one chunk
+        Ok(result.unwrap())
     }
 
     /// Removes specfic occurrences of the given items from the given playlist.
@@ -515,34 +759,70 @@ pub trait OAuthClient: BaseClient {
     /// ```
     /// - snapshot_id: optional id of the playlist snapshot
     ///
+    /// With [`Config::auto_chunk_requests`](crate::Config::auto_chunk_requests),
+    /// more than 100 items are removed in several requests, where the
+    /// positions of each one are moved back by the items removed before.
+    ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/remove-tracks-playlist)
     async fn playlist_remove_specific_occurrences_of_items<'a>(
         &self,
         playlist_id: PlaylistId<'_>,
-        items: impl IntoIterator<Item = ItemPositions<'a>> + Send + 'a,
+        items: impl IntoIterator<Item = ItemPositions<'a>> + MaybeSend + 'a,
         snapshot_id: Option<&str>,
-    ) -> ClientResult<PlaylistResult> {
-        let tracks = items
-            .into_iter()
-            .map(|track| {
-                let mut map = Map::new();
-                map.insert("uri".to_owned(), track.id.uri().into());
-                map.insert("positions".to_owned(), json!(track.positions));
-                map
-            })
-            .collect::<Vec<_>>();
-
-        let params = JsonBuilder::new()
-            .required("tracks", tracks)
-            .optional("snapshot_id", snapshot_id)
-            .build();
+    ) -> ClientResult<PlaylistSnapshotId> {
+        let url = Route::PlaylistItems(playlist_id.as_ref()).to_string();
+        let mut result: Option<PlaylistSnapshotId> = None;
+        // The positions removed by the previous chunks, sorted, since the
+        // items after them are moved back
+        let mut removed = Vec::new();
+        for chunk in chunk_items(self.get_config(), items, 100) {
+            let tracks = chunk
+                .iter()
+                .map(|track| {
+                    let positions = track
+                        .positions
+                        .iter()
+                        .map(|&position| {
+                            position - removed.partition_point(|&prev| prev < position) as u32
+                        })
+                        .collect::<Vec<_>>();
+                    let mut map = Map::new();
+                    map.insert("uri".to_owned(), track.id.uri().into());
+                    map.insert("positions".to_owned(), json!(positions));
+                    map
+                })
+                .collect::<Vec<_>>();
+
+            let snapshot_id = match &result {
+                Some(result) => Some(result.snapshot_id.as_str()),
+                None => snapshot_id,
+            };
+            let params = JsonBuilder::new()
+                .required("tracks", tracks)
+                .optional("snapshot_id", snapshot_id)
+                .build();
+
+            let response = self.api_delete(&url, &params).await?;
+            result = Some(convert_result(self.get_config(), &url, &response)?);
+
+            removed.extend(
+                chunk
+                    .iter()
+                    .flat_map(|track| track.positions.iter().copied()),
+            );
+            removed.sort_unstable();
+        }
 
-        let url = format!("playlists/{}/tracks", playlist_id.id());
-        let result = self.api_delete(&url, &params).await?;
-        convert_result(&result)
+        // There's always at least one chunk
+        Ok(result.unwrap())
     }
 
-    /// Add the current authenticated user as a follower of a playlist.
+    /// Add the current authenticated user as a follower of a playlist, and
+    /// returns the snapshot ID of the playlist.
+    ///
+    /// Spotify doesn't respond with the snapshot ID here, so it's fetched
+    /// with an extra request to
+    /// [`playlist_snapshot_id`](BaseClient::playlist_snapshot_id).
     ///
     /// Parameters:
     /// - playlist_id - the id of the playlist
@@ -552,14 +832,14 @@ pub trait OAuthClient: BaseClient {
         &self,
         playlist_id: PlaylistId<'_>,
         public: Option<bool>,
-    ) -> ClientResult<()> {
-        let url = format!("playlists/{}/followers", playlist_id.id());
+    ) -> ClientResult<PlaylistSnapshotId> {
+        let url = Route::PlaylistFollowers(playlist_id.as_ref()).to_string();
 
         let params = JsonBuilder::new().optional("public", public).build();
 
         self.api_put(&url, &params).await?;
 
-        Ok(())
+        self.playlist_snapshot_id(playlist_id).await
     }
 
     /// Get detailed profile information about the current user.
@@ -567,8 +847,9 @@ pub trait OAuthClient: BaseClient {
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-current-users-profile)
     async fn me(&self) -> ClientResult<PrivateUser> {
-        let result = self.api_get("me/", &Query::new()).await?;
-        convert_result(&result)
+        let url = Route::Me.to_string();
+        let result = self.api_get(&url, &Query::new()).await?;
+        convert_result(self.get_config(), &url, &result)
     }
 
     /// Get detailed profile information about the current user.
@@ -579,17 +860,105 @@ pub trait OAuthClient: BaseClient {
         self.me().await
     }
 
+    /// Get the ID of the current user. It's requested with [`Self::me`] the
+    /// first time and cached afterwards, until the access token changes.
+    async fn current_user_id(&self) -> ClientResult<UserId<'static>> {
+        let access_token = self
+            .get_token()
+            .lock()
+            .await
+            .unwrap()
+            .as_ref()
+            .map(|token| token.access_token.clone());
+
+        if let (Some(cache), Some(access_token)) = (self.get_current_user_cache(), &access_token) {
+            if let Some((cached_token, id)) = cache.lock().await.unwrap().as_ref() {
+                if cached_token == access_token {
+                    return Ok(id.clone());
+                }
+            }
+        }
+
+        let id = self.me().await?.id;
+        if let (Some(cache), Some(access_token)) = (self.get_current_user_cache(), access_token) {
+            *cache.lock().await.unwrap() = Some((access_token, id.clone()));
+        }
+        Ok(id)
+    }
+
+    /// Check if the current user is following the given playlist, without
+    /// having to request their ID first.
+    ///
+    /// Checking a private playlist requires the `playlist-read-private`
+    /// scope; otherwise, Spotify responds with `false`.
+    ///
+    /// Parameters:
+    /// - playlist_id - the id of the playlist
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/check-if-user-follows-playlist)
+    async fn playlist_check_current_user_follows(
+        &self,
+        playlist_id: PlaylistId<'_>,
+    ) -> ClientResult<bool> {
+        let user_id = self.current_user_id().await?;
+        let follows = self.playlist_check_follow(playlist_id, &[user_id]).await?;
+        Ok(follows.first().copied().unwrap_or(false))
+    }
+
+    /// Replaces the stub users in the `added_by` field of the given playlist
+    /// items with their full public profiles, including their display name
+    /// and images, which is useful to attribute the items in collaborative
+    /// playlists.
+    ///
+    /// Each distinct user is requested only once. The users that no longer
+    /// exist are left as they were.
+    ///
+    /// Parameters:
+    /// - page - the playlist items, e.g. from [`playlist_items_manual`]
+    ///
+    /// [`playlist_items_manual`]: crate::clients::BaseClient::playlist_items_manual
+    async fn hydrate_added_by(&self, page: &mut Page<PlaylistItem>) -> ClientResult<()> {
+        // The users that don't exist anymore are saved as `None`
+        let mut users: HashMap<String, Option<PublicUser>> = HashMap::new();
+        for item in &mut page.items {
+            let added_by = match &mut item.added_by {
+                Some(added_by) => added_by,
+                None => continue,
+            };
+
+            let id = added_by.id.id().to_owned();
+            if !users.contains_key(&id) {
+                let user = match self.user(added_by.id.as_ref()).await {
+                    Ok(user) => Some(user),
+                    Err(ClientError::Api { status: 404, .. }) => None,
+                    Err(err) => return Err(err),
+                };
+                users.insert(id.clone(), user);
+            }
+            if let Some(Some(user)) = users.get(&id) {
+                *added_by = user.clone();
+            }
+        }
+
+        Ok(())
+    }
+
     /// Get information about the current users currently playing item.
     ///
+    /// Its track is relinked according to [`Config::default_market`], if
+    /// set. See [`Self::current_playing`] to pass the market instead.
+    ///
+    /// [`Config::default_market`]: crate::Config::default_market
+    ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-the-users-currently-playing-track)
     async fn current_user_playing_item(&self) -> ClientResult<Option<CurrentlyPlayingContext>> {
-        let result = self
-            .api_get("me/player/currently-playing", &Query::new())
-            .await?;
+        let params = build_map([("market", self.market_or_default(None))]);
+        let url = Route::PlayerCurrentlyPlaying.to_string();
+        let result = self.api_get(&url, &params).await?;
         if result.is_empty() {
             Ok(None)
         } else {
-            convert_result(&result)
+            convert_result(self.get_config(), &url, &result)
         }
     }
 
@@ -627,13 +996,14 @@ pub trait OAuthClient: BaseClient {
         let limit = limit.map(|s| s.to_string());
         let offset = offset.map(|s| s.to_string());
         let params = build_map([
-            ("market", market.map(Into::into)),
+            ("market", self.market_or_default(market)),
             ("limit", limit.as_deref()),
             ("offset", offset.as_deref()),
         ]);
 
-        let result = self.api_get("me/albums", &params).await?;
-        convert_result(&result)
+        let url = Route::MyAlbums.to_string();
+        let result = self.api_get(&url, &params).await?;
+        convert_result(self.get_config(), &url, &result)
     }
 
     /// Get a list of the songs saved in the current Spotify user's "Your Music"
@@ -670,23 +1040,91 @@ pub trait OAuthClient: BaseClient {
         let limit = limit.map(|s| s.to_string());
         let offset = offset.map(|s| s.to_string());
         let params = build_map([
-            ("market", market.map(Into::into)),
+            ("market", self.market_or_default(market)),
             ("limit", limit.as_deref()),
             ("offset", offset.as_deref()),
         ]);
 
-        let result = self.api_get("me/tracks", &params).await?;
-        convert_result(&result)
+        let url = Route::MyTracks.to_string();
+        let result = self.api_get(&url, &params).await?;
+        convert_result(self.get_config(), &url, &result)
     }
 
-    /// Gets a list of the artists followed by the current authorized user.
+    /// Gets all the saved tracks of the current user with the borrowed models
+    /// of [`model::borrowed`](crate::model::borrowed), which cut down the
+    /// allocations of large libraries. Each track is passed to `f` as soon as
+    /// its page is parsed, and it's dropped along with the page unless it's
+    /// converted with [`BorrowedSavedTrack::into_owned`].
+    ///
+    /// The pages are as large as [`Config::pagination_chunks`].
     ///
     /// Parameters:
-    /// - after - the last artist ID retrieved from the previous request
-    /// - limit - the number of tracks to return
+    /// - market - Provide this parameter if you want to apply Track Relinking
+    /// - f - the function called for each saved track
+    ///
+    /// [`Config::pagination_chunks`]: crate::Config::pagination_chunks
+    /// [`BorrowedSavedTrack::into_owned`]: crate::model::borrowed::BorrowedSavedTrack::into_owned
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-users-saved-tracks)
+    #[cfg(feature = "model-borrowed")]
+    async fn current_user_saved_tracks_borrowed<F>(
+        &self,
+        market: Option<Market>,
+        mut f: F,
+    ) -> ClientResult<()>
+    where
+        F: FnMut(BorrowedSavedTrack<'_>) + MaybeSend,
+    {
+        let limit = self.get_config().pagination_chunks.to_string();
+        let mut offset = 0;
+        loop {
+            let offset_str = offset.to_string();
+            let params = build_map([
+                ("market", self.market_or_default(market)),
+                ("limit", Some(&limit)),
+                ("offset", Some(&offset_str)),
+            ]);
+
+            let url = Route::MyTracks.to_string();
+            let result = self.api_get(&url, &params).await?;
+            let page = convert_result_borrowed::<BorrowedPage<'_, BorrowedSavedTrack<'_>>>(
+                self.get_config(),
+                &url,
+                &result,
+            )?;
+            let (count, more) = (page.items.len(), page.next.is_some());
+            page.items.into_iter().for_each(&mut f);
+            if !more || count == 0 {
+                return Ok(());
+            }
+            offset += count;
+        }
+    }
+
+    /// Gets the artists followed by the current authorized user.
+    ///
+    /// See [`Self::current_user_followed_artists_manual`] for a manually
+    /// paginated version of this.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-followed)
-    async fn current_user_followed_artists(
+    fn current_user_followed_artists(&self) -> Paginator<'_, ClientResult<FullArtist>> {
+        paginate_cursor_with_ctx(
+            self,
+            |slf, limit, cursors| {
+                let after = cursors.and_then(|cursors| cursors.after.as_deref());
+                slf.current_user_followed_artists_manual(after, Some(limit))
+            },
+            self.get_config().pagination_chunks,
+        )
+    }
+
+    /// The manually paginated version of
+    /// [`Self::current_user_followed_artists`].
+    ///
+    /// Parameters:
+    /// - after - the last artist ID retrieved from the previous request
+    /// - limit - the number of artists to return
+    async fn current_user_followed_artists_manual(
         &self,
         after: Option<&str>,
         limit: Option<u32>,
@@ -698,8 +1136,9 @@ pub trait OAuthClient: BaseClient {
             ("limit", limit.as_deref()),
         ]);
 
-        let result = self.api_get("me/following", &params).await?;
-        convert_result::<CursorPageFullArtists>(&result).map(|x| x.artists)
+        let url = Route::Following.to_string();
+        let result = self.api_get(&url, &params).await?;
+        convert_result::<CursorPageFullArtists>(self.get_config(), &url, &result).map(|x| x.artists)
     }
 
     /// Remove one or more tracks from the current user's "Your Music" library.
@@ -710,10 +1149,12 @@ pub trait OAuthClient: BaseClient {
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/remove-tracks-user)
     async fn current_user_saved_tracks_delete<'a>(
         &self,
-        track_ids: impl IntoIterator<Item = TrackId<'a>> + Send + 'a,
+        track_ids: impl IntoIterator<Item = TrackId<'a>> + MaybeSend + 'a,
     ) -> ClientResult<()> {
-        let url = format!("me/tracks/?ids={}", join_ids(track_ids));
-        self.api_delete(&url, &json!({})).await?;
+        for ids in chunk_items(self.get_config(), track_ids, 50) {
+            let url = Route::SaveTracks(&join_ids(ids)).to_string();
+            self.api_delete(&url, &json!({})).await?;
+        }
 
         Ok(())
     }
@@ -727,11 +1168,19 @@ pub trait OAuthClient: BaseClient {
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/check-users-saved-tracks)
     async fn current_user_saved_tracks_contains<'a>(
         &self,
-        track_ids: impl IntoIterator<Item = TrackId<'a>> + Send + 'a,
+        track_ids: impl IntoIterator<Item = TrackId<'a>> + MaybeSend + 'a,
     ) -> ClientResult<Vec<bool>> {
-        let url = format!("me/tracks/contains/?ids={}", join_ids(track_ids));
-        let result = self.api_get(&url, &Query::new()).await?;
-        convert_result(&result)
+        let mut contains = Vec::new();
+        for ids in chunk_items(self.get_config(), track_ids, 50) {
+            let url = Route::MyTracksContains(&join_ids(ids)).to_string();
+            let result = self.api_get(&url, &Query::new()).await?;
+            contains.extend(convert_result::<Vec<bool>>(
+                self.get_config(),
+                &url,
+                &result,
+            )?);
+        }
+        Ok(contains)
     }
 
     /// Save one or more tracks to the current user's "Your Music" library.
@@ -742,44 +1191,52 @@ pub trait OAuthClient: BaseClient {
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/save-tracks-user)
     async fn current_user_saved_tracks_add<'a>(
         &self,
-        track_ids: impl IntoIterator<Item = TrackId<'a>> + Send + 'a,
+        track_ids: impl IntoIterator<Item = TrackId<'a>> + MaybeSend + 'a,
     ) -> ClientResult<()> {
-        let url = format!("me/tracks/?ids={}", join_ids(track_ids));
-        self.api_put(&url, &json!({})).await?;
+        for ids in chunk_items(self.get_config(), track_ids, 50) {
+            let url = Route::SaveTracks(&join_ids(ids)).to_string();
+            self.api_put(&url, &json!({})).await?;
+        }
 
         Ok(())
     }
 
-    /// Get the current user's top artists.
+    /// Get the current user's top artists or tracks, depending on `T`, which
+    /// is either [`FullArtist`] or [`FullTrack`]:
+    ///
+    /// ```no_run
+    /// # use rspotify::{prelude::*, model::{FullArtist, TimeRange}, AuthCodeSpotify};
+    /// # fn example(spotify: AuthCodeSpotify) {
+    /// let artists = spotify.current_user_top_items::<FullArtist>(Some(TimeRange::ShortTerm));
+    /// # }
+    /// ```
     ///
     /// Parameters:
-    /// - limit - the number of entities to return
-    /// - offset - the index of the first entity to return
     /// - time_range - Over what time frame are the affinities computed
     ///
-    /// See [`Self::current_user_top_artists_manual`] for a manually paginated
-    /// version of this.
+    /// See [`Self::current_user_top_items_manual`] for a manually paginated
+    /// version of this, whose pages also include the total number of items.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-users-top-artists-and-tracks)
-    fn current_user_top_artists(
+    fn current_user_top_items<T: TopItem + MaybeSend>(
         &self,
         time_range: Option<TimeRange>,
-    ) -> Paginator<'_, ClientResult<FullArtist>> {
+    ) -> Paginator<'_, ClientResult<T>> {
         paginate(
             move |limit, offset| {
-                self.current_user_top_artists_manual(time_range, Some(limit), Some(offset))
+                self.current_user_top_items_manual(time_range, Some(limit), Some(offset))
             },
             self.get_config().pagination_chunks,
         )
     }
 
-    /// The manually paginated version of [`Self::current_user_top_artists`].
-    async fn current_user_top_artists_manual(
+    /// The manually paginated version of [`Self::current_user_top_items`].
+    async fn current_user_top_items_manual<T: TopItem + MaybeSend>(
         &self,
         time_range: Option<TimeRange>,
         limit: Option<u32>,
         offset: Option<u32>,
-    ) -> ClientResult<Page<FullArtist>> {
+    ) -> ClientResult<Page<T>> {
         let limit = limit.map(|s| s.to_string());
         let offset = offset.map(|s| s.to_string());
         let params = build_map([
@@ -788,8 +1245,38 @@ pub trait OAuthClient: BaseClient {
             ("offset", offset.as_deref()),
         ]);
 
-        let result = self.api_get("me/top/artists", &params).await?;
-        convert_result(&result)
+        let url = Route::MyTop(T::TYPE).to_string();
+        let result = self.api_get(&url, &params).await?;
+        convert_result(self.get_config(), &url, &result)
+    }
+
+    /// Get the current user's top artists.
+    ///
+    /// Parameters:
+    /// - limit - the number of entities to return
+    /// - offset - the index of the first entity to return
+    /// - time_range - Over what time frame are the affinities computed
+    ///
+    /// See [`Self::current_user_top_artists_manual`] for a manually paginated
+    /// version of this.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-users-top-artists-and-tracks)
+    fn current_user_top_artists(
+        &self,
+        time_range: Option<TimeRange>,
+    ) -> Paginator<'_, ClientResult<FullArtist>> {
+        self.current_user_top_items(time_range)
+    }
+
+    /// The manually paginated version of [`Self::current_user_top_artists`].
+    async fn current_user_top_artists_manual(
+        &self,
+        time_range: Option<TimeRange>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> ClientResult<Page<FullArtist>> {
+        self.current_user_top_items_manual(time_range, limit, offset)
+            .await
     }
 
     /// Get the current user's top tracks.
@@ -807,12 +1294,7 @@ pub trait OAuthClient: BaseClient {
         &self,
         time_range: Option<TimeRange>,
     ) -> Paginator<'_, ClientResult<FullTrack>> {
-        paginate(
-            move |limit, offset| {
-                self.current_user_top_tracks_manual(time_range, Some(limit), Some(offset))
-            },
-            self.get_config().pagination_chunks,
-        )
+        self.current_user_top_items(time_range)
     }
 
     /// The manually paginated version of [`Self::current_user_top_tracks`].
@@ -822,27 +1304,51 @@ pub trait OAuthClient: BaseClient {
         limit: Option<u32>,
         offset: Option<u32>,
     ) -> ClientResult<Page<FullTrack>> {
-        let limit = limit.map(|x| x.to_string());
-        let offset = offset.map(|x| x.to_string());
-        let params = build_map([
-            ("time_range", time_range.map(Into::into)),
-            ("limit", limit.as_deref()),
-            ("offset", offset.as_deref()),
-        ]);
-
-        let result = self.api_get("me/top/tracks", &params).await?;
-        convert_result(&result)
+        self.current_user_top_items_manual(time_range, limit, offset)
+            .await
     }
 
     /// Get the current user's recently played tracks.
     ///
     /// Parameters:
-    /// - limit - the number of entities to return
     /// - time_limit - a timestamp. The endpoint will return all items after
-    ///   or before (but not including) this cursor position.
+    ///   or before (but not including) this cursor position. The next pages
+    ///   continue in the same direction.
+    ///
+    /// See [`Self::current_user_recently_played_manual`] for a manually
+    /// paginated version of this.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-recently-played)
-    async fn current_user_recently_played(
+    fn current_user_recently_played(
+        &self,
+        time_limit: Option<TimeLimits>,
+    ) -> Paginator<'_, ClientResult<PlayHistory>> {
+        paginate_cursor_with_ctx(
+            self,
+            move |slf, limit, cursors| {
+                let time_limit = match (time_limit, cursors) {
+                    (_, None) => time_limit,
+                    (Some(TimeLimits::After(_)), Some(cursors)) => {
+                        cursor_timestamp(cursors.after.as_deref()).map(TimeLimits::After)
+                    }
+                    (_, Some(cursors)) => {
+                        cursor_timestamp(cursors.before.as_deref()).map(TimeLimits::Before)
+                    }
+                };
+                slf.current_user_recently_played_manual(Some(limit), time_limit)
+            },
+            self.get_config().pagination_chunks,
+        )
+    }
+
+    /// The manually paginated version of
+    /// [`Self::current_user_recently_played`].
+    ///
+    /// Parameters:
+    /// - limit - the number of entities to return
+    /// - time_limit - a timestamp. The endpoint will return all items after
+    ///   or before (but not including) this cursor position.
+    async fn current_user_recently_played_manual(
         &self,
         limit: Option<u32>,
         time_limit: Option<TimeLimits>,
@@ -859,8 +1365,55 @@ pub trait OAuthClient: BaseClient {
             params.insert(name, value);
         }
 
-        let result = self.api_get("me/player/recently-played", &params).await?;
-        convert_result(&result)
+        let url = Route::PlayerRecentlyPlayed.to_string();
+        let result = self.api_get(&url, &params).await?;
+        convert_result(self.get_config(), &url, &result)
+    }
+
+    /// Get the tracks the current user played after the given timestamp, or
+    /// all the available history if it's `None`, from oldest to newest.
+    ///
+    /// It's meant to collect the history incrementally, e.g., for scrobbling:
+    /// the `played_at` of the last item is the timestamp for the next call,
+    /// so that no play is missed nor returned twice. The pages are fetched
+    /// backwards from the latest play until the timestamp is reached.
+    ///
+    /// Note that Spotify only keeps a limited history, so the plays older
+    /// than that are lost if this isn't called often enough.
+    async fn current_user_recently_played_since(
+        &self,
+        after: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> ClientResult<Vec<PlayHistory>> {
+        let is_new = |item: &PlayHistory| match after {
+            Some(after) => item.played_at > after,
+            None => true,
+        };
+        let limit = self.get_config().pagination_chunks;
+        let mut history = Vec::new();
+        let mut before = None;
+        loop {
+            let page = self
+                .current_user_recently_played_manual(Some(limit), before.map(TimeLimits::Before))
+                .await?;
+            let reached =
+                page.next.is_none() || page.items.is_empty() || !page.items.iter().all(is_new);
+            let next = page
+                .cursors
+                .and_then(|cursors| cursor_timestamp(cursors.before.as_deref()));
+            history.extend(page.items.into_iter().filter(is_new));
+
+            // The cursor must move backwards, or the same page would be
+            // requested forever
+            match (next, before) {
+                (Some(next), Some(before)) if next >= before => break,
+                (Some(next), _) if !reached => before = Some(next),
+                _ => break,
+            }
+        }
+
+        history.sort_by_key(|item| item.played_at);
+        history.dedup_by_key(|item| item.played_at);
+        Ok(history)
     }
 
     /// Add one or more albums to the current user's "Your Music" library.
@@ -871,10 +1424,12 @@ pub trait OAuthClient: BaseClient {
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/save-albums-user)
     async fn current_user_saved_albums_add<'a>(
         &self,
-        album_ids: impl IntoIterator<Item = AlbumId<'a>> + Send + 'a,
+        album_ids: impl IntoIterator<Item = AlbumId<'a>> + MaybeSend + 'a,
     ) -> ClientResult<()> {
-        let url = format!("me/albums/?ids={}", join_ids(album_ids));
-        self.api_put(&url, &json!({})).await?;
+        for ids in chunk_items(self.get_config(), album_ids, 20) {
+            let url = Route::SaveAlbums(&join_ids(ids)).to_string();
+            self.api_put(&url, &json!({})).await?;
+        }
 
         Ok(())
     }
@@ -887,10 +1442,12 @@ pub trait OAuthClient: BaseClient {
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/remove-albums-user)
     async fn current_user_saved_albums_delete<'a>(
         &self,
-        album_ids: impl IntoIterator<Item = AlbumId<'a>> + Send + 'a,
+        album_ids: impl IntoIterator<Item = AlbumId<'a>> + MaybeSend + 'a,
     ) -> ClientResult<()> {
-        let url = format!("me/albums/?ids={}", join_ids(album_ids));
-        self.api_delete(&url, &json!({})).await?;
+        for ids in chunk_items(self.get_config(), album_ids, 20) {
+            let url = Route::SaveAlbums(&join_ids(ids)).to_string();
+            self.api_delete(&url, &json!({})).await?;
+        }
 
         Ok(())
     }
@@ -904,11 +1461,19 @@ pub trait OAuthClient: BaseClient {
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/check-users-saved-albums)
     async fn current_user_saved_albums_contains<'a>(
         &self,
-        album_ids: impl IntoIterator<Item = AlbumId<'a>> + Send + 'a,
+        album_ids: impl IntoIterator<Item = AlbumId<'a>> + MaybeSend + 'a,
     ) -> ClientResult<Vec<bool>> {
-        let url = format!("me/albums/contains/?ids={}", join_ids(album_ids));
-        let result = self.api_get(&url, &Query::new()).await?;
-        convert_result(&result)
+        let mut contains = Vec::new();
+        for ids in chunk_items(self.get_config(), album_ids, 20) {
+            let url = Route::MyAlbumsContains(&join_ids(ids)).to_string();
+            let result = self.api_get(&url, &Query::new()).await?;
+            contains.extend(convert_result::<Vec<bool>>(
+                self.get_config(),
+                &url,
+                &result,
+            )?);
+        }
+        Ok(contains)
     }
 
     /// Follow one or more artists.
@@ -919,10 +1484,12 @@ pub trait OAuthClient: BaseClient {
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/follow-artists-users)
     async fn user_follow_artists<'a>(
         &self,
-        artist_ids: impl IntoIterator<Item = ArtistId<'a>> + Send + 'a,
+        artist_ids: impl IntoIterator<Item = ArtistId<'a>> + MaybeSend + 'a,
     ) -> ClientResult<()> {
-        let url = format!("me/following?type=artist&ids={}", join_ids(artist_ids));
-        self.api_put(&url, &json!({})).await?;
+        for ids in chunk_items(self.get_config(), artist_ids, 50) {
+            let url = Route::FollowArtists(&join_ids(ids)).to_string();
+            self.api_put(&url, &json!({})).await?;
+        }
 
         Ok(())
     }
@@ -935,10 +1502,12 @@ pub trait OAuthClient: BaseClient {
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/unfollow-artists-users)
     async fn user_unfollow_artists<'a>(
         &self,
-        artist_ids: impl IntoIterator<Item = ArtistId<'a>> + Send + 'a,
+        artist_ids: impl IntoIterator<Item = ArtistId<'a>> + MaybeSend + 'a,
     ) -> ClientResult<()> {
-        let url = format!("me/following?type=artist&ids={}", join_ids(artist_ids));
-        self.api_delete(&url, &json!({})).await?;
+        for ids in chunk_items(self.get_config(), artist_ids, 50) {
+            let url = Route::FollowArtists(&join_ids(ids)).to_string();
+            self.api_delete(&url, &json!({})).await?;
+        }
 
         Ok(())
     }
@@ -952,28 +1521,35 @@ pub trait OAuthClient: BaseClient {
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/check-current-user-follows)
     async fn user_artist_check_follow<'a>(
         &self,
-        artist_ids: impl IntoIterator<Item = ArtistId<'a>> + Send + 'a,
+        artist_ids: impl IntoIterator<Item = ArtistId<'a>> + MaybeSend + 'a,
     ) -> ClientResult<Vec<bool>> {
-        let url = format!(
-            "me/following/contains?type=artist&ids={}",
-            join_ids(artist_ids)
-        );
-        let result = self.api_get(&url, &Query::new()).await?;
-        convert_result(&result)
+        let mut follows = Vec::new();
+        for ids in chunk_items(self.get_config(), artist_ids, 50) {
+            let url = Route::FollowArtistsContains(&join_ids(ids)).to_string();
+            let result = self.api_get(&url, &Query::new()).await?;
+            follows.extend(convert_result::<Vec<bool>>(
+                self.get_config(),
+                &url,
+                &result,
+            )?);
+        }
+        Ok(follows)
     }
 
     /// Follow one or more users.
     ///
     /// Parameters:
-    /// - user_ids - a list of artist IDs
+    /// - user_ids - a list of user IDs
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/follow-artists-users)
     async fn user_follow_users<'a>(
         &self,
-        user_ids: impl IntoIterator<Item = UserId<'a>> + Send + 'a,
+        user_ids: impl IntoIterator<Item = UserId<'a>> + MaybeSend + 'a,
     ) -> ClientResult<()> {
-        let url = format!("me/following?type=user&ids={}", join_ids(user_ids));
-        self.api_put(&url, &json!({})).await?;
+        for ids in chunk_items(self.get_config(), user_ids, 50) {
+            let url = Route::FollowUsers(&join_ids(ids)).to_string();
+            self.api_put(&url, &json!({})).await?;
+        }
 
         Ok(())
     }
@@ -981,25 +1557,52 @@ pub trait OAuthClient: BaseClient {
     /// Unfollow one or more users.
     ///
     /// Parameters:
-    /// - user_ids - a list of artist IDs
+    /// - user_ids - a list of user IDs
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/unfollow-artists-users)
     async fn user_unfollow_users<'a>(
         &self,
-        user_ids: impl IntoIterator<Item = UserId<'a>> + Send + 'a,
+        user_ids: impl IntoIterator<Item = UserId<'a>> + MaybeSend + 'a,
     ) -> ClientResult<()> {
-        let url = format!("me/following?type=user&ids={}", join_ids(user_ids));
-        self.api_delete(&url, &json!({})).await?;
+        for ids in chunk_items(self.get_config(), user_ids, 50) {
+            let url = Route::FollowUsers(&join_ids(ids)).to_string();
+            self.api_delete(&url, &json!({})).await?;
+        }
 
         Ok(())
     }
 
+    /// Check to see if the current user is following one or more other
+    /// Spotify users.
+    ///
+    /// Parameters:
+    /// - user_ids - a list of user IDs
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/check-current-user-follows)
+    async fn user_check_follow_users<'a>(
+        &self,
+        user_ids: impl IntoIterator<Item = UserId<'a>> + MaybeSend + 'a,
+    ) -> ClientResult<Vec<bool>> {
+        let mut follows = Vec::new();
+        for ids in chunk_items(self.get_config(), user_ids, 50) {
+            let url = Route::FollowUsersContains(&join_ids(ids)).to_string();
+            let result = self.api_get(&url, &Query::new()).await?;
+            follows.extend(convert_result::<Vec<bool>>(
+                self.get_config(),
+                &url,
+                &result,
+            )?);
+        }
+        Ok(follows)
+    }
+
     /// Get a User’s Available Devices
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-a-users-available-devices)
     async fn device(&self) -> ClientResult<Vec<Device>> {
-        let result = self.api_get("me/player/devices", &Query::new()).await?;
-        convert_result::<DevicePayload>(&result).map(|x| x.devices)
+        let url = Route::PlayerDevices.to_string();
+        let result = self.api_get(&url, &Query::new()).await?;
+        convert_result::<DevicePayload>(self.get_config(), &url, &result).map(|x| x.devices)
     }
 
     /// Get Information About The User’s Current Playback
@@ -1013,8 +1616,8 @@ pub trait OAuthClient: BaseClient {
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-information-about-the-users-current-playback)
     async fn current_playback<'a>(
         &self,
-        country: Option<Market>,
-        additional_types: Option<impl IntoIterator<Item = &'a AdditionalType> + Send + 'a>,
+        market: Option<Market>,
+        additional_types: Option<impl IntoIterator<Item = &'a AdditionalType> + MaybeSend + 'a>,
     ) -> ClientResult<Option<CurrentPlaybackContext>> {
         let additional_types = additional_types.map(|x| {
             x.into_iter()
@@ -1023,15 +1626,16 @@ pub trait OAuthClient: BaseClient {
                 .join(",")
         });
         let params = build_map([
-            ("country", country.map(Into::into)),
+            ("market", self.market_or_default(market)),
             ("additional_types", additional_types.as_deref()),
         ]);
 
-        let result = self.api_get("me/player", &params).await?;
+        let url = Route::Player.to_string();
+        let result = self.api_get(&url, &params).await?;
         if result.is_empty() {
             Ok(None)
         } else {
-            convert_result(&result)
+            convert_result(self.get_config(), &url, &result)
         }
     }
 
@@ -1047,7 +1651,7 @@ pub trait OAuthClient: BaseClient {
     async fn current_playing<'a>(
         &'a self,
         market: Option<Market>,
-        additional_types: Option<impl IntoIterator<Item = &'a AdditionalType> + Send + 'a>,
+        additional_types: Option<impl IntoIterator<Item = &'a AdditionalType> + MaybeSend + 'a>,
     ) -> ClientResult<Option<CurrentlyPlayingContext>> {
         let additional_types = additional_types.map(|x| {
             x.into_iter()
@@ -1056,15 +1660,16 @@ pub trait OAuthClient: BaseClient {
                 .join(",")
         });
         let params = build_map([
-            ("market", market.map(Into::into)),
+            ("market", self.market_or_default(market)),
             ("additional_types", additional_types.as_deref()),
         ]);
 
-        let result = self.api_get("me/player/currently-playing", &params).await?;
+        let url = Route::PlayerCurrentlyPlaying.to_string();
+        let result = self.api_get(&url, &params).await?;
         if result.is_empty() {
             Ok(None)
         } else {
-            convert_result(&result)
+            convert_result(self.get_config(), &url, &result)
         }
     }
 
@@ -1073,28 +1678,87 @@ pub trait OAuthClient: BaseClient {
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-queue)
     async fn current_user_queue(&self) -> ClientResult<CurrentUserQueue> {
         let params = build_map([]);
-        let result = self.api_get("me/player/queue", &params).await?;
-        convert_result(&result)
+        let url = Route::PlayerQueue.to_string();
+        let result = self.api_get(&url, &params).await?;
+        convert_result(self.get_config(), &url, &result)
     }
 
-    /// Transfer a User’s Playback.
+    /// Fetches the current playback in order to check the actions it allows,
+    /// in case [`Config::check_player_actions`] is enabled. `None` is returned
+    /// if the check is disabled, if there's no active playback, or if the
+    /// command targets a device other than the active one.
     ///
-    /// Note: Although an array is accepted, only a single device_id is
-    /// currently supported. Supplying more than one will return 400 Bad Request
-    ///
-    /// Parameters:
+    /// [`Config::check_player_actions`]: crate::Config::check_player_actions
+    #[doc(hidden)]
+    async fn playback_to_check(
+        &self,
+        device_id: Option<&DeviceId<'_>>,
+    ) -> ClientResult<Option<CurrentPlaybackContext>> {
+        if !self.get_config().check_player_actions {
+            return Ok(None);
+        }
+
+        let playback = self
+            .current_playback(None, None::<&[AdditionalType]>)
+            .await?;
+        Ok(playback.filter(|playback| match device_id {
+            Some(id) => playback.device.id.as_deref() == Some(id.id()),
+            None => true,
+        }))
+    }
+
+    /// Sends a command to the player of the current user, which all the other
+    /// player methods are built on. If [`Config::check_player_actions`] is
+    /// enabled, the command is checked against the current playback first.
+    ///
+    /// Parameters:
+    /// - command - the command to send, with its parameters
+    /// - device_id - device target for playback. It's ignored for transfers,
+    ///   whose target devices are part of the command.
+    ///
+    /// [`Config::check_player_actions`]: crate::Config::check_player_actions
+    async fn player_command(
+        &self,
+        command: PlayerCommand<'_>,
+        device_id: Option<DeviceId<'_>>,
+    ) -> ClientResult<()> {
+        if let Some(playback) = self
+            .playback_to_check(command.checked_device(device_id.as_ref()))
+            .await?
+        {
+            command.check(&playback)?;
+        }
+
+        let url = command.url(device_id.as_ref());
+        let body = command.body();
+        if command.method() == "POST" {
+            self.api_post(&url, &body).await?;
+        } else {
+            self.api_put(&url, &body).await?;
+        }
+        Ok(())
+    }
+
+    /// Transfer a User’s Playback.
+    ///
+    /// Note: Although an array is accepted, only a single device_id is
+    /// currently supported. Supplying more than one will return 400 Bad Request
+    ///
+    /// Parameters:
     /// - device_id - transfer playback to this device
     /// - force_play - true: after transfer, play. false: keep current state.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/transfer-a-users-playback)
-    async fn transfer_playback(&self, device_id: &str, play: Option<bool>) -> ClientResult<()> {
-        let params = JsonBuilder::new()
-            .required("device_ids", [device_id])
-            .optional("play", play)
-            .build();
-
-        self.api_put("me/player", &params).await?;
-        Ok(())
+    async fn transfer_playback(
+        &self,
+        device_id: DeviceId<'_>,
+        play: Option<bool>,
+    ) -> ClientResult<()> {
+        let command = PlayerCommand::Transfer {
+            device_ids: vec![device_id],
+            play,
+        };
+        self.player_command(command, None).await
     }
 
     /// Start/Resume a User’s Playback.
@@ -1109,34 +1773,78 @@ pub trait OAuthClient: BaseClient {
     /// - context_uri - spotify context uri to play
     /// - uris - spotify track uris
     /// - offset - offset into context by index or track
-    /// - position - Indicates from what position to start playback.
+    /// - position - Indicates from what position to start playback. A
+    ///   `chrono::Duration`, like the progress of the playback, is converted
+    ///   with `to_std`.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/start-a-users-playback)
     async fn start_context_playback(
         &self,
         context_uri: PlayContextId<'_>,
-        device_id: Option<&str>,
+        device_id: Option<DeviceId<'_>>,
         offset: Option<Offset>,
-        position: Option<chrono::Duration>,
+        position: Option<std::time::Duration>,
     ) -> ClientResult<()> {
-        let params = JsonBuilder::new()
-            .required("context_uri", context_uri.uri())
-            .optional(
-                "offset",
-                offset.map(|x| match x {
-                    Offset::Position(position) => {
-                        json!({ "position": position.num_milliseconds() })
-                    }
-                    Offset::Uri(uri) => json!({ "uri": uri }),
-                }),
-            )
-            .optional("position_ms", position.map(|p| p.num_milliseconds()))
-            .build();
+        let command = PlayerCommand::PlayContext {
+            context_uri,
+            offset,
+            position,
+        };
+        self.player_command(command, device_id).await
+    }
 
-        let url = append_device_id("me/player/play", device_id);
-        self.api_put(&url, &params).await?;
+    /// Starts playing a context on the given device, transferring the
+    /// playback to it first if it isn't the active one. This is the same as
+    /// calling [`Self::device`], [`Self::transfer_playback`] and
+    /// [`Self::start_context_playback`].
+    ///
+    /// The device is looked up by its ID first, and then by its name,
+    /// ignoring case. The devices without an ID, which can't be controlled,
+    /// are left out.
+    ///
+    /// Parameters:
+    /// - device_name_or_id - the ID or the name of the device, e.g.,
+    ///   `"Living Room"`
+    /// - context_uri - the album, artist, playlist or show to play
+    /// - offset - where to start playing within the context
+    ///
+    /// Returns the device the context is played on, or
+    /// [`ClientError::DeviceNotFound`] if there's no such device.
+    async fn play_on_device(
+        &self,
+        device_name_or_id: &str,
+        context_uri: PlayContextId<'_>,
+        offset: Option<Offset>,
+    ) -> ClientResult<Device> {
+        let devices = self
+            .device()
+            .await?
+            .into_iter()
+            .filter(|device| device.id.is_some())
+            .collect::<Vec<_>>();
+        let name = device_name_or_id.trim();
+        let found = devices
+            .iter()
+            .position(|device| device.id.as_deref() == Some(name))
+            .or_else(|| {
+                devices
+                    .iter()
+                    .position(|device| device.name.trim().eq_ignore_ascii_case(name))
+            });
+        let device = match found {
+            Some(i) => devices.into_iter().nth(i).unwrap(),
+            None => return Err(ClientError::DeviceNotFound(name.to_owned())),
+        };
 
-        Ok(())
+        // The devices are filtered by their ID above
+        let device_id = device.device_id().unwrap();
+        if !device.is_active {
+            self.transfer_playback(device_id.as_ref(), Some(false))
+                .await?;
+        }
+        self.start_context_playback(context_uri, Some(device_id), offset, None)
+            .await?;
+        Ok(device)
     }
 
     /// Start a user's playback
@@ -1145,37 +1853,22 @@ pub trait OAuthClient: BaseClient {
     /// - uris
     /// - device_id
     /// - offset
-    /// - position
+    /// - position - from what position to start playback
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/start-a-users-playback)
     async fn start_uris_playback<'a>(
         &self,
-        uris: impl IntoIterator<Item = PlayableId<'a>> + Send + 'a,
-        device_id: Option<&str>,
+        uris: impl IntoIterator<Item = PlayableId<'a>> + MaybeSend + 'a,
+        device_id: Option<DeviceId<'_>>,
         offset: Option<crate::model::Offset>,
-        position: Option<chrono::Duration>,
+        position: Option<std::time::Duration>,
     ) -> ClientResult<()> {
-        let params = JsonBuilder::new()
-            .required(
-                "uris",
-                uris.into_iter().map(|id| id.uri()).collect::<Vec<_>>(),
-            )
-            .optional("position_ms", position.map(|p| p.num_milliseconds()))
-            .optional(
-                "offset",
-                offset.map(|x| match x {
-                    Offset::Position(position) => {
-                        json!({ "position": position.num_milliseconds() })
-                    }
-                    Offset::Uri(uri) => json!({ "uri": uri }),
-                }),
-            )
-            .build();
-
-        let url = append_device_id("me/player/play", device_id);
-        self.api_put(&url, &params).await?;
-
-        Ok(())
+        let command = PlayerCommand::PlayUris {
+            uris: uris.into_iter().collect(),
+            offset,
+            position,
+        };
+        self.player_command(command, device_id).await
     }
 
     /// Pause a User’s Playback.
@@ -1184,33 +1877,24 @@ pub trait OAuthClient: BaseClient {
     /// - device_id - device target for playback
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/pause-a-users-playback)
-    async fn pause_playback(&self, device_id: Option<&str>) -> ClientResult<()> {
-        let url = append_device_id("me/player/pause", device_id);
-        self.api_put(&url, &json!({})).await?;
-
-        Ok(())
+    async fn pause_playback(&self, device_id: Option<DeviceId<'_>>) -> ClientResult<()> {
+        self.player_command(PlayerCommand::Pause, device_id).await
     }
 
     /// Resume a User’s Playback.
     ///
     /// Parameters:
     /// - device_id - device target for playback
-    /// - position
+    /// - position - from what position to resume playback
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/start-a-users-playback)
     async fn resume_playback(
         &self,
-        device_id: Option<&str>,
-        position: Option<chrono::Duration>,
+        device_id: Option<DeviceId<'_>>,
+        position: Option<std::time::Duration>,
     ) -> ClientResult<()> {
-        let params = JsonBuilder::new()
-            .optional("position_ms", position.map(|p| p.num_milliseconds()))
-            .build();
-
-        let url = append_device_id("me/player/play", device_id);
-        self.api_put(&url, &params).await?;
-
-        Ok(())
+        self.player_command(PlayerCommand::Resume { position }, device_id)
+            .await
     }
 
     /// Skip User’s Playback To Next Track.
@@ -1219,11 +1903,8 @@ pub trait OAuthClient: BaseClient {
     /// - device_id - device target for playback
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/skip-users-playback-to-next-track)
-    async fn next_track(&self, device_id: Option<&str>) -> ClientResult<()> {
-        let url = append_device_id("me/player/next", device_id);
-        self.api_post(&url, &json!({})).await?;
-
-        Ok(())
+    async fn next_track(&self, device_id: Option<DeviceId<'_>>) -> ClientResult<()> {
+        self.player_command(PlayerCommand::Next, device_id).await
     }
 
     /// Skip User’s Playback To Previous Track.
@@ -1232,32 +1913,62 @@ pub trait OAuthClient: BaseClient {
     /// - device_id - device target for playback
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/skip-users-playback-to-previous-track)
-    async fn previous_track(&self, device_id: Option<&str>) -> ClientResult<()> {
-        let url = append_device_id("me/player/previous", device_id);
-        self.api_post(&url, &json!({})).await?;
-
-        Ok(())
+    async fn previous_track(&self, device_id: Option<DeviceId<'_>>) -> ClientResult<()> {
+        self.player_command(PlayerCommand::Previous, device_id)
+            .await
     }
 
     /// Seek To Position In Currently Playing Track.
     ///
     /// Parameters:
-    /// - position - position in milliseconds to seek to
+    /// - position - position to seek to, sent in milliseconds
     /// - device_id - device target for playback
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/seek-to-position-in-currently-playing-track)
     async fn seek_track(
         &self,
-        position: chrono::Duration,
-        device_id: Option<&str>,
+        position: std::time::Duration,
+        device_id: Option<DeviceId<'_>>,
     ) -> ClientResult<()> {
-        let url = append_device_id(
-            &format!("me/player/seek?position_ms={}", position.num_milliseconds()),
-            device_id,
-        );
-        self.api_put(&url, &json!({})).await?;
+        self.player_command(PlayerCommand::Seek(position), device_id)
+            .await
+    }
 
-        Ok(())
+    /// Same as [`Self::seek_track`], but the position is clamped to the
+    /// duration of the item currently playing, which is fetched first.
+    /// Returns the position that was actually sought to. If nothing is
+    /// playing, the position is sent as is.
+    ///
+    /// If `device_id` isn't the device that's currently playing, this fails
+    /// with [`ClientError::DeviceNotFound`], since the duration of its item is
+    /// unknown.
+    ///
+    /// Parameters:
+    /// - position - position to seek to
+    /// - device_id - device target for playback
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/seek-to-position-in-currently-playing-track)
+    async fn seek_track_clamped(
+        &self,
+        position: std::time::Duration,
+        device_id: Option<DeviceId<'_>>,
+    ) -> ClientResult<std::time::Duration> {
+        let playback = self
+            .current_playback(None, None::<&[AdditionalType]>)
+            .await?;
+        if let (Some(playback), Some(device_id)) = (&playback, &device_id) {
+            if playback.device.device_id().as_ref() != Some(device_id) {
+                return Err(ClientError::DeviceNotFound(device_id.id().to_owned()));
+            }
+        }
+        let duration = playback
+            .as_ref()
+            .and_then(|playback| playback.item.as_ref())
+            .map(PlayableItem::duration);
+        let position = clamp_position(position, duration);
+
+        self.seek_track(position, device_id).await?;
+        Ok(position)
     }
 
     /// Set Repeat Mode On User’s Playback.
@@ -1267,14 +1978,13 @@ pub trait OAuthClient: BaseClient {
     /// - device_id - device target for playback
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/set-repeat-mode-on-users-playback)
-    async fn repeat(&self, state: RepeatState, device_id: Option<&str>) -> ClientResult<()> {
-        let url = append_device_id(
-            &format!("me/player/repeat?state={}", <&str>::from(state)),
-            device_id,
-        );
-        self.api_put(&url, &json!({})).await?;
-
-        Ok(())
+    async fn repeat(
+        &self,
+        state: RepeatState,
+        device_id: Option<DeviceId<'_>>,
+    ) -> ClientResult<()> {
+        self.player_command(PlayerCommand::Repeat(state), device_id)
+            .await
     }
 
     /// Set Volume For User’s Playback.
@@ -1284,18 +1994,17 @@ pub trait OAuthClient: BaseClient {
     /// - device_id - device target for playback
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/set-volume-for-users-playback)
-    async fn volume(&self, volume_percent: u8, device_id: Option<&str>) -> ClientResult<()> {
+    async fn volume(
+        &self,
+        volume_percent: u8,
+        device_id: Option<DeviceId<'_>>,
+    ) -> ClientResult<()> {
         debug_assert!(
             volume_percent <= 100u8,
             "volume must be between 0 and 100, inclusive"
         );
-        let url = append_device_id(
-            &format!("me/player/volume?volume_percent={volume_percent}"),
-            device_id,
-        );
-        self.api_put(&url, &json!({})).await?;
-
-        Ok(())
+        self.player_command(PlayerCommand::Volume(volume_percent), device_id)
+            .await
     }
 
     /// Toggle Shuffle For User’s Playback.
@@ -1305,11 +2014,9 @@ pub trait OAuthClient: BaseClient {
     /// - device_id - device target for playback
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/toggle-shuffle-for-users-playback)
-    async fn shuffle(&self, state: bool, device_id: Option<&str>) -> ClientResult<()> {
-        let url = append_device_id(&format!("me/player/shuffle?state={state}"), device_id);
-        self.api_put(&url, &json!({})).await?;
-
-        Ok(())
+    async fn shuffle(&self, state: bool, device_id: Option<DeviceId<'_>>) -> ClientResult<()> {
+        self.player_command(PlayerCommand::Shuffle(state), device_id)
+            .await
     }
 
     /// Add an item to the end of the user's playback queue.
@@ -1324,102 +2031,470 @@ pub trait OAuthClient: BaseClient {
     async fn add_item_to_queue(
         &self,
         item: PlayableId<'_>,
-        device_id: Option<&str>,
+        device_id: Option<DeviceId<'_>>,
     ) -> ClientResult<()> {
-        let url = append_device_id(&format!("me/player/queue?uri={}", item.uri()), device_id);
-        self.api_post(&url, &json!({})).await?;
-
-        Ok(())
+        self.player_command(PlayerCommand::AddToQueue(item), device_id)
+            .await
     }
 
-    /// Add a show or a list of shows to a user’s library.
+    /// Add several items to the end of the user's playback queue, in order.
+    ///
+    /// The Web API only queues one item per request, so they're added one
+    /// after another. Each request is retried according to
+    /// [`Config::retry_policy`], like the rest, e.g., when it's rate limited.
+    /// The errors don't stop the remaining items from being queued.
     ///
     /// Parameters:
-    /// - ids(Required) A comma-separated list of Spotify IDs for the shows to
-    ///   be added to the user’s library.
+    /// - items - The items to add, Tracks or Episodes
+    /// - device id - The id of the device targeting
+    /// - If no device ID provided the user's currently active device is
+    ///   targeted
     ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/save-shows-user)
-    async fn save_shows<'a>(
+    /// Returns the items that couldn't be queued, along with their errors.
+    ///
+    /// [`Config::retry_policy`]: crate::Config::retry_policy
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/add-to-queue)
+    async fn add_items_to_queue<'a>(
         &self,
-        show_ids: impl IntoIterator<Item = ShowId<'a>> + Send + 'a,
-    ) -> ClientResult<()> {
-        let url = format!("me/shows/?ids={}", join_ids(show_ids));
-        self.api_put(&url, &json!({})).await?;
-
-        Ok(())
+        items: impl IntoIterator<Item = PlayableId<'a>> + MaybeSend + 'a,
+        device_id: Option<DeviceId<'a>>,
+    ) -> Vec<(PlayableId<'static>, ClientError)> {
+        let items = items.into_iter().collect::<Vec<_>>();
+        let mut failed = Vec::new();
+        for item in items {
+            let result = self
+                .add_item_to_queue(item.as_ref(), device_id.as_ref().map(DeviceId::as_ref))
+                .await;
+            if let Err(err) = result {
+                failed.push((item.into_static(), err));
+            }
+        }
+        failed
     }
 
     /// Get a list of shows saved in the current Spotify user’s library.
-    /// Optional parameters can be used to limit the number of shows returned.
+    ///
+    /// See [`Self::current_user_saved_shows_manual`] for a manually paginated
+    /// version of this.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-users-saved-shows)
+    fn current_user_saved_shows(&self) -> Paginator<'_, ClientResult<Show>> {
+        paginate(
+            move |limit, offset| self.current_user_saved_shows_manual(Some(limit), Some(offset)),
+            self.get_config().pagination_chunks,
+        )
+    }
+
+    /// The manually paginated version of [`Self::current_user_saved_shows`].
     ///
     /// Parameters:
     /// - limit(Optional). The maximum number of shows to return. Default: 20.
     ///   Minimum: 1. Maximum: 50.
     /// - offset(Optional). The index of the first show to return. Default: 0
     ///   (the first object). Use with limit to get the next set of shows.
+    async fn current_user_saved_shows_manual(
+        &self,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> ClientResult<Page<Show>> {
+        let limit = limit.map(|x| x.to_string());
+        let offset = offset.map(|x| x.to_string());
+        let params = build_map([("limit", limit.as_deref()), ("offset", offset.as_deref())]);
+
+        let url = Route::MyShows.to_string();
+        let result = self.api_get(&url, &params).await?;
+        convert_result(self.get_config(), &url, &result)
+    }
+
+    /// Add one or more shows to the current user's library.
     ///
-    /// See [`Self::get_saved_show_manual`] for a manually paginated version of
-    /// this.
+    /// Parameters:
+    /// - show_ids - a list of show URIs, URLs or IDs
     ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-users-saved-shows)
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/save-shows-user)
+    async fn current_user_saved_shows_add<'a>(
+        &self,
+        show_ids: impl IntoIterator<Item = ShowId<'a>> + MaybeSend + 'a,
+    ) -> ClientResult<()> {
+        for ids in chunk_items(self.get_config(), show_ids, 50) {
+            let url = Route::SaveShows(&join_ids(ids)).to_string();
+            self.api_put(&url, &json!({})).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove one or more shows from the current user's library. The changes
+    /// may not be visible in other Spotify applications immediately.
+    ///
+    /// Parameters:
+    /// - show_ids - a list of show URIs, URLs or IDs
+    /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/remove-shows-user)
+    async fn current_user_saved_shows_delete<'a>(
+        &self,
+        show_ids: impl IntoIterator<Item = ShowId<'a>> + MaybeSend + 'a,
+        market: Option<Market>,
+    ) -> ClientResult<()> {
+        let market = self.market_or_default(market);
+        for ids in chunk_items(self.get_config(), show_ids, 50) {
+            let url = Route::RemoveShows(&join_ids(ids), market).to_string();
+            self.api_delete(&url, &json!({})).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Check if one or more shows are already saved in the current user's
+    /// library.
+    ///
+    /// Parameters:
+    /// - show_ids - a list of show URIs, URLs or IDs
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/check-users-saved-shows)
+    async fn current_user_saved_shows_contains<'a>(
+        &self,
+        show_ids: impl IntoIterator<Item = ShowId<'a>> + MaybeSend + 'a,
+    ) -> ClientResult<Vec<bool>> {
+        let mut contains = Vec::new();
+        for ids in chunk_items(self.get_config(), show_ids, 50) {
+            let ids = join_ids(ids);
+            let params = build_map([("ids", Some(&ids))]);
+            let url = Route::MyShowsContains.to_string();
+            let result = self.api_get(&url, &params).await?;
+            contains.extend(convert_result::<Vec<bool>>(
+                self.get_config(),
+                &url,
+                &result,
+            )?);
+        }
+        Ok(contains)
+    }
+
+    /// Add a show or a list of shows to a user’s library.
+    #[deprecated(note = "use `current_user_saved_shows_add` instead")]
+    async fn save_shows<'a>(
+        &self,
+        show_ids: impl IntoIterator<Item = ShowId<'a>> + MaybeSend + 'a,
+    ) -> ClientResult<()> {
+        self.current_user_saved_shows_add(show_ids).await
+    }
+
+    /// Get a list of shows saved in the current Spotify user’s library.
+    #[deprecated(note = "use `current_user_saved_shows` instead")]
     fn get_saved_show(&self) -> Paginator<'_, ClientResult<Show>> {
-        paginate(
-            move |limit, offset| self.get_saved_show_manual(Some(limit), Some(offset)),
-            self.get_config().pagination_chunks,
-        )
+        self.current_user_saved_shows()
     }
 
     /// The manually paginated version of [`Self::get_saved_show`].
+    #[deprecated(note = "use `current_user_saved_shows_manual` instead")]
     async fn get_saved_show_manual(
         &self,
         limit: Option<u32>,
         offset: Option<u32>,
     ) -> ClientResult<Page<Show>> {
+        self.current_user_saved_shows_manual(limit, offset).await
+    }
+
+    /// Iterates over the episodes of all the shows saved in the current user's
+    /// library, along with the show each of them belongs to, so that the
+    /// listening progress in their `resume_point` can be tracked.
+    ///
+    /// The resume points require the `user-read-playback-position` scope,
+    /// without which they're missing.
+    ///
+    /// Parameters:
+    /// - market - An ISO 3166-1 alpha-2 country code or the string from_token.
+    fn saved_shows_episodes(
+        &self,
+        market: Option<Market>,
+    ) -> Paginator<'_, ClientResult<(ShowId<'static>, SimplifiedEpisode)>> {
+        flat_paginate(self.current_user_saved_shows(), move |saved| {
+            let id = saved.show.id;
+            let episodes = self.get_shows_episodes(id.clone(), market);
+            (id, episodes)
+        })
+    }
+
+    /// Fetches where the current user stopped listening to each of the given
+    /// episodes, which are requested in batches of 50.
+    ///
+    /// The resume points require the `user-read-playback-position` scope.
+    /// The episodes without one are left out of the result.
+    ///
+    /// Parameters:
+    /// - ids - the episode IDs, URIs or URLs
+    /// - market - An ISO 3166-1 alpha-2 country code or the string from_token.
+    async fn get_episodes_resume_points<'a>(
+        &self,
+        ids: impl IntoIterator<Item = EpisodeId<'a>> + MaybeSend + 'a,
+        market: Option<Market>,
+    ) -> ClientResult<HashMap<EpisodeId<'static>, ResumePoint>> {
+        let episodes = self.get_several_episodes(ids, market).await?;
+        Ok(episodes
+            .into_iter()
+            .filter_map(|episode| Some((episode.id, episode.resume_point?)))
+            .collect())
+    }
+
+    /// Check if one or more shows is already saved in the current Spotify user’s library.
+    #[deprecated(note = "use `current_user_saved_shows_contains` instead")]
+    async fn check_users_saved_shows<'a>(
+        &self,
+        ids: impl IntoIterator<Item = ShowId<'a>> + MaybeSend + 'a,
+    ) -> ClientResult<Vec<bool>> {
+        self.current_user_saved_shows_contains(ids).await
+    }
+
+    /// Delete one or more shows from current Spotify user's library.
+    #[deprecated(note = "use `current_user_saved_shows_delete` instead")]
+    async fn remove_users_saved_shows<'a>(
+        &self,
+        show_ids: impl IntoIterator<Item = ShowId<'a>> + MaybeSend + 'a,
+        country: Option<Market>,
+    ) -> ClientResult<()> {
+        self.current_user_saved_shows_delete(show_ids, country)
+            .await
+    }
+
+    /// Get a list of the audiobooks saved in the current Spotify user's
+    /// library.
+    ///
+    /// Parameters:
+    /// - limit(Optional). The maximum number of audiobooks to return. Default:
+    ///   20. Minimum: 1. Maximum: 50.
+    /// - offset(Optional). The index of the first audiobook to return.
+    ///   Default: 0 (the first object). Use with limit to get the next set of
+    ///   audiobooks.
+    ///
+    /// See [`Self::current_user_saved_audiobooks_manual`] for a manually
+    /// paginated version of this.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-users-saved-audiobooks)
+    fn current_user_saved_audiobooks(&self) -> Paginator<'_, ClientResult<SimplifiedAudiobook>> {
+        paginate(
+            move |limit, offset| {
+                self.current_user_saved_audiobooks_manual(Some(limit), Some(offset))
+            },
+            self.get_config().pagination_chunks,
+        )
+    }
+
+    /// The manually paginated version of
+    /// [`Self::current_user_saved_audiobooks`].
+    async fn current_user_saved_audiobooks_manual(
+        &self,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> ClientResult<Page<SimplifiedAudiobook>> {
         let limit = limit.map(|x| x.to_string());
         let offset = offset.map(|x| x.to_string());
         let params = build_map([("limit", limit.as_deref()), ("offset", offset.as_deref())]);
 
-        let result = self.api_get("me/shows", &params).await?;
-        convert_result(&result)
+        let url = Route::MyAudiobooks.to_string();
+        let result = self.api_get(&url, &params).await?;
+        convert_result(self.get_config(), &url, &result)
     }
 
-    /// Check if one or more shows is already saved in the current Spotify user’s library.
+    /// Save one or more audiobooks to the current Spotify user's library.
     ///
-    /// Query Parameters
-    /// - ids: Required. A comma-separated list of the Spotify IDs for the shows. Maximum: 50 IDs.
+    /// Parameters:
+    /// - audiobook_ids - a list of audiobook URIs, URLs or IDs. Maximum: 50
+    ///   IDs.
     ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/check-users-saved-shows)
-    async fn check_users_saved_shows<'a>(
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/save-audiobooks-user)
+    async fn current_user_saved_audiobooks_add<'a>(
+        &self,
+        audiobook_ids: impl IntoIterator<Item = AudiobookId<'a>> + MaybeSend + 'a,
+    ) -> ClientResult<()> {
+        for ids in chunk_items(self.get_config(), audiobook_ids, 50) {
+            let url = Route::SaveAudiobooks(&join_ids(ids)).to_string();
+            self.api_put(&url, &json!({})).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove one or more audiobooks from the current Spotify user's library.
+    ///
+    /// Parameters:
+    /// - audiobook_ids - a list of audiobook URIs, URLs or IDs. Maximum: 50
+    ///   IDs.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/remove-audiobooks-user)
+    async fn current_user_saved_audiobooks_delete<'a>(
         &self,
-        ids: impl IntoIterator<Item = ShowId<'a>> + Send + 'a,
+        audiobook_ids: impl IntoIterator<Item = AudiobookId<'a>> + MaybeSend + 'a,
+    ) -> ClientResult<()> {
+        for ids in chunk_items(self.get_config(), audiobook_ids, 50) {
+            let url = Route::SaveAudiobooks(&join_ids(ids)).to_string();
+            self.api_delete(&url, &json!({})).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Check if one or more audiobooks are already saved in the current
+    /// Spotify user's library.
+    ///
+    /// Parameters:
+    /// - audiobook_ids - a list of audiobook URIs, URLs or IDs. Maximum: 50
+    ///   IDs.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/check-users-saved-audiobooks)
+    async fn current_user_saved_audiobooks_contains<'a>(
+        &self,
+        audiobook_ids: impl IntoIterator<Item = AudiobookId<'a>> + MaybeSend + 'a,
     ) -> ClientResult<Vec<bool>> {
-        let ids = join_ids(ids);
-        let params = build_map([("ids", Some(&ids))]);
-        let result = self.api_get("me/shows/contains", &params).await?;
-        convert_result(&result)
+        let mut contains = Vec::new();
+        for ids in chunk_items(self.get_config(), audiobook_ids, 50) {
+            let ids = join_ids(ids);
+            let params = build_map([("ids", Some(&ids))]);
+            let url = Route::MyAudiobooksContains.to_string();
+            let result = self.api_get(&url, &params).await?;
+            contains.extend(convert_result::<Vec<bool>>(
+                self.get_config(),
+                &url,
+                &result,
+            )?);
+        }
+        Ok(contains)
     }
 
-    /// Delete one or more shows from current Spotify user's library.
-    /// Changes to a user's saved shows may not be visible in other Spotify applications immediately.
+    /// Get a list of the episodes saved in the current Spotify user's
+    /// library.
     ///
-    /// Query Parameters
-    /// - ids: Required. A comma-separated list of Spotify IDs for the shows to be deleted from the user’s library.
-    /// - market: Optional. An ISO 3166-1 alpha-2 country code or the string from_token.
+    /// Parameters:
+    /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
     ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/remove-shows-user)
-    async fn remove_users_saved_shows<'a>(
+    /// See [`Self::current_user_saved_episodes_manual`] for a manually
+    /// paginated version of this.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-users-saved-episodes)
+    fn current_user_saved_episodes(
         &self,
-        show_ids: impl IntoIterator<Item = ShowId<'a>> + Send + 'a,
-        country: Option<Market>,
+        market: Option<Market>,
+    ) -> Paginator<'_, ClientResult<SavedEpisode>> {
+        paginate(
+            move |limit, offset| {
+                self.current_user_saved_episodes_manual(market, Some(limit), Some(offset))
+            },
+            self.get_config().pagination_chunks,
+        )
+    }
+
+    /// The manually paginated version of
+    /// [`Self::current_user_saved_episodes`].
+    async fn current_user_saved_episodes_manual(
+        &self,
+        market: Option<Market>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> ClientResult<Page<SavedEpisode>> {
+        let limit = limit.map(|x| x.to_string());
+        let offset = offset.map(|x| x.to_string());
+        let params = build_map([
+            ("market", self.market_or_default(market)),
+            ("limit", limit.as_deref()),
+            ("offset", offset.as_deref()),
+        ]);
+
+        let url = Route::MyEpisodes.to_string();
+        let result = self.api_get(&url, &params).await?;
+        convert_result(self.get_config(), &url, &result)
+    }
+
+    /// Save one or more episodes to the current Spotify user's library.
+    ///
+    /// Parameters:
+    /// - episode_ids - a list of episode URIs, URLs or IDs. Maximum: 50 IDs.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/save-episodes-user)
+    async fn current_user_saved_episodes_add<'a>(
+        &self,
+        episode_ids: impl IntoIterator<Item = EpisodeId<'a>> + MaybeSend + 'a,
     ) -> ClientResult<()> {
-        let url = format!("me/shows?ids={}", join_ids(show_ids));
-        let params = JsonBuilder::new()
-            .optional("country", country.map(<&str>::from))
-            .build();
-        self.api_delete(&url, &params).await?;
+        for ids in chunk_items(self.get_config(), episode_ids, 50) {
+            let url = Route::SaveEpisodes(&join_ids(ids)).to_string();
+            self.api_put(&url, &json!({})).await?;
+        }
 
         Ok(())
     }
+
+    /// Remove one or more episodes from the current Spotify user's library.
+    ///
+    /// Parameters:
+    /// - episode_ids - a list of episode URIs, URLs or IDs. Maximum: 50 IDs.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/remove-episodes-user)
+    async fn current_user_saved_episodes_delete<'a>(
+        &self,
+        episode_ids: impl IntoIterator<Item = EpisodeId<'a>> + MaybeSend + 'a,
+    ) -> ClientResult<()> {
+        for ids in chunk_items(self.get_config(), episode_ids, 50) {
+            let url = Route::SaveEpisodes(&join_ids(ids)).to_string();
+            self.api_delete(&url, &json!({})).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Check if one or more episodes are already saved in the current Spotify
+    /// user's library.
+    ///
+    /// Parameters:
+    /// - episode_ids - a list of episode URIs, URLs or IDs. Maximum: 50 IDs.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#/operations/check-users-saved-episodes)
+    async fn current_user_saved_episodes_contains<'a>(
+        &self,
+        episode_ids: impl IntoIterator<Item = EpisodeId<'a>> + MaybeSend + 'a,
+    ) -> ClientResult<Vec<bool>> {
+        let mut contains = Vec::new();
+        for ids in chunk_items(self.get_config(), episode_ids, 50) {
+            let ids = join_ids(ids);
+            let params = build_map([("ids", Some(&ids))]);
+            let url = Route::MyEpisodesContains.to_string();
+            let result = self.api_get(&url, &params).await?;
+            contains.extend(convert_result::<Vec<bool>>(
+                self.get_config(),
+                &url,
+                &result,
+            )?);
+        }
+        Ok(contains)
+    }
+}
+
+/// Writes a plain text HTTP response for the local redirect server.
+fn respond(stream: &mut impl Write, status: &str, message: &str) -> ClientResult<()> {
+    let response = format!(
+        "HTTP/1.1 {status}\r\ncontent-type: text/plain; charset=utf-8\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{message}",
+        message.len(),
+    );
+    stream
+        .write_all(response.as_bytes())
+        .map_err(|_| ClientError::AuthCodeListenerWrite)
+}
+
+/// Clamps a seek position to the duration of the item, if known.
+fn clamp_position(
+    position: std::time::Duration,
+    duration: Option<chrono::Duration>,
+) -> std::time::Duration {
+    match duration.and_then(|duration| duration.to_std().ok()) {
+        Some(duration) => position.min(duration),
+        None => position,
+    }
+}
+
+/// Parses a cursor of the recently played tracks, which is a Unix timestamp in
+/// milliseconds.
+fn cursor_timestamp(cursor: Option<&str>) -> Option<chrono::DateTime<chrono::Utc>> {
+    let millis = cursor?.parse().ok()?;
+    chrono::DateTime::from_timestamp_millis(millis)
 }
 
 #[cfg(test)]
@@ -1472,4 +2547,114 @@ mod tests {
             Some(localhost_v6)
         );
     }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_multiple_redirect_uris() {
+        let spotify = AuthCodeSpotify::new(
+            Default::default(),
+            OAuth {
+                redirect_uri: "http://127.0.0.1:8888/callback".to_owned(),
+                redirect_uris: vec!["http://127.0.0.1:9999/callback".to_owned()],
+                redirect_uri_selector: Some(crate::RedirectUriSelector(Arc::new(|candidates| {
+                    candidates[1].clone()
+                }))),
+                ..Default::default()
+            },
+        );
+        assert_eq!(spotify.redirect_uri(), "http://127.0.0.1:8888/callback");
+
+        let url = spotify.get_authorize_url(false).unwrap();
+        assert!(url.contains("redirect_uri=http%3A%2F%2F127.0.0.1%3A9999%2Fcallback"));
+        assert_eq!(spotify.redirect_uri(), "http://127.0.0.1:9999/callback");
+
+        // The user was redirected to the other URI after all
+        let state = &spotify.oauth.state;
+        let callback = format!("http://127.0.0.1:8888/callback?code=abc&state={state}");
+        assert_eq!(spotify.parse_response_code(&callback).unwrap(), "abc");
+        assert_eq!(spotify.redirect_uri(), "http://127.0.0.1:8888/callback");
+
+        // Unknown URIs are still accepted, keeping the selected one
+        let callback = format!("http://localhost/?code=abc&state={state}");
+        assert_eq!(spotify.parse_response_code(&callback).unwrap(), "abc");
+        assert_eq!(spotify.redirect_uri(), "http://127.0.0.1:8888/callback");
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_state_in_another_client() {
+        let oauth = OAuth::default();
+        let callback = format!("http://localhost/?code=abc&state={}", oauth.state);
+
+        // A new client generates a different state, so it rejects the callback
+        let other = AuthCodeSpotify::new(Default::default(), OAuth::default());
+        assert!(!other.oauth.validate_state(&callback));
+        assert_eq!(other.parse_response_code(&callback), None);
+
+        // Unless the state is restored
+        let restored = OAuth::with_state(oauth.state.clone());
+        assert!(restored.validate_state(&callback));
+        let other = AuthCodeSpotify::new(Default::default(), restored);
+        assert_eq!(other.parse_response_code(&callback).unwrap(), "abc");
+
+        assert!(!oauth.validate_state("http://localhost/?code=abc"));
+        assert!(!oauth.validate_state("not a url"));
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_clamp_position() {
+        let seconds = std::time::Duration::from_secs;
+        let duration = |s| chrono::Duration::try_seconds(s);
+        assert_eq!(clamp_position(seconds(50), duration(200)), seconds(50));
+        assert_eq!(clamp_position(seconds(500), duration(200)), seconds(200));
+        assert_eq!(clamp_position(seconds(500), None), seconds(500));
+        // A negative duration can't be clamped to
+        assert_eq!(clamp_position(seconds(5), duration(-1)), seconds(5));
+    }
+
+    #[test]
+    fn get_authcode_listener_ignores_other_paths() {
+        use std::{io::Read, net::TcpStream, thread};
+
+        // Reserve a free port for the redirect URI
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let spotify = AuthCodeSpotify {
+            oauth: OAuth {
+                redirect_uri: format!("http://127.0.0.1:{port}/callback"),
+                state: "xyz".to_owned(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let addr = spotify
+            .get_socket_address(&spotify.get_oauth().redirect_uri)
+            .unwrap();
+
+        let browser = thread::spawn(move || {
+            let mut responses = Vec::new();
+            for target in ["/favicon.ico", "/callback?code=abc&state=xyz"] {
+                let mut stream = loop {
+                    match TcpStream::connect(addr) {
+                        Ok(stream) => break stream,
+                        Err(_) => thread::sleep(std::time::Duration::from_millis(10)),
+                    }
+                };
+                write!(stream, "GET {target} HTTP/1.1\r\nhost: 127.0.0.1\r\n\r\n").unwrap();
+                let mut response = String::new();
+                stream.read_to_string(&mut response).unwrap();
+                responses.push(response);
+            }
+            responses
+        });
+
+        assert_eq!(spotify.get_authcode_listener(addr).unwrap(), "abc");
+        let responses = browser.join().unwrap();
+        assert!(responses[0].starts_with("HTTP/1.1 404 Not Found"));
+        assert!(responses[1].starts_with("HTTP/1.1 200 OK"));
+    }
 }
@@ -1,6 +1,10 @@
 //! Synchronous implementation of automatic pagination requests.
 
-use crate::{model::Page, ClientError, ClientResult};
+use super::{with_totals, PageProgress, Paged};
+use crate::{
+    model::{Cursor, CursorBasedPage, Page},
+    ClientError, ClientResult,
+};
 
 /// Alias for `Iterator<Item = T>`, since sync mode is enabled.
 pub type Paginator<'a, T> = Box<dyn Iterator<Item = T> + 'a>;
@@ -31,6 +35,120 @@ where
     Box::new(pages.flat_map(|result| ResultIter::new(result.map(|page| page.items.into_iter()))))
 }
 
+/// Same as [`paginate`], but `on_page` is invoked with the progress after
+/// each page is fetched, before its items are returned.
+pub fn paginate_with_progress<'a, T: 'a, Request, F>(
+    req: Request,
+    page_size: u32,
+    mut on_page: F,
+) -> Paginator<'a, ClientResult<T>>
+where
+    Request: 'a + Fn(u32, u32) -> ClientResult<Page<T>>,
+    F: 'a + FnMut(PageProgress),
+{
+    let pages = PageIterator {
+        req,
+        offset: 0,
+        done: false,
+        page_size,
+    };
+
+    let mut progress = None;
+    Box::new(
+        pages
+            .inspect(move |result| {
+                if let Ok(page) = result {
+                    let current = PageProgress::next(progress, page);
+                    on_page(current);
+                    progress = Some(current);
+                }
+            })
+            .flat_map(|result| ResultIter::new(result.map(|page| page.items.into_iter()))),
+    )
+}
+
+/// Same as [`paginate`], but the items are returned along with their position
+/// and the total number of items.
+pub fn paginate_with_totals<'a, T: 'a, Request>(
+    req: Request,
+    page_size: u32,
+) -> Paginator<'a, ClientResult<Paged<T>>>
+where
+    Request: 'a + Fn(u32, u32) -> ClientResult<Page<T>>,
+{
+    paginate(
+        move |limit, offset| req(limit, offset).map(with_totals),
+        page_size,
+    )
+}
+
+/// Paginates over the inner paginator of each item in `outer`, whose items are
+/// returned along with the key given by `inner`. The errors of `outer` are
+/// returned as they are.
+pub(crate) fn flat_paginate<'a, T: 'a, K, U: 'a, F>(
+    outer: Paginator<'a, ClientResult<T>>,
+    mut inner: F,
+) -> Paginator<'a, ClientResult<(K, U)>>
+where
+    K: 'a + Clone,
+    F: 'a + FnMut(T) -> (K, Paginator<'a, ClientResult<U>>),
+{
+    Box::new(
+        outer.flat_map(move |result| -> Paginator<'a, ClientResult<(K, U)>> {
+            match result {
+                Ok(item) => {
+                    let (key, items) = inner(item);
+                    Box::new(items.map(move |item| item.map(|item| (key.clone(), item))))
+                }
+                Err(e) => Box::new(std::iter::once(Err(e))),
+            }
+        }),
+    )
+}
+
+/// Same as [`paginate_with_ctx`], but for the endpoints with cursor-based
+/// pagination. The request is given the cursors of the previous page, which
+/// are `None` for the first one.
+pub fn paginate_cursor_with_ctx<'a, Ctx: 'a, T: 'a, Request>(
+    ctx: Ctx,
+    req: Request,
+    page_size: u32,
+) -> Paginator<'a, ClientResult<T>>
+where
+    Request: 'a + Fn(&Ctx, u32, Option<&Cursor>) -> ClientResult<CursorBasedPage<T>>,
+{
+    let mut cursors = None;
+    let mut done = false;
+    let pages = std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+
+        match req(&ctx, page_size, cursors.as_ref()) {
+            Ok(page) => {
+                // Also stopping if the cursors don't change, since the same
+                // page would be requested forever
+                if page.next.is_none() || page.cursors.is_none() || page.cursors == cursors {
+                    done = true;
+                }
+                cursors = page.cursors;
+
+                if page.items.is_empty() {
+                    None
+                } else {
+                    Some(Ok(page.items))
+                }
+            }
+            Err(e) => {
+                done = true;
+                Some(Err(e))
+            }
+        }
+    });
+
+    Box::new(pages.flat_map(|result| ResultIter::new(result.map(Vec::into_iter))))
+}
+
 /// Iterator that repeatedly calls a function that returns a page until an empty
 /// page is returned.
 struct PageIterator<Request> {
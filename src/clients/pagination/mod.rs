@@ -11,6 +11,22 @@
 //!   function, but accepts a generic context that works around lifetime issues
 //!   in the async version due to restrictions in HRTBs
 //!   (<https://kevincox.ca/2022/04/16/rust-generic-closure-lifetimes/>)
+//! * A `paginate_cursor_with_ctx` function for the endpoints with cursor-based
+//!   pagination, whose requests take the cursors of the previous page instead
+//!   of an offset
+//! * A `paginate_with_totals` function that does the same as `paginate`, but
+//!   each item is returned as a [`Paged`], along with its index and the total
+//!   number of items, e.g., to render a progress bar
+//! * A `paginate_with_progress` function that does the same as `paginate`,
+//!   but invokes a callback with the [`PageProgress`] after each page is
+//!   fetched, for long downloads like exporting a whole library
+//!
+//! The pages are requested lazily: the asynchronous paginators only send the
+//! next request once the items of the current page have been consumed and
+//! the stream is polled again, and nothing is buffered beyond the current
+//! page. Consumers that would rather request the next page ahead of time may
+//! opt into it with [`PaginatorExt::prefetch`], or request several pages at
+//! the same time with [`paginate_concurrently`].
 //!
 //! Note that `Paginator` should actually be a trait so that a dynamic
 //! allocation can be avoided when returning it with `-> impl Iterator<T>`, as
@@ -32,11 +48,95 @@ mod stream;
 #[cfg(all(feature = "__async", target_arch = "wasm32"))]
 mod wasm_stream;
 
+#[cfg(feature = "__async")]
+mod prefetch;
+
+#[cfg(feature = "__sync")]
+pub(crate) use iter::flat_paginate;
 #[cfg(feature = "__sync")]
-pub use iter::{paginate, paginate_with_ctx, Paginator};
+pub use iter::{
+    paginate, paginate_cursor_with_ctx, paginate_with_ctx, paginate_with_progress,
+    paginate_with_totals, Paginator,
+};
 
 #[cfg(all(feature = "__async", not(target_arch = "wasm32")))]
-pub use stream::{paginate, paginate_with_ctx, Paginator};
+pub(crate) use stream::flat_paginate;
+#[cfg(all(feature = "__async", not(target_arch = "wasm32")))]
+pub use stream::{
+    paginate, paginate_cursor_with_ctx, paginate_with_ctx, paginate_with_progress,
+    paginate_with_totals, Paginator,
+};
 
 #[cfg(all(feature = "__async", target_arch = "wasm32"))]
-pub use wasm_stream::{paginate, paginate_with_ctx, Paginator};
+pub(crate) use wasm_stream::flat_paginate;
+#[cfg(all(feature = "__async", target_arch = "wasm32"))]
+pub use wasm_stream::{
+    paginate, paginate_cursor_with_ctx, paginate_with_ctx, paginate_with_progress,
+    paginate_with_totals, Paginator,
+};
+
+#[cfg(feature = "__async")]
+pub use prefetch::{paginate_concurrently, PaginatorExt, Prefetch};
+
+use crate::model::Page;
+
+/// An item returned by `paginate_with_totals`, along with its position in the
+/// whole collection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Paged<T> {
+    pub item: T,
+    /// The index of the item, starting at zero.
+    pub index: u32,
+    /// The total number of items, as reported by the page the item is in.
+    pub total: u32,
+}
+
+/// The progress of a download, given by `paginate_with_progress` after each
+/// page is fetched.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PageProgress {
+    /// The index of the page, starting at zero.
+    pub page: u32,
+    /// The number of items fetched so far, including the page.
+    pub fetched: u32,
+    /// The total number of items, as reported by the page.
+    pub total: u32,
+}
+
+impl PageProgress {
+    /// Advances the progress with the given page.
+    fn next<T>(previous: Option<Self>, page: &Page<T>) -> Self {
+        Self {
+            page: previous.map_or(0, |progress| progress.page + 1),
+            fetched: previous.map_or(0, |progress| progress.fetched) + page.items.len() as u32,
+            total: page.total,
+        }
+    }
+}
+
+/// Attaches the position to each item of the page.
+fn with_totals<T>(page: Page<T>) -> Page<Paged<T>> {
+    let Page {
+        href,
+        items,
+        limit,
+        next,
+        offset,
+        previous,
+        total,
+    } = page;
+    let items = items
+        .into_iter()
+        .zip(offset..)
+        .map(|(item, index)| Paged { item, index, total })
+        .collect();
+    Page {
+        href,
+        items,
+        limit,
+        next,
+        offset,
+        previous,
+        total,
+    }
+}
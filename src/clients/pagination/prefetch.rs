@@ -0,0 +1,295 @@
+//! Opt-in prefetching for the asynchronous paginators.
+
+use crate::{model::Page, ClientResult};
+
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::stream::{self, Stream, StreamExt};
+
+/// Adds [`prefetch`](Self::prefetch) to the paginators, and to any other
+/// stream.
+pub trait PaginatorExt: Stream + Sized {
+    /// Keeps up to `n` items buffered ahead of the consumer, so that the next
+    /// page is requested before the current one has been fully consumed.
+    ///
+    /// The items are still only fetched while the stream is polled: every
+    /// time an item is taken from it, the paginator is polled until there are
+    /// `n` more buffered, or until it has to wait for a response. The request
+    /// is then sent, and its response is picked up on the following polls.
+    /// Thus, at most one request beyond the consumer's demand is in flight at
+    /// any time.
+    ///
+    /// With `n = 0` it behaves as the paginator itself. To fetch a whole page
+    /// ahead, `n` should be the page size, i.e.,
+    /// [`Config::pagination_chunks`](crate::Config::pagination_chunks).
+    ///
+    /// ```no_run
+    /// # async fn example(spotify: rspotify::ClientCredsSpotify) {
+    /// use futures::TryStreamExt;
+    /// use rspotify::{clients::pagination::PaginatorExt, model::AlbumId, prelude::*};
+    ///
+    /// let album = AlbumId::from_id("6akEvsycLGftJxYudPjmqK").unwrap();
+    /// let mut tracks = spotify.album_track(album, None).prefetch(50);
+    /// while let Some(track) = tracks.try_next().await.unwrap() {
+    ///     println!("{}", track.name);
+    /// }
+    /// # }
+    /// ```
+    fn prefetch(self, n: usize) -> Prefetch<Self> {
+        Prefetch {
+            inner: self,
+            buffer: VecDeque::with_capacity(n + 1),
+            size: n,
+            done: false,
+        }
+    }
+}
+
+impl<S: Stream> PaginatorExt for S {}
+
+/// The stream returned by [`PaginatorExt::prefetch`].
+pub struct Prefetch<S: Stream> {
+    inner: S,
+    buffer: VecDeque<S::Item>,
+    size: usize,
+    done: bool,
+}
+
+impl<S: Stream> std::fmt::Debug for Prefetch<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Prefetch")
+            .field("buffered", &self.buffer.len())
+            .field("size", &self.size)
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
+
+// The buffered items are never pinned
+impl<S: Stream + Unpin> Unpin for Prefetch<S> {}
+
+impl<S: Stream + Unpin> Stream for Prefetch<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        // One item for the consumer, and `size` more ahead of it
+        while !this.done && this.buffer.len() <= this.size {
+            match Pin::new(&mut this.inner).poll_next(cx) {
+                Poll::Ready(Some(item)) => this.buffer.push_back(item),
+                Poll::Ready(None) => this.done = true,
+                Poll::Pending => break,
+            }
+        }
+
+        match this.buffer.pop_front() {
+            Some(item) => Poll::Ready(Some(item)),
+            None if this.done => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.inner.size_hint();
+        let buffered = self.buffer.len();
+        (
+            lower.saturating_add(buffered),
+            upper.and_then(|upper| upper.checked_add(buffered)),
+        )
+    }
+}
+
+/// Paginates over the items of an offset-based endpoint, requesting up to
+/// `concurrency` pages at the same time.
+///
+/// The first page is requested alone in order to know the total number of
+/// items. The offsets of the rest of the pages are then known in advance, so
+/// their requests are pipelined, which is much faster than the sequential
+/// paginators when dumping large libraries. The items are still returned in
+/// order, and the stream ends after the first error.
+///
+/// The request is usually the manually paginated version of an endpoint, and
+/// `page_size` its maximum limit:
+///
+/// ```no_run
+/// # async fn example(spotify: rspotify::AuthCodeSpotify) {
+/// use futures::TryStreamExt;
+/// use rspotify::{clients::pagination::paginate_concurrently, prelude::*};
+///
+/// let tracks = paginate_concurrently(
+///     |limit, offset| spotify.current_user_saved_tracks_manual(None, Some(limit), Some(offset)),
+///     50,
+///     4,
+/// );
+/// let tracks = tracks.try_collect::<Vec<_>>().await.unwrap();
+/// # }
+/// ```
+///
+/// Note that the pages may be inconsistent if the items are modified during
+/// the pagination, and that the concurrent requests are more likely to hit
+/// the rate limit.
+pub fn paginate_concurrently<'a, T, Fut, Request>(
+    req: Request,
+    page_size: u32,
+    concurrency: usize,
+) -> impl Stream<Item = ClientResult<T>> + 'a
+where
+    T: 'a,
+    Fut: 'a + Future<Output = ClientResult<Page<T>>>,
+    Request: 'a + Fn(u32, u32) -> Fut,
+{
+    use async_stream::stream;
+    let page_size = page_size.max(1);
+    stream! {
+        let first = req(page_size, 0).await?;
+        let total = first.total;
+        let done = first.items.is_empty() || first.next.is_none();
+        for item in first.items {
+            yield Ok(item);
+        }
+        if done {
+            return;
+        }
+
+        let offsets = (1..)
+            .map(|page| page * page_size)
+            .take_while(|&offset| offset < total);
+        let mut pages = stream::iter(offsets)
+            .map(|offset| req(page_size, offset))
+            .buffered(concurrency.max(1));
+        while let Some(page) = pages.next().await {
+            let page = page?;
+            // Same as in the sequential paginators, see
+            // https://github.com/ramsayleung/rspotify/issues/492
+            if page.items.is_empty() {
+                break;
+            }
+            for item in page.items {
+                yield Ok(item);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{paginate_concurrently, PaginatorExt};
+    use crate::{
+        clients::pagination::{paginate, Paginator},
+        model::Page,
+        ClientError, ClientResult,
+    };
+    use futures::{executor::block_on, future, StreamExt};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// A paginator of 10 numbers, in pages of 3, counting the requests.
+    fn numbers(requests: &AtomicU32) -> Paginator<'_, ClientResult<u32>> {
+        paginate(
+            move |limit, offset| {
+                requests.fetch_add(1, Ordering::SeqCst);
+                let items = (offset..10).take(limit as usize).collect::<Vec<_>>();
+                let next = (offset + limit < 10).then(|| "next".to_owned());
+                future::ok(Page {
+                    items,
+                    next,
+                    ..Page::default()
+                })
+            },
+            3,
+        )
+    }
+
+    #[test]
+    fn test_lazy_pagination() {
+        let requests = AtomicU32::new(0);
+        let mut paginator = numbers(&requests);
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+
+        // Only the pages needed for the items taken are requested
+        let items = block_on(paginator.by_ref().take(3).collect::<Vec<_>>());
+        assert_eq!(items.len(), 3);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        block_on(paginator.next());
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_prefetch() {
+        let requests = AtomicU32::new(0);
+        let mut paginator = numbers(&requests).prefetch(3);
+
+        // The second page is already requested along with the first item
+        let first = block_on(paginator.next()).unwrap().unwrap();
+        assert_eq!(first, 0);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        let rest = block_on(paginator.map(Result::unwrap).collect::<Vec<_>>());
+        assert_eq!(rest, (1..10).collect::<Vec<_>>());
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_paginate_concurrently() {
+        let requests = AtomicU32::new(0);
+        let in_flight = AtomicU32::new(0);
+        let max_in_flight = AtomicU32::new(0);
+        let paginator = paginate_concurrently(
+            |limit, offset| {
+                requests.fetch_add(1, Ordering::SeqCst);
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(current, Ordering::SeqCst);
+                let in_flight = &in_flight;
+                async move {
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    let items = (offset..10).take(limit as usize).collect::<Vec<_>>();
+                    let next = (offset + limit < 10).then(|| "next".to_owned());
+                    Ok(Page {
+                        items,
+                        next,
+                        total: 10,
+                        ..Page::default()
+                    })
+                }
+            },
+            3,
+            3,
+        );
+
+        let items = block_on(paginator.map(Result::unwrap).collect::<Vec<_>>());
+        assert_eq!(items, (0..10).collect::<Vec<_>>());
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+        // The three pages after the first one are requested at once
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_paginate_concurrently_error() {
+        let paginator = paginate_concurrently(
+            |limit, offset| {
+                let result = if offset == 4 {
+                    Err(ClientError::InvalidToken)
+                } else {
+                    Ok(Page {
+                        items: (offset..offset + limit).collect::<Vec<_>>(),
+                        next: Some("next".to_owned()),
+                        total: 20,
+                        ..Page::default()
+                    })
+                };
+                future::ready(result)
+            },
+            2,
+            4,
+        );
+
+        let items = block_on(paginator.collect::<Vec<_>>());
+        assert_eq!(items.len(), 5);
+        assert!(matches!(items[4], Err(ClientError::InvalidToken)));
+    }
+}
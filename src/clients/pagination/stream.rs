@@ -1,25 +1,40 @@
 //! Asynchronous implementation of automatic pagination requests.
 
-use crate::{model::Page, ClientResult};
+use super::{with_totals, PageProgress, Paged};
+use crate::{
+    clients::MaybeSend,
+    model::{Cursor, CursorBasedPage, Page},
+    ClientResult,
+};
 
 use std::pin::Pin;
 
-use futures::{future::Future, stream::Stream};
+use futures::{
+    future::{Future, TryFutureExt},
+    stream::Stream,
+};
 
 /// Alias for `futures::stream::Stream<Item = T>`, since async mode is enabled.
+///
+/// This module isn't used on WebAssembly, so the streams and futures are
+/// always [`Send`] here, as required by [`MaybeSend`]. See `wasm_stream` for
+/// the version without it.
 pub type Paginator<'a, T> = Pin<Box<dyn Stream<Item = T> + 'a + Send>>;
 
 pub type RequestFuture<'a, T> = Pin<Box<dyn 'a + Future<Output = ClientResult<Page<T>>> + Send>>;
 
+pub type CursorRequestFuture<'a, T> =
+    Pin<Box<dyn 'a + Future<Output = ClientResult<CursorBasedPage<T>>> + Send>>;
+
 /// This is used to handle paginated requests automatically.
-pub fn paginate_with_ctx<'a, Ctx: 'a + Send, T, Request>(
+pub fn paginate_with_ctx<'a, Ctx: 'a + MaybeSend, T, Request>(
     ctx: Ctx,
     req: Request,
     page_size: u32,
 ) -> Paginator<'a, ClientResult<T>>
 where
-    T: 'a + Unpin + Send,
-    Request: 'a + for<'ctx> Fn(&'ctx Ctx, u32, u32) -> RequestFuture<'ctx, T> + Send,
+    T: 'a + Unpin + MaybeSend,
+    Request: 'a + for<'ctx> Fn(&'ctx Ctx, u32, u32) -> RequestFuture<'ctx, T> + MaybeSend,
 {
     use async_stream::stream;
     let mut offset = 0;
@@ -44,11 +59,74 @@ where
     })
 }
 
+/// Paginates over the inner paginator of each item in `outer`, whose items are
+/// returned along with the key given by `inner`. The errors of `outer` are
+/// returned as they are.
+pub(crate) fn flat_paginate<'a, T, K, U, F>(
+    outer: Paginator<'a, ClientResult<T>>,
+    mut inner: F,
+) -> Paginator<'a, ClientResult<(K, U)>>
+where
+    T: 'a + MaybeSend,
+    K: 'a + Clone + MaybeSend,
+    U: 'a + MaybeSend,
+    F: 'a + FnMut(T) -> (K, Paginator<'a, ClientResult<U>>) + MaybeSend,
+{
+    use futures::stream::{self, StreamExt};
+    Box::pin(
+        outer.flat_map(move |result| -> Paginator<'a, ClientResult<(K, U)>> {
+            match result {
+                Ok(item) => {
+                    let (key, items) = inner(item);
+                    Box::pin(items.map(move |item| item.map(|item| (key.clone(), item))))
+                }
+                Err(e) => Box::pin(stream::once(async { Err(e) })),
+            }
+        }),
+    )
+}
+
+/// Same as [`paginate_with_ctx`], but for the endpoints with cursor-based
+/// pagination. The request is given the cursors of the previous page, which
+/// are `None` for the first one.
+pub fn paginate_cursor_with_ctx<'a, Ctx: 'a + MaybeSend, T, Request>(
+    ctx: Ctx,
+    req: Request,
+    page_size: u32,
+) -> Paginator<'a, ClientResult<T>>
+where
+    T: 'a + Unpin + MaybeSend,
+    Request: 'a
+        + for<'ctx> Fn(&'ctx Ctx, u32, Option<&'ctx Cursor>) -> CursorRequestFuture<'ctx, T>
+        + MaybeSend,
+{
+    use async_stream::stream;
+    Box::pin(stream! {
+        let mut cursors: Option<Cursor> = None;
+        loop {
+            let request = req(&ctx, page_size, cursors.as_ref());
+            let page = request.await?;
+            if page.items.is_empty() {
+                break;
+            }
+            for item in page.items {
+                yield Ok(item);
+            }
+            // Also stopping if the cursors don't change, since the same page
+            // would be requested forever
+            if page.next.is_none() || page.cursors.is_none() || page.cursors == cursors {
+                break;
+            }
+            cursors = page.cursors;
+        }
+    })
+}
+
 pub fn paginate<'a, T, Fut, Request>(req: Request, page_size: u32) -> Paginator<'a, ClientResult<T>>
 where
-    T: 'a + Unpin + Send,
-    Fut: Future<Output = ClientResult<Page<T>>> + Send,
-    Request: 'a + Fn(u32, u32) -> Fut + Send,
+    T: 'a + Unpin + MaybeSend,
+    Fut: Future<Output = ClientResult<Page<T>>> + MaybeSend,
+    Request: 'a + Fn(u32, u32) -> Fut + MaybeSend,
 {
     use async_stream::stream;
     let mut offset = 0;
@@ -67,10 +145,61 @@ where
     })
 }
 
+/// Same as [`paginate`], but `on_page` is invoked with the progress after
+/// each page is fetched, before its items are returned.
+pub fn paginate_with_progress<'a, T, Fut, Request, F>(
+    req: Request,
+    page_size: u32,
+    mut on_page: F,
+) -> Paginator<'a, ClientResult<T>>
+where
+    T: 'a + Unpin + MaybeSend,
+    Fut: Future<Output = ClientResult<Page<T>>> + MaybeSend,
+    Request: 'a + Fn(u32, u32) -> Fut + MaybeSend,
+    F: 'a + FnMut(PageProgress) + MaybeSend,
+{
+    use async_stream::stream;
+    let mut progress: Option<PageProgress> = None;
+    Box::pin(stream! {
+        loop {
+            let offset = progress.map_or(0, |progress| progress.fetched);
+            let request = req(page_size, offset);
+            let page = request.await?;
+            let current = PageProgress::next(progress, &page);
+            on_page(current);
+            progress = Some(current);
+            for item in page.items {
+                yield Ok(item);
+            }
+            if page.next.is_none() {
+                break;
+            }
+        }
+    })
+}
+
+/// Same as [`paginate`], but the items are returned along with their position
+/// and the total number of items.
+pub fn paginate_with_totals<'a, T, Fut, Request>(
+    req: Request,
+    page_size: u32,
+) -> Paginator<'a, ClientResult<Paged<T>>>
+where
+    T: 'a + Unpin + MaybeSend,
+    Fut: Future<Output = ClientResult<Page<T>>> + MaybeSend,
+    Request: 'a + Fn(u32, u32) -> Fut + MaybeSend,
+{
+    paginate(
+        move |limit, offset| req(limit, offset).map_ok(with_totals),
+        page_size,
+    )
+}
+
 #[cfg(test)]
 mod test {
-    use super::paginate;
-    use crate::model::Page;
+    use super::{paginate, paginate_cursor_with_ctx, paginate_with_progress, paginate_with_totals};
+    use crate::clients::pagination::{PageProgress, Paged};
+    use crate::model::{Cursor, CursorBasedPage, Page};
     use futures::{future, StreamExt};
     use std::future::Future;
 
@@ -102,4 +231,102 @@ mod test {
         }
         schedule_future(test());
     }
+
+    #[test]
+    fn test_cursor_pagination() {
+        async fn test() {
+            // Pages of two items, where the cursor is the last one, until 5
+            let paginator = paginate_cursor_with_ctx(
+                (),
+                |_, _, cursors| {
+                    let after = cursors
+                        .and_then(|cursors| cursors.after.as_deref())
+                        .map_or(0, |after| after.parse::<u32>().unwrap());
+                    let items = (after + 1..=5).take(2).collect::<Vec<_>>();
+                    let last = items.last().copied().unwrap_or(after);
+                    let page = CursorBasedPage {
+                        items,
+                        next: Some("next".to_owned()),
+                        cursors: Some(Cursor {
+                            after: Some(last.to_string()),
+                            before: None,
+                        }),
+                        ..Default::default()
+                    };
+                    Box::pin(future::ok(page))
+                },
+                2,
+            );
+
+            let items = paginator.map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(items, [1, 2, 3, 4, 5]);
+        }
+        schedule_future(test());
+    }
+
+    #[test]
+    fn test_pagination_with_totals() {
+        async fn test() {
+            let paginator = paginate_with_totals(
+                |limit, offset| {
+                    let page = Page {
+                        items: (offset..5).take(limit as usize).collect::<Vec<_>>(),
+                        next: (offset + limit < 5).then(|| "next".to_owned()),
+                        offset,
+                        total: 5,
+                        ..Page::default()
+                    };
+                    future::ok(page)
+                },
+                2,
+            );
+
+            let items = paginator.map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(items.len(), 5);
+            assert_eq!(
+                items[3],
+                Paged {
+                    item: 3,
+                    index: 3,
+                    total: 5
+                }
+            );
+        }
+        schedule_future(test());
+    }
+
+    #[test]
+    fn test_pagination_with_progress() {
+        async fn test() {
+            let mut progress = Vec::new();
+            let paginator = paginate_with_progress(
+                |limit, offset| {
+                    let page = Page {
+                        items: (offset..5).take(limit as usize).collect::<Vec<_>>(),
+                        next: (offset + limit < 5).then(|| "next".to_owned()),
+                        offset,
+                        total: 5,
+                        ..Page::default()
+                    };
+                    future::ok(page)
+                },
+                2,
+                |page| progress.push(page),
+            );
+
+            let items = paginator.map(Result::unwrap).collect::<Vec<_>>().await;
+            assert_eq!(items, [0, 1, 2, 3, 4]);
+            assert_eq!(progress.len(), 3);
+            assert_eq!(
+                progress[1],
+                PageProgress {
+                    page: 1,
+                    fetched: 4,
+                    total: 5
+                }
+            );
+            assert_eq!(progress[2].fetched, 5);
+        }
+        schedule_future(test());
+    }
 }
@@ -1,16 +1,26 @@
 //! Asynchronous implementation of automatic pagination requests.
 
-use crate::{model::Page, ClientResult};
+use super::{with_totals, PageProgress, Paged};
+use crate::{
+    model::{Cursor, CursorBasedPage, Page},
+    ClientResult,
+};
 
 use std::pin::Pin;
 
-use futures::{future::Future, stream::Stream};
+use futures::{
+    future::{Future, TryFutureExt},
+    stream::Stream,
+};
 
 /// Alias for `futures::stream::Stream<Item = T>`, since async mode is enabled.
 pub type Paginator<'a, T> = Pin<Box<dyn Stream<Item = T> + 'a>>;
 
 pub type RequestFuture<'a, T> = Pin<Box<dyn 'a + Future<Output = ClientResult<Page<T>>>>>;
 
+pub type CursorRequestFuture<'a, T> =
+    Pin<Box<dyn 'a + Future<Output = ClientResult<CursorBasedPage<T>>>>>;
+
 /// This is used to handle paginated requests automatically.
 pub fn paginate_with_ctx<'a, Ctx: 'a, T, Request>(
     ctx: Ctx,
@@ -38,6 +48,68 @@ where
     })
 }
 
+/// Paginates over the inner paginator of each item in `outer`, whose items are
+/// returned along with the key given by `inner`. The errors of `outer` are
+/// returned as they are.
+pub(crate) fn flat_paginate<'a, T, K, U, F>(
+    outer: Paginator<'a, ClientResult<T>>,
+    mut inner: F,
+) -> Paginator<'a, ClientResult<(K, U)>>
+where
+    T: 'a,
+    K: 'a + Clone,
+    U: 'a,
+    F: 'a + FnMut(T) -> (K, Paginator<'a, ClientResult<U>>),
+{
+    use futures::stream::{self, StreamExt};
+    Box::pin(
+        outer.flat_map(move |result| -> Paginator<'a, ClientResult<(K, U)>> {
+            match result {
+                Ok(item) => {
+                    let (key, items) = inner(item);
+                    Box::pin(items.map(move |item| item.map(|item| (key.clone(), item))))
+                }
+                Err(e) => Box::pin(stream::once(async { Err(e) })),
+            }
+        }),
+    )
+}
+
+/// Same as [`paginate_with_ctx`], but for the endpoints with cursor-based
+/// pagination. The request is given the cursors of the previous page, which
+/// are `None` for the first one.
+pub fn paginate_cursor_with_ctx<'a, Ctx: 'a, T, Request>(
+    ctx: Ctx,
+    req: Request,
+    page_size: u32,
+) -> Paginator<'a, ClientResult<T>>
+where
+    T: 'a + Unpin,
+    Request:
+        'a + for<'ctx> Fn(&'ctx Ctx, u32, Option<&'ctx Cursor>) -> CursorRequestFuture<'ctx, T>,
+{
+    use async_stream::stream;
+    Box::pin(stream! {
+        let mut cursors: Option<Cursor> = None;
+        loop {
+            let request = req(&ctx, page_size, cursors.as_ref());
+            let page = request.await?;
+            if page.items.is_empty() {
+                break;
+            }
+            for item in page.items {
+                yield Ok(item);
+            }
+            // Also stopping if the cursors don't change, since the same page
+            // would be requested forever
+            if page.next.is_none() || page.cursors.is_none() || page.cursors == cursors {
+                break;
+            }
+            cursors = page.cursors;
+        }
+    })
+}
+
 pub fn paginate<'a, T, Fut, Request>(req: Request, page_size: u32) -> Paginator<'a, ClientResult<T>>
 where
     T: 'a + Unpin,
@@ -60,3 +132,53 @@ where
         }
     })
 }
+
+/// Same as [`paginate`], but `on_page` is invoked with the progress after
+/// each page is fetched, before its items are returned.
+pub fn paginate_with_progress<'a, T, Fut, Request, F>(
+    req: Request,
+    page_size: u32,
+    mut on_page: F,
+) -> Paginator<'a, ClientResult<T>>
+where
+    T: 'a + Unpin,
+    Fut: Future<Output = ClientResult<Page<T>>>,
+    Request: 'a + Fn(u32, u32) -> Fut,
+    F: 'a + FnMut(PageProgress),
+{
+    use async_stream::stream;
+    let mut progress: Option<PageProgress> = None;
+    Box::pin(stream! {
+        loop {
+            let offset = progress.map_or(0, |progress| progress.fetched);
+            let request = req(page_size, offset);
+            let page = request.await?;
+            let current = PageProgress::next(progress, &page);
+            on_page(current);
+            progress = Some(current);
+            for item in page.items {
+                yield Ok(item);
+            }
+            if page.next.is_none() {
+                break;
+            }
+        }
+    })
+}
+
+/// Same as [`paginate`], but the items are returned along with their position
+/// and the total number of items.
+pub fn paginate_with_totals<'a, T, Fut, Request>(
+    req: Request,
+    page_size: u32,
+) -> Paginator<'a, ClientResult<Paged<T>>>
+where
+    T: 'a + Unpin,
+    Fut: Future<Output = ClientResult<Page<T>>>,
+    Request: 'a + Fn(u32, u32) -> Fut,
+{
+    paginate(
+        move |limit, offset| req(limit, offset).map_ok(with_totals),
+        page_size,
+    )
+}
@@ -0,0 +1,269 @@
+//! The commands accepted by the player endpoints, so that their URLs and
+//! payloads are built in a single place.
+
+use crate::{
+    clients::route::Route,
+    model::{
+        CurrentPlaybackContext, DeviceId, DisallowKey, Id, Offset, PlayContextId, PlayableId,
+        PlayerError, RepeatState,
+    },
+    util::JsonBuilder,
+};
+
+use serde_json::{json, Value};
+
+use std::time::Duration;
+
+/// A command for the player of the current user, sent with
+/// [`OAuthClient::player_command`](crate::clients::OAuthClient::player_command).
+///
+/// All the documented parameters are supported, except for the target device,
+/// which is passed along with the command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PlayerCommand<'a> {
+    /// Transfers the playback to the given devices, and optionally starts
+    /// playing. Spotify accepts a list, although it currently only supports
+    /// a single device.
+    Transfer {
+        device_ids: Vec<DeviceId<'a>>,
+        play: Option<bool>,
+    },
+    /// Starts playing an album, artist, playlist or show.
+    PlayContext {
+        context_uri: PlayContextId<'a>,
+        offset: Option<Offset>,
+        position: Option<Duration>,
+    },
+    /// Starts playing a list of tracks or episodes.
+    PlayUris {
+        uris: Vec<PlayableId<'a>>,
+        offset: Option<Offset>,
+        position: Option<Duration>,
+    },
+    /// Resumes the current playback, optionally from a position.
+    Resume {
+        position: Option<Duration>,
+    },
+    Pause,
+    Next,
+    Previous,
+    /// Seeks to a position in the current item.
+    Seek(Duration),
+    Repeat(RepeatState),
+    /// Sets the volume, between 0 and 100.
+    Volume(u8),
+    Shuffle(bool),
+    /// Adds a track or episode to the end of the queue.
+    AddToQueue(PlayableId<'a>),
+}
+
+impl PlayerCommand<'_> {
+    /// The HTTP method of the request.
+    #[must_use]
+    pub fn method(&self) -> &'static str {
+        match self {
+            Self::Next | Self::Previous | Self::AddToQueue(_) => "POST",
+            _ => "PUT",
+        }
+    }
+
+    /// The URL of the request, relative to the API's base URL, including its
+    /// query parameters and the target device.
+    #[must_use]
+    pub fn url(&self, device_id: Option<&DeviceId<'_>>) -> String {
+        let (path, mut query) = match self {
+            Self::Transfer { .. } => (Route::Player, Vec::new()),
+            Self::PlayContext { .. } | Self::PlayUris { .. } | Self::Resume { .. } => {
+                (Route::PlayerPlay, Vec::new())
+            }
+            Self::Pause => (Route::PlayerPause, Vec::new()),
+            Self::Next => (Route::PlayerNext, Vec::new()),
+            Self::Previous => (Route::PlayerPrevious, Vec::new()),
+            Self::Seek(position) => (
+                Route::PlayerSeek,
+                vec![("position_ms", millis(*position).to_string())],
+            ),
+            Self::Repeat(state) => (
+                Route::PlayerRepeat,
+                vec![("state", <&str>::from(state).to_owned())],
+            ),
+            Self::Volume(percent) => (
+                Route::PlayerVolume,
+                vec![("volume_percent", percent.to_string())],
+            ),
+            Self::Shuffle(state) => (Route::PlayerShuffle, vec![("state", state.to_string())]),
+            Self::AddToQueue(item) => (Route::PlayerQueue, vec![("uri", item.uri())]),
+        };
+
+        // The device is part of the body when transferring the playback
+        if !matches!(self, Self::Transfer { .. }) {
+            if let Some(device_id) = device_id {
+                query.push(("device_id", device_id.id().to_owned()));
+            }
+        }
+
+        if query.is_empty() {
+            path.to_string()
+        } else {
+            let query = url::form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(query)
+                .finish();
+            format!("{path}?{query}")
+        }
+    }
+
+    /// The JSON body of the request, which is an empty object for the
+    /// commands without one.
+    #[must_use]
+    pub fn body(&self) -> Value {
+        match self {
+            Self::Transfer { device_ids, play } => JsonBuilder::new()
+                .required(
+                    "device_ids",
+                    device_ids.iter().map(DeviceId::id).collect::<Vec<_>>(),
+                )
+                .optional("play", *play)
+                .build(),
+            Self::PlayContext {
+                context_uri,
+                offset,
+                position,
+            } => JsonBuilder::new()
+                .required("context_uri", context_uri.uri())
+                .optional("offset", offset.as_ref().map(offset_json))
+                .optional("position_ms", position.map(millis))
+                .build(),
+            Self::PlayUris {
+                uris,
+                offset,
+                position,
+            } => JsonBuilder::new()
+                .required("uris", uris.iter().map(|id| id.uri()).collect::<Vec<_>>())
+                .optional("offset", offset.as_ref().map(offset_json))
+                .optional("position_ms", position.map(millis))
+                .build(),
+            Self::Resume { position } => JsonBuilder::new()
+                .optional("position_ms", position.map(millis))
+                .build(),
+            _ => json!({}),
+        }
+    }
+
+    /// Checks that the command is allowed in the given playback.
+    pub(crate) fn check(&self, playback: &CurrentPlaybackContext) -> Result<(), PlayerError> {
+        let action = match self {
+            Self::Transfer { .. } => DisallowKey::TransferringPlayback,
+            Self::PlayContext { .. } | Self::PlayUris { .. } => DisallowKey::InterruptingPlayback,
+            Self::Resume { .. } => DisallowKey::Resuming,
+            Self::Pause => DisallowKey::Pausing,
+            Self::Next => DisallowKey::SkippingNext,
+            Self::Previous => DisallowKey::SkippingPrev,
+            Self::Seek(_) => DisallowKey::Seeking,
+            Self::Shuffle(_) => DisallowKey::TogglingShuffle,
+            // Turning it off toggles whichever repeat mode is currently on
+            Self::Repeat(state) => match (state, playback.repeat_state) {
+                (RepeatState::Track, _) | (RepeatState::Off, RepeatState::Track) => {
+                    DisallowKey::TogglingRepeatTrack
+                }
+                (RepeatState::Context, _) | (RepeatState::Off, RepeatState::Context) => {
+                    DisallowKey::TogglingRepeatContext
+                }
+                (RepeatState::Off, RepeatState::Off) => return Ok(()),
+            },
+            Self::Volume(_) | Self::AddToQueue(_) => return Ok(()),
+        };
+        playback.actions.check(action)
+    }
+
+    /// The device whose playback has to be checked, which for transfers is
+    /// the current one rather than the target.
+    pub(crate) fn checked_device<'d>(
+        &self,
+        device_id: Option<&'d DeviceId<'d>>,
+    ) -> Option<&'d DeviceId<'d>> {
+        match self {
+            Self::Transfer { .. } => None,
+            _ => device_id,
+        }
+    }
+}
+
+/// The position in milliseconds, as sent to Spotify, saturating instead of
+/// overflowing for durations far beyond any item.
+fn millis(position: Duration) -> u64 {
+    u64::try_from(position.as_millis()).unwrap_or(u64::MAX)
+}
+
+fn offset_json(offset: &Offset) -> Value {
+    match offset {
+        Offset::Position(position) => json!({ "position": position.num_milliseconds() }),
+        Offset::Uri(uri) => json!({ "uri": uri }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::TrackId;
+
+    #[test]
+    fn test_player_command_requests() {
+        let abc = DeviceId::from_id("abc").unwrap();
+        let other = DeviceId::from_id("fdafdsadfa").unwrap();
+        let seek = PlayerCommand::Seek(Duration::from_secs(25));
+        assert_eq!(seek.method(), "PUT");
+        assert_eq!(seek.url(None), "me/player/seek?position_ms=25000");
+        assert_eq!(
+            seek.url(Some(&abc)),
+            "me/player/seek?position_ms=25000&device_id=abc"
+        );
+        assert_eq!(seek.body(), json!({}));
+
+        let shuffle = PlayerCommand::Shuffle(true);
+        assert_eq!(
+            shuffle.url(Some(&other)),
+            "me/player/shuffle?state=true&device_id=fdafdsadfa"
+        );
+        let resume = PlayerCommand::Resume { position: None };
+        assert_eq!(
+            resume.url(Some(&other)),
+            "me/player/play?device_id=fdafdsadfa"
+        );
+        let resume = PlayerCommand::Resume {
+            position: Some(Duration::from_micros(1_500_900)),
+        };
+        assert_eq!(resume.body(), json!({ "position_ms": 1500 }));
+
+        let track = TrackId::from_id("4iV5W9uYEdYUVa79Axb7Rh").unwrap();
+        let queue = PlayerCommand::AddToQueue(PlayableId::Track(track.as_ref()));
+        assert_eq!(queue.method(), "POST");
+        assert_eq!(
+            queue.url(None),
+            "me/player/queue?uri=spotify%3Atrack%3A4iV5W9uYEdYUVa79Axb7Rh"
+        );
+
+        let play = PlayerCommand::PlayUris {
+            uris: vec![PlayableId::Track(track)],
+            offset: Some(Offset::Position(chrono::Duration::zero())),
+            position: None,
+        };
+        assert_eq!(play.url(Some(&abc)), "me/player/play?device_id=abc");
+        assert_eq!(
+            play.body(),
+            json!({
+                "uris": ["spotify:track:4iV5W9uYEdYUVa79Axb7Rh"],
+                "offset": { "position": 0 },
+            })
+        );
+
+        let transfer = PlayerCommand::Transfer {
+            device_ids: vec![abc.as_ref(), DeviceId::from_id("def").unwrap()],
+            play: Some(true),
+        };
+        assert_eq!(transfer.url(Some(&abc)), "me/player");
+        assert_eq!(
+            transfer.body(),
+            json!({ "device_ids": ["abc", "def"], "play": true })
+        );
+    }
+}
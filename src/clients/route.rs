@@ -0,0 +1,365 @@
+//! The paths of every endpoint, relative to the API's base URL, so that they
+//! are spelled out in a single place instead of in each method.
+//!
+//! Each variant holds the parameters of its path, with the typed ID of the
+//! object where there's one, so that the wrong kind of ID doesn't compile. The
+//! lists of IDs are passed already joined with commas, as done by
+//! [`join_ids`](crate::join_ids). Every parameter is percent-encoded, so that
+//! none of them can change the rest of the path.
+
+use crate::model::{
+    AlbumId, ArtistId, AudiobookId, CategoryId, ChapterId, EpisodeId, Id, PlaylistId, ShowId,
+    TrackId, UserId,
+};
+
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Route<'a> {
+    // Albums
+    Album(AlbumId<'a>),
+    Albums(&'a str),
+    AlbumTracks(AlbumId<'a>),
+
+    // Artists
+    Artist(ArtistId<'a>),
+    Artists(&'a str),
+    ArtistAlbums(ArtistId<'a>),
+    ArtistTopTracks(ArtistId<'a>),
+    ArtistRelatedArtists(ArtistId<'a>),
+
+    // Audiobooks and chapters
+    Audiobook(AudiobookId<'a>),
+    Audiobooks,
+    AudiobookChapters(AudiobookId<'a>),
+    Chapter(ChapterId<'a>),
+    Chapters,
+
+    // Browse
+    Categories,
+    Category(CategoryId<'a>),
+    CategoryPlaylists(CategoryId<'a>),
+    FeaturedPlaylists,
+    NewReleases,
+    Recommendations,
+
+    // Episodes and shows
+    Episode(EpisodeId<'a>),
+    Episodes,
+    Show(ShowId<'a>),
+    Shows,
+    ShowEpisodes(ShowId<'a>),
+
+    // Playlists
+    Playlist(PlaylistId<'a>),
+    PlaylistItems(PlaylistId<'a>),
+    PlaylistFollowers(PlaylistId<'a>),
+    PlaylistFollowersContains {
+        playlist: PlaylistId<'a>,
+        users: &'a str,
+    },
+
+    // Search
+    Search,
+
+    // Tracks
+    Track(TrackId<'a>),
+    Tracks(&'a str),
+    AudioFeature(TrackId<'a>),
+    AudioFeatures(&'a str),
+    AudioAnalysis(TrackId<'a>),
+
+    // Users
+    User(UserId<'a>),
+    UserPlaylists(UserId<'a>),
+    UserPlaylist {
+        user: UserId<'a>,
+        playlist: PlaylistId<'a>,
+    },
+    UserStarred(UserId<'a>),
+
+    // Current user
+    Me,
+    MyPlaylists,
+    MyAlbums,
+    SaveAlbums(&'a str),
+    MyAlbumsContains(&'a str),
+    MyTracks,
+    SaveTracks(&'a str),
+    MyTracksContains(&'a str),
+    MyShows,
+    SaveShows(&'a str),
+    RemoveShows(&'a str, Option<&'a str>),
+    MyShowsContains,
+    MyAudiobooks,
+    SaveAudiobooks(&'a str),
+    MyAudiobooksContains,
+    MyEpisodes,
+    SaveEpisodes(&'a str),
+    MyEpisodesContains,
+    MyTop(&'a str),
+    Following,
+    FollowArtists(&'a str),
+    FollowArtistsContains(&'a str),
+    FollowUsers(&'a str),
+    FollowUsersContains(&'a str),
+
+    // Player
+    Player,
+    PlayerDevices,
+    PlayerCurrentlyPlaying,
+    PlayerRecentlyPlayed,
+    PlayerQueue,
+    PlayerPlay,
+    PlayerPause,
+    PlayerNext,
+    PlayerPrevious,
+    PlayerSeek,
+    PlayerRepeat,
+    PlayerVolume,
+    PlayerShuffle,
+}
+
+impl fmt::Display for Route<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Album(id) => write!(f, "albums/{}", Segment(id.id())),
+            Self::Albums(ids) => write!(f, "albums/?ids={}", Segment(ids)),
+            Self::AlbumTracks(id) => write!(f, "albums/{}/tracks", Segment(id.id())),
+
+            Self::Artist(id) => write!(f, "artists/{}", Segment(id.id())),
+            Self::Artists(ids) => write!(f, "artists/?ids={}", Segment(ids)),
+            Self::ArtistAlbums(id) => write!(f, "artists/{}/albums", Segment(id.id())),
+            Self::ArtistTopTracks(id) => write!(f, "artists/{}/top-tracks", Segment(id.id())),
+            Self::ArtistRelatedArtists(id) => {
+                write!(f, "artists/{}/related-artists", Segment(id.id()))
+            }
+
+            Self::Audiobook(id) => write!(f, "audiobooks/{}", Segment(id.id())),
+            Self::Audiobooks => f.write_str("audiobooks"),
+            Self::AudiobookChapters(id) => write!(f, "audiobooks/{}/chapters", Segment(id.id())),
+            Self::Chapter(id) => write!(f, "chapters/{}", Segment(id.id())),
+            Self::Chapters => f.write_str("chapters"),
+
+            Self::Categories => f.write_str("browse/categories"),
+            Self::Category(id) => write!(f, "browse/categories/{}", Segment(id.id())),
+            Self::CategoryPlaylists(id) => {
+                write!(f, "browse/categories/{}/playlists", Segment(id.id()))
+            }
+            Self::FeaturedPlaylists => f.write_str("browse/featured-playlists"),
+            Self::NewReleases => f.write_str("browse/new-releases"),
+            Self::Recommendations => f.write_str("recommendations"),
+
+            Self::Episode(id) => write!(f, "episodes/{}", Segment(id.id())),
+            Self::Episodes => f.write_str("episodes"),
+            Self::Show(id) => write!(f, "shows/{}", Segment(id.id())),
+            Self::Shows => f.write_str("shows"),
+            Self::ShowEpisodes(id) => write!(f, "shows/{}/episodes", Segment(id.id())),
+
+            Self::Playlist(id) => write!(f, "playlists/{}", Segment(id.id())),
+            Self::PlaylistItems(id) => write!(f, "playlists/{}/tracks", Segment(id.id())),
+            Self::PlaylistFollowers(id) => write!(f, "playlists/{}/followers", Segment(id.id())),
+            Self::PlaylistFollowersContains { playlist, users } => write!(
+                f,
+                "playlists/{}/followers/contains?ids={}",
+                Segment(playlist.id()),
+                Segment(users)
+            ),
+
+            Self::Search => f.write_str("search"),
+
+            Self::Track(id) => write!(f, "tracks/{}", Segment(id.id())),
+            Self::Tracks(ids) => write!(f, "tracks/?ids={}", Segment(ids)),
+            Self::AudioFeature(id) => write!(f, "audio-features/{}", Segment(id.id())),
+            Self::AudioFeatures(ids) => write!(f, "audio-features/?ids={}", Segment(ids)),
+            Self::AudioAnalysis(id) => write!(f, "audio-analysis/{}", Segment(id.id())),
+
+            Self::User(id) => write!(f, "users/{}", Segment(id.id())),
+            Self::UserPlaylists(id) => write!(f, "users/{}/playlists", Segment(id.id())),
+            Self::UserPlaylist { user, playlist } => write!(
+                f,
+                "users/{}/playlists/{}",
+                Segment(user.id()),
+                Segment(playlist.id())
+            ),
+            Self::UserStarred(id) => write!(f, "users/{}/starred", Segment(id.id())),
+
+            Self::Me => f.write_str("me/"),
+            Self::MyPlaylists => f.write_str("me/playlists"),
+            Self::MyAlbums => f.write_str("me/albums"),
+            Self::SaveAlbums(ids) => write!(f, "me/albums/?ids={}", Segment(ids)),
+            Self::MyAlbumsContains(ids) => write!(f, "me/albums/contains/?ids={}", Segment(ids)),
+            Self::MyTracks => f.write_str("me/tracks"),
+            Self::SaveTracks(ids) => write!(f, "me/tracks/?ids={}", Segment(ids)),
+            Self::MyTracksContains(ids) => write!(f, "me/tracks/contains/?ids={}", Segment(ids)),
+            Self::MyShows => f.write_str("me/shows"),
+            Self::SaveShows(ids) => write!(f, "me/shows/?ids={}", Segment(ids)),
+            Self::RemoveShows(ids, None) => write!(f, "me/shows?ids={}", Segment(ids)),
+            Self::RemoveShows(ids, Some(market)) => {
+                write!(
+                    f,
+                    "me/shows?ids={}&market={}",
+                    Segment(ids),
+                    Segment(market)
+                )
+            }
+            Self::MyShowsContains => f.write_str("me/shows/contains"),
+            Self::MyAudiobooks => f.write_str("me/audiobooks"),
+            Self::SaveAudiobooks(ids) => write!(f, "me/audiobooks?ids={}", Segment(ids)),
+            Self::MyAudiobooksContains => f.write_str("me/audiobooks/contains"),
+            Self::MyEpisodes => f.write_str("me/episodes"),
+            Self::SaveEpisodes(ids) => write!(f, "me/episodes?ids={}", Segment(ids)),
+            Self::MyEpisodesContains => f.write_str("me/episodes/contains"),
+            Self::MyTop(kind) => write!(f, "me/top/{}", Segment(kind)),
+            Self::Following => f.write_str("me/following"),
+            Self::FollowArtists(ids) => {
+                write!(f, "me/following?type=artist&ids={}", Segment(ids))
+            }
+            Self::FollowArtistsContains(ids) => {
+                write!(f, "me/following/contains?type=artist&ids={}", Segment(ids))
+            }
+            Self::FollowUsers(ids) => write!(f, "me/following?type=user&ids={}", Segment(ids)),
+            Self::FollowUsersContains(ids) => {
+                write!(f, "me/following/contains?type=user&ids={}", Segment(ids))
+            }
+
+            Self::Player => f.write_str("me/player"),
+            Self::PlayerDevices => f.write_str("me/player/devices"),
+            Self::PlayerCurrentlyPlaying => f.write_str("me/player/currently-playing"),
+            Self::PlayerRecentlyPlayed => f.write_str("me/player/recently-played"),
+            Self::PlayerQueue => f.write_str("me/player/queue"),
+            Self::PlayerPlay => f.write_str("me/player/play"),
+            Self::PlayerPause => f.write_str("me/player/pause"),
+            Self::PlayerNext => f.write_str("me/player/next"),
+            Self::PlayerPrevious => f.write_str("me/player/previous"),
+            Self::PlayerSeek => f.write_str("me/player/seek"),
+            Self::PlayerRepeat => f.write_str("me/player/repeat"),
+            Self::PlayerVolume => f.write_str("me/player/volume"),
+            Self::PlayerShuffle => f.write_str("me/player/shuffle"),
+        }
+    }
+}
+
+/// A parameter of a path, percent-encoded so that it can't change the rest
+/// of the URL. The commas are kept, since they separate the lists of IDs.
+///
+/// The dots are only encoded when they make up the whole segment, since `.`
+/// and `..` would otherwise be resolved as relative paths when the URL is
+/// normalized.
+struct Segment<'a>(&'a str);
+
+impl fmt::Display for Segment<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if matches!(self.0, "." | "..") {
+            return f.write_str(&"%2E".repeat(self.0.len()));
+        }
+
+        for byte in self.0.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b',' => {
+                    write!(f, "{}", byte as char)?;
+                }
+                _ => write!(f, "%{byte:02X}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_routes() {
+        let track = TrackId::from_id("abc").unwrap();
+        let playlist = PlaylistId::from_id("abc").unwrap();
+        let category = CategoryId::from_id("party").unwrap();
+        let user = UserId::from_id("alice").unwrap();
+
+        assert_eq!(Route::Search.to_string(), "search");
+        assert_eq!(Route::Track(track.as_ref()).to_string(), "tracks/abc");
+        assert_eq!(Route::Tracks("abc,def").to_string(), "tracks/?ids=abc,def");
+        assert_eq!(
+            Route::CategoryPlaylists(category).to_string(),
+            "browse/categories/party/playlists"
+        );
+        assert_eq!(
+            Route::PlaylistFollowersContains {
+                playlist: playlist.as_ref(),
+                users: "alice,bob",
+            }
+            .to_string(),
+            "playlists/abc/followers/contains?ids=alice,bob"
+        );
+        assert_eq!(
+            Route::UserPlaylist {
+                user,
+                playlist: playlist.as_ref(),
+            }
+            .to_string(),
+            "users/alice/playlists/abc"
+        );
+        assert_eq!(Route::MyTop("artists").to_string(), "me/top/artists");
+        assert_eq!(
+            Route::FollowUsersContains("alice").to_string(),
+            "me/following/contains?type=user&ids=alice"
+        );
+        assert_eq!(Route::PlayerSeek.to_string(), "me/player/seek");
+
+        // None of the paths are absolute, or the base URL would be dropped
+        // when joining them
+        for route in [Route::Me, Route::Player, Route::Track(track)] {
+            assert!(!route.to_string().starts_with('/'));
+        }
+    }
+
+    #[test]
+    fn test_routes_encoding() {
+        // The user IDs may have any character, which can't escape their
+        // segment
+        let user = UserId::from_id("../me?x=1#y z").unwrap();
+        assert_eq!(
+            Route::User(user.as_ref()).to_string(),
+            "users/..%2Fme%3Fx%3D1%23y%20z"
+        );
+        assert_eq!(
+            Route::UserPlaylists(user).to_string(),
+            "users/..%2Fme%3Fx%3D1%23y%20z/playlists"
+        );
+
+        // Nor can the dot segments, which would otherwise move up the path
+        // once the URL is normalized
+        for (id, encoded) in [(".", "%2E"), ("..", "%2E%2E")] {
+            let user = UserId::from_id(id).unwrap();
+            assert_eq!(
+                Route::User(user.as_ref()).to_string(),
+                format!("users/{encoded}")
+            );
+            assert_eq!(
+                Route::UserPlaylists(user).to_string(),
+                format!("users/{encoded}/playlists")
+            );
+        }
+        let user = UserId::from_id("...").unwrap();
+        assert_eq!(Route::User(user).to_string(), "users/...");
+
+        // Nor can the lists of IDs add more parameters, although their
+        // commas are kept
+        assert_eq!(
+            Route::Tracks("abc,def&market=ES").to_string(),
+            "tracks/?ids=abc,def%26market%3DES"
+        );
+        assert_eq!(
+            Route::FollowUsersContains("alice,bob/followers").to_string(),
+            "me/following/contains?type=user&ids=alice,bob%2Ffollowers"
+        );
+
+        // The categories that would change the path aren't valid IDs
+        for category in ["party/playlists", "party?locale=es", "party#top", ""] {
+            assert!(CategoryId::from_id(category).is_err());
+        }
+        // And neither are the other IDs
+        assert!(PlaylistId::from_id("abc/tracks").is_err());
+    }
+}
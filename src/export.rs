@@ -0,0 +1,288 @@
+//! Export playlists to standard playlist files, for backups or to use them in
+//! other music players.
+//!
+//! Two formats are supported:
+//!
+//! * [M3U8](https://en.wikipedia.org/wiki/M3U), with [`playlist_to_m3u8`]
+//! * [XSPF](https://www.xspf.org/), with [`playlist_to_xspf`], which keeps
+//!   more metadata, like the albums and the cover images
+//!
+//! Spotify's tracks can't be played outside of it, so their locations point to
+//! their page in the Spotify web player. Items that are no longer available
+//! are skipped.
+
+use crate::{
+    clients::BaseClient,
+    model::{FullPlaylist, Id, PlayableItem, PlaylistId, PlaylistItem},
+    ClientResult,
+};
+
+use std::io::Write;
+
+use maybe_async::maybe_async;
+
+/// The maximum number of items per request.
+const PAGE_LIMIT: u32 = 100;
+
+/// The metadata of a playlist item, common to both tracks and episodes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Entry {
+    title: String,
+    creator: String,
+    album: String,
+    duration_ms: i64,
+    location: Option<String>,
+    identifier: Option<String>,
+    image: Option<String>,
+}
+
+impl Entry {
+    fn from_item(item: PlaylistItem) -> Option<Self> {
+        let entry = match item.track? {
+            PlayableItem::Track(track) => Self {
+                creator: track
+                    .artists
+                    .iter()
+                    .map(|artist| artist.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                album: track.album.name,
+                duration_ms: track.duration.num_milliseconds(),
+                location: track.external_urls.get("spotify").cloned(),
+                identifier: track.id.map(|id| id.uri()),
+                image: track.album.images.into_iter().next().map(|image| image.url),
+                title: track.name,
+            },
+            PlayableItem::Episode(episode) => Self {
+                creator: episode.show.publisher,
+                album: episode.show.name,
+                duration_ms: episode.duration.num_milliseconds(),
+                location: episode.external_urls.get("spotify").cloned(),
+                identifier: Some(episode.id.uri()),
+                image: episode.images.into_iter().next().map(|image| image.url),
+                title: episode.name,
+            },
+            PlayableItem::LocalTrack(track) => Self {
+                creator: track.artists.join(", "),
+                album: track.album.unwrap_or_default(),
+                duration_ms: track.duration.num_milliseconds(),
+                location: None,
+                identifier: Some(track.uri),
+                image: None,
+                title: track.name,
+            },
+        };
+        Some(entry)
+    }
+}
+
+/// The playlist's name, its owner, and all of its entries.
+type Export = (String, String, Vec<Entry>);
+
+/// Fetches the playlist along with its first page of items, and then the
+/// rest of the pages.
+#[maybe_async]
+async fn fetch<C: BaseClient>(client: &C, playlist_id: PlaylistId<'_>) -> ClientResult<Export> {
+    let FullPlaylist {
+        name,
+        owner,
+        tracks,
+        ..
+    } = client
+        .playlist(playlist_id.as_ref(), None, None, None)
+        .await?;
+
+    let total = tracks.total;
+    let mut offset = tracks.offset + tracks.items.len() as u32;
+    let mut items = tracks.items;
+    let mut next = tracks.next;
+    while next.is_some() && offset < total {
+        let page = client
+            .playlist_items_manual(
+                playlist_id.as_ref(),
+                None,
+                None,
+                None,
+                Some(PAGE_LIMIT),
+                Some(offset),
+            )
+            .await?;
+        if page.items.is_empty() {
+            break;
+        }
+        offset += page.items.len() as u32;
+        next = page.next;
+        items.extend(page.items);
+    }
+
+    let owner = owner
+        .display_name
+        .unwrap_or_else(|| owner.id.id().to_owned());
+    let entries = items.into_iter().filter_map(Entry::from_item).collect();
+    Ok((name, owner, entries))
+}
+
+/// Writes the given playlist to `writer` in the M3U8 format, fetching all of
+/// its items.
+///
+/// Each entry includes its duration, artists and title in an `#EXTINF`
+/// directive.
+#[maybe_async]
+pub async fn playlist_to_m3u8<C: BaseClient, W: Write>(
+    client: &C,
+    playlist_id: PlaylistId<'_>,
+    writer: W,
+) -> ClientResult<()> {
+    let (name, _, entries) = fetch(client, playlist_id).await?;
+    write_m3u8(writer, &name, &entries)?;
+    Ok(())
+}
+
+/// Writes the given playlist to `writer` in the XSPF format, fetching all of
+/// its items.
+#[maybe_async]
+pub async fn playlist_to_xspf<C: BaseClient, W: Write>(
+    client: &C,
+    playlist_id: PlaylistId<'_>,
+    writer: W,
+) -> ClientResult<()> {
+    let (name, owner, entries) = fetch(client, playlist_id).await?;
+    write_xspf(writer, &name, &owner, &entries)?;
+    Ok(())
+}
+
+fn write_m3u8<W: Write>(mut writer: W, name: &str, entries: &[Entry]) -> std::io::Result<()> {
+    writeln!(writer, "#EXTM3U")?;
+    writeln!(writer, "#PLAYLIST:{}", single_line(name))?;
+    for entry in entries {
+        let location = match entry.location.as_ref().or(entry.identifier.as_ref()) {
+            Some(location) => location,
+            None => continue,
+        };
+        writeln!(
+            writer,
+            "#EXTINF:{},{} - {}",
+            entry.duration_ms / 1000,
+            single_line(&entry.creator),
+            single_line(&entry.title)
+        )?;
+        writeln!(writer, "{location}")?;
+    }
+    writer.flush()
+}
+
+fn write_xspf<W: Write>(
+    mut writer: W,
+    name: &str,
+    owner: &str,
+    entries: &[Entry],
+) -> std::io::Result<()> {
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<playlist version="1" xmlns="http://xspf.org/ns/0/">"#
+    )?;
+    writeln!(writer, "  <title>{}</title>", escape_xml(name))?;
+    writeln!(writer, "  <creator>{}</creator>", escape_xml(owner))?;
+    writeln!(writer, "  <trackList>")?;
+    for entry in entries {
+        writeln!(writer, "    <track>")?;
+        if let Some(location) = &entry.location {
+            writeln!(
+                writer,
+                "      <location>{}</location>",
+                escape_xml(location)
+            )?;
+        }
+        if let Some(identifier) = &entry.identifier {
+            writeln!(
+                writer,
+                "      <identifier>{}</identifier>",
+                escape_xml(identifier)
+            )?;
+        }
+        writeln!(writer, "      <title>{}</title>", escape_xml(&entry.title))?;
+        writeln!(
+            writer,
+            "      <creator>{}</creator>",
+            escape_xml(&entry.creator)
+        )?;
+        writeln!(writer, "      <album>{}</album>", escape_xml(&entry.album))?;
+        writeln!(writer, "      <duration>{}</duration>", entry.duration_ms)?;
+        if let Some(image) = &entry.image {
+            writeln!(writer, "      <image>{}</image>", escape_xml(image))?;
+        }
+        writeln!(writer, "    </track>")?;
+    }
+    writeln!(writer, "  </trackList>")?;
+    writeln!(writer, "</playlist>")?;
+    writer.flush()
+}
+
+/// M3U8 is line-based, so line breaks in the names would corrupt the file.
+fn single_line(text: &str) -> String {
+    text.replace(['\r', '\n'], " ")
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entries() -> Vec<Entry> {
+        vec![
+            Entry {
+                title: "Harder, Better, Faster, Stronger".to_owned(),
+                creator: "Daft Punk".to_owned(),
+                album: "Discovery".to_owned(),
+                duration_ms: 224_693,
+                location: Some("https://open.spotify.com/track/5W3cjX2J3tjhG8zb6u0qHn".to_owned()),
+                identifier: Some("spotify:track:5W3cjX2J3tjhG8zb6u0qHn".to_owned()),
+                image: None,
+            },
+            Entry {
+                title: "Unavailable".to_owned(),
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_write_m3u8() {
+        let mut out = Vec::new();
+        write_m3u8(&mut out, "Rock &\nRoll", &entries()).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "#EXTM3U\n\
+             #PLAYLIST:Rock & Roll\n\
+             #EXTINF:224,Daft Punk - Harder, Better, Faster, Stronger\n\
+             https://open.spotify.com/track/5W3cjX2J3tjhG8zb6u0qHn\n"
+        );
+    }
+
+    #[test]
+    fn test_write_xspf() {
+        let mut out = Vec::new();
+        write_xspf(&mut out, "Rock & Roll", "<me>", &entries()[..1]).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("<title>Rock &amp; Roll</title>"));
+        assert!(out.contains("<creator>&lt;me&gt;</creator>"));
+        assert!(out.contains("<identifier>spotify:track:5W3cjX2J3tjhG8zb6u0qHn</identifier>"));
+        assert!(out.contains("<duration>224693</duration>"));
+        assert!(!out.contains("<image>"));
+    }
+}
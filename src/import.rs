@@ -0,0 +1,549 @@
+//! Import playlists from other music players, the inverse of the [`export`]
+//! module.
+//!
+//! The entries are parsed from M3U files with [`parse_m3u`] or CSV files with
+//! [`parse_csv`], and then [`import_playlist`] looks for each of them with the
+//! search endpoint, unless they're already a Spotify track, like the ones
+//! exported to M3U by the [`export`] module. Since names rarely match exactly
+//! (e.g., `Song (Remastered)` and `Song - Remastered`), the results are ranked
+//! with a fuzzy comparison, and only the ones above
+//! [`ImportOptions::min_confidence`] are added to the new playlist. The rest
+//! are returned so that they can be reviewed manually.
+//!
+//! [`export`]: crate::export
+
+use crate::{
+    clients::OAuthClient,
+    model::{FullPlaylist, FullTrack, Market, PlayableId, SearchResult, SearchType, TrackId},
+    ClientError, ClientResult,
+};
+
+use maybe_async::maybe_async;
+
+/// The number of search results compared with each entry.
+const SEARCH_LIMIT: u32 = 10;
+
+/// The tolerance when comparing durations, in milliseconds.
+const DURATION_TOLERANCE_MS: i64 = 10_000;
+
+/// An entry to be imported.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TrackQuery {
+    pub artist: String,
+    pub title: String,
+    pub album: Option<String>,
+    pub duration_ms: Option<u32>,
+    /// The Spotify track, if it's already known, in which case it isn't
+    /// searched for.
+    pub id: Option<TrackId<'static>>,
+}
+
+impl TrackQuery {
+    #[must_use]
+    pub fn new(artist: impl Into<String>, title: impl Into<String>) -> Self {
+        Self {
+            artist: artist.into(),
+            title: title.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Parses the usual `Artist - Title` format, as used in M3U files.
+    fn from_label(label: &str) -> Option<Self> {
+        let (artist, title) = label.split_once(" - ")?;
+        let (artist, title) = (artist.trim(), title.trim());
+        if artist.is_empty() || title.is_empty() {
+            None
+        } else {
+            Some(Self::new(artist, title))
+        }
+    }
+}
+
+/// Options for [`import_playlist`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportOptions {
+    /// The name of the created playlist.
+    pub name: String,
+    /// The description of the created playlist.
+    pub description: Option<String>,
+    /// Whether the created playlist is public.
+    pub public: Option<bool>,
+    /// The minimum confidence, from 0 to 1, for a search result to be
+    /// considered a match. By default it's 0.8.
+    pub min_confidence: f64,
+    /// The market used to search for the tracks.
+    pub market: Option<Market>,
+}
+
+impl ImportOptions {
+    /// The default options for a playlist with the given name.
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            public: None,
+            min_confidence: 0.8,
+            market: None,
+        }
+    }
+}
+
+/// An entry that was found in Spotify.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackMatch {
+    pub query: TrackQuery,
+    pub track: FullTrack,
+    /// How similar the track is to the query, from 0 to 1.
+    pub confidence: f64,
+}
+
+/// The result of [`import_playlist`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportReport {
+    /// The created playlist, before the tracks were added.
+    pub playlist: FullPlaylist,
+    /// The entries that were added to the playlist, in order.
+    pub matched: Vec<TrackMatch>,
+    /// The entries without a confident enough match, which weren't added.
+    pub unmatched: Vec<TrackQuery>,
+}
+
+/// Parses the entries of an M3U or M3U8 playlist.
+///
+/// The artist and title are read from the `#EXTINF` directives, which usually
+/// follow the `Artist - Title` format. Entries without one fall back to the
+/// file name of their location. Locations that are Spotify track URLs or URIs
+/// are kept as the [`TrackQuery::id`]. Other entries that can't be parsed are
+/// skipped.
+#[must_use]
+pub fn parse_m3u(contents: &str) -> Vec<TrackQuery> {
+    let mut entries = Vec::new();
+    let mut info = None;
+    for line in contents.lines().map(str::trim) {
+        if let Some(extinf) = line.strip_prefix("#EXTINF:") {
+            info = extinf.split_once(',').map(|(duration, label)| {
+                let duration_ms = duration
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|secs| *secs > 0.0)
+                    .map(|secs| (secs * 1000.0) as u32);
+                (duration_ms, label.to_owned())
+            });
+        } else if line.is_empty() || line.starts_with('#') {
+            continue;
+        } else {
+            let id = spotify_track(line);
+            let (duration_ms, label) = match info.take() {
+                Some(info) => info,
+                None if id.is_some() => (None, String::new()),
+                None => {
+                    let file = line.rsplit(['/', '\\']).next().unwrap_or(line);
+                    let stem = file.rsplit_once('.').map_or(file, |(stem, _)| stem);
+                    (None, stem.to_owned())
+                }
+            };
+            let mut entry = TrackQuery::from_label(&label);
+            if id.is_some() {
+                entry = Some(TrackQuery {
+                    id,
+                    ..entry.unwrap_or_default()
+                });
+            }
+            if let Some(mut entry) = entry {
+                entry.duration_ms = duration_ms;
+                entries.push(entry);
+            }
+        }
+    }
+    entries
+}
+
+/// Parses the entries of a CSV file.
+///
+/// If the first row is a header with `artist` and `title` (or `track` or
+/// `name`) columns, and optionally `album`, they're read from them. Otherwise,
+/// the columns are expected to be the artist, the title and, optionally, the
+/// album. Fields may be quoted with `"` to include commas. Rows without an
+/// artist or title are skipped.
+#[must_use]
+pub fn parse_csv(contents: &str) -> Vec<TrackQuery> {
+    let mut rows = contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(split_csv_row)
+        .peekable();
+
+    let mut columns = (0, 1, Some(2));
+    if let Some(header) = rows.peek() {
+        let find = |names: &[&str]| {
+            header
+                .iter()
+                .position(|field| names.contains(&field.to_lowercase().as_str()))
+        };
+        if let (Some(artist), Some(title)) = (find(&["artist"]), find(&["title", "track", "name"]))
+        {
+            columns = (artist, title, find(&["album"]));
+            rows.next();
+        }
+    }
+
+    let (artist, title, album) = columns;
+    rows.filter_map(|row| {
+        let field = |i: usize| row.get(i).map(|f| f.trim()).filter(|f| !f.is_empty());
+        let mut entry = TrackQuery::new(field(artist)?, field(title)?);
+        entry.album = album.and_then(field).map(ToOwned::to_owned);
+        Some(entry)
+    })
+    .collect()
+}
+
+/// The track of a location that's a Spotify URL or URI. Bare IDs aren't
+/// accepted, since any file name without an extension might be one.
+fn spotify_track(location: &str) -> Option<TrackId<'static>> {
+    if !location.contains(['/', ':']) {
+        return None;
+    }
+    TrackId::from_any(location).ok().map(TrackId::into_static)
+}
+
+fn split_csv_row(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Creates a playlist for the current user with the given entries, looking
+/// for each of them in Spotify.
+///
+/// The entries with a [`TrackQuery::id`] are added as they are. The rest are
+/// searched by their artist, title and album first, and if there's no
+/// confident enough match, with less strict queries. The best result is then
+/// added to the playlist if its confidence is at least
+/// [`ImportOptions::min_confidence`].
+#[maybe_async]
+pub async fn import_playlist<C: OAuthClient>(
+    client: &C,
+    entries: Vec<TrackQuery>,
+    options: &ImportOptions,
+) -> ClientResult<ImportReport> {
+    let mut matched = Vec::new();
+    let mut unmatched = Vec::new();
+    for query in entries {
+        match find_track(client, &query, options).await? {
+            Some((track, confidence)) => matched.push(TrackMatch {
+                query,
+                track,
+                confidence,
+            }),
+            None => unmatched.push(query),
+        }
+    }
+
+    let user_id = client.current_user_id().await?;
+    let playlist = client
+        .user_playlist_create(
+            user_id,
+            &options.name,
+            options.public,
+            None,
+            options.description.as_deref(),
+        )
+        .await?;
+
+    let ids = matched
+        .iter()
+        .filter_map(|m| m.track.id.as_ref())
+        .map(|id| PlayableId::Track(id.as_ref()))
+        .collect::<Vec<_>>();
+    for chunk in ids.chunks(100) {
+        client
+            .playlist_add_items(playlist.id.as_ref(), chunk.to_vec(), None)
+            .await?;
+    }
+
+    Ok(ImportReport {
+        playlist,
+        matched,
+        unmatched,
+    })
+}
+
+#[maybe_async]
+async fn find_track<C: OAuthClient>(
+    client: &C,
+    query: &TrackQuery,
+    options: &ImportOptions,
+) -> ClientResult<Option<(FullTrack, f64)>> {
+    if let Some(id) = &query.id {
+        match client.track(id.as_ref(), options.market).await {
+            Ok(track) => return Ok(Some((track, 1.0))),
+            // The track may have been removed, in which case it's searched
+            // for like the rest, if its name is known
+            Err(ClientError::Api {
+                status: 400 | 404, ..
+            }) => {
+                if query.title.is_empty() {
+                    return Ok(None);
+                }
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    let strict = format!(
+        "track:{} artist:{}",
+        search_term(&query.title),
+        search_term(&query.artist)
+    );
+    let mut queries = Vec::new();
+    if let Some(album) = &query.album {
+        queries.push(format!("{strict} album:{}", search_term(album)));
+    }
+    queries.push(strict);
+    queries.push(format!("{} {}", query.artist, query.title));
+
+    let mut best: Option<(FullTrack, f64)> = None;
+    for q in queries {
+        let result = client
+            .search(
+                &q,
+                SearchType::Track,
+                options.market,
+                None,
+                Some(SEARCH_LIMIT),
+                None,
+            )
+            .await?;
+        let tracks = match result {
+            SearchResult::Tracks(page) => page.items,
+            _ => continue,
+        };
+        for track in tracks {
+            let score = confidence(query, &track);
+            let better = match &best {
+                Some((_, best)) => score > *best,
+                None => true,
+            };
+            if better {
+                best = Some((track, score));
+            }
+        }
+        if matches!(&best, Some((_, score)) if *score >= options.min_confidence) {
+            break;
+        }
+    }
+
+    Ok(best.filter(|(track, score)| *score >= options.min_confidence && track.id.is_some()))
+}
+
+/// Quotes the term for the field filters of the search.
+fn search_term(term: &str) -> String {
+    format!("\"{}\"", term.replace('"', ""))
+}
+
+/// How similar the track is to the query, from 0 to 1. The title weights more
+/// than the artist, which weights more than the album, if known, and durations
+/// that don't match lower the confidence.
+fn confidence(query: &TrackQuery, track: &FullTrack) -> f64 {
+    let title = similarity(&query.title, &track.name);
+    let joined = track
+        .artists
+        .iter()
+        .map(|artist| artist.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let artist = track
+        .artists
+        .iter()
+        .map(|artist| similarity(&query.artist, &artist.name))
+        .fold(similarity(&query.artist, &joined), f64::max);
+
+    let mut score = match &query.album {
+        Some(album) => 0.5 * title + 0.3 * artist + 0.2 * similarity(album, &track.album.name),
+        None => 0.6 * title + 0.4 * artist,
+    };
+    if let Some(duration_ms) = query.duration_ms {
+        let diff = (track.duration.num_milliseconds() - i64::from(duration_ms)).abs();
+        if diff > DURATION_TOLERANCE_MS {
+            score *= 0.8;
+        }
+    }
+    score
+}
+
+/// Compares two names, ignoring case, punctuation and the usual suffixes
+/// like `(Remastered)` or `- Live`.
+fn similarity(a: &str, b: &str) -> f64 {
+    let full = ratio(&normalize(a), &normalize(b));
+    let base = ratio(&normalize(strip_suffix(a)), &normalize(strip_suffix(b)));
+    full.max(base)
+}
+
+fn strip_suffix(name: &str) -> &str {
+    let end = [" - ", " (", " ["]
+        .iter()
+        .filter_map(|sep| name.find(sep))
+        .min()
+        .unwrap_or(name.len());
+    &name[..end]
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .flat_map(char::to_lowercase)
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The normalized Levenshtein similarity between two strings.
+fn ratio(a: &str, b: &str) -> f64 {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let max = a.len().max(b.len());
+    if max == 0 {
+        return 1.0;
+    }
+
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            curr[j + 1] = (prev[j + 1] + 1).min(curr[j] + 1).min(prev[j] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    1.0 - prev[b.len()] as f64 / max as f64
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_m3u() {
+        let m3u = "#EXTM3U\n\
+                   #EXTINF:224,Daft Punk - Harder, Better, Faster, Stronger\n\
+                   https://open.spotify.com/track/5W3cjX2J3tjhG8zb6u0qHn\n\
+                   /music/Queen - Bohemian Rhapsody.mp3\n\
+                   #EXTINF:-1,Untitled\n\
+                   untitled.mp3\n\
+                   spotify:track:4iV5W9uYEdYUVa79Axb7Rh\n\
+                   4iV5W9uYEdYUVa79Axb7Rh\n";
+        let entries = parse_m3u(m3u);
+        let id = |id| Some(TrackId::from_id(id).unwrap());
+        assert_eq!(
+            entries,
+            vec![
+                TrackQuery {
+                    duration_ms: Some(224_000),
+                    id: id("5W3cjX2J3tjhG8zb6u0qHn"),
+                    ..TrackQuery::new("Daft Punk", "Harder, Better, Faster, Stronger")
+                },
+                TrackQuery::new("Queen", "Bohemian Rhapsody"),
+                TrackQuery {
+                    id: id("4iV5W9uYEdYUVa79Axb7Rh"),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let csv = "Title,Artist,Album\n\
+                   \"Harder, Better, Faster, Stronger\",Daft Punk,Discovery\n\
+                   ,Nobody,\n\
+                   \"The \"\"Real\"\" Slim Shady\",Eminem,\n";
+        let entries = parse_csv(csv);
+        assert_eq!(
+            entries,
+            vec![
+                TrackQuery {
+                    album: Some("Discovery".to_owned()),
+                    ..TrackQuery::new("Daft Punk", "Harder, Better, Faster, Stronger")
+                },
+                TrackQuery::new("Eminem", "The \"Real\" Slim Shady"),
+            ]
+        );
+
+        // Without a header
+        assert_eq!(
+            parse_csv("Queen,Bohemian Rhapsody"),
+            vec![TrackQuery::new("Queen", "Bohemian Rhapsody")]
+        );
+    }
+
+    #[test]
+    fn test_confidence() {
+        let track: FullTrack = serde_json::from_value(serde_json::json!({
+            "album": {
+                "artists": [],
+                "external_urls": {},
+                "images": [],
+                "name": "Discovery",
+            },
+            "artists": [{ "external_urls": {}, "name": "Daft Punk" }],
+            "disc_number": 1,
+            "duration_ms": 224_000,
+            "explicit": false,
+            "external_ids": {},
+            "external_urls": {},
+            "href": null,
+            "id": "5W3cjX2J3tjhG8zb6u0qHn",
+            "is_local": false,
+            "name": "Harder, Better, Faster, Stronger",
+            "popularity": 80,
+            "preview_url": null,
+            "track_number": 4,
+        }))
+        .unwrap();
+        let query = TrackQuery::new("Daft Punk", "Harder, Better, Faster, Stronger");
+        assert!((confidence(&query, &track) - 1.0).abs() < f64::EPSILON);
+
+        // The album counts when it's known
+        let same_album = TrackQuery {
+            album: Some("Discovery".to_owned()),
+            ..query.clone()
+        };
+        assert!((confidence(&same_album, &track) - 1.0).abs() < f64::EPSILON);
+        let other_album = TrackQuery {
+            album: Some("Alive 2007".to_owned()),
+            ..query
+        };
+        assert!(confidence(&other_album, &track) < 0.9);
+    }
+
+    #[test]
+    fn test_similarity() {
+        assert!((similarity("Bohemian Rhapsody", "bohemian rhapsody!") - 1.0).abs() < f64::EPSILON);
+        assert!(
+            (similarity("Let It Be (Remastered 2009)", "Let It Be - Remastered 2009") - 1.0).abs()
+                < f64::EPSILON
+        );
+        assert!(similarity("Yesterday", "Yellow Submarine") < 0.5);
+        assert!(similarity("Smells Like Teen Spirit", "Smells Like Teen Spirt") > 0.9);
+    }
+}
@@ -0,0 +1,288 @@
+//! Write-ahead journal of the mutating requests sent to the API.
+//!
+//! When a [`RequestJournal`] is configured in [`Config::journal`], every
+//! `POST`, `PUT` and `DELETE` request is recorded as pending *before* it's
+//! sent, and its outcome (including the resulting snapshot ID, for playlist
+//! modifications) is recorded once it finishes. If the program crashes in the
+//! middle of a request, the entries without an outcome are the ones that may
+//! or may not have been applied, so they can be audited or replayed.
+//!
+//! [`FileJournal`] is provided as a simple implementation that appends one
+//! JSON object per line to a file. The journal is asynchronous in the async
+//! clients, so that its writes don't block the executor.
+//!
+//! [`Config::journal`]: crate::Config::journal
+
+use crate::{
+    alphabets, generate_random_string, sync::run_blocking, ClientError, ClientResult, Config,
+};
+
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Utc};
+use maybe_async::maybe_async;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A mutating request sent to the API.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct JournalEntry {
+    /// A random identifier, used to match the entry with its outcome.
+    pub id: String,
+    /// When the request was about to be sent.
+    pub timestamp: DateTime<Utc>,
+    /// The HTTP method, i.e., `POST`, `PUT` or `DELETE`.
+    pub method: String,
+    /// The full URL of the request, including its query parameters.
+    pub url: String,
+    /// The JSON body of the request.
+    pub params: Value,
+}
+
+/// The result of a journaled request.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum JournalOutcome {
+    /// The request succeeded. Endpoints that modify playlists respond with
+    /// their new snapshot ID, which is saved here.
+    Completed { snapshot_id: Option<String> },
+    /// The request failed with the given error.
+    Failed { error: String },
+}
+
+/// A durable sink for [`JournalEntry`]s.
+///
+/// Returning an error in [`Self::record_pending`] aborts the request before
+/// it's sent, so that no modification is made without being recorded first.
+#[cfg_attr(target_arch = "wasm32", maybe_async(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), maybe_async)]
+pub trait RequestJournal: Send + Sync + fmt::Debug {
+    /// Records a request that is about to be sent.
+    async fn record_pending(&self, entry: &JournalEntry) -> ClientResult<()>;
+
+    /// Records the outcome of the request with the given entry ID.
+    async fn record_outcome(&self, id: &str, outcome: &JournalOutcome) -> ClientResult<()>;
+}
+
+/// A line in the file written by [`FileJournal`].
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum FileRecord {
+    Pending(JournalEntry),
+    Outcome {
+        id: String,
+        #[serde(flatten)]
+        outcome: JournalOutcome,
+    },
+}
+
+/// A [`RequestJournal`] that appends its records as JSON lines to a file,
+/// flushing them to disk right after. The file is written off the executor in
+/// the async clients.
+#[derive(Debug)]
+pub struct FileJournal {
+    path: PathBuf,
+    // Serializes the writes so that lines from concurrent requests aren't
+    // interleaved.
+    lock: Arc<Mutex<()>>,
+}
+
+impl FileJournal {
+    /// Builds a journal that writes to the given path. The file is created on
+    /// the first record if it doesn't exist.
+    #[must_use]
+    pub fn new<T: AsRef<Path>>(path: T) -> Self {
+        Self {
+            path: path.as_ref().to_owned(),
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// The path of the journal file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads all the journaled requests in order, along with their outcome,
+    /// if any.
+    pub fn entries(&self) -> ClientResult<Vec<(JournalEntry, Option<JournalOutcome>)>> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut entries = Vec::new();
+        let mut positions = HashMap::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match serde_json::from_str(&line)? {
+                FileRecord::Pending(entry) => {
+                    positions.insert(entry.id.clone(), entries.len());
+                    entries.push((entry, None));
+                }
+                FileRecord::Outcome { id, outcome } => {
+                    if let Some(&pos) = positions.get(&id) {
+                        entries[pos].1 = Some(outcome);
+                    }
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Reads the requests that were sent but whose outcome is unknown, which
+    /// is what happens when the program is interrupted in the middle of them.
+    pub fn pending(&self) -> ClientResult<Vec<JournalEntry>> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .filter(|(_, outcome)| outcome.is_none())
+            .map(|(entry, _)| entry)
+            .collect())
+    }
+
+    #[maybe_async]
+    async fn append(&self, record: &FileRecord) -> ClientResult<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+
+        let (path, lock) = (self.path.clone(), Arc::clone(&self.lock));
+        run_blocking(move || {
+            let _guard = lock
+                .lock()
+                .map_err(|_| ClientError::CacheFile("journal lock poisoned".to_owned()))?;
+            let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+            file.write_all(line.as_bytes())?;
+            file.sync_data()?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", maybe_async(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), maybe_async)]
+impl RequestJournal for FileJournal {
+    async fn record_pending(&self, entry: &JournalEntry) -> ClientResult<()> {
+        self.append(&FileRecord::Pending(entry.clone())).await
+    }
+
+    async fn record_outcome(&self, id: &str, outcome: &JournalOutcome) -> ClientResult<()> {
+        self.append(&FileRecord::Outcome {
+            id: id.to_owned(),
+            outcome: outcome.clone(),
+        })
+        .await
+    }
+}
+
+/// Records the request in the configured journal, if any, before it's sent.
+#[maybe_async]
+pub(crate) async fn begin(
+    config: &Config,
+    method: &str,
+    url: &str,
+    params: &Value,
+) -> ClientResult<Option<JournalEntry>> {
+    let journal = match &config.journal {
+        Some(journal) => journal,
+        None => return Ok(None),
+    };
+
+    let entry = JournalEntry {
+        id: generate_random_string(16, alphabets::ALPHANUM)?,
+        timestamp: Utc::now(),
+        method: method.to_owned(),
+        url: url.to_owned(),
+        params: params.clone(),
+    };
+    journal.record_pending(&entry).await?;
+    Ok(Some(entry))
+}
+
+/// Records the outcome of a request previously passed to [`begin`].
+///
+/// The request has already been made at this point, so failing to record its
+/// outcome is only logged instead of hiding the actual result.
+#[maybe_async]
+pub(crate) async fn finish(
+    config: &Config,
+    entry: Option<JournalEntry>,
+    result: &ClientResult<String>,
+) {
+    let (journal, entry) = match (&config.journal, entry) {
+        (Some(journal), Some(entry)) => (journal, entry),
+        _ => return,
+    };
+
+    let outcome = match result {
+        Ok(response) => JournalOutcome::Completed {
+            snapshot_id: serde_json::from_str::<Value>(response)
+                .ok()
+                .and_then(|json| json.get("snapshot_id")?.as_str().map(ToOwned::to_owned)),
+        },
+        Err(err) => JournalOutcome::Failed {
+            error: err.to_string(),
+        },
+    };
+    if let Err(err) = journal.record_outcome(&entry.id, &outcome).await {
+        log::warn!("Couldn't record the outcome of request {}: {err}", entry.id);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+    use std::{fs, sync::Arc};
+
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_file_journal() {
+        let path = std::env::temp_dir().join(format!(
+            "rspotify_journal_{}.jsonl",
+            generate_random_string(8, alphabets::ALPHANUM).unwrap()
+        ));
+        let config = Config {
+            journal: Some(Arc::new(FileJournal::new(&path))),
+            ..Default::default()
+        };
+
+        let params = json!({"uris": ["spotify:track:4iV5W9uYEdYUVa79Axb7Rh"]});
+        let done = begin(&config, "POST", "playlists/x/tracks", &params)
+            .await
+            .unwrap();
+        finish(&config, done, &Ok(r#"{"snapshot_id":"abc"}"#.to_owned())).await;
+        let crashed = begin(&config, "DELETE", "playlists/x/tracks", &params)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let journal = FileJournal::new(&path);
+        let entries = journal.entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].0.params, params);
+        assert_eq!(
+            entries[0].1,
+            Some(JournalOutcome::Completed {
+                snapshot_id: Some("abc".to_owned())
+            })
+        );
+        assert_eq!(journal.pending().unwrap(), vec![crashed]);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,559 @@
+//! String-in, string-out access to the endpoints, available with the
+//! `json-api` feature.
+//!
+//! This is intended for language bindings and other embedding scenarios
+//! (e.g., Python or Node through UniFFI or napi), where exposing all of
+//! RSpotify's models would be impractical. The endpoints are identified by
+//! the same names as the client methods, their parameters are passed as a JSON
+//! object, and the response is returned as the JSON sent by Spotify. The
+//! requests still go through the client's authentication, token refreshing
+//! and error handling.
+//!
+//! ```no_run
+//! # fn example(spotify: rspotify::ClientCredsSpotify) {
+//! // With `.await` in the async clients
+//! let result = rspotify::json_api::call_endpoint(
+//!     &spotify,
+//!     "artist_top_tracks",
+//!     r#"{"id": "0OdUWJ0sBjDrqHygGUXeCF", "market": "US"}"#,
+//! );
+//! # let _ = result;
+//! # }
+//! ```
+
+use crate::{
+    clients::{route::Route, BaseClient},
+    model::{
+        AlbumId, ArtistId, AudiobookId, CategoryId, ChapterId, EpisodeId, IdError, PlaylistId,
+        ShowId, TrackId, UserId,
+    },
+    ClientError, ClientResult,
+};
+
+use maybe_async::maybe_async;
+use serde_json::{Map, Value};
+use Method::{Delete, Get, Post, Put};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Method {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
+
+/// Builds the route of an endpoint from the values of its path parameters,
+/// in the same order as they're listed in [`Endpoint::path`]. It fails if an
+/// ID isn't valid.
+type RouteFn = for<'a> fn(&[&'a str]) -> ClientResult<Route<'a>>;
+
+/// An endpoint of the API. The parameters listed in `path` are required and
+/// used to build its [`Route`]. The rest of the parameters are sent in the
+/// query for `GET` requests; for the others, only the ones listed in `query`
+/// are, and the rest are sent as the JSON body.
+struct Endpoint {
+    name: &'static str,
+    method: Method,
+    path: &'static [&'static str],
+    route: RouteFn,
+    query: &'static [&'static str],
+}
+
+const fn endpoint(
+    name: &'static str,
+    method: Method,
+    path: &'static [&'static str],
+    route: RouteFn,
+) -> Endpoint {
+    Endpoint {
+        name,
+        method,
+        path,
+        route,
+        query: &[],
+    }
+}
+
+const fn with_query(
+    name: &'static str,
+    method: Method,
+    path: &'static [&'static str],
+    route: RouteFn,
+    query: &'static [&'static str],
+) -> Endpoint {
+    Endpoint {
+        name,
+        method,
+        path,
+        route,
+        query,
+    }
+}
+
+const PLAYER_QUERY: &[&str] = &["device_id"];
+
+static ENDPOINTS: &[Endpoint] = &[
+    // Catalog
+    endpoint("track", Get, &["id"], |p| {
+        Ok(Route::Track(id(p[0], TrackId::from_id)?))
+    }),
+    endpoint("tracks", Get, &["ids"], |p| Ok(Route::Tracks(p[0]))),
+    endpoint("artist", Get, &["id"], |p| {
+        Ok(Route::Artist(id(p[0], ArtistId::from_id)?))
+    }),
+    endpoint("artists", Get, &["ids"], |p| Ok(Route::Artists(p[0]))),
+    endpoint("artist_albums", Get, &["id"], |p| {
+        Ok(Route::ArtistAlbums(id(p[0], ArtistId::from_id)?))
+    }),
+    endpoint("artist_top_tracks", Get, &["id"], |p| {
+        Ok(Route::ArtistTopTracks(id(p[0], ArtistId::from_id)?))
+    }),
+    endpoint("album", Get, &["id"], |p| {
+        Ok(Route::Album(id(p[0], AlbumId::from_id)?))
+    }),
+    endpoint("albums", Get, &["ids"], |p| Ok(Route::Albums(p[0]))),
+    endpoint("album_track", Get, &["id"], |p| {
+        Ok(Route::AlbumTracks(id(p[0], AlbumId::from_id)?))
+    }),
+    endpoint("search", Get, &[], |_| Ok(Route::Search)),
+    endpoint("user", Get, &["id"], |p| {
+        Ok(Route::User(id(p[0], UserId::from_id)?))
+    }),
+    endpoint("user_playlists", Get, &["id"], |p| {
+        Ok(Route::UserPlaylists(id(p[0], UserId::from_id)?))
+    }),
+    endpoint("playlist", Get, &["id"], |p| {
+        Ok(Route::Playlist(id(p[0], PlaylistId::from_id)?))
+    }),
+    endpoint("playlist_items", Get, &["id"], |p| {
+        Ok(Route::PlaylistItems(id(p[0], PlaylistId::from_id)?))
+    }),
+    endpoint("get_a_show", Get, &["id"], |p| {
+        Ok(Route::Show(id(p[0], ShowId::from_id)?))
+    }),
+    endpoint("get_several_shows", Get, &[], |_| Ok(Route::Shows)),
+    endpoint("get_shows_episodes", Get, &["id"], |p| {
+        Ok(Route::ShowEpisodes(id(p[0], ShowId::from_id)?))
+    }),
+    endpoint("get_an_episode", Get, &["id"], |p| {
+        Ok(Route::Episode(id(p[0], EpisodeId::from_id)?))
+    }),
+    endpoint("get_several_episodes", Get, &[], |_| Ok(Route::Episodes)),
+    endpoint("get_an_audiobook", Get, &["id"], |p| {
+        Ok(Route::Audiobook(id(p[0], AudiobookId::from_id)?))
+    }),
+    endpoint("get_several_audiobooks", Get, &[], |_| {
+        Ok(Route::Audiobooks)
+    }),
+    endpoint("get_audiobook_chapters", Get, &["id"], |p| {
+        Ok(Route::AudiobookChapters(id(p[0], AudiobookId::from_id)?))
+    }),
+    endpoint("get_a_chapter", Get, &["id"], |p| {
+        Ok(Route::Chapter(id(p[0], ChapterId::from_id)?))
+    }),
+    endpoint("get_several_chapters", Get, &[], |_| Ok(Route::Chapters)),
+    endpoint("categories", Get, &[], |_| Ok(Route::Categories)),
+    endpoint("category", Get, &["id"], |p| {
+        Ok(Route::Category(id(p[0], CategoryId::from_id)?))
+    }),
+    endpoint("category_playlists", Get, &["id"], |p| {
+        Ok(Route::CategoryPlaylists(id(p[0], CategoryId::from_id)?))
+    }),
+    endpoint("featured_playlists", Get, &[], |_| {
+        Ok(Route::FeaturedPlaylists)
+    }),
+    endpoint("new_releases", Get, &[], |_| Ok(Route::NewReleases)),
+    // Current user
+    endpoint("me", Get, &[], |_| Ok(Route::Me)),
+    endpoint("current_user_playlists", Get, &[], |_| {
+        Ok(Route::MyPlaylists)
+    }),
+    endpoint("current_user_top_artists", Get, &[], |_| {
+        Ok(Route::MyTop("artists"))
+    }),
+    endpoint("current_user_top_tracks", Get, &[], |_| {
+        Ok(Route::MyTop("tracks"))
+    }),
+    endpoint("current_user_recently_played", Get, &[], |_| {
+        Ok(Route::PlayerRecentlyPlayed)
+    }),
+    endpoint("current_user_followed_artists", Get, &[], |_| {
+        Ok(Route::Following)
+    }),
+    endpoint("current_user_saved_tracks", Get, &[], |_| {
+        Ok(Route::MyTracks)
+    }),
+    endpoint("current_user_saved_tracks_add", Put, &[], |_| {
+        Ok(Route::MyTracks)
+    }),
+    endpoint("current_user_saved_tracks_delete", Delete, &[], |_| {
+        Ok(Route::MyTracks)
+    }),
+    endpoint("current_user_saved_tracks_contains", Get, &["ids"], |p| {
+        Ok(Route::MyTracksContains(p[0]))
+    }),
+    endpoint("current_user_saved_albums", Get, &[], |_| {
+        Ok(Route::MyAlbums)
+    }),
+    endpoint("current_user_saved_albums_add", Put, &[], |_| {
+        Ok(Route::MyAlbums)
+    }),
+    endpoint("current_user_saved_albums_delete", Delete, &[], |_| {
+        Ok(Route::MyAlbums)
+    }),
+    endpoint("current_user_saved_albums_contains", Get, &["ids"], |p| {
+        Ok(Route::MyAlbumsContains(p[0]))
+    }),
+    endpoint("current_user_saved_shows", Get, &[], |_| Ok(Route::MyShows)),
+    endpoint("current_user_saved_shows_add", Put, &[], |_| {
+        Ok(Route::MyShows)
+    }),
+    endpoint("current_user_saved_shows_delete", Delete, &[], |_| {
+        Ok(Route::MyShows)
+    }),
+    endpoint("current_user_saved_shows_contains", Get, &[], |_| {
+        Ok(Route::MyShowsContains)
+    }),
+    endpoint("get_saved_show", Get, &[], |_| Ok(Route::MyShows)),
+    endpoint("save_shows", Put, &[], |_| Ok(Route::MyShows)),
+    endpoint("remove_users_saved_shows", Delete, &[], |_| {
+        Ok(Route::MyShows)
+    }),
+    endpoint("check_users_saved_shows", Get, &[], |_| {
+        Ok(Route::MyShowsContains)
+    }),
+    endpoint("current_user_saved_episodes", Get, &[], |_| {
+        Ok(Route::MyEpisodes)
+    }),
+    endpoint("current_user_saved_episodes_add", Put, &[], |_| {
+        Ok(Route::MyEpisodes)
+    }),
+    endpoint("current_user_saved_episodes_delete", Delete, &[], |_| {
+        Ok(Route::MyEpisodes)
+    }),
+    endpoint("current_user_saved_episodes_contains", Get, &[], |_| {
+        Ok(Route::MyEpisodesContains)
+    }),
+    endpoint("user_follow_artists", Put, &["ids"], |p| {
+        Ok(Route::FollowArtists(p[0]))
+    }),
+    endpoint("user_unfollow_artists", Delete, &["ids"], |p| {
+        Ok(Route::FollowArtists(p[0]))
+    }),
+    endpoint("user_artist_check_follow", Get, &["ids"], |p| {
+        Ok(Route::FollowArtistsContains(p[0]))
+    }),
+    endpoint("user_follow_users", Put, &["ids"], |p| {
+        Ok(Route::FollowUsers(p[0]))
+    }),
+    endpoint("user_unfollow_users", Delete, &["ids"], |p| {
+        Ok(Route::FollowUsers(p[0]))
+    }),
+    endpoint("user_check_follow_users", Get, &["ids"], |p| {
+        Ok(Route::FollowUsersContains(p[0]))
+    }),
+    // Playlists
+    endpoint("user_playlist_create", Post, &["user_id"], |p| {
+        Ok(Route::UserPlaylists(id(p[0], UserId::from_id)?))
+    }),
+    endpoint("playlist_change_detail", Put, &["id"], |p| {
+        Ok(Route::Playlist(id(p[0], PlaylistId::from_id)?))
+    }),
+    endpoint("playlist_follow", Put, &["id"], |p| {
+        Ok(Route::PlaylistFollowers(id(p[0], PlaylistId::from_id)?))
+    }),
+    endpoint("playlist_unfollow", Delete, &["id"], |p| {
+        Ok(Route::PlaylistFollowers(id(p[0], PlaylistId::from_id)?))
+    }),
+    endpoint("playlist_add_items", Post, &["id"], |p| {
+        Ok(Route::PlaylistItems(id(p[0], PlaylistId::from_id)?))
+    }),
+    endpoint("playlist_replace_items", Put, &["id"], |p| {
+        Ok(Route::PlaylistItems(id(p[0], PlaylistId::from_id)?))
+    }),
+    endpoint(
+        "playlist_remove_all_occurrences_of_items",
+        Delete,
+        &["id"],
+        |p| Ok(Route::PlaylistItems(id(p[0], PlaylistId::from_id)?)),
+    ),
+    endpoint("playlist_check_follow", Get, &["id", "ids"], |p| {
+        Ok(Route::PlaylistFollowersContains {
+            playlist: id(p[0], PlaylistId::from_id)?,
+            users: p[1],
+        })
+    }),
+    // Player
+    endpoint("device", Get, &[], |_| Ok(Route::PlayerDevices)),
+    endpoint("current_playback", Get, &[], |_| Ok(Route::Player)),
+    endpoint("current_playing", Get, &[], |_| {
+        Ok(Route::PlayerCurrentlyPlaying)
+    }),
+    endpoint("current_user_queue", Get, &[], |_| Ok(Route::PlayerQueue)),
+    endpoint("transfer_playback", Put, &[], |_| Ok(Route::Player)),
+    with_query(
+        "start_playback",
+        Put,
+        &[],
+        |_| Ok(Route::PlayerPlay),
+        PLAYER_QUERY,
+    ),
+    with_query(
+        "pause_playback",
+        Put,
+        &[],
+        |_| Ok(Route::PlayerPause),
+        PLAYER_QUERY,
+    ),
+    with_query(
+        "next_track",
+        Post,
+        &[],
+        |_| Ok(Route::PlayerNext),
+        PLAYER_QUERY,
+    ),
+    with_query(
+        "previous_track",
+        Post,
+        &[],
+        |_| Ok(Route::PlayerPrevious),
+        PLAYER_QUERY,
+    ),
+    with_query(
+        "seek_track",
+        Put,
+        &[],
+        |_| Ok(Route::PlayerSeek),
+        &["position_ms", "device_id"],
+    ),
+    with_query(
+        "repeat",
+        Put,
+        &[],
+        |_| Ok(Route::PlayerRepeat),
+        &["state", "device_id"],
+    ),
+    with_query(
+        "volume",
+        Put,
+        &[],
+        |_| Ok(Route::PlayerVolume),
+        &["volume_percent", "device_id"],
+    ),
+    with_query(
+        "shuffle",
+        Put,
+        &[],
+        |_| Ok(Route::PlayerShuffle),
+        &["state", "device_id"],
+    ),
+    with_query(
+        "add_item_to_queue",
+        Post,
+        &[],
+        |_| Ok(Route::PlayerQueue),
+        &["uri", "device_id"],
+    ),
+];
+
+/// The names of the endpoints that can be called with [`call_endpoint`].
+pub fn endpoints() -> impl Iterator<Item = &'static str> {
+    ENDPOINTS.iter().map(|endpoint| endpoint.name)
+}
+
+/// Calls the endpoint with the given name, which is the same as the method of
+/// the client that wraps it (e.g., `"artist_albums"`). The only exception is
+/// `"start_playback"`, which covers both `start_context_playback` and
+/// `start_uris_playback`.
+///
+/// The parameters are passed as a JSON object (or an empty string if there
+/// are none), with the same names used in Spotify's documentation. Lists,
+/// like `ids`, may be given as JSON arrays. The JSON response from Spotify is
+/// returned as is, or `null` if the endpoint doesn't respond with a body.
+#[maybe_async]
+pub async fn call_endpoint<C: BaseClient>(
+    client: &C,
+    name: &str,
+    params: &str,
+) -> ClientResult<String> {
+    let endpoint = ENDPOINTS
+        .iter()
+        .find(|endpoint| endpoint.name == name)
+        .ok_or_else(|| ClientError::JsonApi(format!("unknown endpoint `{name}`")))?;
+
+    let params = match params.trim() {
+        "" => Map::new(),
+        params => match serde_json::from_str(params)? {
+            Value::Object(params) => params,
+            Value::Null => Map::new(),
+            _ => {
+                return Err(ClientError::JsonApi(
+                    "the parameters must be a JSON object".to_owned(),
+                ))
+            }
+        },
+    };
+    let (url, query, body) = build_request(endpoint, params)?;
+
+    let query = query
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .collect();
+    let body = Value::Object(body);
+    let response = match endpoint.method {
+        Get => client.api_get_raw(&url, &query).await?,
+        Post => client.api_post_raw(&url, &body).await?,
+        Put => client.api_put_raw(&url, &body).await?,
+        Delete => client.api_delete_raw(&url, &body).await?,
+    };
+
+    if response.trim().is_empty() {
+        Ok("null".to_owned())
+    } else {
+        Ok(response)
+    }
+}
+
+type Request = (String, Vec<(String, String)>, Map<String, Value>);
+
+/// Splits the parameters into the URL, its query and the body.
+fn build_request(endpoint: &Endpoint, mut params: Map<String, Value>) -> ClientResult<Request> {
+    let path = endpoint
+        .path
+        .iter()
+        .map(|key| {
+            params
+                .remove(*key)
+                .as_ref()
+                .and_then(query_value)
+                .ok_or_else(|| ClientError::JsonApi(format!("missing parameter `{key}`")))
+        })
+        .collect::<ClientResult<Vec<_>>>()?;
+    let path = path.iter().map(String::as_str).collect::<Vec<_>>();
+    let mut url = (endpoint.route)(&path)?.to_string();
+
+    let mut query = Vec::new();
+    let mut body = Map::new();
+    for (key, value) in params {
+        if endpoint.method == Get || endpoint.query.contains(&key.as_str()) {
+            if let Some(value) = query_value(&value) {
+                query.push((key, value));
+            }
+        } else {
+            body.insert(key, value);
+        }
+    }
+
+    // The query of the requests with a body has to be included in the URL
+    if endpoint.method != Get && !query.is_empty() {
+        let encoded = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(query.drain(..))
+            .finish();
+        let separator = if url.contains('?') { '&' } else { '?' };
+        url = format!("{url}{separator}{encoded}");
+    }
+
+    Ok((url, query, body))
+}
+
+/// Parses an ID passed as a path parameter.
+fn id<'a, T>(id: &'a str, from_id: fn(&'a str) -> Result<T, IdError>) -> ClientResult<T> {
+    from_id(id).map_err(|err| ClientError::JsonApi(format!("invalid ID `{id}`: {err}")))
+}
+
+/// The representation of a JSON value in a query parameter, where lists are
+/// separated by commas. `null` values are skipped.
+fn query_value(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(value) => Some(value.clone()),
+        Value::Array(values) => Some(
+            values
+                .iter()
+                .filter_map(query_value)
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        value => Some(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    fn request(name: &str, params: Value) -> ClientResult<Request> {
+        let endpoint = ENDPOINTS.iter().find(|e| e.name == name).unwrap();
+        match params {
+            Value::Object(params) => build_request(endpoint, params),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_build_request() {
+        let (url, query, body) = request(
+            "artist_albums",
+            json!({"id": "0OdUWJ0sBjDrqHygGUXeCF", "include_groups": ["album", "single"], "market": null}),
+        )
+        .unwrap();
+        assert_eq!(url, "artists/0OdUWJ0sBjDrqHygGUXeCF/albums");
+        assert_eq!(
+            query,
+            vec![("include_groups".to_owned(), "album,single".to_owned())]
+        );
+        assert!(body.is_empty());
+
+        let (url, query, body) = request(
+            "seek_track",
+            json!({"position_ms": 25000, "device_id": "abc"}),
+        )
+        .unwrap();
+        assert_eq!(url, "me/player/seek?device_id=abc&position_ms=25000");
+        assert!(query.is_empty());
+        assert!(body.is_empty());
+
+        let (url, _, body) = request(
+            "playlist_add_items",
+            json!({"id": "37i9dQZF1DXcBWIGoYBM5M", "uris": ["spotify:track:4iV5W9uYEdYUVa79Axb7Rh"]}),
+        )
+        .unwrap();
+        assert_eq!(url, "playlists/37i9dQZF1DXcBWIGoYBM5M/tracks");
+        assert_eq!(body["uris"][0], "spotify:track:4iV5W9uYEdYUVa79Axb7Rh");
+
+        let (url, query, _) =
+            request("tracks", json!({"ids": ["abc", "def"], "market": "ES"})).unwrap();
+        assert_eq!(url, "tracks/?ids=abc,def");
+        assert_eq!(query, vec![("market".to_owned(), "ES".to_owned())]);
+
+        let (url, _, _) = request("user_follow_artists", json!({"ids": ["abc"]})).unwrap();
+        assert_eq!(url, "me/following?type=artist&ids=abc");
+
+        // The path parameters can't escape their segment
+        let (url, _, _) = request("user", json!({"id": "../me?x=1 y"})).unwrap();
+        assert_eq!(url, "users/..%2Fme%3Fx%3D1%20y");
+        let (url, _, _) = request("user", json!({"id": ".."})).unwrap();
+        assert_eq!(url, "users/%2E%2E");
+        let (url, _, body) = request(
+            "user_playlist_create",
+            json!({"user_id": "..", "name": "Mix"}),
+        )
+        .unwrap();
+        assert_eq!(url, "users/%2E%2E/playlists");
+        assert_eq!(body["name"], "Mix");
+
+        assert!(matches!(
+            request("track", json!({})),
+            Err(ClientError::JsonApi(_))
+        ));
+
+        // The IDs are validated instead
+        for (name, id) in [("track", "abc/../me"), ("category", "party?x=1")] {
+            assert!(matches!(
+                request(name, json!({ "id": id })),
+                Err(ClientError::JsonApi(_))
+            ));
+        }
+    }
+}
@@ -48,6 +48,15 @@
 //! }
 //! ```
 //!
+//! Large responses, like the pages of a big library, are transferred faster
+//! when compressed. The `gzip` and `brotli` features enable it for both
+//! clients, and `deflate` for reqwest only; the responses are decompressed
+//! transparently.
+//!
+//! The `blocking` feature provides blocking versions of the async clients,
+//! for the few synchronous code paths of an async application, see the
+//! `blocking` module.
+//!
 //! ### Proxies
 //!
 //! Both [reqwest][reqwest-proxies] and [ureq][ureq-proxying] support system
@@ -59,6 +68,9 @@
 //! - [reqwest](https://docs.rs/reqwest/latest/src/reqwest/proxy.rs.html#897-920)
 //! - [ureq](https://docs.rs/ureq/latest/src/ureq/proxy.rs.html#73-95)
 //!
+//! A proxy can also be configured for a single client with [`Config::proxy`],
+//! in which case the environment variables are ignored.
+//!
 //! ### Environmental variables
 //!
 //! RSpotify supports the `dotenvy` crate, which allows you to save credentials
@@ -74,7 +86,31 @@
 //!
 //! RSpotify includes basic support for Cli apps to obtain access tokens by
 //! prompting the user, after enabling the `cli` feature. See the
-//! [Authorization](#authorization) section for more information.
+//! [Authorization](#authorization) section for more information. It also
+//! includes [`cli::doctor`], which diagnoses common setup problems.
+//!
+//! The token of CLI apps may be kept in the credential manager of the system
+//! rather than in a plaintext file with the `keyring` feature, which adds
+//! `KeyringTokenStore` to be used as [`Config::token_store`].
+//!
+//! ### Tracing
+//!
+//! With the `tracing` feature, the requests to Spotify are instrumented with
+//! spans of the [`tracing`](https://docs.rs/tracing) crate, so that they show
+//! up in the traces of the application:
+//!
+//! - `spotify_api` for each call to an endpoint of the Web API, with its
+//!   `method` and `endpoint` (e.g., `artists/{id}`). It includes the retries
+//!   and the requests replayed after refreshing the token.
+//! - `spotify_auth` for each request to the authentication endpoints.
+//! - `spotify_http_request` for each HTTP request, within the spans above,
+//!   with its `method`, `url` (without the query), and, once it's done, its
+//!   `status` and `latency_ms`.
+//!
+//! ```toml
+//! [dependencies]
+//! rspotify = { version = "...", features = ["tracing"] }
+//! ```
 //!
 //! ## Getting Started
 //!
@@ -102,6 +138,11 @@
 //!   has not been tested on a browser yet. If you'd like support for it, let us
 //!   know in an issue!
 //!
+//! All of them can also be built with [`SpotifyBuilder`], which takes the
+//! credentials, OAuth information, configuration and token one at a time.
+//! Code that accepts the client of any flow may take the enums of the
+//! [`any_client`] module instead of being generic over it.
+//!
 //! In order to help other developers to get used to `rspotify`, there are
 //! public credentials available for a dummy account. You can test `rspotify`
 //! with this account's `RSPOTIFY_CLIENT_ID` and `RSPOTIFY_CLIENT_SECRET` inside
@@ -117,6 +158,12 @@
 //! [Spotify recommends][spotify-auth-flows] using [`AuthCodePkceSpotify`] for
 //! authorization flows on the web.
 //!
+//! The futures returned by the clients don't need to be `Send` on this target.
+//! If your application is single-threaded and uses `Rc`-based state, enable
+//! the `wasm-single-thread` feature as well, so that the arguments passed to
+//! the endpoints (and your own client implementations) don't need to be `Send`
+//! or `Sync` either.
+//!
 //! Importing the Client ID via `RSPOTIFY_CLIENT_ID` is not possible since WASM
 //! web runtimes are isolated from the host environment. The client ID must be
 //! passed explicitly to [`Credentials::new_pkce`]. Alternatively, it can be
@@ -142,12 +189,41 @@
 //! [spotify-auth-code-pkce]: https://developer.spotify.com/documentation/web-api/tutorials/code-pkce-flow
 //! [spotify-implicit-grant]: https://developer.spotify.com/documentation/general/guides/authorization/implicit-grant
 
+pub mod any_client;
 mod auth_code;
 mod auth_code_pkce;
+pub mod backup;
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub mod blocking;
+pub mod builder;
+#[cfg(feature = "cli")]
+pub mod cli;
 mod client_creds;
 pub mod clients;
+pub mod export;
+pub mod import;
+#[cfg(feature = "journal")]
+pub mod journal;
+#[cfg(feature = "json-api")]
+pub mod json_api;
+pub mod library;
+#[cfg(feature = "__async")]
+pub mod player_state;
+pub mod playlist_sync;
+pub mod playlist_watcher;
+pub mod response_cache;
+pub mod retry;
+pub mod scope_audit;
+pub mod search;
+#[cfg(feature = "__async")]
+pub mod search_session;
 pub mod sync;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod token_refresher;
+mod token_store;
 mod util;
+pub mod web;
 
 // Subcrate re-exports
 pub use rspotify_http as http;
@@ -155,15 +231,22 @@ pub use rspotify_macros as macros;
 pub use rspotify_model as model;
 // Top-level re-exports
 pub use auth_code::AuthCodeSpotify;
-pub use auth_code_pkce::AuthCodePkceSpotify;
+pub use auth_code_pkce::{AuthCodePkceSpotify, PkceVerifier};
+pub use builder::SpotifyBuilder;
 pub use client_creds::ClientCredsSpotify;
 pub use macros::scopes;
 pub use model::Token;
+#[cfg(feature = "keyring")]
+pub use token_store::KeyringTokenStore;
+pub use token_store::{FileTokenStore, TokenStore};
 
-use crate::{http::HttpError, model::Id};
+use crate::{
+    http::HttpError,
+    model::{Id, PlayerErrorReason},
+};
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     env, fmt,
     net::SocketAddr,
     path::PathBuf,
@@ -216,8 +299,10 @@ pub(crate) mod auth_urls {
 /// Possible errors returned from the `rspotify` client.
 #[derive(Debug, Error)]
 pub enum ClientError {
+    /// A response couldn't be deserialized. When it comes from an endpoint,
+    /// the error includes its path and the JSON around the failure.
     #[error("json parse error: {0}")]
-    ParseJson(#[from] serde_json::Error),
+    ParseJson(Box<ParseJsonError>),
 
     #[error("url parse error: {0}")]
     ParseUrl(#[from] url::ParseError),
@@ -228,6 +313,24 @@ pub enum ClientError {
     #[error("http error: {0}")]
     Http(Box<HttpError>),
 
+    /// The Web API responded with an unsuccessful status code. The error
+    /// object in its body is parsed when possible, and otherwise `message`
+    /// contains the raw body. `reason` is only included in the player errors,
+    /// e.g., [`PlayerErrorReason::NoActiveDevice`] when a device has to be
+    /// chosen first. `retry_after` is the delay requested by the
+    /// `Retry-After` header, which Spotify sends along with the `429 Too Many
+    /// Requests` responses.
+    #[error(
+        "api error {status}{}: {message}",
+        reason.as_ref().map(|r| format!(" ({r})")).unwrap_or_default()
+    )]
+    Api {
+        status: u16,
+        message: String,
+        reason: Option<PlayerErrorReason>,
+        retry_after: Option<std::time::Duration>,
+    },
+
     #[error("input/output error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -235,6 +338,10 @@ pub enum ClientError {
     #[error("cli error: {0}")]
     Cli(String),
 
+    #[cfg(feature = "json-api")]
+    #[error("json api error: {0}")]
+    JsonApi(String),
+
     #[error("cache file error: {0}")]
     CacheFile(String),
 
@@ -244,9 +351,50 @@ pub enum ClientError {
     #[error("model error: {0}")]
     Model(#[from] model::ModelError),
 
+    #[error("player error: {0}")]
+    Player(#[from] model::PlayerError),
+
+    /// The playlist was modified while reading it with
+    /// [`playlist_items_snapshot`](crate::clients::BaseClient::playlist_items_snapshot).
+    #[error("playlist snapshot changed from {expected} to {found}")]
+    SnapshotChanged { expected: String, found: String },
+
+    /// There's no device with the given name or ID, see
+    /// [`play_on_device`](crate::clients::OAuthClient::play_on_device), or
+    /// it's not the one currently playing, see
+    /// [`seek_track_clamped`](crate::clients::OAuthClient::seek_track_clamped).
+    #[error("device not found: {0}")]
+    DeviceNotFound(String),
+
+    /// The random values, like the OAuth state or the PKCE verifier,
+    /// couldn't be generated.
+    #[error("random generation error: {0}")]
+    Random(#[from] getrandom::Error),
+
     #[error("Token is not valid")]
     InvalidToken,
 
+    /// One of the [`Credentials::extra_token_headers`] isn't a valid HTTP
+    /// header name or value. The request wasn't sent.
+    #[error("invalid token header: {0}")]
+    InvalidHeader(String),
+
+    /// The token lacks a scope required by the endpoint, see
+    /// [`Config::validate_scopes`]. The request wasn't sent.
+    #[error("missing scope: {0}")]
+    MissingScope(String),
+
+    /// The show, episode, audiobook or chapter of the given endpoint wasn't
+    /// found because the request had no market, which the client
+    /// credentials flow requires. Pass one to the endpoint or set
+    /// [`Config::default_market`].
+    #[error("market required by {0}: the content is unavailable without one")]
+    MarketRequired(String),
+
+    /// The user was redirected back without authorizing the client.
+    #[error("authorization error: {0}")]
+    Authorization(String),
+
     #[error("Failed to bind server to {addr} ({e})")]
     AuthCodeListenerBind { addr: SocketAddr, e: std::io::Error },
 
@@ -270,6 +418,58 @@ impl From<HttpError> for ClientError {
     }
 }
 
+impl From<serde_json::Error> for ClientError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::ParseJson(Box::new(ParseJsonError::from(err)))
+    }
+}
+
+/// The details of a [`ClientError::ParseJson`].
+#[derive(Debug)]
+pub struct ParseJsonError {
+    /// The endpoint whose response couldn't be parsed, relative to
+    /// [`Config::api_base_url`], e.g. `"artists/0OdUWJ0sBjDrqHygGUXeCF"`.
+    pub endpoint: Option<String>,
+    /// The JSON around the position of the error, with its whitespace
+    /// collapsed.
+    pub snippet: Option<String>,
+    /// The whole response, with its tokens redacted, if
+    /// [`Config::parse_error_body`] is enabled.
+    pub body: Option<String>,
+    /// The original error from `serde_json`, with the line and column.
+    pub source: serde_json::Error,
+}
+
+impl From<serde_json::Error> for ParseJsonError {
+    fn from(source: serde_json::Error) -> Self {
+        Self {
+            endpoint: None,
+            snippet: None,
+            body: None,
+            source,
+        }
+    }
+}
+
+impl fmt::Display for ParseJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)?;
+        if let Some(endpoint) = &self.endpoint {
+            write!(f, " in the response of `{endpoint}`")?;
+        }
+        if let Some(snippet) = &self.snippet {
+            write!(f, " near `{snippet}`")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseJsonError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
 pub type ClientResult<T> = Result<T, ClientError>;
 
 pub const DEFAULT_API_BASE_URL: &str = "https://api.spotify.com/v1/";
@@ -292,6 +492,158 @@ impl fmt::Debug for TokenCallback {
     }
 }
 
+/// A handler for the responses that can't be parsed, set in
+/// [`Config::on_parse_error`]. It receives the raw JSON and the name of the
+/// type it was parsed into, and may return a patched JSON to parse instead.
+///
+/// This is meant as a stopgap for sudden changes in the responses of Spotify,
+/// until the models are updated. For example, to turn floats back into
+/// integers:
+///
+/// ```
+/// use rspotify::{Config, ParseErrorHandler};
+/// use serde_json::Value;
+/// use std::sync::Arc;
+///
+/// fn truncate_floats(value: &mut Value) {
+///     match value {
+///         Value::Number(n) if n.is_f64() => *value = Value::from(n.as_f64().unwrap() as i64),
+///         Value::Array(items) => items.iter_mut().for_each(truncate_floats),
+///         Value::Object(fields) => fields.values_mut().for_each(truncate_floats),
+///         _ => {}
+///     }
+/// }
+///
+/// let handler = ParseErrorHandler(Box::new(|raw_json, type_name| {
+///     if !type_name.ends_with("FullArtist") {
+///         return None;
+///     }
+///     let mut value = serde_json::from_str(raw_json).ok()?;
+///     truncate_floats(&mut value);
+///     Some(value)
+/// }));
+/// let config = Config {
+///     on_parse_error: Some(Arc::new(handler)),
+///     ..Default::default()
+/// };
+/// ```
+pub struct ParseErrorHandler(pub Box<ParseErrorFn>);
+
+/// The function wrapped by [`ParseErrorHandler`].
+pub type ParseErrorFn = dyn Fn(&str, &str) -> Option<serde_json::Value> + Send + Sync;
+
+impl fmt::Debug for ParseErrorHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ParseErrorHandler")
+    }
+}
+
+/// A hook that receives the refreshed token, see [`TokenRefreshHooks`].
+pub type TokenRefreshSuccessHook = Box<dyn Fn(&Token) + Send + Sync>;
+
+/// A hook that receives the error of a failed refresh, see
+/// [`TokenRefreshHooks`].
+pub type TokenRefreshFailureHook = Box<dyn Fn(&ClientError) + Send + Sync>;
+
+/// What to do when a playlist is modified while reading it with
+/// [`playlist_items_snapshot`](crate::clients::BaseClient::playlist_items_snapshot).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnSnapshotChange {
+    /// Fail with [`ClientError::SnapshotChanged`].
+    Abort,
+    /// Read the playlist again from the start, up to `max_restarts` times,
+    /// after which it fails like [`Self::Abort`].
+    Restart { max_restarts: u32 },
+}
+
+/// The episodes of several shows, returned by
+/// [`episodes_by_show_bulk`](crate::clients::BaseClient::episodes_by_show_bulk).
+#[derive(Debug, Default)]
+pub struct BulkEpisodes {
+    /// The episodes of all the shows that could be fetched, along with their
+    /// show, with the newest first.
+    pub episodes: Vec<(model::ShowId<'static>, model::SimplifiedEpisode)>,
+    /// The shows whose episodes couldn't be fetched, along with the error.
+    pub failed: Vec<(model::ShowId<'static>, ClientError)>,
+}
+
+/// Hooks invoked around every token refresh, including the automatic ones.
+/// They're useful to log the refreshes, or to alert or rotate the credentials
+/// when they fail.
+///
+/// The hooks are run synchronously right before and after the refresh, so
+/// they shouldn't block for long.
+#[derive(Default)]
+pub struct TokenRefreshHooks {
+    /// Invoked before requesting a new token.
+    pub on_token_refresh_start: Option<Box<dyn Fn() + Send + Sync>>,
+    /// Invoked with the new token after a successful refresh, before it's
+    /// saved in the client.
+    pub on_token_refresh_success: Option<TokenRefreshSuccessHook>,
+    /// Invoked with the error after a failed refresh.
+    pub on_token_refresh_failure: Option<TokenRefreshFailureHook>,
+}
+
+impl fmt::Debug for TokenRefreshHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenRefreshHooks")
+            .field(
+                "on_token_refresh_start",
+                &self.on_token_refresh_start.is_some(),
+            )
+            .field(
+                "on_token_refresh_success",
+                &self.on_token_refresh_success.is_some(),
+            )
+            .field(
+                "on_token_refresh_failure",
+                &self.on_token_refresh_failure.is_some(),
+            )
+            .finish()
+    }
+}
+
+/// How to log the bodies of the responses from Spotify, which is helpful to
+/// debug the responses that can't be parsed. See
+/// [`Config::log_response_bodies`].
+///
+/// The bodies are logged with [`log::info!`] after removing the values of
+/// their token fields, like `access_token` and `refresh_token`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ResponseLogging {
+    /// The maximum length of each logged body in bytes, after which it's
+    /// truncated. 4096 by default.
+    pub max_len: usize,
+    /// The fraction of the responses that are logged, from `0.0` to `1.0`.
+    /// All of them by default.
+    pub sample_rate: f64,
+}
+
+impl Default for ResponseLogging {
+    fn default() -> Self {
+        Self {
+            max_len: 4096,
+            sample_rate: 1.0,
+        }
+    }
+}
+
+impl ResponseLogging {
+    /// Randomly decides whether to log a response, according to the sample
+    /// rate.
+    pub(crate) fn sample(&self) -> bool {
+        if self.sample_rate >= 1.0 {
+            return true;
+        }
+
+        let mut buf = [0u8; 4];
+        if getrandom(&mut buf).is_err() {
+            return false;
+        }
+        f64::from(u32::from_le_bytes(buf)) / f64::from(u32::MAX) < self.sample_rate
+    }
+}
+
 /// Struct to configure the Spotify client.
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -305,6 +657,11 @@ pub struct Config {
     /// [`DEFAULT_CACHE_PATH`]
     pub cache_path: PathBuf,
 
+    /// The backend used to persist the token when `token_cached` is enabled.
+    /// If `None`, which is the default, the token is saved as JSON in
+    /// `cache_path` with a [`FileTokenStore`].
+    pub token_store: Option<Arc<dyn TokenStore>>,
+
     /// The pagination chunk size used when performing automatically paginated
     /// requests, like [`artist_albums`](crate::clients::BaseClient). This
     /// means that a request will be performed every `pagination_chunks` items.
@@ -321,11 +678,140 @@ pub struct Config {
 
     /// Whether or not to check if the token has expired when sending a
     /// request with credentials, and in that case, automatically refresh it.
+    /// This also makes the client refresh the token and retry the request
+    /// once if it's rejected with a `401 Unauthorized` status code.
     pub token_refreshing: bool,
 
+    /// Whether or not to request a token on the first request if there's
+    /// none yet, instead of failing with [`ClientError::InvalidToken`]. This
+    /// only applies to the client credentials flow, since the rest require
+    /// the user's authorization. Enabled by default.
+    pub token_fetching: bool,
+
+    /// How long before its expiration the token is already considered
+    /// expired, so that it's refreshed in advance rather than rejected
+    /// because of the clock skew or the duration of the request. 10 seconds
+    /// by default.
+    pub token_expiry_margin: chrono::Duration,
+
     /// Whenever client succeeds to request or refresh a token, the callback function
     /// will be invoked
     pub token_callback_fn: Arc<Option<TokenCallback>>,
+
+    /// The hooks invoked before and after refreshing the token, whether it
+    /// succeeds or fails. None are set by default.
+    pub token_refresh_hooks: Arc<TokenRefreshHooks>,
+
+    /// The market applied to the endpoints that accept one when `None` is
+    /// given, e.g., `Some(Market::FromToken)` to always use the country of
+    /// the current user. Unset by default.
+    ///
+    /// Note that this doesn't apply to the `country` parameter of the browse
+    /// endpoints, which doesn't support `from_token`.
+    pub default_market: Option<model::Market>,
+
+    /// Whether or not to check the actions allowed in the current playback
+    /// before sending a player command, such as [`next_track`] or
+    /// [`seek_track`]. If the action is disallowed (e.g., skipping an ad),
+    /// [`ClientError::Player`] is returned without sending the command. This
+    /// costs an additional request to fetch the playback state, so it's
+    /// disabled by default.
+    ///
+    /// [`next_track`]: crate::clients::OAuthClient::next_track
+    /// [`seek_track`]: crate::clients::OAuthClient::seek_track
+    pub check_player_actions: bool,
+
+    /// Whether or not to split the endpoints that take a list of IDs into
+    /// multiple requests when there are more IDs than Spotify accepts at once
+    /// (e.g., 50 tracks or 20 albums), aggregating their results. For the
+    /// playlist modifications, the snapshot ID of the last request is
+    /// returned. Disabled by default, in which case Spotify rejects the
+    /// request.
+    pub auto_chunk_requests: bool,
+
+    /// The journal where mutating requests are recorded before being sent,
+    /// so that they can be audited or replayed after a crash. Disabled by
+    /// default. See the [`journal`] module for more information.
+    #[cfg(feature = "journal")]
+    pub journal: Option<Arc<dyn journal::RequestJournal>>,
+
+    /// The proxy for the requests of this client, which takes precedence over
+    /// the proxy environment variables. It's only read when building the
+    /// client with `with_config`, which panics if the proxy is invalid, or
+    /// with `try_with_config`, which returns an error instead. Unset by
+    /// default.
+    pub proxy: Option<http::ProxyConfig>,
+
+    /// The client certificate presented in both the authentication and the
+    /// API requests, for mutual TLS. Like [`Self::proxy`], it's only read
+    /// when building the client, which fails if it's invalid or unsupported
+    /// by the enabled TLS backend. Unset by default.
+    pub tls_identity: Option<http::TlsIdentity>,
+
+    /// The interceptors invoked around every request to Spotify, e.g., to add
+    /// tracing headers or to record metrics. Like [`Self::proxy`], they're
+    /// only read when building the client with `with_config`. None are set
+    /// by default.
+    pub interceptors: Vec<Arc<dyn http::RequestInterceptor>>,
+
+    /// The timeout of each request, from connecting until the response is
+    /// read. If `None`, which is the default, it's
+    /// [`DEFAULT_TIMEOUT`](http::DEFAULT_TIMEOUT). Like [`Self::proxy`], it's
+    /// only read when building the client with `with_config`.
+    pub http_timeout: Option<std::time::Duration>,
+
+    /// The timeout to establish a connection, within [`Self::http_timeout`].
+    /// Unset by default, and only read when building the client with
+    /// `with_config`.
+    pub connect_timeout: Option<std::time::Duration>,
+
+    /// The `User-Agent` header of the requests, e.g., to identify the
+    /// application in the logs of a proxy. If `None`, which is the default,
+    /// the HTTP library sets its own. Only read when building the client
+    /// with `with_config`.
+    pub user_agent: Option<String>,
+
+    /// The HTTP client to use instead of building one from the options
+    /// above, which are then ignored. For example, a
+    /// [`MockHttpClient`](http::MockHttpClient) converted into the regular
+    /// client, with the `client-mock` feature. Unset by default.
+    pub http_client: Option<http::HttpClient>,
+
+    /// Whether to log the bodies of the responses, both from the Web API and
+    /// the authentication endpoints. Disabled by default.
+    pub log_response_bodies: Option<ResponseLogging>,
+
+    /// The audit where the scopes needed by each request are recorded, to
+    /// find the requested ones that are never used. Disabled by default. See
+    /// the [`scope_audit`] module for more information.
+    pub scope_audit: Option<Arc<scope_audit::ScopeAudit>>,
+
+    /// Whether the requests that the token lacks the scopes for fail with
+    /// [`ClientError::MissingScope`] before they're sent, instead of with
+    /// the `403 Forbidden` of the Web API. Only the scopes that are always
+    /// required by an endpoint are checked, according to
+    /// [`scope_audit::missing_scope`]. Disabled by default.
+    pub validate_scopes: bool,
+
+    /// The cache for the responses of the catalog endpoints, which may be
+    /// shared by multiple clients. Disabled by default. See the
+    /// [`response_cache`] module for more information.
+    pub response_cache: Option<Arc<response_cache::ResponseCache>>,
+
+    /// The handler invoked when a response can't be parsed, which may patch
+    /// its JSON before giving up. Unset by default. See
+    /// [`ParseErrorHandler`] for more information.
+    pub on_parse_error: Option<Arc<ParseErrorHandler>>,
+
+    /// Whether the whole response is included in [`ClientError::ParseJson`]
+    /// when it can't be parsed, besides the snippet around the error. Its
+    /// tokens are redacted, but it may be large. Disabled by default.
+    pub parse_error_body: bool,
+
+    /// The policy to retry the requests to the Web API that fail
+    /// transiently, e.g., with a `503 Service Unavailable`. Disabled by
+    /// default. See the [`retry`] module for more information.
+    pub retry_policy: Option<Arc<retry::RetryPolicy>>,
 }
 
 impl Default for Config {
@@ -334,26 +820,92 @@ impl Default for Config {
             api_base_url: String::from(DEFAULT_API_BASE_URL),
             auth_base_url: String::from(DEFAULT_AUTH_BASE_URL),
             cache_path: PathBuf::from(DEFAULT_CACHE_PATH),
+            token_store: None,
             pagination_chunks: DEFAULT_PAGINATION_CHUNKS,
             token_cached: false,
             token_refreshing: true,
+            token_fetching: true,
+            token_expiry_margin: chrono::Duration::try_seconds(10).unwrap(),
             token_callback_fn: Arc::new(None),
+            token_refresh_hooks: Arc::default(),
+            default_market: None,
+            check_player_actions: false,
+            auto_chunk_requests: false,
+            #[cfg(feature = "journal")]
+            journal: None,
+            proxy: None,
+            tls_identity: None,
+            interceptors: Vec::new(),
+            http_timeout: None,
+            connect_timeout: None,
+            user_agent: None,
+            http_client: None,
+            log_response_bodies: None,
+            scope_audit: None,
+            validate_scopes: false,
+            response_cache: None,
+            on_parse_error: None,
+            parse_error_body: false,
+            retry_policy: None,
+        }
+    }
+}
+
+impl Config {
+    /// Returns the configured [`TokenStore`], falling back to a
+    /// [`FileTokenStore`] at `cache_path`.
+    #[must_use]
+    pub fn get_token_store(&self) -> Arc<dyn TokenStore> {
+        match &self.token_store {
+            Some(store) => Arc::clone(store),
+            None => Arc::new(FileTokenStore::new(&self.cache_path)),
+        }
+    }
+
+    /// Builds the HTTP client for this configuration, unless one was given.
+    /// Fails if the proxy or the TLS identity are invalid.
+    pub(crate) fn build_http_client(&self) -> ClientResult<http::HttpClient> {
+        if let Some(http) = &self.http_client {
+            return Ok(http.clone());
         }
+
+        let options_unset = self.proxy.is_none()
+            && self.tls_identity.is_none()
+            && self.interceptors.is_empty()
+            && self.http_timeout.is_none()
+            && self.connect_timeout.is_none()
+            && self.user_agent.is_none();
+        if options_unset {
+            return Ok(http::HttpClient::default());
+        }
+
+        let options = http::HttpOptions {
+            proxy: self.proxy.clone(),
+            identity: self.tls_identity.clone(),
+            interceptors: self.interceptors.clone(),
+            timeout: self.http_timeout,
+            connect_timeout: self.connect_timeout,
+            user_agent: self.user_agent.clone(),
+        };
+        Ok(http::HttpClient::with_options(&options)?)
     }
 }
 
 /// Generate `length` random chars from the Operating System.
 ///
 /// It is assumed that system always provides high-quality cryptographically
-/// secure random data, ideally backed by hardware entropy sources.
-pub(crate) fn generate_random_string(length: usize, alphabet: &[u8]) -> String {
+/// secure random data, ideally backed by hardware entropy sources. It fails
+/// with [`ClientError::Random`] if there's no source available, e.g., on
+/// WebAssembly outside of a browser.
+pub(crate) fn generate_random_string(length: usize, alphabet: &[u8]) -> ClientResult<String> {
     let mut buf = vec![0u8; length];
-    getrandom(&mut buf).unwrap();
+    getrandom(&mut buf)?;
     let range = alphabet.len();
 
-    buf.iter()
+    Ok(buf
+        .iter()
         .map(|byte| alphabet[*byte as usize % range] as char)
-        .collect()
+        .collect())
 }
 
 #[inline]
@@ -362,13 +914,26 @@ pub(crate) fn join_ids<'a, T: Id + 'a>(ids: impl IntoIterator<Item = T>) -> Stri
     ids.iter().map(Id::id).collect::<Vec<_>>().join(",")
 }
 
-#[inline]
-pub(crate) fn join_scopes(scopes: &HashSet<String>) -> String {
-    scopes
-        .iter()
-        .map(String::as_str)
-        .collect::<Vec<_>>()
-        .join(" ")
+/// Joins the scopes as expected by Spotify. They're sorted so that the result
+/// is always the same for the same scopes.
+#[must_use]
+pub fn join_scopes(scopes: &HashSet<String>) -> String {
+    let mut scopes = scopes.iter().map(String::as_str).collect::<Vec<_>>();
+    scopes.sort_unstable();
+    scopes.join(" ")
+}
+
+/// Builds the URL where the user authorizes the application out of the URL of
+/// the `authorize` endpoint and the query parameters. These are sorted by
+/// name, so that the same parameters always result in the same URL (e.g., for
+/// snapshot tests or proxies that compare them).
+pub fn build_authorize_url<'a>(
+    authorize_url: &str,
+    params: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> ClientResult<String> {
+    let params = params.into_iter().collect::<BTreeMap<_, _>>();
+    let url = url::Url::parse_with_params(authorize_url, params)?;
+    Ok(url.into())
 }
 
 /// Simple client credentials object for Spotify.
@@ -377,6 +942,15 @@ pub struct Credentials {
     pub id: String,
     /// PKCE doesn't require a client secret
     pub secret: Option<String>,
+    /// Additional form parameters sent when requesting and refreshing the
+    /// token, e.g., for a gateway in front of the accounts service. They
+    /// don't replace the parameters set by RSpotify.
+    pub extra_token_params: HashMap<String, String>,
+    /// Additional headers sent when requesting and refreshing the token. They
+    /// don't replace the headers set by RSpotify, like the authorization,
+    /// regardless of their case. An invalid name or value fails the request
+    /// with [`ClientError::InvalidHeader`].
+    pub extra_token_headers: HashMap<String, String>,
 }
 
 impl Credentials {
@@ -386,6 +960,7 @@ impl Credentials {
         Self {
             id: id.to_owned(),
             secret: Some(secret.to_owned()),
+            ..Default::default()
         }
     }
 
@@ -395,6 +970,7 @@ impl Credentials {
         Self {
             id: id.to_owned(),
             secret: None,
+            ..Default::default()
         }
     }
 
@@ -412,9 +988,28 @@ impl Credentials {
         Some(Self {
             id: env::var("RSPOTIFY_CLIENT_ID").ok()?,
             secret: env::var("RSPOTIFY_CLIENT_SECRET").ok(),
+            ..Default::default()
         })
     }
 
+    /// Adds a form parameter to the token requests, see
+    /// [`Self::extra_token_params`].
+    #[must_use]
+    pub fn with_token_param(mut self, name: &str, value: &str) -> Self {
+        self.extra_token_params
+            .insert(name.to_owned(), value.to_owned());
+        self
+    }
+
+    /// Adds a header to the token requests, see
+    /// [`Self::extra_token_headers`].
+    #[must_use]
+    pub fn with_token_header(mut self, name: &str, value: &str) -> Self {
+        self.extra_token_headers
+            .insert(name.to_owned(), value.to_owned());
+        self
+    }
+
     /// Generates an HTTP basic authorization header with proper formatting
     ///
     /// This will only work when the client secret is set to `Option::Some`.
@@ -430,33 +1025,116 @@ impl Credentials {
     }
 }
 
+/// Picks the redirect URI for a new authorization out of
+/// [`OAuth::redirect_uri`] followed by [`OAuth::redirect_uris`], see
+/// [`OAuth::redirect_uri_selector`]. It may also build a new one, e.g., with
+/// a port that's free at that moment.
+#[derive(Clone)]
+pub struct RedirectUriSelector(pub Arc<RedirectUriSelectorFn>);
+
+/// The function wrapped by [`RedirectUriSelector`].
+pub type RedirectUriSelectorFn = dyn Fn(&[String]) -> String + Send + Sync;
+
+impl fmt::Debug for RedirectUriSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RedirectUriSelector")
+    }
+}
+
 /// Structure that holds the required information for requests with OAuth.
 #[derive(Debug, Clone)]
 pub struct OAuth {
     pub redirect_uri: String,
+    /// Other redirect URIs registered for the application. Spotify may
+    /// redirect to any of them, depending on the one chosen by
+    /// [`Self::redirect_uri_selector`]. Empty by default.
+    pub redirect_uris: Vec<String>,
+    /// Chooses the redirect URI every time an authorization URL is built,
+    /// which is then used for the rest of that authorization. If `None`,
+    /// which is the default, [`Self::redirect_uri`] is always used.
+    pub redirect_uri_selector: Option<RedirectUriSelector>,
     /// The state is generated by default, as suggested by the OAuth2 spec:
     /// [Cross-Site Request Forgery](https://tools.ietf.org/html/rfc6749#section-10.12)
     pub state: String,
-    /// You could use macro [scopes!](crate::scopes) to build it at compile time easily
+    /// You could use macro [scopes!](crate::scopes) to build it at compile time easily,
+    /// either from strings or from [`Scope`](crate::model::Scope).
     pub scopes: HashSet<String>,
-    pub proxies: Option<String>,
 }
 
+/// # Panics
+///
+/// If the state can't be generated, since an empty state would accept any
+/// callback. See [`OAuth::try_default`] to handle the error instead.
 impl Default for OAuth {
     fn default() -> Self {
+        Self::try_default().unwrap_or_else(|err| {
+            panic!(
+                "Couldn't generate the OAuth state, use `OAuth::try_default` to handle it: {err}"
+            )
+        })
+    }
+}
+
+impl OAuth {
+    /// The same as [`OAuth::default`], but it fails with
+    /// [`ClientError::Random`] if the state can't be generated.
+    pub fn try_default() -> ClientResult<Self> {
+        let state = generate_random_string(16, alphabets::ALPHANUM)?;
+        Ok(Self::with_state(state))
+    }
+
+    /// The same as [`OAuth::default`], but with the given state instead of a
+    /// random one.
+    ///
+    /// The state is checked when the callback is parsed, so a server with
+    /// multiple instances must handle it with the same state the
+    /// authorization URL was built with. It may store the state of a new
+    /// authorization, e.g., in the session of the user, and then build the
+    /// client for the callback with it in whichever instance receives it:
+    ///
+    /// ```
+    /// use rspotify::{prelude::*, AuthCodeSpotify, Credentials, OAuth};
+    ///
+    /// // When the user logs in
+    /// let oauth = OAuth::try_default().unwrap();
+    /// let stored_state = oauth.state.clone();
+    ///
+    /// // When Spotify redirects the user back, maybe in another instance
+    /// let oauth = OAuth::with_state(stored_state.clone());
+    /// let callback = format!("http://localhost:8888/callback?code=abc&state={stored_state}");
+    /// assert!(oauth.validate_state(&callback));
+    /// let spotify = AuthCodeSpotify::new(Credentials::default(), oauth);
+    /// assert_eq!(spotify.parse_response_code(&callback).unwrap(), "abc");
+    /// ```
+    pub fn with_state(state: impl Into<String>) -> Self {
         Self {
             redirect_uri: String::new(),
-            state: generate_random_string(16, alphabets::ALPHANUM),
+            redirect_uris: Vec::new(),
+            redirect_uri_selector: None,
+            state: state.into(),
             scopes: HashSet::new(),
-            proxies: None,
         }
     }
-}
 
-impl OAuth {
+    /// Whether the `state` parameter of the callback URL matches
+    /// [`Self::state`]. It's `false` if the URL can't be parsed or it has no
+    /// state.
+    #[must_use]
+    pub fn validate_state(&self, url: &str) -> bool {
+        url::Url::parse(url).map_or(false, |url| self.state_matches(&url))
+    }
+
+    pub(crate) fn state_matches(&self, url: &url::Url) -> bool {
+        url.query_pairs()
+            .any(|(key, value)| key == "state" && value == self.state.as_str())
+    }
+
     /// Parses the credentials from the environment variable
     /// `RSPOTIFY_REDIRECT_URI`. You can optionally activate the `env-file`
     /// feature in order to read these variables from a `.env` file.
+    ///
+    /// It's `None` if the variable isn't set, or if the state can't be
+    /// generated, like with [`OAuth::try_default`].
     #[must_use]
     pub fn from_env(scopes: HashSet<String>) -> Option<Self> {
         #[cfg(feature = "env-file")]
@@ -467,9 +1145,43 @@ impl OAuth {
         Some(Self {
             scopes,
             redirect_uri: env::var("RSPOTIFY_REDIRECT_URI").ok()?,
-            ..Default::default()
+            ..Self::try_default().ok()?
         })
     }
+
+    /// Returns the redirect URI for a new authorization, with
+    /// [`Self::redirect_uri_selector`] if set.
+    #[must_use]
+    pub fn select_redirect_uri(&self) -> String {
+        match &self.redirect_uri_selector {
+            Some(selector) => {
+                let candidates = std::iter::once(&self.redirect_uri)
+                    .chain(&self.redirect_uris)
+                    .filter(|uri| !uri.is_empty())
+                    .cloned()
+                    .collect::<Vec<_>>();
+                selector.0(&candidates)
+            }
+            None => self.redirect_uri.clone(),
+        }
+    }
+
+    /// Finds the configured redirect URI that the given URL was sent to,
+    /// ignoring its query.
+    pub(crate) fn matching_redirect_uri(&self, url: &url::Url) -> Option<&str> {
+        std::iter::once(&self.redirect_uri)
+            .chain(&self.redirect_uris)
+            .find(|uri| match url::Url::parse(uri) {
+                Ok(uri) => {
+                    uri.scheme() == url.scheme()
+                        && uri.host_str() == url.host_str()
+                        && uri.port_or_known_default() == url.port_or_known_default()
+                        && uri.path() == url.path()
+                }
+                Err(_) => false,
+            })
+            .map(String::as_str)
+    }
 }
 
 #[cfg(test)]
@@ -483,7 +1195,7 @@ pub mod test {
     fn test_generate_random_string() {
         let mut containers = HashSet::new();
         for _ in 1..101 {
-            containers.insert(generate_random_string(10, alphabets::ALPHANUM));
+            containers.insert(generate_random_string(10, alphabets::ALPHANUM).unwrap());
         }
         assert_eq!(containers.len(), 100);
     }
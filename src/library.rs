@@ -0,0 +1,300 @@
+//! A facade over the endpoints of the current user's library, "Your Music".
+//!
+//! The saved tracks, albums, shows, episodes and audiobooks are managed by
+//! several methods of [`OAuthClient`] each, whose names grew inconsistent over
+//! time. [`Library`] groups them by kind of item, with the same operations for
+//! all of them:
+//!
+//! ```no_run
+//! # #[cfg(feature = "__async")]
+//! # async fn example(spotify: rspotify::AuthCodeSpotify) -> rspotify::ClientResult<()> {
+//! use rspotify::{model::{AlbumId, TrackId}, prelude::*};
+//!
+//! let library = spotify.library();
+//! let track = TrackId::from_id("4iV5W9uYEdYUVa79Axb7Rh").unwrap();
+//! library.tracks().add([track]).await?;
+//!
+//! let album = AlbumId::from_id("0sNOF9WDwhWunNAHPD3Baj").unwrap();
+//! let saved = library.albums().contains([album]).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The followed artists and users are managed the same way, with
+//! [`Library::artists`] and [`Library::users`]. The Web API only lists the
+//! followed artists and shows, though, so the users can be followed,
+//! unfollowed and checked but not listed.
+//!
+//! The items are added, removed and checked in batches as large as each
+//! endpoint accepts when [`Config::auto_chunk_requests`] is enabled, like the
+//! methods they wrap.
+//!
+//! [`Config::auto_chunk_requests`]: crate::Config::auto_chunk_requests
+
+use crate::{
+    clients::{pagination::Paginator, MaybeSend, OAuthClient},
+    model::{
+        AlbumId, ArtistId, AudiobookId, EpisodeId, FullArtist, Market, SavedAlbum, SavedEpisode,
+        SavedTrack, Show, ShowId, SimplifiedAudiobook, TrackId, UserId,
+    },
+    ClientResult,
+};
+
+use maybe_async::maybe_async;
+
+/// The paginator of the items followed by the current user, i.e., the
+/// artists of [`FollowedArtists::list`] and the shows of
+/// [`SavedShows::list`].
+pub type FollowedItems<'a, T> = Paginator<'a, ClientResult<T>>;
+
+/// The library of the current user, see the [module-level
+/// documentation](self). It's built with [`OAuthClient::library`].
+#[derive(Debug)]
+pub struct Library<'a, C> {
+    client: &'a C,
+}
+
+// Deriving them would require `C: Clone`
+impl<C> Clone for Library<'_, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C> Copy for Library<'_, C> {}
+
+impl<'a, C: OAuthClient> Library<'a, C> {
+    /// Builds the facade for the given client.
+    #[must_use]
+    pub fn new(client: &'a C) -> Self {
+        Self { client }
+    }
+
+    /// The saved tracks, or liked songs.
+    #[must_use]
+    pub fn tracks(&self) -> SavedTracks<'a, C> {
+        SavedTracks {
+            client: self.client,
+        }
+    }
+
+    /// The saved albums.
+    #[must_use]
+    pub fn albums(&self) -> SavedAlbums<'a, C> {
+        SavedAlbums {
+            client: self.client,
+        }
+    }
+
+    /// The followed shows.
+    #[must_use]
+    pub fn shows(&self) -> SavedShows<'a, C> {
+        SavedShows {
+            client: self.client,
+        }
+    }
+
+    /// The saved episodes.
+    #[must_use]
+    pub fn episodes(&self) -> SavedEpisodes<'a, C> {
+        SavedEpisodes {
+            client: self.client,
+        }
+    }
+
+    /// The saved audiobooks.
+    #[must_use]
+    pub fn audiobooks(&self) -> SavedAudiobooks<'a, C> {
+        SavedAudiobooks {
+            client: self.client,
+        }
+    }
+
+    /// The followed artists.
+    #[must_use]
+    pub fn artists(&self) -> FollowedArtists<'a, C> {
+        FollowedArtists {
+            client: self.client,
+        }
+    }
+
+    /// The followed users.
+    #[must_use]
+    pub fn users(&self) -> FollowedUsers<'a, C> {
+        FollowedUsers {
+            client: self.client,
+        }
+    }
+}
+
+/// Defines the items of a kind in the library, with the methods to add,
+/// remove and check them. Each one wraps a method of [`OAuthClient`], which
+/// is called with the IDs followed by the given extra arguments.
+macro_rules! library_items {
+    (
+        $(#[$attr:meta])*
+        $name:ident<$id:ident>, $items:literal {
+            add: $add_verb:literal => $add:ident($($add_arg:expr),*),
+            remove: $remove_verb:literal => $remove:ident($($remove_arg:expr),*),
+            contains: $state:literal => $contains:ident($($contains_arg:expr),*),
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug)]
+        pub struct $name<'a, C> {
+            client: &'a C,
+        }
+
+        #[maybe_async]
+        impl<'a, C: OAuthClient> $name<'a, C> {
+            #[doc = concat!(
+                $add_verb, " the ", $items, ", see [`OAuthClient::", stringify!($add), "`]."
+            )]
+            pub async fn add<'b>(
+                &self,
+                ids: impl IntoIterator<Item = $id<'b>> + MaybeSend + 'b,
+            ) -> ClientResult<()> {
+                self.client.$add(ids $(, $add_arg)*).await
+            }
+
+            #[doc = concat!(
+                $remove_verb, " the ", $items, ", see [`OAuthClient::", stringify!($remove), "`]."
+            )]
+            pub async fn remove<'b>(
+                &self,
+                ids: impl IntoIterator<Item = $id<'b>> + MaybeSend + 'b,
+            ) -> ClientResult<()> {
+                self.client.$remove(ids $(, $remove_arg)*).await
+            }
+
+            #[doc = concat!(
+                "Whether each of the ", $items, " is ", $state, ", see [`OAuthClient::",
+                stringify!($contains), "`]."
+            )]
+            pub async fn contains<'b>(
+                &self,
+                ids: impl IntoIterator<Item = $id<'b>> + MaybeSend + 'b,
+            ) -> ClientResult<Vec<bool>> {
+                self.client.$contains(ids $(, $contains_arg)*).await
+            }
+        }
+    };
+}
+
+library_items! {
+    /// The saved tracks of the current user, see [`Library::tracks`].
+    SavedTracks<TrackId>, "tracks" {
+        add: "Saves" => current_user_saved_tracks_add(),
+        remove: "Removes" => current_user_saved_tracks_delete(),
+        contains: "saved" => current_user_saved_tracks_contains(),
+    }
+}
+
+library_items! {
+    /// The saved albums of the current user, see [`Library::albums`].
+    SavedAlbums<AlbumId>, "albums" {
+        add: "Saves" => current_user_saved_albums_add(),
+        remove: "Removes" => current_user_saved_albums_delete(),
+        contains: "saved" => current_user_saved_albums_contains(),
+    }
+}
+
+library_items! {
+    /// The followed shows of the current user, see [`Library::shows`].
+    SavedShows<ShowId>, "shows" {
+        add: "Follows" => current_user_saved_shows_add(),
+        remove: "Unfollows" => current_user_saved_shows_delete(None),
+        contains: "followed" => current_user_saved_shows_contains(),
+    }
+}
+
+library_items! {
+    /// The saved episodes of the current user, see [`Library::episodes`].
+    SavedEpisodes<EpisodeId>, "episodes" {
+        add: "Saves" => current_user_saved_episodes_add(),
+        remove: "Removes" => current_user_saved_episodes_delete(),
+        contains: "saved" => current_user_saved_episodes_contains(),
+    }
+}
+
+library_items! {
+    /// The saved audiobooks of the current user, see [`Library::audiobooks`].
+    SavedAudiobooks<AudiobookId>, "audiobooks" {
+        add: "Saves" => current_user_saved_audiobooks_add(),
+        remove: "Removes" => current_user_saved_audiobooks_delete(),
+        contains: "saved" => current_user_saved_audiobooks_contains(),
+    }
+}
+
+library_items! {
+    /// The followed artists of the current user, see [`Library::artists`].
+    FollowedArtists<ArtistId>, "artists" {
+        add: "Follows" => user_follow_artists(),
+        remove: "Unfollows" => user_unfollow_artists(),
+        contains: "followed" => user_artist_check_follow(),
+    }
+}
+
+library_items! {
+    /// The followed users of the current user, see [`Library::users`]. Unlike
+    /// the rest, they can't be listed.
+    FollowedUsers<UserId>, "users" {
+        add: "Follows" => user_follow_users(),
+        remove: "Unfollows" => user_unfollow_users(),
+        contains: "followed" => user_check_follow_users(),
+    }
+}
+
+impl<'a, C: OAuthClient> SavedTracks<'a, C> {
+    /// All the saved tracks, see
+    /// [`OAuthClient::current_user_saved_tracks`].
+    #[must_use]
+    pub fn list(&self, market: Option<Market>) -> Paginator<'a, ClientResult<SavedTrack>> {
+        self.client.current_user_saved_tracks(market)
+    }
+}
+
+impl<'a, C: OAuthClient> SavedAlbums<'a, C> {
+    /// All the saved albums, see
+    /// [`OAuthClient::current_user_saved_albums`].
+    #[must_use]
+    pub fn list(&self, market: Option<Market>) -> Paginator<'a, ClientResult<SavedAlbum>> {
+        self.client.current_user_saved_albums(market)
+    }
+}
+
+impl<'a, C: OAuthClient> SavedShows<'a, C> {
+    /// All the followed shows, see
+    /// [`OAuthClient::current_user_saved_shows`].
+    #[must_use]
+    pub fn list(&self) -> FollowedItems<'a, Show> {
+        self.client.current_user_saved_shows()
+    }
+}
+
+impl<'a, C: OAuthClient> SavedEpisodes<'a, C> {
+    /// All the saved episodes, see
+    /// [`OAuthClient::current_user_saved_episodes`].
+    #[must_use]
+    pub fn list(&self, market: Option<Market>) -> Paginator<'a, ClientResult<SavedEpisode>> {
+        self.client.current_user_saved_episodes(market)
+    }
+}
+
+impl<'a, C: OAuthClient> SavedAudiobooks<'a, C> {
+    /// All the saved audiobooks, see
+    /// [`OAuthClient::current_user_saved_audiobooks`].
+    #[must_use]
+    pub fn list(&self) -> Paginator<'a, ClientResult<SimplifiedAudiobook>> {
+        self.client.current_user_saved_audiobooks()
+    }
+}
+
+impl<'a, C: OAuthClient> FollowedArtists<'a, C> {
+    /// All the followed artists, see
+    /// [`OAuthClient::current_user_followed_artists`].
+    #[must_use]
+    pub fn list(&self) -> FollowedItems<'a, FullArtist> {
+        self.client.current_user_followed_artists()
+    }
+}
@@ -0,0 +1,122 @@
+//! Polling of the player state, available with the asynchronous clients.
+//!
+//! The Web API doesn't push the changes in the playback, so applications like
+//! now-playing widgets have to poll `me/player` instead.
+//! [`player_state_stream`] does so periodically and only yields the states
+//! that actually changed, along with what changed:
+//!
+//! ```no_run
+//! # async fn example(spotify: rspotify::AuthCodeSpotify) {
+//! use futures::stream::TryStreamExt;
+//! use rspotify::{model::PlaybackChange, player_state::player_state_stream};
+//! use std::time::Duration;
+//!
+//! let mut updates = Box::pin(player_state_stream(&spotify, Duration::from_secs(5)));
+//! while let Some(update) = updates.try_next().await.unwrap() {
+//!     for change in update.changes {
+//!         if let PlaybackChange::Item { to, .. } = change {
+//!             println!("Now playing {to:?}");
+//!         }
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! It waits between polls with the timer of Tokio. Other timers may be used
+//! instead with [`player_state_stream_with_sleep`], which is required on
+//! WebAssembly.
+
+use crate::{
+    clients::OAuthClient,
+    model::{AdditionalType, CurrentPlaybackContext, PlaybackChange},
+    ClientResult,
+};
+
+use std::{future::Future, time::Duration};
+
+use futures::stream::Stream;
+
+/// A new state of the player, yielded by [`player_state_stream`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayerStateUpdate {
+    /// The current playback, or `None` if there's no active device.
+    pub state: Option<CurrentPlaybackContext>,
+    /// What changed since the previous update. It's empty for the first
+    /// update, and when the playback starts or stops, in which case only
+    /// `state` tells the difference.
+    pub changes: Vec<PlaybackChange>,
+}
+
+/// Polls the playback of the user every `interval`, waiting with the timer of
+/// Tokio, and yields the state whenever it changes according to
+/// [`CurrentPlaybackContext::changes_since`]. The first state is always
+/// yielded.
+///
+/// Both tracks and episodes are requested. The errors are yielded as well,
+/// after which the polling goes on, so it's up to the caller to stop on
+/// them. The stream never ends otherwise, so it should be dropped once it's
+/// no longer needed.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn player_state_stream<C: OAuthClient>(
+    client: &C,
+    interval: Duration,
+) -> impl Stream<Item = ClientResult<PlayerStateUpdate>> + '_ {
+    player_state_stream_with_sleep(client, interval, tokio::time::sleep)
+}
+
+/// Same as [`player_state_stream`], but waiting with the `sleep` function,
+/// e.g., `async_std::task::sleep` or, on WebAssembly,
+/// `gloo_timers::future::sleep`.
+pub fn player_state_stream_with_sleep<'a, C, S, F>(
+    client: &'a C,
+    interval: Duration,
+    sleep: S,
+) -> impl Stream<Item = ClientResult<PlayerStateUpdate>> + 'a
+where
+    C: OAuthClient,
+    S: Fn(Duration) -> F + 'a,
+    F: Future<Output = ()> + 'a,
+{
+    use async_stream::stream;
+
+    stream! {
+        let mut previous: Option<Option<CurrentPlaybackContext>> = None;
+        loop {
+            let types = [AdditionalType::Track, AdditionalType::Episode];
+            match client.current_playback(None, Some(&types)).await {
+                Ok(state) => {
+                    if let Some(update) = update(previous.as_ref(), &state) {
+                        yield Ok(update);
+                    }
+                    previous = Some(state);
+                }
+                Err(err) => yield Err(err),
+            }
+            sleep(interval).await;
+        }
+    }
+}
+
+/// The update for the new `state`, or `None` if it didn't change since the
+/// `previous` one. `previous` is `None` before the first state.
+fn update(
+    previous: Option<&Option<CurrentPlaybackContext>>,
+    state: &Option<CurrentPlaybackContext>,
+) -> Option<PlayerStateUpdate> {
+    let changes = match (previous, state) {
+        (Some(Some(previous)), Some(state)) => {
+            let changes = state.changes_since(previous);
+            if changes.is_empty() {
+                return None;
+            }
+            changes
+        }
+        (Some(None), None) => return None,
+        _ => Vec::new(),
+    };
+
+    Some(PlayerStateUpdate {
+        state: state.clone(),
+        changes,
+    })
+}
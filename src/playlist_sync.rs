@@ -0,0 +1,393 @@
+//! Make a playlist match a list of items with as few changes as possible.
+//!
+//! Replacing all the items of a playlist is simpler, but it resets the date
+//! each of them was added, and it's slow for large playlists. [`playlist_sync`]
+//! instead compares the current items with the desired ones in
+//! [`plan_sync`], which plans the changes in three steps:
+//!
+//! 1. The items that aren't desired, or that are repeated more times than
+//!    desired, are removed.
+//! 2. The rest are reordered, moving the fewest of them.
+//! 3. The missing items are added in their positions.
+//!
+//! The items without an ID, like local files, can't be added by ID, so they
+//! are kept after the desired items.
+//!
+//! With `dry_run`, the planned operations are only returned, so that they can
+//! be reviewed first:
+//!
+//! ```no_run
+//! # #[cfg(feature = "__async")]
+//! # async fn example(spotify: rspotify::AuthCodeSpotify) {
+//! use rspotify::{
+//!     model::{PlayableId, PlaylistId, TrackId},
+//!     playlist_sync::playlist_sync,
+//! };
+//!
+//! let playlist = PlaylistId::from_id("37i9dQZF1DXcBWIGoYBM5M").unwrap();
+//! let desired = [
+//!     PlayableId::Track(TrackId::from_id("4iV5W9uYEdYUVa79Axb7Rh").unwrap()),
+//!     PlayableId::Track(TrackId::from_id("1301WleyT98MSxVHPZCA6M").unwrap()),
+//! ];
+//! let operations = playlist_sync(&spotify, playlist, &desired, true)
+//!     .await
+//!     .unwrap();
+//! println!("{} changes pending", operations.len());
+//! # }
+//! ```
+
+use crate::{
+    clients::OAuthClient,
+    model::{Id, ItemPositions, PlayableId, PlayableItem, PlaylistId},
+    ClientResult, OnSnapshotChange,
+};
+
+use std::collections::{HashMap, VecDeque};
+
+use maybe_async::maybe_async;
+
+/// The maximum number of items removed or added in a single request.
+const MAX_ITEMS: usize = 100;
+
+/// A change to a playlist, planned by [`plan_sync`]. Its positions are
+/// relative to the playlist after applying the previous operations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SyncOperation {
+    /// Removes the item at the given position.
+    Remove {
+        id: PlayableId<'static>,
+        position: u32,
+    },
+    /// Moves the item at `range_start` before the one at `insert_before`,
+    /// like [`OAuthClient::playlist_reorder_items`].
+    Move {
+        range_start: u32,
+        insert_before: u32,
+    },
+    /// Adds the items at the given position.
+    Add {
+        items: Vec<PlayableId<'static>>,
+        position: u32,
+    },
+}
+
+/// Plans the operations that turn the `current` items of a playlist into the
+/// `desired` ones, where `None` is an item without an ID. See the
+/// [module-level documentation](self) for more information.
+///
+/// The removals are sorted from the end of the playlist, so that the
+/// consecutive ones may be sent in the same request.
+#[must_use]
+pub fn plan_sync(
+    current: &[Option<PlayableId<'_>>],
+    desired: &[PlayableId<'_>],
+) -> Vec<SyncOperation> {
+    // The desired positions of each item, to be matched in order with its
+    // occurrences in the playlist
+    let mut wanted = HashMap::<String, VecDeque<usize>>::new();
+    for (index, id) in desired.iter().enumerate() {
+        wanted.entry(id.uri()).or_default().push_back(index);
+    }
+
+    // The kept items are ranked by their desired position, and the items
+    // without an ID after all of them
+    let mut ranks = Vec::new();
+    let mut matched = vec![false; desired.len()];
+    let mut removals = Vec::new();
+    for (position, id) in current.iter().enumerate() {
+        let id = match id {
+            Some(id) => id,
+            None => {
+                ranks.push(desired.len() + ranks.len());
+                continue;
+            }
+        };
+
+        match wanted.get_mut(&id.uri()).and_then(VecDeque::pop_front) {
+            Some(index) => {
+                matched[index] = true;
+                ranks.push(index);
+            }
+            None => removals.push(SyncOperation::Remove {
+                id: id.clone_static(),
+                position: position as u32,
+            }),
+        }
+    }
+    removals.reverse();
+    let mut operations = removals;
+
+    // The longest sequence that's already in order stays in place, and the
+    // rest of the items are moved after the one that precedes them, in order
+    let stationary = longest_increasing(&ranks);
+    let mut moving = ranks
+        .iter()
+        .zip(&stationary)
+        .filter(|(_, stationary)| !**stationary)
+        .map(|(rank, _)| *rank)
+        .collect::<Vec<_>>();
+    moving.sort_unstable();
+    let mut list = ranks;
+    for rank in moving {
+        let from = list.iter().position(|r| *r == rank).unwrap();
+        let insert_before = list
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| **r < rank)
+            .max_by_key(|(_, r)| **r)
+            .map_or(0, |(position, _)| position + 1);
+        if insert_before == from || insert_before == from + 1 {
+            continue;
+        }
+
+        operations.push(SyncOperation::Move {
+            range_start: from as u32,
+            insert_before: insert_before as u32,
+        });
+        let item = list.remove(from);
+        let to = if insert_before > from {
+            insert_before - 1
+        } else {
+            insert_before
+        };
+        list.insert(to, item);
+    }
+
+    // The items before each missing one are in place by now
+    let mut index = 0;
+    while index < desired.len() {
+        if matched[index] {
+            index += 1;
+            continue;
+        }
+
+        let start = index;
+        while index < desired.len() && !matched[index] {
+            index += 1;
+        }
+        operations.push(SyncOperation::Add {
+            items: desired[start..index]
+                .iter()
+                .map(PlayableId::clone_static)
+                .collect(),
+            position: start as u32,
+        });
+    }
+
+    operations
+}
+
+/// Marks the longest increasing subsequence of the given ranks, which are
+/// unique.
+fn longest_increasing(ranks: &[usize]) -> Vec<bool> {
+    // The last index of the best subsequence found for each length, and the
+    // previous index in the subsequence of each one
+    let mut tails = Vec::<usize>::new();
+    let mut previous = vec![None; ranks.len()];
+    for (index, rank) in ranks.iter().enumerate() {
+        let len = tails.partition_point(|tail| ranks[*tail] < *rank);
+        if len > 0 {
+            previous[index] = Some(tails[len - 1]);
+        }
+        if len == tails.len() {
+            tails.push(index);
+        } else {
+            tails[len] = index;
+        }
+    }
+
+    let mut stationary = vec![false; ranks.len()];
+    let mut next = tails.last().copied();
+    while let Some(index) = next {
+        stationary[index] = true;
+        next = previous[index];
+    }
+    stationary
+}
+
+/// Makes the playlist match the `desired` items, and returns the operations
+/// that were applied, or only planned if `dry_run` is set. See the
+/// [module-level documentation](self) for more information.
+///
+/// The playlist is read with
+/// [`playlist_items_snapshot`](crate::clients::BaseClient::playlist_items_snapshot),
+/// and each request is made against the snapshot left by the previous one.
+#[maybe_async]
+pub async fn playlist_sync<C: OAuthClient>(
+    client: &C,
+    playlist_id: PlaylistId<'_>,
+    desired: &[PlayableId<'_>],
+    dry_run: bool,
+) -> ClientResult<Vec<SyncOperation>> {
+    let (mut snapshot_id, items) = client
+        .playlist_items_snapshot(playlist_id.as_ref(), None, OnSnapshotChange::Abort)
+        .await?;
+    let current = items
+        .iter()
+        .map(|item| item.track.as_ref().and_then(PlayableItem::id))
+        .collect::<Vec<_>>();
+    let operations = plan_sync(&current, desired);
+    if dry_run {
+        return Ok(operations);
+    }
+
+    let mut index = 0;
+    while index < operations.len() {
+        match &operations[index] {
+            SyncOperation::Remove { .. } => {
+                // Being sorted from the end, the positions of the consecutive
+                // removals are still valid when they're sent together
+                let mut removals = Vec::<(PlayableId<'_>, Vec<u32>)>::new();
+                for operation in operations[index..].iter().take(MAX_ITEMS) {
+                    let (id, position) = match operation {
+                        SyncOperation::Remove { id, position } => (id, position),
+                        _ => break,
+                    };
+                    match removals.iter_mut().find(|(other, _)| other == id) {
+                        Some((_, positions)) => positions.push(*position),
+                        None => removals.push((id.as_ref(), vec![*position])),
+                    }
+                    index += 1;
+                }
+
+                let items = removals.iter().map(|(id, positions)| ItemPositions {
+                    id: id.as_ref(),
+                    positions,
+                });
+                snapshot_id = client
+                    .playlist_remove_specific_occurrences_of_items(
+                        playlist_id.as_ref(),
+                        items,
+                        Some(snapshot_id.as_str()),
+                    )
+                    .await?;
+            }
+            SyncOperation::Move {
+                range_start,
+                insert_before,
+            } => {
+                snapshot_id = client
+                    .playlist_reorder_items(
+                        playlist_id.as_ref(),
+                        Some(*range_start as i32),
+                        Some(*insert_before as i32),
+                        None,
+                        Some(snapshot_id.as_str()),
+                    )
+                    .await?;
+                index += 1;
+            }
+            SyncOperation::Add { items, position } => {
+                for (chunk, offset) in items
+                    .chunks(MAX_ITEMS)
+                    .zip((*position..).step_by(MAX_ITEMS))
+                {
+                    snapshot_id = client
+                        .playlist_add_items(
+                            playlist_id.as_ref(),
+                            chunk.iter().map(PlayableId::as_ref),
+                            Some(offset),
+                        )
+                        .await?;
+                }
+                index += 1;
+            }
+        }
+    }
+
+    Ok(operations)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::TrackId;
+
+    fn track(id: &str) -> PlayableId<'_> {
+        PlayableId::Track(TrackId::from_id(id).unwrap())
+    }
+
+    /// Applies the operations like Spotify would.
+    fn apply<'a>(
+        mut items: Vec<Option<PlayableId<'a>>>,
+        operations: &[SyncOperation],
+    ) -> Vec<Option<PlayableId<'a>>> {
+        for operation in operations {
+            match operation {
+                SyncOperation::Remove { position, .. } => {
+                    items.remove(*position as usize);
+                }
+                SyncOperation::Move {
+                    range_start,
+                    insert_before,
+                } => {
+                    let (from, before) = (*range_start as usize, *insert_before as usize);
+                    let item = items.remove(from);
+                    items.insert(if before > from { before - 1 } else { before }, item);
+                }
+                SyncOperation::Add {
+                    items: added,
+                    position,
+                } => {
+                    for (offset, id) in added.iter().enumerate() {
+                        items.insert(*position as usize + offset, Some(id.clone()));
+                    }
+                }
+            }
+        }
+        items
+    }
+
+    #[test]
+    fn test_plan_sync() {
+        let ids = ["a", "b", "c", "d", "e"].map(|c| c.repeat(22));
+        let [a, b, c, d, e] = [0, 1, 2, 3, 4].map(|i| track(&ids[i]));
+
+        // Moving the first item to the end only takes a single operation
+        let current = vec![Some(a.clone()), Some(b.clone()), Some(c.clone())];
+        let desired = [b.clone(), c.clone(), a.clone()];
+        let operations = plan_sync(&current, &desired);
+        assert_eq!(
+            operations,
+            [SyncOperation::Move {
+                range_start: 0,
+                insert_before: 3
+            }]
+        );
+
+        // Removals, a duplicate, moves, additions and a local file at once
+        let current = vec![
+            Some(c.clone()),
+            Some(a.clone()),
+            None,
+            Some(d.clone()),
+            Some(a.clone()),
+            Some(b.clone()),
+        ];
+        let desired = [a.clone(), e.clone(), b.clone(), c.clone()];
+        let operations = plan_sync(&current, &desired);
+        let expected = desired.iter().cloned().map(Some).chain([None]);
+        assert_eq!(apply(current, &operations), expected.collect::<Vec<_>>());
+        assert_eq!(
+            operations[..2],
+            [
+                SyncOperation::Remove {
+                    id: a.clone_static(),
+                    position: 4
+                },
+                SyncOperation::Remove {
+                    id: d.clone_static(),
+                    position: 3
+                },
+            ]
+        );
+        assert!(operations.contains(&SyncOperation::Add {
+            items: vec![e.clone_static()],
+            position: 1
+        }));
+
+        // Nothing to do
+        let current = desired.iter().cloned().map(Some).collect::<Vec<_>>();
+        assert!(plan_sync(&current, &desired).is_empty());
+    }
+}
@@ -0,0 +1,411 @@
+//! Polling of playlists for changes.
+//!
+//! Spotify doesn't notify applications when a playlist is modified, so
+//! they have to poll it instead. A [`PlaylistWatcher`] keeps the last known
+//! snapshot of each watched playlist, and compares it with the current one to
+//! tell what changed. The items are only read again when the snapshot ID
+//! changes, so polling an unmodified playlist takes a single small request.
+//!
+//! With the asynchronous clients, [`PlaylistWatcher::into_changes`] returns a
+//! stream of the changes, polling the playlists every interval:
+//!
+//! ```no_run
+//! # #[cfg(feature = "__async")]
+//! # async fn example(spotify: rspotify::AuthCodeSpotify) {
+//! use futures::stream::TryStreamExt;
+//! use rspotify::{
+//!     model::PlaylistId,
+//!     playlist_watcher::{PlaylistChange, PlaylistWatcher},
+//! };
+//! use std::time::Duration;
+//!
+//! let playlist = PlaylistId::from_id("37i9dQZF1DXcBWIGoYBM5M").unwrap();
+//! let watcher = PlaylistWatcher::new([playlist]);
+//! let mut changes = Box::pin(watcher.into_changes(&spotify, Duration::from_secs(60)));
+//! while let Some(change) = changes.try_next().await.unwrap() {
+//!     if let PlaylistChange::ItemsAdded { items, .. } = change {
+//!         println!("{} new items", items.len());
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! It waits between the polls with the timer of Tokio. Other timers may be
+//! used instead with [`PlaylistWatcher::into_changes_with_sleep`], which is
+//! required on WebAssembly. With the synchronous clients, it returns an
+//! iterator that sleeps in the current thread between the polls instead.
+//! [`PlaylistWatcher::poll`] may also be called directly to poll on a schedule
+//! of one's own.
+
+use crate::{
+    clients::{convert_result, route::Route, BaseClient, OAuthClient},
+    model::{Id, PlayableId, PlayableItem, PlaylistId},
+    util::build_map,
+    ClientResult, OnSnapshotChange,
+};
+
+use std::{collections::HashMap, hash::Hash, time::Duration};
+
+use maybe_async::maybe_async;
+use serde::{Deserialize, Serialize};
+
+/// How many times the items are read again if the playlist is modified while
+/// reading them.
+const MAX_RESTARTS: u32 = 3;
+
+/// The details of a playlist compared by the [`PlaylistWatcher`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchedDetails {
+    pub name: String,
+    pub description: Option<String>,
+    pub public: Option<bool>,
+    pub collaborative: bool,
+}
+
+/// A change in a watched playlist, found by [`PlaylistWatcher::poll`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PlaylistChange {
+    /// Items were added to the playlist, in the order they're found in it.
+    /// An item that was added several times is repeated as many times.
+    ItemsAdded {
+        playlist_id: PlaylistId<'static>,
+        items: Vec<PlayableId<'static>>,
+    },
+    /// Items were removed from the playlist, in the order they were found in
+    /// it.
+    ItemsRemoved {
+        playlist_id: PlaylistId<'static>,
+        items: Vec<PlayableId<'static>>,
+    },
+    /// The name, description, visibility or collaborative status of the
+    /// playlist changed.
+    DetailsChanged {
+        playlist_id: PlaylistId<'static>,
+        from: WatchedDetails,
+        to: WatchedDetails,
+    },
+}
+
+/// The last known state of a playlist.
+#[derive(Clone, Debug)]
+struct Snapshot {
+    snapshot_id: String,
+    details: WatchedDetails,
+    items: Vec<PlayableId<'static>>,
+}
+
+/// The response to the request of the details, which includes the snapshot ID
+/// as well.
+#[derive(Deserialize)]
+struct DetailsResult {
+    snapshot_id: String,
+    #[serde(flatten)]
+    details: WatchedDetails,
+}
+
+/// Watches playlists for changes. See the [module-level documentation](self)
+/// for more information.
+#[derive(Clone, Debug, Default)]
+pub struct PlaylistWatcher {
+    playlists: Vec<(PlaylistId<'static>, Option<Snapshot>)>,
+}
+
+impl PlaylistWatcher {
+    /// Creates a watcher for the given playlists. Their current state is
+    /// recorded the first time they're polled.
+    pub fn new(playlists: impl IntoIterator<Item = PlaylistId<'static>>) -> Self {
+        let mut watcher = Self::default();
+        for playlist_id in playlists {
+            watcher.watch(playlist_id);
+        }
+        watcher
+    }
+
+    /// Starts watching a playlist, unless it's watched already.
+    pub fn watch(&mut self, playlist_id: PlaylistId<'static>) {
+        if !self.is_watched(&playlist_id) {
+            self.playlists.push((playlist_id, None));
+        }
+    }
+
+    /// Stops watching a playlist.
+    pub fn unwatch(&mut self, playlist_id: &PlaylistId<'_>) {
+        self.playlists.retain(|(id, _)| id.id() != playlist_id.id());
+    }
+
+    /// Whether the playlist is being watched.
+    #[must_use]
+    pub fn is_watched(&self, playlist_id: &PlaylistId<'_>) -> bool {
+        self.playlists
+            .iter()
+            .any(|(id, _)| id.id() == playlist_id.id())
+    }
+
+    /// The watched playlists, in the order they're polled.
+    pub fn playlists(&self) -> impl Iterator<Item = &PlaylistId<'static>> {
+        self.playlists.iter().map(|(id, _)| id)
+    }
+
+    /// The last known snapshot ID of a playlist, which is `None` until it's
+    /// polled for the first time.
+    #[must_use]
+    pub fn snapshot_id(&self, playlist_id: &PlaylistId<'_>) -> Option<&str> {
+        self.playlists
+            .iter()
+            .find(|(id, _)| id.id() == playlist_id.id())
+            .and_then(|(_, snapshot)| snapshot.as_ref())
+            .map(|snapshot| snapshot.snapshot_id.as_str())
+    }
+
+    /// Polls a playlist, which starts being watched if it wasn't, and returns
+    /// what changed since the previous poll. The first poll only records its
+    /// state, so it never returns any changes.
+    ///
+    /// The details are requested on every poll, and the items only when the
+    /// snapshot ID changed. The local files are ignored, as they have no ID.
+    /// If the request fails, the last known state is kept, so the changes
+    /// are returned by the next successful poll.
+    #[maybe_async]
+    pub async fn poll<C: OAuthClient>(
+        &mut self,
+        client: &C,
+        playlist_id: PlaylistId<'_>,
+    ) -> ClientResult<Vec<PlaylistChange>> {
+        let index = match self
+            .playlists
+            .iter()
+            .position(|(id, _)| id.id() == playlist_id.id())
+        {
+            Some(index) => index,
+            None => {
+                self.playlists.push((playlist_id.clone_static(), None));
+                self.playlists.len() - 1
+            }
+        };
+        let (playlist_id, previous) = &self.playlists[index];
+
+        let current = fetch_details(client, playlist_id.as_ref()).await?;
+        let unchanged = previous
+            .as_ref()
+            .filter(|previous| previous.snapshot_id == current.snapshot_id);
+        let (snapshot_id, items) = match unchanged {
+            Some(previous) => (previous.snapshot_id.clone(), previous.items.clone()),
+            None => {
+                let on_change = OnSnapshotChange::Restart {
+                    max_restarts: MAX_RESTARTS,
+                };
+                let (snapshot_id, items) = client
+                    .playlist_items_snapshot(playlist_id.as_ref(), None, on_change)
+                    .await?;
+                let items = items
+                    .iter()
+                    .filter_map(|item| item.track.as_ref().and_then(PlayableItem::id))
+                    .map(PlayableId::into_static)
+                    .collect();
+                (snapshot_id.into_string(), items)
+            }
+        };
+
+        let changes = match previous {
+            Some(previous) => changes(playlist_id, previous, &current.details, &items),
+            None => Vec::new(),
+        };
+        self.playlists[index].1 = Some(Snapshot {
+            snapshot_id,
+            details: current.details,
+            items,
+        });
+        Ok(changes)
+    }
+
+    /// Polls the watched playlists every `interval`, waiting with the timer
+    /// of Tokio, and yields their changes. The first round only records their
+    /// state.
+    ///
+    /// The errors are yielded as well, after which the polling goes on, so
+    /// it's up to the caller to stop on them. The stream never ends
+    /// otherwise, so it should be dropped once it's no longer needed.
+    #[cfg(all(feature = "__async", not(target_arch = "wasm32")))]
+    pub fn into_changes<C: OAuthClient>(
+        self,
+        client: &C,
+        interval: Duration,
+    ) -> impl futures::stream::Stream<Item = ClientResult<PlaylistChange>> + '_ {
+        self.into_changes_with_sleep(client, interval, tokio::time::sleep)
+    }
+
+    /// Same as [`Self::into_changes`], but waiting with the `sleep`
+    /// function, e.g., `async_std::task::sleep` or, on WebAssembly,
+    /// `gloo_timers::future::sleep`.
+    #[cfg(feature = "__async")]
+    pub fn into_changes_with_sleep<'a, C, S, F>(
+        mut self,
+        client: &'a C,
+        interval: Duration,
+        sleep: S,
+    ) -> impl futures::stream::Stream<Item = ClientResult<PlaylistChange>> + 'a
+    where
+        C: OAuthClient,
+        S: Fn(Duration) -> F + 'a,
+        F: std::future::Future<Output = ()> + 'a,
+    {
+        use async_stream::stream;
+
+        stream! {
+            loop {
+                let playlists = self.playlists().cloned().collect::<Vec<_>>();
+                for playlist_id in playlists {
+                    match self.poll(client, playlist_id).await {
+                        Ok(changes) => {
+                            for change in changes {
+                                yield Ok(change);
+                            }
+                        }
+                        Err(err) => yield Err(err),
+                    }
+                }
+                sleep(interval).await;
+            }
+        }
+    }
+
+    /// Polls the watched playlists every `interval`, sleeping in the current
+    /// thread, and yields their changes. The first round only records their
+    /// state.
+    ///
+    /// The errors are yielded as well, after which the polling goes on, so
+    /// it's up to the caller to stop on them. The iterator never ends
+    /// otherwise.
+    #[cfg(feature = "__sync")]
+    pub fn into_changes<'a, C: OAuthClient>(
+        mut self,
+        client: &'a C,
+        interval: Duration,
+    ) -> impl Iterator<Item = ClientResult<PlaylistChange>> + 'a {
+        let mut pending = std::collections::VecDeque::new();
+        let mut next = 0;
+        std::iter::from_fn(move || loop {
+            if let Some(change) = pending.pop_front() {
+                return Some(change);
+            }
+            if next >= self.playlists.len() {
+                next = 0;
+                std::thread::sleep(interval);
+            }
+            let playlist_id = match self.playlists.get(next) {
+                Some((playlist_id, _)) => playlist_id.clone(),
+                // Nothing to watch, but the iterator never ends either
+                None => continue,
+            };
+            next += 1;
+            match self.poll(client, playlist_id) {
+                Ok(changes) => pending.extend(changes.into_iter().map(Ok)),
+                Err(err) => pending.push_back(Err(err)),
+            }
+        })
+    }
+}
+
+/// Requests the snapshot ID and details of a playlist.
+#[maybe_async]
+async fn fetch_details<C: BaseClient>(
+    client: &C,
+    playlist_id: PlaylistId<'_>,
+) -> ClientResult<DetailsResult> {
+    let url = Route::Playlist(playlist_id.as_ref()).to_string();
+    let fields = "snapshot_id,name,description,public,collaborative";
+    let params = build_map([("fields", Some(fields))]);
+    let result = client.api_get(&url, &params).await?;
+    convert_result(client.get_config(), &url, &result)
+}
+
+/// The changes from the `previous` snapshot of a playlist to its current
+/// `details` and `items`.
+fn changes(
+    playlist_id: &PlaylistId<'static>,
+    previous: &Snapshot,
+    details: &WatchedDetails,
+    items: &[PlayableId<'static>],
+) -> Vec<PlaylistChange> {
+    let mut changes = Vec::new();
+    let (added, removed) = diff(&previous.items, items);
+    if !added.is_empty() {
+        changes.push(PlaylistChange::ItemsAdded {
+            playlist_id: playlist_id.clone(),
+            items: added,
+        });
+    }
+    if !removed.is_empty() {
+        changes.push(PlaylistChange::ItemsRemoved {
+            playlist_id: playlist_id.clone(),
+            items: removed,
+        });
+    }
+    if previous.details != *details {
+        changes.push(PlaylistChange::DetailsChanged {
+            playlist_id: playlist_id.clone(),
+            from: previous.details.clone(),
+            to: details.clone(),
+        });
+    }
+    changes
+}
+
+/// The items that were added to `old` and removed from it to get `new`,
+/// counting the repeated ones. Moving an item isn't a change.
+fn diff<T: Clone + Eq + Hash>(old: &[T], new: &[T]) -> (Vec<T>, Vec<T>) {
+    let mut counts = HashMap::<&T, i64>::new();
+    for item in old {
+        *counts.entry(item).or_default() -= 1;
+    }
+    for item in new {
+        *counts.entry(item).or_default() += 1;
+    }
+
+    let added = pick(new, &mut counts, 1);
+    let removed = pick(old, &mut counts, -1);
+    (added, removed)
+}
+
+/// Takes the items whose count has the given `sign`, as many times as their
+/// count. The list is walked backwards, so that the last occurrences are the
+/// ones taken.
+fn pick<'a, T: Clone + Eq + Hash>(
+    items: &'a [T],
+    counts: &mut HashMap<&'a T, i64>,
+    sign: i64,
+) -> Vec<T> {
+    let mut picked = items
+        .iter()
+        .rev()
+        .filter(|item| match counts.get_mut(item) {
+            Some(count) if *count * sign > 0 => {
+                *count -= sign;
+                true
+            }
+            _ => false,
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    picked.reverse();
+    picked
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let (added, removed) = diff(&["a", "b", "c"], &["c", "a", "b"]);
+        assert!(added.is_empty() && removed.is_empty());
+
+        let (added, removed) = diff(&["a", "b", "b", "c"], &["d", "a", "b", "e", "d"]);
+        assert_eq!(added, ["d", "e", "d"]);
+        assert_eq!(removed, ["b", "c"]);
+
+        let (added, removed) = diff(&[], &["a", "a"]);
+        assert_eq!(added, ["a", "a"]);
+        assert!(removed.is_empty());
+    }
+}
@@ -0,0 +1,246 @@
+//! An optional cache for the responses of the catalog endpoints.
+//!
+//! Data like artists or albums rarely changes, so applications that request
+//! the same items over and over may keep their responses for a while instead,
+//! saving part of their quota. With a [`ResponseCache`] in
+//! [`Config::response_cache`](crate::Config::response_cache), the successful
+//! responses to `GET` requests of the cached endpoints are reused until their
+//! time to live expires:
+//!
+//! ```
+//! use rspotify::{response_cache::ResponseCache, ClientCredsSpotify, Config, Credentials};
+//! use std::{sync::Arc, time::Duration};
+//!
+//! let cache = ResponseCache::new(Duration::from_secs(60 * 60)).with_max_entries(500);
+//! let config = Config {
+//!     response_cache: Some(Arc::new(cache)),
+//!     ..Default::default()
+//! };
+//! let spotify = ClientCredsSpotify::with_config(Credentials::default(), config);
+//! ```
+//!
+//! The Web API doesn't send caching headers for most endpoints, so the
+//! responses are simply kept for a fixed time. By default, only the endpoints
+//! in [`ResponseCache::DEFAULT_ENDPOINTS`] are cached, since they're the
+//! catalog ones.
+//!
+//! Even those depend on the user, though, e.g., the tracks are relinked to
+//! the market of the user unless another one is given. Thus, the responses
+//! are kept for the access token they were requested with, and they're only
+//! reused by the requests with that same token. The cache may be shared by
+//! the clients of different users, but each client only benefits from its own
+//! responses, which are requested again once its token is refreshed.
+
+use crate::http::Query;
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::Mutex,
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+
+/// The default maximum number of responses kept by [`ResponseCache`].
+pub const DEFAULT_MAX_ENTRIES: usize = 1000;
+
+#[derive(Debug)]
+struct CachedResponse {
+    body: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// Keeps the responses of the catalog endpoints for a while. See the
+/// [module-level documentation](self) for more information.
+#[derive(Debug)]
+pub struct ResponseCache {
+    ttl: Duration,
+    max_entries: usize,
+    endpoints: Vec<String>,
+    entries: Mutex<HashMap<String, CachedResponse>>,
+}
+
+impl ResponseCache {
+    /// The endpoints cached by default, given by the first segment of their
+    /// path.
+    pub const DEFAULT_ENDPOINTS: &'static [&'static str] = &[
+        "albums",
+        "artists",
+        "tracks",
+        "audiobooks",
+        "chapters",
+        "audio-features",
+        "audio-analysis",
+    ];
+
+    /// Builds a cache whose responses are kept for `ttl`.
+    #[must_use]
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            endpoints: Self::DEFAULT_ENDPOINTS
+                .iter()
+                .map(|endpoint| (*endpoint).to_owned())
+                .collect(),
+            entries: Mutex::default(),
+        }
+    }
+
+    /// Sets the maximum number of responses kept. Once it's reached, the
+    /// responses that expire first are evicted.
+    #[must_use]
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Replaces the cached endpoints, given by the first segment of their
+    /// path (e.g., `"shows"` for both `shows/{id}` and `shows/{id}/episodes`).
+    #[must_use]
+    pub fn with_endpoints<S: Into<String>>(
+        mut self,
+        endpoints: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.endpoints = endpoints.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// The number of responses currently kept, including the expired ones
+    /// that haven't been evicted yet.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forgets all the responses.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// The key of the request sent with the given access token if it may be
+    /// cached, where `url` is relative to the API's base URL.
+    fn key(&self, token: &str, url: &str, query: &Query<'_>) -> Option<String> {
+        let endpoint = url.trim_start_matches('/').split(['/', '?']).next()?;
+        if !self.endpoints.iter().any(|cached| cached == endpoint) {
+            return None;
+        }
+
+        // The token is hashed so that it isn't kept in the cache as well
+        let mut hasher = DefaultHasher::new();
+        token.hash(&mut hasher);
+        let mut key = format!("{:016x}:{url}", hasher.finish());
+
+        // The query is sorted so that the key doesn't depend on its order
+        let mut query = query.iter().collect::<Vec<_>>();
+        query.sort_unstable();
+        for (name, value) in query {
+            key.push_str(&format!("&{name}={value}"));
+        }
+        Some(key)
+    }
+
+    /// The response to the given request sent with `token`, if it's cached
+    /// and hasn't expired.
+    pub(crate) fn get(&self, token: &str, url: &str, query: &Query<'_>) -> Option<String> {
+        let key = self.key(token, url, query)?;
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(&key) {
+            Some(cached) if cached.expires_at > Utc::now() => {
+                log::debug!("Using the cached response for {url}");
+                Some(cached.body.clone())
+            }
+            Some(_) => {
+                entries.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Keeps the response to the given request sent with `token`, if it may
+    /// be cached.
+    pub(crate) fn insert(&self, token: &str, url: &str, query: &Query<'_>, body: &str) {
+        let key = match self.key(token, url, query) {
+            Some(key) if self.max_entries > 0 => key,
+            _ => return,
+        };
+        let ttl = chrono::Duration::from_std(self.ttl).unwrap_or_else(|_| chrono::Duration::zero());
+        let now = Utc::now();
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            entries.retain(|_, cached| cached.expires_at > now);
+            if entries.len() >= self.max_entries {
+                let first = entries
+                    .iter()
+                    .min_by_key(|(_, cached)| cached.expires_at)
+                    .map(|(key, _)| key.clone());
+                if let Some(first) = first {
+                    entries.remove(&first);
+                }
+            }
+        }
+        entries.insert(
+            key,
+            CachedResponse {
+                body: body.to_owned(),
+                expires_at: now + ttl,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_response_cache() {
+        let cache = ResponseCache::new(Duration::from_secs(60)).with_max_entries(2);
+        let mut query = Query::new();
+        query.insert("market", "ES");
+
+        cache.insert("token", "artists/abc", &query, "artist");
+        assert_eq!(
+            cache.get("token", "artists/abc", &query).as_deref(),
+            Some("artist")
+        );
+        assert_eq!(cache.get("token", "artists/abc", &Query::new()), None);
+        assert_eq!(cache.get("token", "artists/def", &query), None);
+
+        // The responses aren't shared by different tokens
+        assert_eq!(cache.get("other-token", "artists/abc", &query), None);
+
+        // Other endpoints aren't cached
+        cache.insert("token", "me/tracks", &Query::new(), "tracks");
+        assert_eq!(cache.len(), 1);
+
+        // The oldest response is evicted
+        cache.insert("token", "tracks/abc", &Query::new(), "track");
+        cache.insert("token", "tracks/def", &Query::new(), "track");
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("token", "tracks/def", &Query::new()).is_some());
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_response_cache_expiration() {
+        let cache = ResponseCache::new(Duration::ZERO).with_endpoints(["shows"]);
+        cache.insert("token", "shows/abc/episodes", &Query::new(), "episodes");
+        assert_eq!(cache.len(), 1);
+        assert_eq!(
+            cache.get("token", "shows/abc/episodes", &Query::new()),
+            None
+        );
+        assert!(cache.is_empty());
+    }
+}
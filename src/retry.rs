@@ -0,0 +1,276 @@
+//! An optional policy to retry the requests that fail transiently.
+//!
+//! Spotify sometimes answers with a `502 Bad Gateway` or a `503 Service
+//! Unavailable` that goes away on its own a moment later. With a
+//! [`RetryPolicy`] in [`Config::retry_policy`](crate::Config::retry_policy),
+//! the requests to the Web API that fail this way are sent again after an
+//! exponential backoff:
+//!
+//! ```
+//! use rspotify::{retry::RetryPolicy, ClientCredsSpotify, Config, Credentials};
+//! use std::{sync::Arc, time::Duration};
+//!
+//! let policy = RetryPolicy::default()
+//!     .with_max_attempts(5)
+//!     .with_base_delay(Duration::from_millis(200));
+//! let config = Config {
+//!     retry_policy: Some(Arc::new(policy)),
+//!     ..Default::default()
+//! };
+//! let spotify = ClientCredsSpotify::with_config(Credentials::default(), config);
+//! ```
+//!
+//! The delay before the `n`th retry is `base_delay * 2^(n - 1)`, up to
+//! `max_delay`. With jitter, which is enabled by default, a random delay
+//! between half of it and all of it is used instead, so that many clients
+//! failing at once don't retry at the same time either. When the response
+//! has a `Retry-After` header, as the `429 Too Many Requests` ones do, its
+//! delay is used instead, since retrying earlier would fail again. If it's
+//! longer than `max_delay`, the request isn't retried and the error is
+//! returned right away, rather than blocking the client for that long.
+//!
+//! Which failures are retried is up to [`RetryPolicy::with_retry_on`]. By
+//! default, it's the `429 Too Many Requests` and the `5xx` status codes that
+//! are usually transient, for all methods. Note that a `POST` may have been
+//! applied even if it failed with a `5xx`, e.g., adding the items to a
+//! playlist twice, so the predicate may be narrowed down for those.
+//!
+//! # Waiting in async clients
+//!
+//! The sync clients simply block the thread while waiting. The async ones
+//! use the timer of Tokio by default, which is the runtime `reqwest` runs on.
+//! Another timer may be used instead with [`RetryPolicy::with_sleep`]; this
+//! is required on WebAssembly, where there's no Tokio, e.g., with
+//! `gloo_timers::future::sleep`. Without it, the requests aren't retried
+//! there, since they'd be sent again right away.
+
+use crate::http::HttpError;
+
+use std::{fmt, sync::Arc, time::Duration};
+
+use getrandom::getrandom;
+
+/// The default maximum number of attempts of each request, including the
+/// first one.
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Decides whether a failed request is retried, given its method (e.g.,
+/// `"GET"`) and error.
+pub type RetryPredicate = dyn Fn(&str, &HttpError) -> bool + Send + Sync;
+
+/// The future returned by the timer of [`RetryPolicy::with_sleep`].
+#[cfg(all(feature = "__async", not(target_arch = "wasm32")))]
+pub type SleepFuture = futures::future::BoxFuture<'static, ()>;
+/// The future returned by the timer of [`RetryPolicy::with_sleep`].
+#[cfg(all(feature = "__async", target_arch = "wasm32"))]
+pub type SleepFuture = futures::future::LocalBoxFuture<'static, ()>;
+
+/// A timer for the async clients, see [`RetryPolicy::with_sleep`].
+#[cfg(feature = "__async")]
+pub type SleepFn = dyn Fn(Duration) -> SleepFuture + Send + Sync;
+
+/// Retries the requests that fail transiently. See the
+/// [module-level documentation](self) for more information.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    retry_on: Arc<RetryPredicate>,
+    #[cfg(feature = "__async")]
+    sleep: Option<Arc<SleepFn>>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            retry_on: Arc::new(is_transient),
+            #[cfg(feature = "__async")]
+            sleep: None,
+        }
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("base_delay", &self.base_delay)
+            .field("max_delay", &self.max_delay)
+            .field("jitter", &self.jitter)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RetryPolicy {
+    /// Sets the maximum number of attempts of each request, including the
+    /// first one. With `1` or less, the requests aren't retried.
+    #[must_use]
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Sets the delay before the first retry, which is doubled for each of
+    /// the next ones. 500 milliseconds by default.
+    #[must_use]
+    pub fn with_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Sets the maximum delay between two attempts. 30 seconds by default.
+    #[must_use]
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Sets whether the delays are randomized. Enabled by default.
+    #[must_use]
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Replaces the predicate that decides which failures are retried, which
+    /// is [`is_transient`] by default.
+    #[must_use]
+    pub fn with_retry_on<F>(mut self, retry_on: F) -> Self
+    where
+        F: Fn(&str, &HttpError) -> bool + Send + Sync + 'static,
+    {
+        self.retry_on = Arc::new(retry_on);
+        self
+    }
+
+    /// Sets the timer used by the async clients to wait between attempts,
+    /// e.g., `tokio::time::sleep`.
+    #[cfg(all(feature = "__async", not(target_arch = "wasm32")))]
+    #[must_use]
+    pub fn with_sleep<F, Fut>(mut self, sleep: F) -> Self
+    where
+        F: Fn(Duration) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        self.sleep = Some(Arc::new(move |delay| Box::pin(sleep(delay))));
+        self
+    }
+
+    /// Sets the timer used by the async clients to wait between attempts,
+    /// e.g., `gloo_timers::future::sleep`.
+    #[cfg(all(feature = "__async", target_arch = "wasm32"))]
+    #[must_use]
+    pub fn with_sleep<F, Fut>(mut self, sleep: F) -> Self
+    where
+        F: Fn(Duration) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = ()> + 'static,
+    {
+        self.sleep = Some(Arc::new(move |delay| Box::pin(sleep(delay))));
+        self
+    }
+
+    /// The delay before the given retry, starting at 1, or `None` if the
+    /// failed request shouldn't be retried. It's the one in the `Retry-After`
+    /// header of the response if there's any, as long as it's within
+    /// `max_delay`.
+    pub(crate) fn delay(&self, retry: u32, method: &str, err: &HttpError) -> Option<Duration> {
+        if retry >= self.max_attempts || !(self.retry_on)(method, err) || !self.can_wait() {
+            return None;
+        }
+        match err.retry_after() {
+            Some(delay) if delay > self.max_delay => {
+                log::warn!("Not retrying, since the server asked to wait for {delay:?}");
+                None
+            }
+            Some(delay) => Some(delay),
+            None => Some(self.backoff(retry)),
+        }
+    }
+
+    /// Whether there's a timer to wait between attempts, which is missing in
+    /// the async clients on WebAssembly unless [`Self::with_sleep`] is used.
+    fn can_wait(&self) -> bool {
+        #[cfg(all(feature = "__async", target_arch = "wasm32"))]
+        if self.sleep.is_none() {
+            log::warn!("Not retrying, since there's no timer; see `RetryPolicy::with_sleep`");
+            return false;
+        }
+        true
+    }
+
+    /// The delay before the given retry, starting at 1, with jitter if it's
+    /// enabled.
+    fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        let delay = self
+            .base_delay
+            .checked_mul(factor)
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay));
+        if !self.jitter {
+            return delay;
+        }
+
+        let mut buf = [0u8; 4];
+        if getrandom(&mut buf).is_err() {
+            return delay;
+        }
+        let random = f64::from(u32::from_le_bytes(buf)) / f64::from(u32::MAX);
+        delay.mul_f64(0.5 + random / 2.0)
+    }
+
+    /// Waits for the given delay.
+    #[cfg(feature = "__sync")]
+    pub(crate) fn wait(&self, delay: Duration) {
+        std::thread::sleep(delay);
+    }
+
+    /// Waits for the given delay, with the timer of [`Self::with_sleep`] or
+    /// with the one of Tokio otherwise. On WebAssembly, [`Self::delay`] never
+    /// allows a retry without the former.
+    #[cfg(feature = "__async")]
+    pub(crate) async fn wait(&self, delay: Duration) {
+        if let Some(sleep) = &self.sleep {
+            return sleep(delay).await;
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// The default predicate of [`RetryPolicy`], which retries the requests that
+/// failed with `429 Too Many Requests`, `500 Internal Server Error`, `502 Bad
+/// Gateway`, `503 Service Unavailable` or `504 Gateway Timeout`.
+#[must_use]
+pub fn is_transient(_method: &str, err: &HttpError) -> bool {
+    matches!(err.status_code(), Some(429 | 500 | 502 | 503 | 504))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_retry_backoff() {
+        let policy = RetryPolicy::default()
+            .with_base_delay(Duration::from_secs(1))
+            .with_max_delay(Duration::from_secs(3))
+            .with_jitter(false);
+        assert_eq!(policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(3), Duration::from_secs(3));
+        assert_eq!(policy.backoff(40), Duration::from_secs(3));
+
+        let policy = policy.with_jitter(true);
+        for _ in 0..10 {
+            let delay = policy.backoff(2);
+            assert!(delay >= Duration::from_secs(1) && delay <= Duration::from_secs(2));
+        }
+    }
+}
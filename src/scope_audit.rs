@@ -0,0 +1,345 @@
+//! Runtime audit of the scopes requested in [`OAuth`](crate::OAuth), to find
+//! the ones that are never needed.
+//!
+//! Applications often ask for more scopes than they use, which makes the
+//! consent screen more intimidating than it should be. With a [`ScopeAudit`]
+//! in [`Config::scope_audit`](crate::Config::scope_audit), the client records
+//! the scopes that could be required by each endpoint it calls, and
+//! [`OAuthClient::unused_scopes`](crate::clients::OAuthClient::unused_scopes)
+//! then warns about the requested scopes that none of them needed:
+//!
+//! ```no_run
+//! # fn example() {
+//! use rspotify::{scope_audit::ScopeAudit, scopes, AuthCodeSpotify, Config, Credentials, OAuth};
+//! use rspotify::prelude::*;
+//! use std::sync::Arc;
+//!
+//! let config = Config {
+//!     scope_audit: Some(Arc::new(ScopeAudit::default())),
+//!     ..Default::default()
+//! };
+//! let oauth = OAuth {
+//!     scopes: scopes!("user-read-private", "user-library-read"),
+//!     ..Default::default()
+//! };
+//! let spotify = AuthCodeSpotify::with_config(Credentials::default(), oauth, config);
+//!
+//! // ...use the client for a while, and then, e.g., before exiting:
+//! for scope in spotify.unused_scopes().unwrap_or_default() {
+//!     println!("`{scope}` was requested but never used");
+//! }
+//! # }
+//! ```
+//!
+//! Endpoints that accept several scopes (e.g., both `playlist-modify-public`
+//! and `playlist-modify-private`) mark all of them as used, since the
+//! required one depends on the resource. The audit only covers the endpoints
+//! called during the session, so a scope may still be needed by a feature
+//! that wasn't exercised.
+//!
+//! The same table of scopes is used by
+//! [`Config::validate_scopes`](crate::Config::validate_scopes) to reject the
+//! requests that the token isn't authorized for with
+//! [`ClientError::MissingScope`](crate::ClientError::MissingScope), without
+//! sending them.
+
+use crate::{model::Scope, Config};
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    sync::Mutex,
+};
+
+/// A known endpoint of the Web API along with the scopes it may require. Path
+/// segments with `*` match any value, e.g., an ID.
+///
+/// If `required` is set, the requests fail without any of the scopes.
+/// Otherwise, they only unlock part of the response, e.g., the private
+/// playlists, so they can't be validated beforehand.
+struct EndpointScopes {
+    method: &'static str,
+    path: &'static str,
+    scopes: &'static [Scope],
+    required: bool,
+}
+
+const fn scoped(
+    method: &'static str,
+    path: &'static str,
+    scopes: &'static [Scope],
+) -> EndpointScopes {
+    EndpointScopes {
+        method,
+        path,
+        scopes,
+        required: true,
+    }
+}
+
+const fn optional(
+    method: &'static str,
+    path: &'static str,
+    scopes: &'static [Scope],
+) -> EndpointScopes {
+    EndpointScopes {
+        method,
+        path,
+        scopes,
+        required: false,
+    }
+}
+
+const LIBRARY_READ: &[Scope] = &[Scope::UserLibraryRead];
+const LIBRARY_MODIFY: &[Scope] = &[Scope::UserLibraryModify];
+const PLAYLIST_READ: &[Scope] = &[Scope::PlaylistReadPrivate, Scope::PlaylistReadCollaborative];
+const PLAYLIST_MODIFY: &[Scope] = &[Scope::PlaylistModifyPublic, Scope::PlaylistModifyPrivate];
+const PLAYBACK_READ: &[Scope] = &[Scope::UserReadPlaybackState];
+const PLAYBACK_MODIFY: &[Scope] = &[Scope::UserModifyPlaybackState];
+const PLAYBACK_POSITION: &[Scope] = &[Scope::UserReadPlaybackPosition];
+
+/// The first match wins, so the other endpoints under `me` go before the
+/// generic patterns of the library, like `me/*`.
+static ENDPOINT_SCOPES: &[EndpointScopes] = &[
+    // User profile
+    optional("GET", "me", &[Scope::UserReadPrivate, Scope::UserReadEmail]),
+    scoped("GET", "me/top/*", &[Scope::UserTopRead]),
+    // Player
+    scoped(
+        "GET",
+        "me/player/recently-played",
+        &[Scope::UserReadRecentlyPlayed],
+    ),
+    scoped(
+        "GET",
+        "me/player/currently-playing",
+        &[Scope::UserReadCurrentlyPlaying],
+    ),
+    scoped(
+        "GET",
+        "me/player/queue",
+        &[
+            Scope::UserReadPlaybackState,
+            Scope::UserReadCurrentlyPlaying,
+        ],
+    ),
+    scoped("GET", "me/player", PLAYBACK_READ),
+    scoped("GET", "me/player/devices", PLAYBACK_READ),
+    scoped("PUT", "me/player", PLAYBACK_MODIFY),
+    scoped("PUT", "me/player/*", PLAYBACK_MODIFY),
+    scoped("POST", "me/player/*", PLAYBACK_MODIFY),
+    // Follow
+    scoped("GET", "me/following", &[Scope::UserFollowRead]),
+    scoped("GET", "me/following/contains", &[Scope::UserFollowRead]),
+    scoped("PUT", "me/following", &[Scope::UserFollowModify]),
+    scoped("DELETE", "me/following", &[Scope::UserFollowModify]),
+    // Library
+    optional("GET", "me/playlists", PLAYLIST_READ),
+    scoped(
+        "GET",
+        "me/episodes",
+        &[Scope::UserLibraryRead, Scope::UserReadPlaybackPosition],
+    ),
+    scoped("GET", "me/*", LIBRARY_READ),
+    scoped("GET", "me/*/contains", LIBRARY_READ),
+    scoped("PUT", "me/*", LIBRARY_MODIFY),
+    scoped("DELETE", "me/*", LIBRARY_MODIFY),
+    // Playlists
+    optional("GET", "users/*/playlists", PLAYLIST_READ),
+    scoped("POST", "users/*/playlists", PLAYLIST_MODIFY),
+    optional("GET", "playlists/*", PLAYLIST_READ),
+    optional("GET", "playlists/*/tracks", PLAYLIST_READ),
+    optional(
+        "GET",
+        "playlists/*/followers/contains",
+        &[Scope::PlaylistReadPrivate],
+    ),
+    scoped(
+        "PUT",
+        "playlists/*/images",
+        &[
+            Scope::UgcImageUpload,
+            Scope::PlaylistModifyPublic,
+            Scope::PlaylistModifyPrivate,
+        ],
+    ),
+    scoped("PUT", "playlists/*", PLAYLIST_MODIFY),
+    scoped("PUT", "playlists/*/*", PLAYLIST_MODIFY),
+    scoped("POST", "playlists/*/tracks", PLAYLIST_MODIFY),
+    scoped("DELETE", "playlists/*/*", PLAYLIST_MODIFY),
+    // Shows and episodes, with the resume points of the user
+    optional("GET", "episodes", PLAYBACK_POSITION),
+    optional("GET", "episodes/*", PLAYBACK_POSITION),
+    optional("GET", "shows/*/episodes", PLAYBACK_POSITION),
+];
+
+/// Returns the scopes that may be required by the given request, where `url`
+/// is relative to [`Config::api_base_url`], e.g., `"me/tracks?limit=20"`.
+/// It's empty for the endpoints that don't need any.
+#[must_use]
+pub fn required_scopes(method: &str, url: &str) -> &'static [Scope] {
+    find_endpoint(method, url).map_or(&[], |endpoint| endpoint.scopes)
+}
+
+/// Returns the scope that the given request needs but isn't among the
+/// `granted` ones, or `None` if it can be sent. When any of several scopes
+/// would do, the first one is returned. The endpoints whose scopes only
+/// unlock part of the response are never rejected.
+#[must_use]
+pub fn missing_scope(method: &str, url: &str, granted: &HashSet<String>) -> Option<Scope> {
+    let endpoint = find_endpoint(method, url).filter(|endpoint| endpoint.required)?;
+    if endpoint
+        .scopes
+        .iter()
+        .any(|scope| granted.contains(scope.as_ref()))
+    {
+        return None;
+    }
+    endpoint.scopes.first().copied()
+}
+
+fn find_endpoint(method: &str, url: &str) -> Option<&'static EndpointScopes> {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+
+    ENDPOINT_SCOPES
+        .iter()
+        .find(|endpoint| endpoint.method == method && matches_path(endpoint.path, &segments))
+}
+
+fn matches_path(pattern: &str, segments: &[&str]) -> bool {
+    let pattern = pattern.split('/').collect::<Vec<_>>();
+    pattern.len() == segments.len()
+        && pattern
+            .iter()
+            .zip(segments)
+            .all(|(expected, segment)| *expected == "*" || expected == segment)
+}
+
+/// Records the scopes that may be required by the requests sent by a client.
+/// It's shared by cloning the [`Arc`](std::sync::Arc) it's stored in, so that
+/// multiple clients may contribute to the same audit.
+#[derive(Debug, Default)]
+pub struct ScopeAudit {
+    used: Mutex<HashSet<Scope>>,
+}
+
+impl ScopeAudit {
+    /// Marks the scopes of the given request as used.
+    pub fn record(&self, method: &str, url: &str) {
+        let scopes = required_scopes(method, url);
+        if !scopes.is_empty() {
+            self.used.lock().unwrap().extend(scopes);
+        }
+    }
+
+    /// The scopes needed so far, sorted alphabetically.
+    #[must_use]
+    pub fn used_scopes(&self) -> Vec<String> {
+        let used = self.used.lock().unwrap();
+        let sorted = used.iter().map(Scope::as_ref).collect::<BTreeSet<_>>();
+        sorted.into_iter().map(str::to_owned).collect()
+    }
+
+    /// The scopes out of `requested` that haven't been needed so far, sorted
+    /// alphabetically.
+    #[must_use]
+    pub fn unused_scopes(&self, requested: &HashSet<String>) -> Vec<String> {
+        let used = self.used.lock().unwrap();
+        let unused = requested
+            .iter()
+            .filter(|scope| match scope.parse::<Scope>() {
+                Ok(scope) => !used.contains(&scope),
+                Err(_) => true,
+            })
+            .collect::<BTreeSet<_>>();
+        unused.into_iter().cloned().collect()
+    }
+
+    /// Forgets the scopes recorded so far.
+    pub fn reset(&self) {
+        self.used.lock().unwrap().clear();
+    }
+}
+
+/// Records the request in the audit of the configuration, if enabled.
+pub(crate) fn record(config: &Config, method: &str, url: &str) {
+    if let Some(audit) = &config.scope_audit {
+        audit.record(method, url);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_required_scopes() {
+        assert_eq!(required_scopes("GET", "me/tracks/?limit=20"), LIBRARY_READ);
+        assert_eq!(required_scopes("GET", "me/shows/contains"), LIBRARY_READ);
+        assert_eq!(required_scopes("PUT", "me/albums"), LIBRARY_MODIFY);
+        assert_eq!(
+            required_scopes("PUT", "me/following?type=artist"),
+            &[Scope::UserFollowModify]
+        );
+        assert_eq!(required_scopes("GET", "me/player"), PLAYBACK_READ);
+        assert_eq!(
+            required_scopes("PUT", "me/player/play?device_id=abc"),
+            PLAYBACK_MODIFY
+        );
+        assert_eq!(required_scopes("POST", "me/player/queue"), PLAYBACK_MODIFY);
+        assert_eq!(required_scopes("GET", "me/playlists"), PLAYLIST_READ);
+        assert_eq!(
+            required_scopes("DELETE", "playlists/abc/tracks"),
+            PLAYLIST_MODIFY
+        );
+        assert_eq!(
+            required_scopes("GET", "shows/abc/episodes"),
+            PLAYBACK_POSITION
+        );
+        assert!(required_scopes("GET", "tracks/abc").is_empty());
+        assert!(required_scopes("GET", "search?q=abc").is_empty());
+    }
+
+    #[test]
+    fn test_missing_scope() {
+        let granted = ["user-library-read", "playlist-modify-private"]
+            .iter()
+            .map(|scope| (*scope).to_owned())
+            .collect::<HashSet<_>>();
+        assert_eq!(missing_scope("GET", "me/tracks", &granted), None);
+        assert_eq!(
+            missing_scope("PUT", "me/tracks", &granted),
+            Some(Scope::UserLibraryModify)
+        );
+        assert_eq!(
+            missing_scope("POST", "playlists/abc/tracks", &granted),
+            None
+        );
+        assert_eq!(
+            missing_scope("PUT", "me/player/pause", &granted),
+            Some(Scope::UserModifyPlaybackState)
+        );
+        assert_eq!(missing_scope("GET", "playlists/abc", &granted), None);
+        assert_eq!(missing_scope("GET", "tracks/abc", &granted), None);
+    }
+
+    #[test]
+    fn test_unused_scopes() {
+        let audit = ScopeAudit::default();
+        audit.record("GET", "me/tracks");
+        audit.record("GET", "artists/abc");
+
+        let requested = ["user-library-read", "user-top-read", "user-read-email"]
+            .iter()
+            .map(|scope| (*scope).to_owned())
+            .collect();
+        assert_eq!(
+            audit.unused_scopes(&requested),
+            vec!["user-read-email", "user-top-read"]
+        );
+        assert_eq!(audit.used_scopes(), vec!["user-library-read"]);
+
+        audit.reset();
+        assert_eq!(audit.unused_scopes(&requested).len(), 3);
+    }
+}
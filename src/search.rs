@@ -0,0 +1,216 @@
+//! Builder for the queries of [`BaseClient::search`].
+//!
+//! Spotify's search queries are plain strings with a small syntax of their
+//! own: the keywords may be narrowed down with field filters like
+//! `artist:"Daft Punk"` or `year:2000-2010`, which may in turn be negated with
+//! `NOT` or combined with `OR`. [`SearchQuery`] takes care of quoting the
+//! values and joining everything together:
+//!
+//! ```
+//! use rspotify::search::{SearchFilter, SearchQuery};
+//!
+//! let query = SearchQuery::new("one more time")
+//!     .artist("Daft Punk")
+//!     .exclude(SearchFilter::Album, "Alive 2007")
+//!     .years(2000, 2010);
+//! assert_eq!(
+//!     query.to_string(),
+//!     r#"one more time artist:"Daft Punk" NOT album:"Alive 2007" year:2000-2010"#
+//! );
+//! ```
+//!
+//! The query is then passed to [`BaseClient::search_query`], or to any of the
+//! other search methods after converting it with [`ToString`].
+//!
+//! [`BaseClient::search`]: crate::clients::BaseClient::search
+//! [`BaseClient::search_query`]: crate::clients::BaseClient::search_query
+
+use std::fmt;
+
+/// The fields that the search results may be filtered by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SearchFilter {
+    Album,
+    Artist,
+    Track,
+    Year,
+    Genre,
+    Isrc,
+    Upc,
+    Tag,
+}
+
+impl SearchFilter {
+    /// The name of the field in the query.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Album => "album",
+            Self::Artist => "artist",
+            Self::Track => "track",
+            Self::Year => "year",
+            Self::Genre => "genre",
+            Self::Isrc => "isrc",
+            Self::Upc => "upc",
+            Self::Tag => "tag",
+        }
+    }
+}
+
+/// A search query, built from its keywords and filters. See the
+/// [module-level documentation](self) for more information.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    keywords: String,
+    filters: Vec<String>,
+}
+
+impl SearchQuery {
+    /// Builds a query for the given keywords, which may be empty when
+    /// searching by the filters alone.
+    #[must_use]
+    pub fn new(keywords: &str) -> Self {
+        Self {
+            keywords: keywords.trim().to_owned(),
+            filters: Vec::new(),
+        }
+    }
+
+    /// Only returns the items whose field matches the value.
+    #[must_use]
+    pub fn filter(mut self, filter: SearchFilter, value: &str) -> Self {
+        self.filters.push(field(filter, value));
+        self
+    }
+
+    /// Leaves out the items whose field matches the value.
+    #[must_use]
+    pub fn exclude(mut self, filter: SearchFilter, value: &str) -> Self {
+        self.filters.push(format!("NOT {}", field(filter, value)));
+        self
+    }
+
+    /// Only returns the items whose field matches any of the values. Nothing
+    /// is added if there are no values.
+    #[must_use]
+    pub fn any_of<'a>(
+        mut self,
+        filter: SearchFilter,
+        values: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        let values = values
+            .into_iter()
+            .map(|value| field(filter, value))
+            .collect::<Vec<_>>();
+        if !values.is_empty() {
+            self.filters.push(values.join(" OR "));
+        }
+        self
+    }
+
+    /// Shorthand for [`Self::filter`] with [`SearchFilter::Album`].
+    #[must_use]
+    pub fn album(self, album: &str) -> Self {
+        self.filter(SearchFilter::Album, album)
+    }
+
+    /// Shorthand for [`Self::filter`] with [`SearchFilter::Artist`].
+    #[must_use]
+    pub fn artist(self, artist: &str) -> Self {
+        self.filter(SearchFilter::Artist, artist)
+    }
+
+    /// Shorthand for [`Self::filter`] with [`SearchFilter::Track`].
+    #[must_use]
+    pub fn track(self, track: &str) -> Self {
+        self.filter(SearchFilter::Track, track)
+    }
+
+    /// Shorthand for [`Self::filter`] with [`SearchFilter::Genre`].
+    #[must_use]
+    pub fn genre(self, genre: &str) -> Self {
+        self.filter(SearchFilter::Genre, genre)
+    }
+
+    /// Only returns the items released in the given year.
+    #[must_use]
+    pub fn year(self, year: u16) -> Self {
+        self.filter(SearchFilter::Year, &year.to_string())
+    }
+
+    /// Only returns the items released between the given years, both
+    /// included.
+    #[must_use]
+    pub fn years(self, from: u16, to: u16) -> Self {
+        self.filter(SearchFilter::Year, &format!("{from}-{to}"))
+    }
+
+    /// Only returns the albums released in the past two weeks.
+    #[must_use]
+    pub fn tag_new(self) -> Self {
+        self.filter(SearchFilter::Tag, "new")
+    }
+
+    /// Only returns the albums with the lowest 10% popularity.
+    #[must_use]
+    pub fn tag_hipster(self) -> Self {
+        self.filter(SearchFilter::Tag, "hipster")
+    }
+}
+
+impl fmt::Display for SearchQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts = std::iter::once(&self.keywords)
+            .filter(|keywords| !keywords.is_empty())
+            .chain(&self.filters);
+        for (i, part) in parts.enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            f.write_str(part)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<SearchQuery> for String {
+    fn from(query: SearchQuery) -> Self {
+        query.to_string()
+    }
+}
+
+/// Formats a filter, quoting the values with spaces. Quotes can't be escaped
+/// in the queries, so they're removed from the value.
+fn field(filter: SearchFilter, value: &str) -> String {
+    let value = value.trim().replace('"', "");
+    if value.contains(char::is_whitespace) {
+        format!("{}:\"{value}\"", filter.as_str())
+    } else {
+        format!("{}:{value}", filter.as_str())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_search_query() {
+        assert_eq!(SearchQuery::default().to_string(), "");
+        assert_eq!(SearchQuery::new(" daft punk ").to_string(), "daft punk");
+
+        let query = SearchQuery::new("")
+            .any_of(SearchFilter::Artist, ["Daft Punk", "Justice"])
+            .exclude(SearchFilter::Genre, "french house")
+            .album("Say \"Cheese\"")
+            .year(2007)
+            .tag_new();
+        assert_eq!(
+            query.to_string(),
+            r#"artist:"Daft Punk" OR artist:Justice NOT genre:"french house" album:"Say Cheese" year:2007 tag:new"#
+        );
+
+        let query = SearchQuery::new("time").any_of(SearchFilter::Track, []);
+        assert_eq!(String::from(query), "time");
+    }
+}
@@ -0,0 +1,227 @@
+//! Search-as-you-type helper for interactive applications, available with the
+//! asynchronous clients.
+//!
+//! Searching on every keystroke floods the API with requests whose results
+//! are obsolete by the time they arrive, and they may even arrive out of
+//! order. [`SearchSession`] waits until the user stops typing for a while
+//! before searching, and cancels the pending search as soon as a newer query
+//! comes in, so that only the results of the latest one are returned.
+//!
+//! ```no_run
+//! # async fn example(spotify: rspotify::ClientCredsSpotify) {
+//! use rspotify::{model::SearchType, search_session::SearchSession};
+//! use std::time::Duration;
+//!
+//! let session = SearchSession::new(spotify, SearchType::Track, Duration::from_millis(300));
+//!
+//! // Usually called from a spawned task every time the input changes
+//! match session.search("daft pu").await {
+//!     Ok(Some(results)) => println!("{results:?}"),
+//!     // A newer query superseded this one
+//!     Ok(None) => {}
+//!     Err(err) => eprintln!("search failed: {err}"),
+//! }
+//! # }
+//! ```
+//!
+//! It waits with the timer of Tokio. Other timers may be used instead with
+//! [`SearchSession::with_sleep`], which is required on WebAssembly.
+
+use crate::{
+    clients::BaseClient,
+    model::{IncludeExternal, Market, SearchResult, SearchType},
+    ClientResult,
+};
+
+use std::{fmt, future::Future, sync::Mutex, time::Duration};
+
+use futures::future::{AbortHandle, Abortable};
+
+/// Debounces successive search queries, cancelling the superseded ones.
+///
+/// `S` is the timer used to wait, given by [`SearchSession::with_sleep`], or
+/// the one of Tokio with [`SearchSession::new`].
+pub struct SearchSession<C, S> {
+    /// The type of items to search for.
+    pub search_type: SearchType,
+    /// The market passed to [`BaseClient::search`].
+    pub market: Option<Market>,
+    /// Whether to include externally hosted audio content.
+    pub include_external: Option<IncludeExternal>,
+    /// The number of items to return for each search.
+    pub limit: Option<u32>,
+    /// How long to wait for newer queries before actually searching.
+    pub debounce: Duration,
+    client: C,
+    sleep: S,
+    in_flight: Mutex<Option<AbortHandle>>,
+}
+
+impl<C: fmt::Debug, S> fmt::Debug for SearchSession<C, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SearchSession")
+            .field("search_type", &self.search_type)
+            .field("market", &self.market)
+            .field("include_external", &self.include_external)
+            .field("limit", &self.limit)
+            .field("debounce", &self.debounce)
+            .field("client", &self.client)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<C: BaseClient> SearchSession<C, fn(Duration) -> tokio::time::Sleep> {
+    /// Builds a new session that searches for the given type of items with
+    /// the client, waiting `debounce` with the timer of Tokio first.
+    #[must_use]
+    pub fn new(client: C, search_type: SearchType, debounce: Duration) -> Self {
+        Self::with_sleep(client, search_type, debounce, tokio::time::sleep)
+    }
+}
+
+impl<C, S, F> SearchSession<C, S>
+where
+    C: BaseClient,
+    S: Fn(Duration) -> F,
+    F: Future<Output = ()>,
+{
+    /// Same as [`SearchSession::new`], but waiting with the `sleep` function,
+    /// e.g., `async_std::task::sleep` or, on WebAssembly,
+    /// `gloo_timers::future::sleep`.
+    #[must_use]
+    pub fn with_sleep(client: C, search_type: SearchType, debounce: Duration, sleep: S) -> Self {
+        Self {
+            search_type,
+            market: None,
+            include_external: None,
+            limit: None,
+            debounce,
+            client,
+            sleep,
+            in_flight: Mutex::new(None),
+        }
+    }
+
+    /// The client used to search.
+    #[must_use]
+    pub fn client(&self) -> &C {
+        &self.client
+    }
+
+    /// Searches for the given query once no newer query has been received for
+    /// the configured debounce time.
+    ///
+    /// Calling this cancels the previous search if it's still in progress,
+    /// which then returns `Ok(None)`. The same happens for blank queries, so
+    /// that clearing the input discards the pending results.
+    pub async fn search(&self, query: &str) -> ClientResult<Option<SearchResult>> {
+        let query = query.trim();
+        if query.is_empty() {
+            self.cancel();
+            return Ok(None);
+        }
+
+        let request = async {
+            (self.sleep)(self.debounce).await;
+            self.client
+                .search(
+                    query,
+                    self.search_type,
+                    self.market,
+                    self.include_external,
+                    self.limit,
+                    None,
+                )
+                .await
+        };
+
+        match self.supersede(request).await {
+            Some(result) => result.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Cancels the search in progress, if any.
+    pub fn cancel(&self) {
+        if let Some(handle) = self.in_flight.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+
+    /// Runs the given future after cancelling the previous one. Returns `None`
+    /// if it's cancelled by a newer call as well.
+    async fn supersede<T>(&self, request: impl Future<Output = T>) -> Option<T> {
+        let (handle, registration) = AbortHandle::new_pair();
+        if let Some(previous) = self.in_flight.lock().unwrap().replace(handle) {
+            previous.abort();
+        }
+
+        Abortable::new(request, registration).await.ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ClientCredsSpotify;
+    use futures::future::{join, ready, Ready};
+    use std::{
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    /// A timer that completes on its second poll, which is enough to let a
+    /// newer search start in the meantime.
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                Poll::Ready(())
+            } else {
+                self.0 = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    fn no_sleep(_: Duration) -> Ready<()> {
+        ready(())
+    }
+
+    #[tokio::test]
+    async fn test_supersede() {
+        let session = SearchSession::with_sleep(
+            ClientCredsSpotify::default(),
+            SearchType::Track,
+            Duration::ZERO,
+            no_sleep,
+        );
+
+        let old = session.supersede(async {
+            YieldOnce(false).await;
+            "old"
+        });
+        let new = session.supersede(async {
+            YieldOnce(false).await;
+            "new"
+        });
+        assert_eq!(join(old, new).await, (None, Some("new")));
+
+        // Blank queries cancel the search without making any requests
+        let (old, blank) = join(
+            session.supersede(async {
+                YieldOnce(false).await;
+                "old"
+            }),
+            session.search("  "),
+        )
+        .await;
+        assert_eq!(old, None);
+        assert!(blank.unwrap().is_none());
+    }
+}
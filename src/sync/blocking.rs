@@ -1 +1,10 @@
 pub use std::sync::Mutex;
+
+/// Runs a blocking operation, which is done in place in synchronous mode.
+pub fn run_blocking<T, F>(f: F) -> T
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    f()
+}
@@ -14,3 +14,23 @@ impl<T> Mutex<T> {
         Ok(val)
     }
 }
+
+/// Runs a blocking operation, like file I/O, in Tokio's thread pool for
+/// blocking tasks, so that the executor isn't blocked meanwhile. On
+/// WebAssembly, which has no threads, it's run in place.
+pub async fn run_blocking<T, F>(f: F) -> T
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        tokio::task::spawn_blocking(f)
+            .await
+            .unwrap_or_else(|err| std::panic::resume_unwind(err.into_panic()))
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        f()
+    }
+}
@@ -14,3 +14,7 @@ use self::futures as imp;
 /// A type alias for either an asynchronous mutex or [`std::sync::Mutex`], depending on whether
 /// this library is compiled in asynchronous or synchronous mode.
 pub type Mutex<T> = imp::Mutex<T>;
+
+/// Runs a blocking operation without blocking the executor in asynchronous
+/// mode.
+pub(crate) use self::imp::run_blocking;
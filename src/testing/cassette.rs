@@ -0,0 +1,242 @@
+//! Recorded responses from the Web API, replayed to run the tests offline.
+//!
+//! A [`Cassette`] is the list of the requests made by a test, along with the
+//! responses Spotify sent back. They're recorded against the real API by
+//! adding a [`CassetteRecorder`] to [`Config::interceptors`], and saved as a
+//! JSON file:
+//!
+//! ```no_run
+//! use rspotify::{
+//!     http::RequestInterceptor, testing::cassette::CassetteRecorder, ClientCredsSpotify,
+//!     Config, Credentials,
+//! };
+//! use std::sync::Arc;
+//!
+//! let recorder = Arc::new(CassetteRecorder::default());
+//! let config = Config {
+//!     interceptors: vec![Arc::clone(&recorder) as Arc<dyn RequestInterceptor>],
+//!     ..Default::default()
+//! };
+//! let spotify = ClientCredsSpotify::with_config(Credentials::from_env().unwrap(), config);
+//! // ...request the token and call the endpoints under test
+//!
+//! recorder.save("tests/cassettes/album.json").unwrap();
+//! ```
+//!
+//! The cassette is then replayed by loading it into the offline client with
+//! [`Cassette::load_into`], so that the same test runs without credentials nor
+//! network access. This also makes it easy to contribute the payloads that
+//! break the models as regression tests.
+//!
+//! Only the requests to the Web API are recorded, so that the tokens from the
+//! authentication endpoints don't end up in the cassettes. The headers aren't
+//! recorded either.
+//!
+//! [`Config::interceptors`]: crate::Config::interceptors
+
+use crate::{
+    http::{InterceptedResponse, MockHttpClient, MockResponse, RequestInterceptor},
+    ClientResult, DEFAULT_API_BASE_URL,
+};
+
+use std::{collections::BTreeMap, fs, path::Path, sync::Mutex};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A request made to the Web API, and the response it got.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Interaction {
+    /// The HTTP method, e.g., `GET`.
+    pub method: String,
+    /// The full URL, without the query parameters of `GET` requests.
+    pub url: String,
+    /// The query parameters of `GET` requests, which have to be the same for
+    /// the response to be replayed.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub query: BTreeMap<String, String>,
+    /// The status code of the response.
+    pub status: u16,
+    /// The body of the response. It's saved as JSON when possible so that
+    /// the cassettes are readable, or as a string otherwise. It's empty for
+    /// the unsuccessful responses, whose body isn't available to the
+    /// recorder.
+    pub body: Value,
+}
+
+impl Interaction {
+    /// The body of the response, as it was received.
+    #[must_use]
+    pub fn raw_body(&self) -> String {
+        match &self.body {
+            Value::String(body) => body.clone(),
+            body => body.to_string(),
+        }
+    }
+}
+
+/// The interactions of a test, in order.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Reads a cassette from a JSON file.
+    pub fn load<P: AsRef<Path>>(path: P) -> ClientResult<Self> {
+        let contents = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// Writes the cassette into a JSON file, creating its parent directory
+    /// if needed.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> ClientResult<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut contents = serde_json::to_string_pretty(self)?;
+        contents.push('\n');
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Registers the responses of the cassette in the offline client, in the
+    /// same order they were recorded. The responses to `GET` requests are
+    /// only replayed for the same query parameters.
+    pub fn load_into(&self, http: &MockHttpClient) {
+        for interaction in &self.interactions {
+            let response = MockResponse::with_status(interaction.status, interaction.raw_body());
+            if interaction.method == "GET" {
+                let query = interaction
+                    .query
+                    .iter()
+                    .map(|(key, value)| (key.as_str(), value.as_str()));
+                http.respond_with_query("GET", &interaction.url, query, response);
+            } else {
+                http.respond(&interaction.method, &interaction.url, response);
+            }
+        }
+    }
+}
+
+/// A [`RequestInterceptor`] that records the interactions with the Web API
+/// into a [`Cassette`].
+#[derive(Debug)]
+pub struct CassetteRecorder {
+    api_base_url: String,
+    cassette: Mutex<Cassette>,
+}
+
+impl Default for CassetteRecorder {
+    fn default() -> Self {
+        Self::new(DEFAULT_API_BASE_URL)
+    }
+}
+
+impl CassetteRecorder {
+    /// Builds a recorder for the requests to the given API base URL, which
+    /// should be the same as [`Config::api_base_url`](crate::Config::api_base_url).
+    #[must_use]
+    pub fn new(api_base_url: &str) -> Self {
+        Self {
+            api_base_url: api_base_url.to_owned(),
+            cassette: Mutex::default(),
+        }
+    }
+
+    /// The interactions recorded so far.
+    #[must_use]
+    pub fn cassette(&self) -> Cassette {
+        self.cassette.lock().unwrap().clone()
+    }
+
+    /// Writes the interactions recorded so far into a JSON file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> ClientResult<()> {
+        self.cassette().save(path)
+    }
+}
+
+impl RequestInterceptor for CassetteRecorder {
+    fn after_response(&self, method: &str, url: &str, response: &InterceptedResponse<'_>) {
+        if !url.starts_with(&self.api_base_url) {
+            return;
+        }
+        let status = match response.status {
+            Some(status) => status,
+            // The request couldn't be completed, so there's nothing to replay
+            None => return,
+        };
+
+        // The query of `GET` requests is kept apart so that it's matched
+        // regardless of the order of the parameters
+        let mut url = url.to_owned();
+        let mut query = BTreeMap::new();
+        if method == "GET" {
+            if let Ok(mut parsed) = url::Url::parse(&url) {
+                query = parsed.query_pairs().into_owned().collect();
+                parsed.set_query(None);
+                url = parsed.into();
+            }
+        }
+
+        let body = response.body.unwrap_or_default();
+        let body = serde_json::from_str(body).unwrap_or_else(|_| Value::from(body));
+        let interaction = Interaction {
+            method: method.to_owned(),
+            url,
+            query,
+            status,
+            body,
+        };
+        self.cassette.lock().unwrap().interactions.push(interaction);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cassette_recorder() {
+        let recorder = CassetteRecorder::default();
+        let response = InterceptedResponse {
+            status: Some(200),
+            body: Some(r#"{"id": "abc"}"#),
+            elapsed: None,
+        };
+        recorder.after_response(
+            "GET",
+            "https://api.spotify.com/v1/tracks/abc?market=ES",
+            &response,
+        );
+        // The authentication requests are left out
+        recorder.after_response("POST", "https://accounts.spotify.com/api/token", &response);
+        let response = InterceptedResponse {
+            status: Some(204),
+            body: Some(""),
+            elapsed: None,
+        };
+        recorder.after_response(
+            "PUT",
+            "https://api.spotify.com/v1/me/player/pause",
+            &response,
+        );
+
+        let cassette = recorder.cassette();
+        assert_eq!(cassette.interactions.len(), 2);
+        assert_eq!(
+            cassette.interactions[0].url,
+            "https://api.spotify.com/v1/tracks/abc"
+        );
+        assert_eq!(cassette.interactions[0].query["market"], "ES");
+        assert_eq!(cassette.interactions[0].body["id"], "abc");
+        assert_eq!(cassette.interactions[0].raw_body(), r#"{"id":"abc"}"#);
+        assert_eq!(cassette.interactions[1].raw_body(), "");
+
+        let path = std::env::temp_dir().join("rspotify_test_cassette.json");
+        cassette.save(&path).unwrap();
+        assert_eq!(Cassette::load(&path).unwrap(), cassette);
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,5 @@
+//! Utilities to test the code that uses RSpotify, which are also used by the
+//! test suite of RSpotify itself. They're only available with the `testing`
+//! feature, which also enables `client-mock`.
+
+pub mod cassette;
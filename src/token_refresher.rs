@@ -0,0 +1,223 @@
+//! Proactive refreshing of the token for long-running applications.
+//!
+//! With [`Config::token_refreshing`](crate::Config::token_refreshing), an
+//! expired token is refreshed right before the next request, which then takes
+//! noticeably longer. Daemons that send requests sporadically may instead run
+//! a refresher in the background, which refreshes the token a margin before
+//! it expires.
+//!
+//! With the asynchronous clients, [`refresh_in_background`] returns a future
+//! that has to be spawned, and which waits with the timer of Tokio. Other
+//! timers may be used instead with [`refresh_in_background_with_sleep`],
+//! which is required on WebAssembly. With the synchronous clients, [`spawn`]
+//! starts a thread instead.
+//!
+//! The refresher stops by itself if the token can't be refreshed at all,
+//! e.g., because there's no refresh token. Failed refreshes are logged and
+//! retried after [`RETRY_DELAY`]. The margin is at most half the lifetime of
+//! the token, since a longer one would refresh it over and over.
+
+use crate::{clients::BaseClient, ClientResult, Token};
+
+use std::time::Duration;
+
+use chrono::Utc;
+use maybe_async::maybe_async;
+
+/// How long to wait after a refresh before checking the token again, or to
+/// retry a failed one.
+pub const RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// How long to wait before checking the token again when there's none yet.
+pub const NO_TOKEN_DELAY: Duration = Duration::from_secs(60);
+
+/// How long to wait until the token has to be refreshed, which is `None` if
+/// there's no token with an expiration date.
+fn refresh_delay(token: Option<&Token>, margin: Duration) -> Option<Duration> {
+    let token = token?;
+    let expires_at = token.expires_at?;
+    let mut margin =
+        chrono::Duration::from_std(margin).unwrap_or_else(|_| chrono::Duration::zero());
+    // Otherwise, a new token would be within the margin right away
+    if token.expires_in > chrono::Duration::zero() {
+        margin = margin.min(token.expires_in / 2);
+    }
+    let remaining = expires_at - margin - Utc::now();
+    Some(remaining.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// What the refresher should do next.
+enum Step {
+    Wait(Duration),
+    Stop,
+}
+
+/// Refreshes the token if it's due, and returns how long to wait until the
+/// next check.
+#[maybe_async]
+async fn step<C: BaseClient>(client: &C, margin: Duration) -> Step {
+    // The token can't be left locked while refreshing it
+    let delay = {
+        let token = client.get_token();
+        let token = token.lock().await.unwrap();
+        refresh_delay(token.as_ref(), margin)
+    };
+
+    match delay {
+        None => Step::Wait(NO_TOKEN_DELAY),
+        Some(delay) if delay > Duration::ZERO => Step::Wait(delay),
+        Some(_) => match refresh(client).await {
+            // Also waiting after a refresh, in case the new token expires
+            // within the margin as well
+            Ok(true) => Step::Wait(RETRY_DELAY),
+            Ok(false) => {
+                log::warn!("The token can't be refreshed, stopping the background refresher");
+                Step::Stop
+            }
+            Err(err) => {
+                log::warn!("Couldn't refresh the token in the background: {err}");
+                Step::Wait(RETRY_DELAY)
+            }
+        },
+    }
+}
+
+/// Same as [`BaseClient::refresh_token`], except that the current token is
+/// kept if there's no new one. Returns whether it was refreshed.
+#[maybe_async]
+async fn refresh<C: BaseClient>(client: &C) -> ClientResult<bool> {
+    let token = match client.refetch_token_with_hooks().await? {
+        Some(token) => token,
+        None => return Ok(false),
+    };
+    *client.get_token().lock().await.unwrap() = Some(token);
+    client.write_token_cache().await?;
+    Ok(true)
+}
+
+/// Refreshes the token of the client `margin` before it expires, forever,
+/// waiting with the timer of Tokio. The client should be a clone of the one
+/// used for the requests, which shares its token.
+///
+/// The returned future only completes if the token can't be refreshed, so it
+/// should be spawned as a task and aborted once it's no longer needed:
+///
+/// ```no_run
+/// # async fn example(spotify: rspotify::AuthCodeSpotify) {
+/// use rspotify::token_refresher;
+/// use std::time::Duration;
+///
+/// let refresher =
+///     token_refresher::refresh_in_background(spotify.clone(), Duration::from_secs(60));
+/// let task = tokio::spawn(refresher);
+///
+/// // ...and once the client isn't needed anymore
+/// task.abort();
+/// # }
+/// ```
+#[cfg(all(feature = "__async", not(target_arch = "wasm32")))]
+pub async fn refresh_in_background<C: BaseClient>(client: C, margin: Duration) {
+    refresh_in_background_with_sleep(client, margin, tokio::time::sleep).await;
+}
+
+/// Same as [`refresh_in_background`], but waiting with the `sleep` function,
+/// e.g., `async_std::task::sleep` or, on WebAssembly,
+/// `gloo_timers::future::sleep`.
+#[cfg(feature = "__async")]
+pub async fn refresh_in_background_with_sleep<C, S, F>(client: C, margin: Duration, sleep: S)
+where
+    C: BaseClient,
+    S: Fn(Duration) -> F,
+    F: std::future::Future<Output = ()>,
+{
+    loop {
+        match step(&client, margin).await {
+            Step::Wait(delay) => sleep(delay).await,
+            Step::Stop => return,
+        }
+    }
+}
+
+/// The refresher thread started by [`spawn`].
+#[cfg(feature = "__sync")]
+#[derive(Debug)]
+pub struct RefresherHandle {
+    stop: std::sync::mpsc::Sender<()>,
+    thread: std::thread::JoinHandle<()>,
+}
+
+#[cfg(feature = "__sync")]
+impl RefresherHandle {
+    /// Stops the refresher and waits for its thread to finish.
+    pub fn stop(self) {
+        drop(self.stop);
+        let _ = self.thread.join();
+    }
+}
+
+/// Starts a thread that refreshes the token of the client `margin` before it
+/// expires. The client should be a clone of the one used for the requests,
+/// which shares its token.
+///
+/// The thread stops with [`RefresherHandle::stop`] or when the handle is
+/// dropped:
+///
+/// ```no_run
+/// # fn example(spotify: rspotify::AuthCodeSpotify) {
+/// use rspotify::token_refresher;
+/// use std::time::Duration;
+///
+/// let refresher = token_refresher::spawn(spotify.clone(), Duration::from_secs(60));
+/// // ...
+/// refresher.stop();
+/// # }
+/// ```
+#[cfg(feature = "__sync")]
+pub fn spawn<C: BaseClient + 'static>(client: C, margin: Duration) -> RefresherHandle {
+    use std::sync::mpsc::{self, RecvTimeoutError};
+
+    let (stop, stopped) = mpsc::channel::<()>();
+    let thread = std::thread::spawn(move || loop {
+        let delay = match step(&client, margin) {
+            Step::Wait(delay) => delay,
+            Step::Stop => return,
+        };
+        // Both a message and the sender being dropped stop the thread
+        if let Err(RecvTimeoutError::Disconnected) | Ok(()) = stopped.recv_timeout(delay) {
+            return;
+        }
+    });
+
+    RefresherHandle { stop, thread }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_refresh_delay() {
+        let margin = Duration::from_secs(60);
+        assert_eq!(refresh_delay(None, margin), None);
+
+        let mut token = Token {
+            expires_at: None,
+            ..Default::default()
+        };
+        assert_eq!(refresh_delay(Some(&token), margin), None);
+
+        token.expires_at = Some(Utc::now() + chrono::Duration::try_minutes(10).unwrap());
+        let delay = refresh_delay(Some(&token), margin).unwrap();
+        assert!(delay > Duration::from_secs(530) && delay <= Duration::from_secs(540));
+
+        // Already within the margin
+        token.expires_at = Some(Utc::now() + chrono::Duration::try_seconds(30).unwrap());
+        assert_eq!(refresh_delay(Some(&token), margin), Some(Duration::ZERO));
+
+        // The margin is at most half the lifetime of the token
+        token.expires_in = chrono::Duration::try_minutes(10).unwrap();
+        token.expires_at = Some(Utc::now() + token.expires_in);
+        let delay = refresh_delay(Some(&token), Duration::from_secs(60 * 60)).unwrap();
+        assert!(delay > Duration::from_secs(290) && delay <= Duration::from_secs(300));
+    }
+}
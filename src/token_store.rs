@@ -0,0 +1,209 @@
+//! Persistence of the access token between sessions.
+//!
+//! The clients don't read or write the token cache directly; they go through
+//! the [`TokenStore`] configured in [`Config::token_store`]. By default the
+//! token is saved as JSON in [`Config::cache_path`] with [`FileTokenStore`],
+//! but web applications may implement the trait for anything else, like a
+//! database or Redis, without having to rewrite the refreshing logic.
+//!
+//! With the `keyring` feature, [`KeyringTokenStore`] keeps the token in the
+//! credential manager of the system instead of a plaintext file, e.g., for
+//! CLI applications:
+//!
+//! ```no_run
+//! # #[cfg(feature = "keyring")]
+//! # fn main() -> rspotify::ClientResult<()> {
+//! use rspotify::{Config, KeyringTokenStore};
+//! use std::sync::Arc;
+//!
+//! let store = KeyringTokenStore::new("my-spotify-cli", "default")?;
+//! let config = Config {
+//!     token_cached: true,
+//!     token_store: Some(Arc::new(store)),
+//!     ..Default::default()
+//! };
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "keyring"))]
+//! # fn main() {}
+//! ```
+//!
+//! [`Config::token_store`]: crate::Config::token_store
+//! [`Config::cache_path`]: crate::Config::cache_path
+
+use crate::{sync::run_blocking, ClientResult, Token};
+
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use maybe_async::maybe_async;
+
+/// A storage backend for the access token.
+///
+/// It's only used by the clients when [`Config::token_cached`] is enabled.
+///
+/// Its methods are asynchronous in the async clients, like the ones of
+/// [`BaseClient`](crate::clients::BaseClient), so that backends such as
+/// databases may be used without blocking the executor. Custom backends may
+/// report their errors with
+/// [`ClientError::CacheFile`](crate::ClientError::CacheFile).
+///
+/// [`Config::token_cached`]: crate::Config::token_cached
+#[cfg_attr(target_arch = "wasm32", maybe_async(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), maybe_async)]
+pub trait TokenStore: Send + Sync + fmt::Debug {
+    /// Reads the stored token, returning `Ok(None)` when there's none yet.
+    async fn load(&self) -> ClientResult<Option<Token>>;
+
+    /// Saves the given token, replacing the previous one, if any.
+    async fn save(&self, token: &Token) -> ClientResult<()>;
+
+    /// Removes the stored token. It should do nothing if there's none.
+    async fn clear(&self) -> ClientResult<()>;
+}
+
+/// The default [`TokenStore`], which saves the token as JSON in a file. The
+/// file is accessed off the executor in the async clients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTokenStore {
+    path: PathBuf,
+}
+
+impl FileTokenStore {
+    /// Builds a new store that saves the token at the given path.
+    #[must_use]
+    pub fn new<T: AsRef<Path>>(path: T) -> Self {
+        Self {
+            path: path.as_ref().to_owned(),
+        }
+    }
+
+    /// The path where the token is saved.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", maybe_async(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), maybe_async)]
+impl TokenStore for FileTokenStore {
+    async fn load(&self) -> ClientResult<Option<Token>> {
+        let path = self.path.clone();
+        run_blocking(move || {
+            if !path.exists() {
+                return Ok(None);
+            }
+
+            Ok(Some(Token::from_cache(&path)?))
+        })
+        .await
+    }
+
+    async fn save(&self, token: &Token) -> ClientResult<()> {
+        let (path, token) = (self.path.clone(), token.clone());
+        run_blocking(move || {
+            token.write_cache(&path)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn clear(&self) -> ClientResult<()> {
+        let path = self.path.clone();
+        run_blocking(move || match fs::remove_file(&path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        })
+        .await
+    }
+}
+
+/// A [`TokenStore`] that saves the token as JSON in the credential manager of
+/// the system: the Keychain on macOS, the Credential Manager on Windows and
+/// the Secret Service (e.g., GNOME Keyring or KWallet) on Linux.
+///
+/// The credential manager is accessed off the executor in the async clients,
+/// since its calls are blocking.
+#[cfg(feature = "keyring")]
+#[derive(Debug)]
+pub struct KeyringTokenStore {
+    entry: std::sync::Arc<keyring::Entry>,
+}
+
+#[cfg(feature = "keyring")]
+impl KeyringTokenStore {
+    /// Builds a new store that saves the token in the entry of the given
+    /// service and user, e.g., the name of the application and the account
+    /// it's for.
+    pub fn new(service: &str, user: &str) -> ClientResult<Self> {
+        let entry = keyring::Entry::new(service, user).map_err(keyring_error)?;
+        Ok(Self {
+            entry: std::sync::Arc::new(entry),
+        })
+    }
+}
+
+#[cfg(feature = "keyring")]
+#[cfg_attr(target_arch = "wasm32", maybe_async(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), maybe_async)]
+impl TokenStore for KeyringTokenStore {
+    async fn load(&self) -> ClientResult<Option<Token>> {
+        let entry = std::sync::Arc::clone(&self.entry);
+        run_blocking(move || match entry.get_password() {
+            Ok(json) => Ok(Some(serde_json::from_str(&json)?)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(err) => Err(keyring_error(err)),
+        })
+        .await
+    }
+
+    async fn save(&self, token: &Token) -> ClientResult<()> {
+        let json = serde_json::to_string(token)?;
+        let entry = std::sync::Arc::clone(&self.entry);
+        run_blocking(move || entry.set_password(&json).map_err(keyring_error)).await
+    }
+
+    async fn clear(&self) -> ClientResult<()> {
+        let entry = std::sync::Arc::clone(&self.entry);
+        run_blocking(move || match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(err) => Err(keyring_error(err)),
+        })
+        .await
+    }
+}
+
+#[cfg(feature = "keyring")]
+fn keyring_error(err: keyring::Error) -> crate::ClientError {
+    crate::ClientError::CacheFile(format!("keyring error: {err}"))
+}
+
+#[cfg(all(test, feature = "keyring"))]
+mod test {
+    use super::*;
+
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_keyring_token_store() {
+        keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+
+        let store = KeyringTokenStore::new("rspotify-test", "test-user").unwrap();
+        let stored = store.load().await.unwrap();
+        assert_eq!(stored, None);
+        store.clear().await.unwrap();
+
+        let token = Token {
+            access_token: "test-access-token".to_owned(),
+            ..Default::default()
+        };
+        store.save(&token).await.unwrap();
+        let stored = store.load().await.unwrap();
+        assert_eq!(stored, Some(token));
+
+        store.clear().await.unwrap();
+        let stored = store.load().await.unwrap();
+        assert_eq!(stored, None);
+    }
+}
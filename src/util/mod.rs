@@ -2,6 +2,8 @@
 
 use std::collections::HashMap;
 
+use crate::model::AdditionalType;
+
 use serde::Serialize;
 use std::marker::PhantomData;
 
@@ -19,6 +21,18 @@ pub fn build_map<'key, 'value, const N: usize>(
     map
 }
 
+/// The `additional_types` parameter of the endpoints whose items may be
+/// either tracks or episodes. Without types, both of them are requested,
+/// since otherwise the episodes would be returned in the format of a track.
+pub fn additional_types_param(types: Option<&[AdditionalType]>) -> String {
+    let types = types.unwrap_or(&[AdditionalType::Track, AdditionalType::Episode]);
+    types
+        .iter()
+        .map(|tpe| -> &'static str { tpe.into() })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
 /// The `Len` parameter is a type-level natural number (encoded as a Peano
 /// number using the `Zero` and `Successor<T>` types) holding the number of
 /// times `optional` or `required` must be called before the builder is
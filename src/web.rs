@@ -0,0 +1,122 @@
+//! Helpers for the [PKCE flow](crate::AuthCodePkceSpotify) in browsers, where
+//! the `cli` prompt isn't available. They only rely on URLs, so they can be
+//! used on WebAssembly.
+//!
+//! The user leaves the page when redirected to Spotify, so the client is lost
+//! by the time they come back. [`authorize_url`] returns a [`PkceVerifier`],
+//! which should be saved (e.g., serialized in the browser's `sessionStorage`)
+//! and passed to [`handle_redirect`] with the URL of the page Spotify
+//! redirected to (i.e., `window.location.href`):
+//!
+//! ```no_run
+//! # fn example() -> rspotify::ClientResult<()> {
+//! use rspotify::{web, AuthCodePkceSpotify, Credentials, OAuth};
+//!
+//! let creds = Credentials::new_pkce("my-client-id");
+//! let oauth = OAuth {
+//!     redirect_uri: "https://example.com/callback".to_owned(),
+//!     ..Default::default()
+//! };
+//!
+//! // Before redirecting the user to Spotify
+//! let spotify = AuthCodePkceSpotify::new(creds.clone(), oauth.clone());
+//! let (url, verifier) = web::authorize_url(&spotify, None)?;
+//! // ...save `verifier`, then go to `url`
+//!
+//! // Once back in the redirect URI, with `.await` in the async clients
+//! let spotify = AuthCodePkceSpotify::new(creds, oauth);
+//! let location = "https://example.com/callback?code=...&state=...";
+//! let result = web::handle_redirect(&spotify, location, &verifier);
+//! # let _ = result;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{AuthCodePkceSpotify, ClientError, ClientResult, PkceVerifier};
+
+use std::collections::HashMap;
+
+use maybe_async::maybe_async;
+use url::Url;
+
+/// Builds the URL to authorize the client, generating a new code verifier.
+///
+/// This is the same as
+/// [`AuthCodePkceSpotify::get_authorize_url_with_verifier`]. See
+/// [`AuthCodePkceSpotify::get_authorize_url`] for the meaning of
+/// `verifier_bytes`.
+pub fn authorize_url(
+    client: &AuthCodePkceSpotify,
+    verifier_bytes: Option<usize>,
+) -> ClientResult<(String, PkceVerifier)> {
+    client.get_authorize_url_with_verifier(verifier_bytes)
+}
+
+/// Requests the token with the code in the URL Spotify redirected to, using
+/// the verifier returned by [`authorize_url`].
+///
+/// This fails with [`ClientError::Authorization`] if the user denied access,
+/// the state doesn't match, or there's no code in the URL.
+#[maybe_async]
+pub async fn handle_redirect(
+    client: &AuthCodePkceSpotify,
+    redirect_url: &str,
+    verifier: &PkceVerifier,
+) -> ClientResult<()> {
+    let (code, state) = parse_redirect(redirect_url)?;
+    client
+        .request_token_with_verifier(&code, &state, verifier)
+        .await
+}
+
+/// Extracts the code and the state from the redirect URL.
+fn parse_redirect(redirect_url: &str) -> ClientResult<(String, String)> {
+    let url = Url::parse(redirect_url)?;
+    let params = url.query_pairs().collect::<HashMap<_, _>>();
+
+    if let Some(error) = params.get("error") {
+        return Err(ClientError::Authorization(format!(
+            "access wasn't granted: {error}"
+        )));
+    }
+    match (params.get("code"), params.get("state")) {
+        (Some(code), Some(state)) => Ok((code.to_string(), state.to_string())),
+        (None, _) => Err(ClientError::Authorization(
+            "there's no code in the redirect".to_owned(),
+        )),
+        (_, None) => Err(ClientError::Authorization(
+            "the state in the redirect doesn't match".to_owned(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_redirect() {
+        let ok = "https://example.com/callback?code=abc&state=xyz";
+        assert_eq!(
+            parse_redirect(ok).unwrap(),
+            ("abc".to_owned(), "xyz".to_owned())
+        );
+
+        let no_state = "https://example.com/callback?code=abc";
+        assert!(matches!(
+            parse_redirect(no_state),
+            Err(ClientError::Authorization(_))
+        ));
+
+        let denied = "https://example.com/callback?error=access_denied&state=xyz";
+        assert_eq!(
+            parse_redirect(denied).unwrap_err().to_string(),
+            "authorization error: access wasn't granted: access_denied"
+        );
+
+        assert!(matches!(
+            parse_redirect("not a url"),
+            Err(ClientError::ParseUrl(_))
+        ));
+    }
+}
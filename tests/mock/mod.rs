@@ -0,0 +1,144 @@
+//! The helpers shared by the tests of the `client-mock` feature, with the
+//! responses built from the JSON fixtures in `tests/fixtures`.
+#![allow(dead_code)]
+
+use chrono::{Duration, Utc};
+use rspotify::{
+    clients::pagination::Paginator,
+    http::{MockHttpClient, MockResponse},
+    AuthCodeSpotify, ClientResult, Config, Token,
+};
+use serde_json::{json, Value};
+
+/// Parses one of the fixtures in `tests/fixtures`.
+macro_rules! fixture {
+    ($name:literal) => {
+        serde_json::from_str::<Value>(include_str!(concat!("../fixtures/", $name, ".json")))
+            .unwrap()
+    };
+}
+
+pub fn token() -> Token {
+    Token {
+        access_token: "test-access-token".to_owned(),
+        expires_at: Some(Utc::now() + Duration::try_hours(1).unwrap()),
+        ..Default::default()
+    }
+}
+
+/// The configuration with its requests served by the given mock.
+pub fn with_mock(config: Config, http: &MockHttpClient) -> Config {
+    Config {
+        http_client: Some(http.clone().into()),
+        ..config
+    }
+}
+
+/// A client authenticated with [`token`], and the mock serving its requests.
+pub fn client() -> (AuthCodeSpotify, MockHttpClient) {
+    client_with_config(Config::default())
+}
+
+pub fn client_with_config(config: Config) -> (AuthCodeSpotify, MockHttpClient) {
+    let http = MockHttpClient::default();
+    let config = with_mock(config, &http);
+    let spotify = AuthCodeSpotify::from_token_with_config(
+        token(),
+        Default::default(),
+        Default::default(),
+        config,
+    );
+    (spotify, http)
+}
+
+#[maybe_async::maybe_async]
+pub async fn fetch_all<T>(paginator: Paginator<'_, ClientResult<T>>) -> Vec<T> {
+    #[cfg(feature = "__async")]
+    {
+        use futures::stream::TryStreamExt;
+
+        paginator.try_collect::<Vec<_>>().await.unwrap()
+    }
+
+    #[cfg(feature = "__sync")]
+    {
+        paginator.collect::<ClientResult<Vec<_>>>().unwrap()
+    }
+}
+
+/// The response of the accounts service with a new access token.
+pub fn token_response() -> MockResponse {
+    MockResponse::json(&fixture!("token"))
+}
+
+pub fn artist(popularity: Value) -> Value {
+    let mut artist = fixture!("artist");
+    artist["popularity"] = popularity;
+    artist
+}
+
+pub fn user(id: &str) -> Value {
+    let mut user = fixture!("user");
+    user["href"] = json!(format!("https://api.spotify.com/v1/users/{id}"));
+    user["id"] = json!(id);
+    user
+}
+
+pub fn page(items: Vec<Value>) -> Value {
+    let mut page = fixture!("page");
+    page["total"] = json!(items.len());
+    page["items"] = json!(items);
+    page
+}
+
+pub fn playlist(id: &str, owner: &str, items: Vec<Value>) -> Value {
+    let mut playlist = fixture!("playlist");
+    playlist["href"] = json!(format!("https://api.spotify.com/v1/playlists/{id}"));
+    playlist["id"] = json!(id);
+    playlist["name"] = json!(format!("Playlist {id}"));
+    playlist["owner"] = user(owner);
+    playlist["tracks"] = page(items);
+    playlist
+}
+
+pub fn playback(is_playing: bool) -> Value {
+    let mut playback = fixture!("playback");
+    playback["is_playing"] = json!(is_playing);
+    playback
+}
+
+pub fn devices() -> Value {
+    fixture!("devices")
+}
+
+pub fn audio_features(id: &str) -> Value {
+    let mut features = fixture!("audio_features");
+    features["analysis_url"] = json!(format!("https://api.spotify.com/v1/audio-analysis/{id}"));
+    features["id"] = json!(id);
+    features["track_href"] = json!(format!("https://api.spotify.com/v1/tracks/{id}"));
+    features["uri"] = json!(format!("spotify:track:{id}"));
+    features
+}
+
+/// The items of the regression cassette, a track and an episode.
+pub fn cassette_items() -> Vec<Value> {
+    let cassette: Value =
+        serde_json::from_str(include_str!("../cassettes/playlist_items_episode.json")).unwrap();
+    cassette["interactions"][0]["body"]["items"]
+        .as_array()
+        .unwrap()
+        .clone()
+}
+
+/// An episode taken from the cassettes, with the given ID and resume point.
+pub fn episode(id: &str, resume_position_ms: Option<u64>) -> Value {
+    let mut episode = cassette_items()[1]["track"].clone();
+    episode["id"] = json!(id);
+    if let Some(position) = resume_position_ms {
+        episode["resume_point"] = json!({
+            "fully_played": false,
+            "resume_position_ms": position,
+        });
+    }
+    episode
+}
@@ -0,0 +1,381 @@
+//! Endpoint tests that replay the responses recorded in `tests/cassettes`, so
+//! that they run offline with the `testing` feature:
+//!
+//! ```sh
+//! cargo test --features testing --test test_cassettes
+//! ```
+//!
+//! When the credentials are exported as `RSPOTIFY_CLIENT_ID` and
+//! `RSPOTIFY_CLIENT_SECRET`, they run against Spotify instead, without
+//! touching the cassettes. Setting `RSPOTIFY_RECORD_CASSETTES=1` also
+//! overwrites the cassettes with the new responses.
+//!
+//! The payloads that break the models can be contributed as regression
+//! cassettes too. These are only replayed, since Spotify may no longer return
+//! the same responses.
+#![cfg(feature = "testing")]
+
+use chrono::{Duration, Utc};
+use maybe_async::maybe_async;
+use rspotify::{
+    http::{MockHttpClient, Query, RequestInterceptor},
+    model::{
+        AlbumId, AlbumType, ArtistId, Country, FullArtist, Market, PlaylistId, SearchResult,
+        SearchType, TrackId, UserId,
+    },
+    prelude::*,
+    testing::cassette::{Cassette, CassetteRecorder},
+    ClientCredsSpotify, ClientError, Config, Credentials, SpotifyBuilder, Token,
+};
+use std::sync::Arc;
+
+/// A client whose interactions are replayed from, or recorded into, a
+/// cassette.
+struct CassetteTest {
+    spotify: ClientCredsSpotify,
+    recorder: Option<Arc<CassetteRecorder>>,
+    path: String,
+}
+
+impl CassetteTest {
+    /// Saves the cassette, if it was recorded.
+    fn finish(self) {
+        if let Some(recorder) = self.recorder {
+            recorder.save(&self.path).unwrap();
+        }
+    }
+}
+
+/// A client that replays the given cassette offline.
+fn replay(path: &str, mut config: Config) -> ClientCredsSpotify {
+    let http = MockHttpClient::default();
+    Cassette::load(path).unwrap().load_into(&http);
+    config.http_client = Some(http.into());
+    let token = Token {
+        access_token: "test-access-token".to_owned(),
+        expires_at: Some(Utc::now() + Duration::try_hours(1).unwrap()),
+        ..Default::default()
+    };
+    SpotifyBuilder::new()
+        .token(token)
+        .config(config)
+        .client_credentials()
+}
+
+#[maybe_async]
+async fn cassette_test(name: &str) -> CassetteTest {
+    cassette_test_with_config(name, Config::default()).await
+}
+
+/// Replays the cassette with the given name, or runs against Spotify if the
+/// credentials are in the environment. The cassette is only recorded if
+/// `RSPOTIFY_RECORD_CASSETTES` is set.
+#[maybe_async]
+async fn cassette_test_with_config(name: &str, mut config: Config) -> CassetteTest {
+    let path = format!("tests/cassettes/{name}.json");
+    let record = std::env::var_os("RSPOTIFY_RECORD_CASSETTES").is_some();
+    let live = ["RSPOTIFY_CLIENT_ID", "RSPOTIFY_CLIENT_SECRET"]
+        .iter()
+        .all(|var| std::env::var_os(var).is_some());
+    if !record && !live {
+        return CassetteTest {
+            spotify: replay(&path, config),
+            recorder: None,
+            path,
+        };
+    }
+
+    let creds = Credentials::from_env().expect("the credentials are required to run live");
+    let recorder = record.then(|| {
+        let recorder = Arc::new(CassetteRecorder::default());
+        config
+            .interceptors
+            .push(Arc::clone(&recorder) as Arc<dyn RequestInterceptor>);
+        recorder
+    });
+    let spotify = ClientCredsSpotify::with_config(creds, config);
+    spotify.request_token().await.unwrap();
+    CassetteTest {
+        spotify,
+        recorder,
+        path,
+    }
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_album() {
+    let test = cassette_test("album").await;
+    let id = AlbumId::from_uri("spotify:album:0sNOF9WDwhWunNAHPD3Baj").unwrap();
+    let album = test.spotify.album(id.clone(), None).await.unwrap();
+    assert_eq!(album.id, id);
+    test.finish();
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_albums() {
+    let test = cassette_test("albums").await;
+    let ids = [
+        AlbumId::from_uri("spotify:album:41MnTivkwTO3UUJ8DrqEJJ").unwrap(),
+        AlbumId::from_uri("spotify:album:6JWc4iAiJ9FjyK0B59ABb4").unwrap(),
+        AlbumId::from_uri("spotify:album:6UXCm6bOO4gFlDQZV5yL37").unwrap(),
+    ];
+    let albums = test.spotify.albums(ids, None).await.unwrap();
+    assert_eq!(albums.len(), 3);
+    test.finish();
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_album_tracks() {
+    let test = cassette_test("album_tracks").await;
+    let id = AlbumId::from_uri("spotify:album:6akEvsycLGftJxYudPjmqK").unwrap();
+    let page = test
+        .spotify
+        .album_track_manual(id, None, Some(2), None)
+        .await
+        .unwrap();
+    assert_eq!(page.items.len(), 2);
+    test.finish();
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_artist() {
+    let test = cassette_test("artist").await;
+    let id = ArtistId::from_uri("spotify:artist:2WX2uTcsvV5OnS0inACecP").unwrap();
+    let artist = test.spotify.artist(id).await.unwrap();
+    assert_eq!(artist.name, "Birdy");
+    test.finish();
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_custom_request() {
+    let test = cassette_test("custom_request").await;
+    let mut params = Query::new();
+    params.insert("market", "ES");
+
+    let artist: FullArtist = test
+        .spotify
+        .api_get_typed("artists/2WX2uTcsvV5OnS0inACecP", &params)
+        .await
+        .unwrap();
+    assert_eq!(artist.name, "Birdy");
+
+    let raw = test
+        .spotify
+        .api_get_raw("artists/2WX2uTcsvV5OnS0inACecP", &params)
+        .await
+        .unwrap();
+    assert!(raw.contains("Birdy"));
+    test.finish();
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_artist_albums() {
+    let test = cassette_test("artist_albums").await;
+    let id = ArtistId::from_uri("spotify:artist:2WX2uTcsvV5OnS0inACecP").unwrap();
+    let market = Some(Market::Country(Country::UnitedStates));
+    let albums = test
+        .spotify
+        .artist_albums_manual(id.clone(), Some(AlbumType::Album), market, Some(10), None)
+        .await
+        .unwrap();
+    assert!(albums
+        .items
+        .iter()
+        .all(|album| album.album_type.as_deref() == Some("album")));
+
+    let include_groups = [
+        AlbumType::Album,
+        AlbumType::Single,
+        AlbumType::Compilation,
+        AlbumType::AppearsOn,
+    ];
+    let all = test
+        .spotify
+        .artist_albums_manual(id.clone(), include_groups, market, Some(10), None)
+        .await
+        .unwrap();
+    assert!(all.total >= albums.total);
+
+    // Without any album type, all of them are returned as well
+    let empty = test
+        .spotify
+        .artist_albums_manual(id, [], market, Some(10), None)
+        .await
+        .unwrap();
+    assert_eq!(empty.total, all.total);
+    test.finish();
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_artists() {
+    let test = cassette_test("artists").await;
+    let ids = [
+        ArtistId::from_uri("spotify:artist:0oSGxfWSnnOXhD2fKuz2Gy").unwrap(),
+        ArtistId::from_uri("spotify:artist:3dBVyJ7JuOMt4GE9607Qin").unwrap(),
+    ];
+    let artists = test.spotify.artists(ids).await.unwrap();
+    assert_eq!(artists.len(), 2);
+    test.finish();
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_artist_top_tracks() {
+    let test = cassette_test("artist_top_tracks").await;
+    let id = ArtistId::from_uri("spotify:artist:2WX2uTcsvV5OnS0inACecP").unwrap();
+    let market = Some(Market::Country(Country::UnitedStates));
+    let tracks = test.spotify.artist_top_tracks(id, market).await.unwrap();
+    assert!(!tracks.is_empty());
+    test.finish();
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_user() {
+    let test = cassette_test("user").await;
+    let id = UserId::from_id("tuggareutangranser").unwrap();
+    let user = test.spotify.user(id.clone()).await.unwrap();
+    assert_eq!(user.id, id);
+    test.finish();
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_track() {
+    let test = cassette_test("track").await;
+    let id = TrackId::from_id("11dFghVXANMlKmJXsNCbNl").unwrap();
+    let track = test.spotify.track(id.as_ref(), None).await.unwrap();
+    assert_eq!(track.id, Some(id));
+    assert_eq!(track.name, "Cut To The Feeling");
+    test.finish();
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_tracks() {
+    let test = cassette_test("tracks").await;
+    let ids = [
+        TrackId::from_uri("spotify:track:3n3Ppam7vgaVa1iaRUc9Lp").unwrap(),
+        TrackId::from_uri("spotify:track:3twNvmDtFQtAd5gMKedhLD").unwrap(),
+    ];
+    let tracks = test.spotify.tracks(ids, None).await.unwrap();
+    assert_eq!(tracks.len(), 2);
+    test.finish();
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_playlist() {
+    let test = cassette_test("playlist").await;
+    let id = PlaylistId::from_id("0fwsN3jhWKTbJ1J7cR7fgu").unwrap();
+    let playlist = test
+        .spotify
+        .playlist(id.clone(), None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(playlist.id, id);
+
+    let fake = PlaylistId::from_id("fakeid").unwrap();
+    let result = test.spotify.playlist(fake, None, None, None).await;
+    assert!(matches!(result, Err(ClientError::Api { .. })));
+    test.finish();
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_search() {
+    let test = cassette_test("search").await;
+    let query = "album:arrival artist:abba";
+    let result = test
+        .spotify
+        .search(query, SearchType::Album, None, None, Some(10), Some(0))
+        .await
+        .unwrap();
+    assert!(matches!(result, SearchResult::Albums(_)));
+
+    let result = test
+        .spotify
+        .search_multiple(
+            query,
+            vec![SearchType::Artist, SearchType::Album],
+            None,
+            None,
+            Some(10),
+            Some(0),
+        )
+        .await
+        .unwrap();
+    assert!(result.artists.is_some() && result.albums.is_some());
+    test.finish();
+}
+
+/// Iterates the 10 tracks of an album with 5 requests of 2 items.
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_pagination() {
+    let config = Config {
+        pagination_chunks: 2,
+        ..Default::default()
+    };
+    let test = cassette_test_with_config("pagination", config).await;
+    let album = AlbumId::from_uri("spotify:album:2T7DdrOvsqOqU9bGTkjBYu").unwrap();
+    let tracks = test.spotify.album_track(album, None);
+
+    #[cfg(feature = "__async")]
+    let names = {
+        use futures_util::TryStreamExt;
+
+        let tracks = tracks.try_collect::<Vec<_>>().await.unwrap();
+        tracks
+            .into_iter()
+            .map(|track| track.name)
+            .collect::<Vec<_>>()
+    };
+    #[cfg(feature = "__sync")]
+    let names = tracks.map(|track| track.unwrap().name).collect::<Vec<_>>();
+
+    assert_eq!(
+        names,
+        [
+            "Human After All",
+            "The Prime Time of Your Life",
+            "Robot Rock",
+            "Steam Machine",
+            "Make Love",
+            "The Brainwasher",
+            "On / Off",
+            "Television Rules the Nation",
+            "Technologic",
+            "Emotion",
+        ]
+    );
+    test.finish();
+}
+
+/// The regression cassettes, which are only replayed.
+mod regressions {
+    use super::*;
+    use rspotify::model::PlayableItem;
+
+    /// Playlists may contain episodes, which have to be parsed as such rather
+    /// than failing or being mistaken for tracks.
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_playlist_items_with_episode() {
+        let spotify = replay(
+            "tests/cassettes/playlist_items_episode.json",
+            Config::default(),
+        );
+
+        let id = PlaylistId::from_id("3cEYpjA9oz9GiPac4AsH4n").unwrap();
+        let page = spotify
+            .playlist_items_manual(id, None, None, None, Some(50), None)
+            .await
+            .unwrap();
+        let items = page
+            .items
+            .into_iter()
+            .filter_map(|item| item.track)
+            .collect::<Vec<_>>();
+        assert!(matches!(items[0], PlayableItem::Track(_)));
+        assert!(matches!(items[1], PlayableItem::Episode(_)));
+
+        let missing = PlaylistId::from_id("0000000000000000000000").unwrap();
+        let result = spotify
+            .playlist_items_manual(missing, None, None, None, Some(50), None)
+            .await;
+        assert!(matches!(result, Err(ClientError::Api { status: 404, .. })));
+    }
+}
@@ -69,6 +69,21 @@ fn test_additional_type() {
 fn test_current_playing_type() {
     let ad = CurrentlyPlayingType::Advertisement;
     assert_eq!(<&str>::from(ad), "ad");
+
+    let ad: CurrentlyPlayingType = serde_json::from_str(r#""ad""#).unwrap();
+    assert_eq!(ad, CurrentlyPlayingType::Advertisement);
+    // New types are tolerated
+    let audiobook: CurrentlyPlayingType = serde_json::from_str(r#""audiobook""#).unwrap();
+    assert_eq!(audiobook, CurrentlyPlayingType::Unknown);
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn test_subscription_level() {
+    let open: SubscriptionLevel = serde_json::from_str(r#""open""#).unwrap();
+    assert_eq!(open, SubscriptionLevel::Free);
+    let duo: SubscriptionLevel = serde_json::from_str(r#""duo""#).unwrap();
+    assert_eq!(duo, SubscriptionLevel::Unknown);
 }
 
 #[test]
@@ -84,3 +99,19 @@ fn test_convert_country_from_str() {
     let zimbabwe = Country::Zimbabwe;
     assert_eq!(<&str>::from(zimbabwe), "ZW");
 }
+
+#[test]
+#[wasm_bindgen_test]
+fn test_scope() {
+    let scope = Scope::UserReadPlaybackState;
+    assert_eq!(<&str>::from(scope), "user-read-playback-state");
+    assert_eq!("user-read-playback-state".parse::<Scope>(), Ok(scope));
+    assert!("user-read-playack-state".parse::<Scope>().is_err());
+
+    let scopes = rspotify::scopes!(Scope::Streaming, Scope::UserReadPrivate);
+    assert_eq!(scopes, rspotify::scopes!("streaming user-read-private"));
+
+    // The macro validates the literals with its own list
+    let names = Scope::ALL.iter().map(<&str>::from).collect::<Vec<_>>();
+    assert_eq!(names, rspotify::macros::__private::SCOPES);
+}
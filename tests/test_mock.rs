@@ -0,0 +1,943 @@
+//! Tests for the offline HTTP client of the `client-mock` feature.
+#![cfg(feature = "client-mock")]
+
+mod mock;
+
+use chrono::Duration;
+use mock::{
+    artist, audio_features, cassette_items, client, client_with_config, episode, fetch_all, page,
+    token, token_response, user, with_mock,
+};
+use rspotify::{
+    any_client::{AnyClient, AnyOAuthClient},
+    clients::pagination::paginate_with_progress,
+    http::{MockHttpClient, MockResponse},
+    model::{ArtistId, CategoryId, Country, Market, Page, SearchType, ShowId, TrackId, UserId},
+    prelude::*,
+    response_cache::ResponseCache,
+    retry::RetryPolicy,
+    scopes,
+    search::{SearchFilter, SearchQuery},
+    AuthCodePkceSpotify, AuthCodeSpotify, ClientCredsSpotify, ClientError, Config, Credentials,
+    OAuth, ParseErrorHandler, SpotifyBuilder, Token,
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_mock_client() {
+    let (spotify, http) = client();
+    http.respond(
+        "GET",
+        "/v1/users/test-user",
+        MockResponse::json(&json!({
+            "display_name": "Test",
+            "external_urls": {},
+            "href": "https://api.spotify.com/v1/users/test-user",
+            "id": "test-user",
+        })),
+    );
+    http.respond("PUT", "/v1/me/tracks", MockResponse::ok(""));
+
+    let user = spotify
+        .user(UserId::from_id("test-user").unwrap())
+        .await
+        .unwrap();
+    assert_eq!(user.display_name.as_deref(), Some("Test"));
+
+    let track = TrackId::from_id("4iV5W9uYEdYUVa79Axb7Rh").unwrap();
+    spotify
+        .current_user_saved_tracks_add([track])
+        .await
+        .unwrap();
+
+    let requests = http.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(
+        requests[0].headers.get("authorization").map(String::as_str),
+        Some("Bearer test-access-token")
+    );
+    assert_eq!(requests[1].method, "PUT");
+    assert_eq!(
+        requests[1].url,
+        "https://api.spotify.com/v1/me/tracks/?ids=4iV5W9uYEdYUVa79Axb7Rh"
+    );
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_mock_client_errors() {
+    let (spotify, http) = client();
+    http.respond(
+        "GET",
+        "/v1/me",
+        MockResponse::with_status(404, r#"{"error": {"status": 404, "message": "Not found"}}"#),
+    );
+
+    match spotify.me().await {
+        Err(ClientError::Api {
+            status, message, ..
+        }) => {
+            assert_eq!(status, 404);
+            assert_eq!(message, "Not found");
+        }
+        result => panic!("unexpected result: {result:?}"),
+    }
+
+    // The delay requested by Spotify is kept
+    http.respond(
+        "GET",
+        "/v1/me/player/devices",
+        MockResponse::with_status(429, "").with_header("Retry-After", "7"),
+    );
+    match spotify.device().await {
+        Err(ClientError::Api {
+            status,
+            retry_after,
+            ..
+        }) => {
+            assert_eq!(status, 429);
+            assert_eq!(retry_after, Some(std::time::Duration::from_secs(7)));
+        }
+        result => panic!("unexpected result: {result:?}"),
+    }
+
+    // Nothing registered, which is answered as an unknown endpoint
+    let result = spotify.current_user_playlists_manual(None, None).await;
+    assert!(matches!(result, Err(ClientError::Api { status: 404, .. })));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_refresh_on_unauthorized() {
+    let expired = Token {
+        refresh_token: Some("test-refresh-token".to_owned()),
+        ..token()
+    };
+    let http = MockHttpClient::default();
+    let spotify = SpotifyBuilder::new()
+        .credentials(Credentials::new("client-id", "client-secret"))
+        .token(expired)
+        .config(with_mock(Config::default(), &http))
+        .auth_code();
+    http.respond(
+        "GET",
+        "/v1/me",
+        MockResponse::with_status(401, r#"{"error": {"status": 401, "message": "Expired"}}"#),
+    );
+    http.respond("GET", "/v1/me", MockResponse::json(&user("alice")));
+    http.respond(
+        "POST",
+        "https://accounts.spotify.com/api/token",
+        token_response(),
+    );
+
+    let user = spotify.me().await.unwrap();
+    assert_eq!(user.id.id(), "alice");
+
+    let requests = http.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(
+        requests[0].headers["authorization"],
+        "Bearer test-access-token"
+    );
+    assert_eq!(requests[1].method, "POST");
+    assert_eq!(requests[1].body["grant_type"], "refresh_token");
+    assert_eq!(requests[1].body["refresh_token"], "test-refresh-token");
+    assert_eq!(requests[2].url, requests[0].url);
+    assert_eq!(
+        requests[2].headers["authorization"],
+        "Bearer new-access-token"
+    );
+
+    // The new token is kept for the next requests
+    spotify.me().await.unwrap();
+    let requests = http.requests();
+    assert_eq!(requests.len(), 4);
+    assert_eq!(
+        requests[3].headers["authorization"],
+        "Bearer new-access-token"
+    );
+
+    // Without token refreshing, the 401 is returned as is
+    let http = MockHttpClient::default();
+    let config = Config {
+        token_refreshing: false,
+        ..Default::default()
+    };
+    let spotify = SpotifyBuilder::new()
+        .credentials(Credentials::new("client-id", "client-secret"))
+        .token(token())
+        .config(with_mock(config, &http))
+        .auth_code();
+    http.respond(
+        "GET",
+        "/v1/me",
+        MockResponse::with_status(401, r#"{"error": {"status": 401, "message": "Expired"}}"#),
+    );
+    let result = spotify.me().await;
+    assert!(matches!(result, Err(ClientError::Api { status: 401, .. })));
+    assert_eq!(http.requests().len(), 1);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_response_cache() {
+    let config = Config {
+        response_cache: Some(Arc::new(ResponseCache::new(
+            std::time::Duration::from_secs(60),
+        ))),
+        ..Default::default()
+    };
+    let (spotify, http) = client_with_config(config);
+    http.respond(
+        "GET",
+        "/v1/artists/0OdUWJ0sBjDrqHygGUXeCF",
+        MockResponse::json(&artist(json!(50))),
+    );
+
+    let id = ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+    for _ in 0..3 {
+        let artist = spotify.artist(id.as_ref()).await.unwrap();
+        assert_eq!(artist.name, "Band of Horses");
+    }
+    assert_eq!(http.requests().len(), 1);
+
+    // The responses requested by another user aren't reused
+    let token = Token {
+        access_token: "other-access-token".to_owned(),
+        ..token()
+    };
+    let other = AuthCodeSpotify::from_token_with_config(
+        token,
+        Credentials::default(),
+        OAuth::default(),
+        spotify.config.clone(),
+    );
+    other.artist(id.as_ref()).await.unwrap();
+    assert_eq!(http.requests().len(), 2);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_parse_error_recovery() {
+    let path = "/v1/artists/0OdUWJ0sBjDrqHygGUXeCF";
+    let id = ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+
+    let (spotify, http) = client();
+    http.respond("GET", path, MockResponse::json(&artist(json!(50.0))));
+    let result = spotify.artist(id.as_ref()).await;
+    match result {
+        Err(ClientError::ParseJson(err)) => {
+            assert_eq!(
+                err.endpoint.as_deref(),
+                Some("artists/0OdUWJ0sBjDrqHygGUXeCF")
+            );
+            assert!(err.snippet.unwrap().contains(r#""popularity":50.0"#));
+            assert!(err.body.is_none());
+        }
+        result => panic!("unexpected result: {result:?}"),
+    }
+
+    // The handler turns the float into an integer again
+    let handler = ParseErrorHandler(Box::new(|raw_json, type_name| {
+        assert!(type_name.ends_with("FullArtist"));
+        let mut value = serde_json::from_str::<Value>(raw_json).ok()?;
+        let popularity = value["popularity"].as_f64()?;
+        value["popularity"] = json!(popularity as u32);
+        Some(value)
+    }));
+    let (spotify, http) = client_with_config(Config {
+        on_parse_error: Some(Arc::new(handler)),
+        parse_error_body: true,
+        ..Default::default()
+    });
+    http.respond("GET", path, MockResponse::json(&artist(json!(50.0))));
+    let result = spotify.artist(id.as_ref()).await.unwrap();
+    assert_eq!(result.popularity, 50);
+
+    // The original error is kept if it can't be patched
+    http.reset();
+    http.respond("GET", path, MockResponse::json(&artist(json!("high"))));
+    let result = spotify.artist(id.as_ref()).await;
+    match result {
+        Err(ClientError::ParseJson(err)) => assert!(err.body.unwrap().contains(r#""high""#)),
+        result => panic!("unexpected result: {result:?}"),
+    }
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_retry_policy() {
+    let policy = RetryPolicy::default()
+        .with_max_attempts(3)
+        .with_base_delay(std::time::Duration::from_millis(1))
+        .with_retry_on(|method, err| method == "GET" && rspotify::retry::is_transient(method, err));
+    let (spotify, http) = client_with_config(Config {
+        retry_policy: Some(Arc::new(policy)),
+        ..Default::default()
+    });
+    let path = "/v1/artists/0OdUWJ0sBjDrqHygGUXeCF";
+    http.respond("GET", path, MockResponse::with_status(503, "unavailable"));
+    http.respond("GET", path, MockResponse::json(&artist(json!(50))));
+
+    let id = ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+    let artist = spotify.artist(id.as_ref()).await.unwrap();
+    assert_eq!(artist.name, "Band of Horses");
+    assert_eq!(http.requests().len(), 2);
+
+    // Gives up after the last attempt
+    http.reset();
+    http.respond("GET", path, MockResponse::with_status(502, "bad gateway"));
+    let result = spotify.artist(id.as_ref()).await;
+    assert!(result.is_err());
+    assert_eq!(http.requests().len(), 3);
+
+    // Neither the permanent errors nor the methods left out are retried
+    http.reset();
+    http.respond("GET", path, MockResponse::with_status(404, "not found"));
+    http.respond(
+        "PUT",
+        "/v1/me/player/pause",
+        MockResponse::with_status(503, ""),
+    );
+    let artist = spotify.artist(id.as_ref()).await;
+    let pause = spotify.pause_playback(None).await;
+    assert!(artist.is_err() && pause.is_err());
+    assert_eq!(http.requests().len(), 2);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_retry_policy_retry_after() {
+    // The backoff would take a minute, but the response asks for no delay
+    let policy = RetryPolicy::default()
+        .with_base_delay(std::time::Duration::from_secs(60))
+        .with_jitter(false);
+    let (spotify, http) = client_with_config(Config {
+        retry_policy: Some(Arc::new(policy)),
+        ..Default::default()
+    });
+    let path = "/v1/artists/0OdUWJ0sBjDrqHygGUXeCF";
+    http.respond(
+        "GET",
+        path,
+        MockResponse::with_status(429, "slow down").with_header("Retry-After", "0"),
+    );
+    http.respond("GET", path, MockResponse::json(&artist(json!(50))));
+
+    let id = ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+    let start = std::time::Instant::now();
+    let artist = spotify.artist(id).await.unwrap();
+    assert_eq!(artist.name, "Band of Horses");
+    assert_eq!(http.requests().len(), 2);
+    assert!(start.elapsed() < std::time::Duration::from_secs(10));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_retry_policy_long_retry_after() {
+    // Waiting for an hour would exceed the maximum delay, so it fails instead
+    let policy = RetryPolicy::default().with_max_delay(std::time::Duration::from_secs(30));
+    let (spotify, http) = client_with_config(Config {
+        retry_policy: Some(Arc::new(policy)),
+        ..Default::default()
+    });
+    let path = "/v1/artists/0OdUWJ0sBjDrqHygGUXeCF";
+    http.respond(
+        "GET",
+        path,
+        MockResponse::with_status(429, "slow down").with_header("Retry-After", "3600"),
+    );
+
+    let id = ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+    let result = spotify.artist(id).await;
+    assert!(matches!(
+        result,
+        Err(ClientError::Api {
+            status: 429,
+            retry_after: Some(_),
+            ..
+        })
+    ));
+    assert_eq!(http.requests().len(), 1);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_search_query() {
+    let (spotify, http) = client();
+    http.respond(
+        "GET",
+        "/v1/search",
+        MockResponse::json(&json!({ "tracks": page(vec![]) })),
+    );
+
+    let query = SearchQuery::new("one more time")
+        .any_of(SearchFilter::Artist, ["Daft Punk", "Justice"])
+        .exclude(SearchFilter::Album, "Alive 2007");
+    let result = spotify
+        .search_query(&query, SearchType::Track, None, None, Some(10), None)
+        .await
+        .unwrap();
+    assert_eq!(result.tracks().map(|page| page.total), Some(0));
+
+    let requests = http.requests();
+    assert_eq!(
+        requests[0].query.get("q").map(String::as_str),
+        Some(r#"one more time artist:"Daft Punk" OR artist:Justice NOT album:"Alive 2007""#)
+    );
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_pkce_verifier_in_another_client() {
+    let creds = Credentials::new_pkce("test-client-id");
+    let oauth = OAuth {
+        redirect_uri: "http://localhost:8888/callback".to_owned(),
+        ..Default::default()
+    };
+    let (_, verifier) = AuthCodePkceSpotify::new(creds.clone(), oauth.clone())
+        .get_authorize_url_with_verifier(None)
+        .unwrap();
+
+    // The callback is handled by a client that never saw the verifier
+    let http = MockHttpClient::default();
+    let spotify =
+        AuthCodePkceSpotify::with_config(creds, oauth, with_mock(Config::default(), &http));
+    http.respond(
+        "POST",
+        "https://accounts.spotify.com/api/token",
+        token_response(),
+    );
+    // The state of the callback is checked against the verifier's
+    let result = spotify
+        .request_token_with_verifier("test-code", "other-state", &verifier)
+        .await;
+    assert!(matches!(result, Err(ClientError::Authorization(_))));
+    assert!(http.requests().is_empty());
+
+    spotify
+        .request_token_with_verifier("test-code", &verifier.state, &verifier)
+        .await
+        .unwrap();
+
+    let token = spotify.get_token();
+    let token = token.lock().await.unwrap();
+    assert_eq!(token.as_ref().unwrap().access_token, "new-access-token");
+    let requests = http.requests();
+    assert_eq!(requests[0].body["code_verifier"], verifier.verifier);
+    assert_eq!(
+        requests[0].body["redirect_uri"],
+        "http://localhost:8888/callback"
+    );
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+#[allow(deprecated)]
+async fn test_track_analysis_parts() {
+    let interval = |start: f64| json!({ "start": start, "duration": 0.5, "confidence": 0.9 });
+    // The rest of the analysis is ignored, even if it's incomplete
+    let analysis = json!({
+        "beats": [interval(0.0), interval(0.5), interval(1.0)],
+        "sections": [{
+            "start": 0.0,
+            "duration": 1.5,
+            "confidence": 1,
+            "loudness": -20.074,
+            "tempo": 120.0,
+            "tempo_confidence": 0.767,
+            "key": 5,
+            "key_confidence": 0.327,
+            "mode": 1,
+            "mode_confidence": 0.566,
+            "time_signature": 4,
+            "time_signature_confidence": 1
+        }],
+        "meta": {},
+    });
+
+    let (spotify, http) = client();
+    http.respond(
+        "GET",
+        "/v1/audio-analysis/4iV5W9uYEdYUVa79Axb7Rh",
+        MockResponse::json(&analysis),
+    );
+    let id = TrackId::from_id("4iV5W9uYEdYUVa79Axb7Rh").unwrap();
+
+    let beats = spotify.track_analysis_beats(id.as_ref()).await.unwrap();
+    assert_eq!(beats.len(), 3);
+    assert_eq!(beats[1].start, Duration::try_milliseconds(500).unwrap());
+    assert_eq!(beats[1].end(), Duration::try_seconds(1).unwrap());
+    assert!(beats[1].confidence.is_at_least(0.9));
+
+    let sections = spotify.track_analysis_sections(id.as_ref()).await.unwrap();
+    assert_eq!(sections.len(), 1);
+    assert_eq!(sections[0].tempo, 120.0);
+
+    let result = spotify.track_analysis(id).await;
+    assert!(matches!(result, Err(ClientError::ParseJson(_))));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_extra_token_params() {
+    let creds = Credentials::new("test-client-id", "test-client-secret")
+        .with_token_param("audience", "spotify-gateway")
+        .with_token_param("grant_type", "ignored")
+        .with_token_header("x-gateway-key", "test-gateway-key");
+    let http = MockHttpClient::default();
+    let spotify = ClientCredsSpotify::with_config(creds, with_mock(Config::default(), &http));
+    http.respond(
+        "POST",
+        "https://accounts.spotify.com/api/token",
+        token_response(),
+    );
+    spotify.request_token().await.unwrap();
+
+    let requests = http.requests();
+    assert_eq!(requests[0].body["audience"], "spotify-gateway");
+    assert_eq!(requests[0].body["grant_type"], "client_credentials");
+    assert_eq!(requests[0].headers["x-gateway-key"], "test-gateway-key");
+    assert!(requests[0].headers["authorization"].starts_with("Basic "));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_extra_token_headers_invalid() {
+    for (name, value) in [
+        ("x gateway", "key"),
+        ("x-gateway-key", "key\nx-injected: 1"),
+    ] {
+        let creds =
+            Credentials::new("test-client-id", "test-client-secret").with_token_header(name, value);
+        let http = MockHttpClient::default();
+        let spotify = ClientCredsSpotify::with_config(creds, with_mock(Config::default(), &http));
+        let result = spotify.request_token().await;
+        assert!(matches!(result, Err(ClientError::InvalidHeader(header)) if header == name));
+        assert!(http.requests().is_empty());
+    }
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_extra_token_headers_case() {
+    let creds = Credentials::new("test-client-id", "test-client-secret")
+        .with_token_header("Authorization", "Bearer gateway");
+    let http = MockHttpClient::default();
+    let spotify = ClientCredsSpotify::with_config(creds, with_mock(Config::default(), &http));
+    http.respond(
+        "POST",
+        "https://accounts.spotify.com/api/token",
+        token_response(),
+    );
+    spotify.request_token().await.unwrap();
+
+    let requests = http.requests();
+    let authorization = requests[0]
+        .headers
+        .iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case("authorization"))
+        .map(|(_, value)| value.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(authorization.len(), 1);
+    assert!(authorization[0].starts_with("Basic "));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_next_page() {
+    let (spotify, http) = client();
+    let mut second = page(vec![json!(2)]);
+    second["offset"] = json!(1);
+    second["previous"] = json!("https://api.spotify.com/v1/me/tracks?offset=0&limit=1");
+    http.respond("GET", "/v1/me/tracks", MockResponse::json(&second));
+
+    let mut first = page(vec![json!(1)]);
+    first["next"] = json!("https://api.spotify.com/v1/me/tracks?offset=1&limit=1");
+    let first = serde_json::from_value::<Page<Value>>(first).unwrap();
+    let previous = spotify.previous_page(&first).await.unwrap();
+    assert_eq!(previous, None);
+
+    let second = spotify.next_page(&first).await.unwrap().unwrap();
+    assert_eq!(second.items, [json!(2)]);
+    let next = spotify.next_page(&second).await.unwrap();
+    assert_eq!(next, None);
+
+    let requests = http.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].url, "https://api.spotify.com/v1/me/tracks");
+    assert_eq!(requests[0].query["offset"], "1");
+    assert_eq!(requests[0].query["limit"], "1");
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_validate_scopes() {
+    let (spotify, http) = client_with_config(Config {
+        validate_scopes: true,
+        ..Default::default()
+    });
+    spotify
+        .get_token()
+        .lock()
+        .await
+        .unwrap()
+        .as_mut()
+        .unwrap()
+        .scopes = scopes!("user-library-read");
+    http.respond("GET", "/v1/me/tracks", MockResponse::json(&page(vec![])));
+
+    let saved = spotify
+        .current_user_saved_tracks_manual(None, None, None)
+        .await;
+    assert!(saved.is_ok());
+    let track = TrackId::from_id("4iV5W9uYEdYUVa79Axb7Rh").unwrap();
+    let added = spotify.current_user_saved_tracks_add([track]).await;
+    match added {
+        Err(ClientError::MissingScope(scope)) => assert_eq!(scope, "user-library-modify"),
+        other => panic!("unexpected result: {other:?}"),
+    }
+    assert_eq!(http.requests().len(), 1);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_builder() {
+    let http = MockHttpClient::default();
+    let spotify = SpotifyBuilder::try_new()
+        .unwrap()
+        .token(token())
+        .config(with_mock(
+            Config {
+                auto_chunk_requests: true,
+                ..Default::default()
+            },
+            &http,
+        ))
+        .client_credentials();
+    assert!(spotify.config.auto_chunk_requests);
+    http.respond(
+        "GET",
+        "/v1/artists/0OdUWJ0sBjDrqHygGUXeCF",
+        MockResponse::json(&artist(json!(80))),
+    );
+    let id = ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+    spotify.artist(id).await.unwrap();
+    assert_eq!(
+        http.requests()[0].headers["authorization"],
+        "Bearer test-access-token"
+    );
+
+    let spotify = SpotifyBuilder::new()
+        .oauth(OAuth {
+            scopes: scopes!("user-library-read"),
+            ..Default::default()
+        })
+        .credentials(Credentials::new_pkce("client-id"))
+        .pkce();
+    assert_eq!(spotify.creds.id, "client-id");
+    assert_eq!(spotify.oauth.scopes, scopes!("user-library-read"));
+    let token = spotify.token.lock().await.unwrap().clone();
+    assert!(token.is_none());
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_any_client() {
+    let (spotify, http) = client();
+    let spotify = AnyClient::from(spotify);
+    http.respond(
+        "GET",
+        "/v1/artists/0OdUWJ0sBjDrqHygGUXeCF",
+        MockResponse::json(&artist(json!(80))),
+    );
+    let id = ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+    spotify.artist(id).await.unwrap();
+    assert_eq!(
+        http.requests()[0].headers["authorization"],
+        "Bearer test-access-token"
+    );
+
+    let spotify = AnyOAuthClient::try_from(spotify).unwrap();
+    http.respond(
+        "GET",
+        "/v1/me/following/contains",
+        MockResponse::json(&json!([true])),
+    );
+    let ids = [UserId::from_id("someone").unwrap()];
+    let follows = spotify.user_check_follow_users(ids).await.unwrap();
+    assert_eq!(follows, vec![true]);
+
+    let spotify = AnyClient::from(ClientCredsSpotify::from_token(token()));
+    assert!(AnyOAuthClient::try_from(spotify).is_err());
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_client_creds_token_fetching() {
+    let http = MockHttpClient::default();
+    let spotify = ClientCredsSpotify::with_config(
+        Credentials::new("client-id", "client-secret"),
+        with_mock(Config::default(), &http),
+    );
+    http.respond(
+        "POST",
+        "https://accounts.spotify.com/api/token",
+        token_response(),
+    );
+    http.respond(
+        "GET",
+        "/v1/artists/0OdUWJ0sBjDrqHygGUXeCF",
+        MockResponse::json(&artist(json!(80))),
+    );
+    let id = ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+    spotify.artist(id.clone()).await.unwrap();
+    spotify.artist(id.clone()).await.unwrap();
+
+    // The token is only requested once, and then reused
+    let requests = http.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0].body["grant_type"], "client_credentials");
+    assert_eq!(
+        requests[2].headers["authorization"],
+        "Bearer new-access-token"
+    );
+
+    let http = MockHttpClient::default();
+    let config = Config {
+        token_fetching: false,
+        ..Default::default()
+    };
+    let spotify = ClientCredsSpotify::with_config(
+        Credentials::new("client-id", "client-secret"),
+        with_mock(config, &http),
+    );
+    let result = spotify.artist(id).await;
+    assert!(matches!(result, Err(ClientError::InvalidToken)));
+    assert!(http.requests().is_empty());
+
+    // The flows with user authorization can't obtain it by themselves
+    let result = AuthCodeSpotify::default().current_user().await;
+    assert!(matches!(result, Err(ClientError::InvalidToken)));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_pagination_progress() {
+    let (spotify, http) = client();
+    let track = cassette_items()[0]["track"].clone();
+    let saved = json!({ "added_at": "2024-01-05T08:00:00Z", "track": track });
+    let mut first = page(vec![saved.clone()]);
+    first["total"] = json!(2);
+    first["next"] = json!("https://api.spotify.com/v1/me/tracks?offset=1&limit=1");
+    let mut second = page(vec![saved]);
+    second["total"] = json!(2);
+    second["offset"] = json!(1);
+    http.respond("GET", "/v1/me/tracks", MockResponse::json(&first));
+    http.respond("GET", "/v1/me/tracks", MockResponse::json(&second));
+
+    let mut progress = Vec::new();
+    let paginator = paginate_with_progress(
+        |limit, offset| spotify.current_user_saved_tracks_manual(None, Some(limit), Some(offset)),
+        1,
+        |page| progress.push((page.page, page.fetched, page.total)),
+    );
+    let items = fetch_all(paginator).await;
+    assert_eq!(items.len(), 2);
+    assert_eq!(progress, [(0, 1, 2), (1, 2, 2)]);
+    assert_eq!(http.requests()[1].query["offset"], "1");
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_market_required() {
+    let http = MockHttpClient::default();
+    let spotify = SpotifyBuilder::new()
+        .token(token())
+        .config(with_mock(Config::default(), &http))
+        .client_credentials();
+    let path = "/v1/shows/5CfCWKI5pZ28U0uOzXkDHe";
+    let not_found = r#"{"error": {"status": 404, "message": "Non existing id"}}"#;
+    http.respond("GET", path, MockResponse::with_status(404, not_found));
+    let id = ShowId::from_id("5CfCWKI5pZ28U0uOzXkDHe").unwrap();
+    let result = spotify.get_a_show(id.clone(), None).await;
+    assert!(matches!(
+        result,
+        Err(ClientError::MarketRequired(endpoint)) if endpoint == "shows/5CfCWKI5pZ28U0uOzXkDHe"
+    ));
+
+    // With a market, the show really doesn't exist
+    let market = Some(Market::Country(Country::Spain));
+    let result = spotify.get_a_show(id.clone(), market).await;
+    assert!(matches!(result, Err(ClientError::Api { status: 404, .. })));
+
+    // The flows with user authorization use the country of the user instead
+    let (spotify, http) = client();
+    http.respond("GET", path, MockResponse::with_status(404, not_found));
+    let result = spotify.get_a_show(id, None).await;
+    assert!(matches!(result, Err(ClientError::Api { status: 404, .. })));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_episodes_by_show_bulk() {
+    let (spotify, http) = client();
+    let dated = |id: &str, date: &str, precision: &str| {
+        let mut episode = episode(id, None);
+        episode["release_date"] = json!(date);
+        episode["release_date_precision"] = json!(precision);
+        episode
+    };
+    let a = ShowId::from_id("5CfCWKI5pZ28U0uOzXkDHe").unwrap();
+    let b = ShowId::from_id("5as3aKmN2k11yfDDDSrvaZ").unwrap();
+    http.respond(
+        "GET",
+        "/v1/shows/5CfCWKI5pZ28U0uOzXkDHe/episodes",
+        MockResponse::json(&page(vec![
+            dated("a0", "2022", "year"),
+            dated("a1", "2021-03-05", "day"),
+            dated("a2", "2020", "year"),
+        ])),
+    );
+    http.respond(
+        "GET",
+        "/v1/shows/5as3aKmN2k11yfDDDSrvaZ/episodes",
+        MockResponse::json(&page(vec![
+            dated("b0", "2022-01-01", "day"),
+            dated("b1", "2021-03", "month"),
+            dated("b2", "2021-02-28", "day"),
+        ])),
+    );
+
+    let bulk = spotify.episodes_by_show_bulk([a, b], 3, None).await;
+    assert!(bulk.failed.is_empty());
+    let ids = bulk
+        .episodes
+        .iter()
+        .map(|(_, episode)| episode.id.id())
+        .collect::<Vec<_>>();
+    // The dates of the same day keep their order, regardless of the precision
+    assert_eq!(ids, ["a0", "b0", "a1", "b1", "b2", "a2"]);
+}
+
+#[allow(deprecated)]
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_tracks_features_map() {
+    let (spotify, http) = client_with_config(Config {
+        auto_chunk_requests: true,
+        ..Default::default()
+    });
+    let ids = (0..101).map(|i| format!("{i:0>22}")).collect::<Vec<_>>();
+    // The first chunk has a track without audio features
+    let mut first = ids[..100]
+        .iter()
+        .map(|id| audio_features(id))
+        .collect::<Vec<_>>();
+    first[1] = Value::Null;
+    http.respond(
+        "GET",
+        "/v1/audio-features/",
+        MockResponse::json(&json!({ "audio_features": first })),
+    );
+    http.respond(
+        "GET",
+        "/v1/audio-features/",
+        MockResponse::json(&json!({ "audio_features": [audio_features(&ids[100])] })),
+    );
+
+    let features = spotify
+        .tracks_features_map(ids.iter().map(|id| TrackId::from_id(id.as_str()).unwrap()))
+        .await
+        .unwrap();
+    assert_eq!(http.requests().len(), 2);
+    assert_eq!(features.len(), 100);
+    let last = TrackId::from_id(ids[100].as_str()).unwrap();
+    assert_eq!(features[&last].key, 5);
+    assert!(!features.contains_key(&TrackId::from_id(ids[1].as_str()).unwrap()));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_token_expiry_margin() {
+    // The token expires in an hour, which is already within the margin
+    let http = MockHttpClient::default();
+    let spotify = SpotifyBuilder::new()
+        .credentials(Credentials::new("client-id", "client-secret"))
+        .token(token())
+        .config(with_mock(
+            Config {
+                token_expiry_margin: Duration::try_hours(2).unwrap(),
+                ..Default::default()
+            },
+            &http,
+        ))
+        .client_credentials();
+    http.respond(
+        "POST",
+        "https://accounts.spotify.com/api/token",
+        token_response(),
+    );
+    http.respond(
+        "GET",
+        "/v1/artists/0OdUWJ0sBjDrqHygGUXeCF",
+        MockResponse::json(&artist(json!(80))),
+    );
+    let id = ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+    spotify.artist(id).await.unwrap();
+
+    let requests = http.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].body["grant_type"], "client_credentials");
+    assert_eq!(
+        requests[1].headers["authorization"],
+        "Bearer new-access-token"
+    );
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_sign_out() {
+    let cache_path = std::env::temp_dir().join("rspotify_test_sign_out.json");
+    let http = MockHttpClient::default();
+    let mut spotify = SpotifyBuilder::new()
+        .token(token())
+        .config(with_mock(
+            Config {
+                token_cached: true,
+                cache_path: cache_path.clone(),
+                ..Default::default()
+            },
+            &http,
+        ))
+        .pkce();
+    spotify.get_authorize_url(None).unwrap();
+    spotify.write_token_cache().await.unwrap();
+    http.respond("GET", "/v1/me", MockResponse::json(&user("alice")));
+    spotify.current_user_id().await.unwrap();
+    assert!(cache_path.exists());
+
+    // The clones share the token, but not the code verifier
+    let clone = spotify.clone();
+    spotify.sign_out().await.unwrap();
+    let token = spotify.token.lock().await.unwrap().clone();
+    assert_eq!(token, None);
+    assert_eq!(spotify.verifier, None);
+    assert!(clone.verifier.is_some());
+    let token = clone.token.lock().await.unwrap().clone();
+    assert_eq!(token, None);
+    assert!(!cache_path.exists());
+
+    // The cached ID of the previous user isn't returned anymore
+    let result = spotify.current_user_id().await;
+    assert!(matches!(result, Err(ClientError::InvalidToken)));
+
+    // Signing out again does nothing
+    spotify.sign_out().await.unwrap();
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_category() {
+    let (spotify, http) = client();
+    http.respond(
+        "GET",
+        "/v1/browse/categories/dinner",
+        MockResponse::json(&json!({
+            "href": "https://api.spotify.com/v1/browse/categories/dinner",
+            "icons": [],
+            "id": "dinner",
+            "name": "Cena",
+        })),
+    );
+
+    let category_id = CategoryId::from_id("dinner").unwrap();
+    let market = Market::Country(Country::Spain);
+    let category = spotify
+        .category(category_id, Some("es_ES"), Some(market))
+        .await
+        .unwrap();
+    assert_eq!(category.name, "Cena");
+    assert_eq!(category.category_id().unwrap().id(), "dinner");
+    let requests = http.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].query["locale"], "es_ES");
+    assert_eq!(requests[0].query["country"], "ES");
+}
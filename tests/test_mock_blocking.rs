@@ -0,0 +1,100 @@
+//! Tests for the blocking client, with the `client-mock` feature.
+#![cfg(all(feature = "client-mock", feature = "blocking"))]
+
+mod mock;
+
+use mock::{artist, client, page};
+use rspotify::{
+    blocking::{self, BlockingRunner},
+    http::MockResponse,
+    model::ArtistId,
+    prelude::*,
+    AuthCodeSpotify,
+};
+use serde_json::json;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+#[test]
+fn test_blocking() {
+    let (spotify, http) = client();
+    let spotify = BlockingRunner::new(spotify).unwrap();
+    http.respond(
+        "GET",
+        "/v1/artists/0OdUWJ0sBjDrqHygGUXeCF",
+        MockResponse::json(&artist(json!(80))),
+    );
+
+    let fetch = |spotify: AuthCodeSpotify| async move {
+        let id = ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+        spotify.artist(id).await
+    };
+    let artist = spotify.run(fetch);
+    assert_eq!(artist.unwrap().popularity, 80);
+
+    // The runtime survives the panics of the requests
+    let clone = spotify.clone();
+    let panicked = thread::spawn(move || clone.run(|_| async { panic!("request") }));
+    assert!(panicked.join().is_err());
+    assert!(spotify.run(fetch).is_ok());
+    assert_eq!(http.requests().len(), 2);
+}
+
+#[test]
+fn test_blocking_client() {
+    let (spotify, http) = client();
+    let spotify = blocking::AuthCodeSpotify::from_async(spotify).unwrap();
+    http.respond(
+        "GET",
+        "/v1/artists/0OdUWJ0sBjDrqHygGUXeCF",
+        MockResponse::json(&artist(json!(80))),
+    );
+    http.respond(
+        "GET",
+        "/v1/me/top/artists",
+        MockResponse::json(&page(vec![artist(json!(70)), artist(json!(60))])),
+    );
+
+    let id = ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+    assert_eq!(spotify.artist(id).unwrap().popularity, 80);
+
+    let top = spotify
+        .current_user_top_artists(None)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(top.len(), 2);
+    assert_eq!(top[1].popularity, 60);
+}
+
+#[test]
+fn test_blocking_concurrent() {
+    let (spotify, _) = client();
+    let spotify = BlockingRunner::new(spotify).unwrap();
+
+    // The first request waits for the second one, which would never start if
+    // they were run one after the other
+    let (started_tx, started_rx) = mpsc::channel();
+    let done = Arc::new(AtomicBool::new(false));
+    let (clone, waiting_done) = (spotify.clone(), Arc::clone(&done));
+    let waiting = thread::spawn(move || {
+        clone.run(move |_| async move {
+            started_tx.send(()).unwrap();
+            for _ in 0..500 {
+                if waiting_done.load(Ordering::SeqCst) {
+                    return true;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            false
+        })
+    });
+    started_rx.recv().unwrap();
+    spotify.run(move |_| async move { done.store(true, Ordering::SeqCst) });
+    assert!(waiting.join().unwrap());
+}
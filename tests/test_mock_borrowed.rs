@@ -0,0 +1,61 @@
+//! Tests for the borrowed models, with the `client-mock` feature.
+#![cfg(all(feature = "client-mock", feature = "model-borrowed"))]
+
+mod mock;
+
+use mock::{client_with_config, page};
+use rspotify::{http::MockResponse, prelude::*, Config};
+use serde_json::json;
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_saved_tracks_borrowed() {
+    use std::borrow::Cow;
+
+    let track = |id: &str, name: &str| {
+        json!({
+            "added_at": "2024-01-01T00:00:00Z",
+            "track": {
+                "album": { "artists": [], "name": "Album" },
+                "artists": [{ "id": "0OdUWJ0sBjDrqHygGUXeCF", "name": "Band of Horses" }],
+                "disc_number": 1,
+                "duration_ms": 1000,
+                "explicit": false,
+                "id": id,
+                "is_local": false,
+                "name": name,
+                "popularity": 10,
+                "track_number": 1,
+            }
+        })
+    };
+    let mut first = page(vec![track("4iV5W9uYEdYUVa79Axb7Rh", "Plain")]);
+    first["next"] = json!("https://api.spotify.com/v1/me/tracks?offset=1&limit=1");
+    let second = page(vec![track("1301WleyT98MSxVHPZCA6M", "With \"quotes\"")]);
+    let (spotify, http) = client_with_config(Config {
+        pagination_chunks: 1,
+        ..Default::default()
+    });
+    http.respond("GET", "/v1/me/tracks", MockResponse::json(&first));
+    http.respond("GET", "/v1/me/tracks", MockResponse::json(&second));
+
+    let mut tracks = Vec::new();
+    spotify
+        .current_user_saved_tracks_borrowed(None, |saved| {
+            // Only the strings with escape sequences have to be allocated
+            let escaped = saved.track.name.contains('"');
+            assert_eq!(matches!(saved.track.name, Cow::Owned(_)), escaped);
+            assert!(matches!(saved.track.id, Some(Cow::Borrowed(_))));
+            tracks.push(saved.into_owned());
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(tracks.len(), 2);
+    assert_eq!(tracks[1].track.name, "With \"quotes\"");
+    let id = tracks[0].track.track_id().unwrap().unwrap();
+    assert_eq!(id.id(), "4iV5W9uYEdYUVa79Axb7Rh");
+    assert_eq!(tracks[0].track.artists[0].name, "Band of Horses");
+    let requests = http.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[1].query["offset"], "1");
+}
@@ -0,0 +1,293 @@
+//! Tests for the library and the account backups, with the `client-mock`
+//! feature.
+#![cfg(feature = "client-mock")]
+
+mod mock;
+
+use mock::{cassette_items, client, client_with_config, episode, fetch_all, page, playlist, user};
+use rspotify::{
+    backup::{backup_account, restore_account, AccountBackup, Checkpoint, Section},
+    http::MockResponse,
+    model::{Country, EpisodeId, JsonModel, Market, ShowId, TrackId, UserId},
+    prelude::*,
+    Config,
+};
+use serde_json::json;
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_backup_and_restore() {
+    let (spotify, http) = client();
+    let items = cassette_items();
+    let mut simplified = playlist("1111111111111111111111", "alice", Vec::new());
+    simplified["tracks"] = json!({ "href": "", "total": 2 });
+    let mut followed = playlist("2222222222222222222222", "bob", Vec::new());
+    followed["tracks"] = json!({ "href": "", "total": 0 });
+
+    http.respond("GET", "/v1/me", MockResponse::json(&user("alice")));
+    http.respond(
+        "GET",
+        "/v1/me/playlists",
+        MockResponse::json(&page(vec![simplified, followed])),
+    );
+    http.respond(
+        "GET",
+        "/v1/playlists/1111111111111111111111",
+        MockResponse::json(&playlist("1111111111111111111111", "alice", items)),
+    );
+    http.respond(
+        "GET",
+        "/v1/playlists/2222222222222222222222",
+        MockResponse::json(&playlist("2222222222222222222222", "bob", Vec::new())),
+    );
+    // The saved tracks fail the first time
+    http.respond(
+        "GET",
+        "/v1/me/tracks",
+        MockResponse::with_status(503, "unavailable"),
+    );
+    let track = cassette_items()[0]["track"].clone();
+    let saved = json!({ "added_at": "2024-01-05T08:00:00Z", "track": track });
+    http.respond(
+        "GET",
+        "/v1/me/tracks",
+        MockResponse::json(&page(vec![saved])),
+    );
+    for path in ["/v1/me/albums", "/v1/me/shows", "/v1/me/episodes"] {
+        http.respond("GET", path, MockResponse::json(&page(Vec::new())));
+    }
+    let mut artists = page(Vec::new());
+    artists["cursors"] = json!({ "after": null });
+    http.respond(
+        "GET",
+        "/v1/me/following",
+        MockResponse::json(&json!({ "artists": artists })),
+    );
+
+    let mut backup = AccountBackup::default();
+    let mut reports = 0;
+    let result = backup_account(&spotify, &mut backup, |_| reports += 1).await;
+    assert!(result.is_err());
+    assert_eq!(backup.checkpoint.section, Section::SavedTracks);
+    assert_eq!(backup.playlists[0].items.len(), 2);
+    assert_eq!(
+        backup.playlists[0].description.as_deref(),
+        Some("Backed up")
+    );
+
+    // It's resumed from the saved tracks, after being saved and loaded
+    let mut backup = AccountBackup::from_json_str(&backup.to_json_string().unwrap()).unwrap();
+    let result = backup_account(&spotify, &mut backup, |_| reports += 1).await;
+    result.unwrap();
+    assert!(backup.is_complete());
+    assert_eq!(backup.saved_tracks.len(), 1);
+    assert!(reports > 0);
+    let requests = http.requests();
+    let playlist_requests = requests
+        .iter()
+        .filter(|request| request.url.contains("/v1/playlists/"))
+        .count();
+    assert_eq!(playlist_requests, 2);
+
+    // The playlist of another user is followed, and the owned one created
+    http.reset();
+    http.respond("GET", "/v1/me", MockResponse::json(&user("carol")));
+    http.respond(
+        "PUT",
+        "/v1/playlists/2222222222222222222222/followers",
+        MockResponse::ok(""),
+    );
+    http.respond(
+        "GET",
+        "/v1/playlists/2222222222222222222222",
+        MockResponse::json(&json!({ "snapshot_id": "snapshot" })),
+    );
+    http.respond(
+        "POST",
+        "/v1/users/carol/playlists",
+        MockResponse::json(&playlist("3333333333333333333333", "carol", Vec::new())),
+    );
+    http.respond(
+        "POST",
+        "/v1/playlists/3333333333333333333333/tracks",
+        MockResponse::json(&json!({ "snapshot_id": "snapshot" })),
+    );
+    http.respond("PUT", "/v1/me/tracks", MockResponse::ok(""));
+
+    let mut checkpoint = Checkpoint::default();
+    let result = restore_account(&spotify, &backup, &mut checkpoint, |_| {}).await;
+    result.unwrap();
+    assert_eq!(checkpoint.section, Section::Done);
+
+    let requests = http.requests();
+    let methods = requests
+        .iter()
+        .map(|request| request.method.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(methods, ["PUT", "GET", "GET", "POST", "POST", "PUT"]);
+    assert_eq!(
+        requests[4].body["uris"],
+        json!([
+            "spotify:track:11dFghVXANMlKmJXsNCbNl",
+            "spotify:episode:512ojhOuo1ktJprKbVcKyQ",
+        ])
+    );
+    assert!(requests[5].url.ends_with("ids=11dFghVXANMlKmJXsNCbNl"));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_episode_resume_points() {
+    let (spotify, http) = client();
+    let show = |id: &str| {
+        let mut show = episode("", None)["show"].clone();
+        show["id"] = json!(id);
+        json!({ "added_at": "2024-01-01T00:00:00Z", "show": show })
+    };
+    http.respond(
+        "GET",
+        "/v1/me/shows",
+        MockResponse::json(&page(vec![
+            show("1111111111111111111111"),
+            show("2222222222222222222222"),
+        ])),
+    );
+    http.respond(
+        "GET",
+        "/v1/shows/1111111111111111111111/episodes",
+        MockResponse::json(&page(vec![episode("aaaaaaaaaaaaaaaaaaaaaa", Some(1000))])),
+    );
+    http.respond(
+        "GET",
+        "/v1/shows/2222222222222222222222/episodes",
+        MockResponse::json(&page(vec![
+            episode("bbbbbbbbbbbbbbbbbbbbbb", None),
+            episode("cccccccccccccccccccccc", Some(0)),
+        ])),
+    );
+    http.respond(
+        "GET",
+        "/v1/episodes",
+        MockResponse::json(&json!({
+            "episodes": [
+                episode("aaaaaaaaaaaaaaaaaaaaaa", Some(1000)),
+                episode("bbbbbbbbbbbbbbbbbbbbbb", None),
+            ]
+        })),
+    );
+
+    let episodes = fetch_all(spotify.saved_shows_episodes(None)).await;
+    let shows = episodes
+        .iter()
+        .map(|(show, episode)| (show.id(), episode.resume_point.is_some()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        shows,
+        [
+            ("1111111111111111111111", true),
+            ("2222222222222222222222", false),
+            ("2222222222222222222222", true),
+        ]
+    );
+
+    let a = EpisodeId::from_id("aaaaaaaaaaaaaaaaaaaaaa").unwrap();
+    let b = EpisodeId::from_id("bbbbbbbbbbbbbbbbbbbbbb").unwrap();
+    let resume_points = spotify
+        .get_episodes_resume_points([a.as_ref(), b], None)
+        .await
+        .unwrap();
+    assert_eq!(resume_points.len(), 1);
+    assert_eq!(resume_points[&a].resume_position.num_milliseconds(), 1000);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_library() {
+    let (spotify, http) = client();
+    http.respond("PUT", "/v1/me/tracks/", MockResponse::ok(""));
+    http.respond("DELETE", "/v1/me/shows", MockResponse::ok(""));
+    http.respond(
+        "GET",
+        "/v1/me/shows/contains",
+        MockResponse::json(&json!([true, false])),
+    );
+
+    let library = spotify.library();
+    let track = TrackId::from_id("4iV5W9uYEdYUVa79Axb7Rh").unwrap();
+    library.tracks().add([track]).await.unwrap();
+
+    let shows = [
+        ShowId::from_id("5CfCWKI5pZ28U0uOzXkDHe").unwrap(),
+        ShowId::from_id("5as3aKmN2k11yfDDDSrvaZ").unwrap(),
+    ];
+    library.shows().remove(shows.clone()).await.unwrap();
+    let contains = library.shows().contains(shows).await.unwrap();
+    assert_eq!(contains, [true, false]);
+
+    let requests = http.requests();
+    assert!(requests[0]
+        .url
+        .ends_with("/me/tracks/?ids=4iV5W9uYEdYUVa79Axb7Rh"));
+    assert!(requests[1]
+        .url
+        .ends_with("/me/shows?ids=5CfCWKI5pZ28U0uOzXkDHe,5as3aKmN2k11yfDDDSrvaZ"));
+    assert_eq!(
+        requests[2].query["ids"],
+        "5CfCWKI5pZ28U0uOzXkDHe,5as3aKmN2k11yfDDDSrvaZ"
+    );
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_saved_shows_delete_market() {
+    let (spotify, http) = client_with_config(Config {
+        default_market: Some(Market::Country(Country::Spain)),
+        ..Default::default()
+    });
+    http.respond("DELETE", "/v1/me/shows", MockResponse::ok(""));
+
+    let show = ShowId::from_id("5CfCWKI5pZ28U0uOzXkDHe").unwrap();
+    spotify
+        .current_user_saved_shows_delete([show.as_ref()], None)
+        .await
+        .unwrap();
+    spotify
+        .current_user_saved_shows_delete([show], Some(Market::FromToken))
+        .await
+        .unwrap();
+
+    let requests = http.requests();
+    assert!(requests[0]
+        .url
+        .ends_with("/me/shows?ids=5CfCWKI5pZ28U0uOzXkDHe&market=ES"));
+    assert!(requests[1]
+        .url
+        .ends_with("/me/shows?ids=5CfCWKI5pZ28U0uOzXkDHe&market=from_token"));
+    assert_eq!(requests[0].body, json!({}));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_followed_users() {
+    let (spotify, http) = client_with_config(Config {
+        auto_chunk_requests: true,
+        ..Default::default()
+    });
+    let contains = "/v1/me/following/contains";
+    http.respond("GET", contains, MockResponse::json(&json!(vec![true; 50])));
+    http.respond("GET", contains, MockResponse::json(&json!([false])));
+    http.respond("PUT", "/v1/me/following", MockResponse::ok(""));
+
+    let ids = (0..51)
+        .map(|i| UserId::from_id(format!("user{i}")).unwrap())
+        .collect::<Vec<_>>();
+    let users = spotify.library().users();
+    let follows = users.contains(ids.clone()).await.unwrap();
+    assert_eq!(follows.len(), 51);
+    assert!(follows[..50].iter().all(|&follows| follows));
+    assert!(!follows[50]);
+    users.add(ids).await.unwrap();
+
+    let requests = http.requests();
+    assert_eq!(requests.len(), 4);
+    assert!(requests[0].url.contains("type=user&ids=user0,user1,"));
+    assert!(requests[1]
+        .url
+        .ends_with("/me/following/contains?type=user&ids=user50"));
+    assert!(requests[3].url.ends_with("type=user&ids=user50"));
+}
@@ -0,0 +1,262 @@
+//! Tests for the player endpoints and helpers, with the `client-mock` feature.
+#![cfg(feature = "client-mock")]
+
+mod mock;
+
+use mock::{cassette_items, client, client_with_config, devices, playback};
+use rspotify::{
+    http::MockResponse,
+    model::{Country, DeviceId, EpisodeId, Market, PlayableId, PlaylistId, TrackId},
+    prelude::*,
+    retry::RetryPolicy,
+    ClientError, Config,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_transfer_playback_to_device() {
+    let (spotify, http) = client();
+    http.respond(
+        "GET",
+        "/v1/me/player/devices",
+        MockResponse::json(&devices()),
+    );
+    http.respond("PUT", "/v1/me/player", MockResponse::ok(""));
+    http.respond("PUT", "/v1/me/player/volume", MockResponse::ok(""));
+
+    let devices = spotify.device().await.unwrap();
+    let device = devices[0].device_id().unwrap();
+    spotify
+        .transfer_playback(device.as_ref(), Some(true))
+        .await
+        .unwrap();
+    spotify.volume(40, Some(device)).await.unwrap();
+
+    let requests = http.requests();
+    assert_eq!(
+        requests[1].body,
+        json!({ "device_ids": ["0d1841b0976bae2a3a310dd74c0f3df354899bc8"], "play": true })
+    );
+    assert!(requests[2]
+        .url
+        .ends_with("volume_percent=40&device_id=0d1841b0976bae2a3a310dd74c0f3df354899bc8"));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_play_on_device() {
+    let (spotify, http) = client();
+    http.respond(
+        "GET",
+        "/v1/me/player/devices",
+        MockResponse::json(&json!({
+            "devices": [{
+                "id": null,
+                "is_active": false,
+                "is_private_session": false,
+                "is_restricted": true,
+                "name": "Kitchen speaker",
+                "type": "Speaker",
+                "volume_percent": null,
+            }, {
+                "id": "0d1841b0976bae2a3a310dd74c0f3df354899bc8",
+                "is_active": false,
+                "is_private_session": false,
+                "is_restricted": false,
+                "name": "Kitchen Speaker",
+                "type": "Speaker",
+                "volume_percent": 59,
+            }]
+        })),
+    );
+    http.respond("PUT", "/v1/me/player", MockResponse::ok(""));
+    http.respond("PUT", "/v1/me/player/play", MockResponse::ok(""));
+
+    let playlist = PlaylistId::from_id("37i9dQZF1DXcBWIGoYBM5M").unwrap();
+    let device = spotify
+        .play_on_device(" kitchen speaker", playlist.into(), None)
+        .await
+        .unwrap();
+    assert_eq!(device.name, "Kitchen Speaker");
+
+    let requests = http.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(
+        requests[1].body,
+        json!({ "device_ids": ["0d1841b0976bae2a3a310dd74c0f3df354899bc8"], "play": false })
+    );
+    assert!(requests[2]
+        .url
+        .ends_with("/me/player/play?device_id=0d1841b0976bae2a3a310dd74c0f3df354899bc8"));
+    assert_eq!(
+        requests[2].body["context_uri"],
+        "spotify:playlist:37i9dQZF1DXcBWIGoYBM5M"
+    );
+
+    let playlist = PlaylistId::from_id("37i9dQZF1DXcBWIGoYBM5M").unwrap();
+    let result = spotify
+        .play_on_device("Living Room", playlist.into(), None)
+        .await;
+    assert!(matches!(result, Err(ClientError::DeviceNotFound(name)) if name == "Living Room"));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_seek_track_clamped_device() {
+    let (spotify, http) = client();
+    http.respond("GET", "/v1/me/player", MockResponse::json(&playback(true)));
+    http.respond("PUT", "/v1/me/player/seek", MockResponse::ok(""));
+    let position = std::time::Duration::from_secs(25);
+
+    // The device that's playing
+    let playing = DeviceId::from_id("28d0f845293d03a2713392905c6d30b6442719b5").unwrap();
+    let sought = spotify
+        .seek_track_clamped(position, Some(playing))
+        .await
+        .unwrap();
+    assert_eq!(sought, position);
+    assert_eq!(http.requests().len(), 2);
+
+    // Another device, whose item is unknown
+    let other = DeviceId::from_id("0d1841b0976bae2a3a310dd74c0f3df354899bc8").unwrap();
+    let result = spotify.seek_track_clamped(position, Some(other)).await;
+    assert!(matches!(
+        result,
+        Err(ClientError::DeviceNotFound(id)) if id == "0d1841b0976bae2a3a310dd74c0f3df354899bc8"
+    ));
+    assert_eq!(http.requests().len(), 3);
+}
+
+#[cfg(feature = "__async")]
+#[tokio::test]
+async fn test_player_state_stream() {
+    use futures::stream::{StreamExt, TryStreamExt};
+    use rspotify::{model::PlaybackChange, player_state::player_state_stream};
+
+    let (spotify, http) = client();
+    for response in [
+        MockResponse::json(&playback(true)),
+        MockResponse::json(&playback(true)),
+        MockResponse::json(&playback(false)),
+        MockResponse::with_status(204, ""),
+    ] {
+        http.respond("GET", "/v1/me/player", response);
+    }
+
+    let updates = player_state_stream(&spotify, std::time::Duration::ZERO)
+        .take(3)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert!(updates[0].state.as_ref().unwrap().is_playing);
+    assert!(updates[0].changes.is_empty());
+    // The repeated state is skipped
+    assert_eq!(updates[1].changes, vec![PlaybackChange::Playing(false)]);
+    assert_eq!(updates[2].state, None);
+    assert_eq!(http.requests().len(), 4);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_add_items_to_queue() {
+    // The rate limited request is retried by the configured policy
+    let policy = RetryPolicy::default().with_base_delay(std::time::Duration::from_millis(1));
+    let (spotify, http) = client_with_config(Config {
+        retry_policy: Some(Arc::new(policy)),
+        ..Default::default()
+    });
+    let path = "/v1/me/player/queue";
+    http.respond("POST", path, MockResponse::ok(""));
+    http.respond("POST", path, MockResponse::with_status(429, ""));
+    http.respond("POST", path, MockResponse::ok(""));
+    http.respond("POST", path, MockResponse::with_status(404, ""));
+
+    let items = [
+        PlayableId::Track(TrackId::from_id("4iV5W9uYEdYUVa79Axb7Rh").unwrap()),
+        PlayableId::Episode(EpisodeId::from_id("512ojhOuo1ktJprKbVcKyQ").unwrap()),
+        PlayableId::Track(TrackId::from_id("1301WleyT98MSxVHPZCA6M").unwrap()),
+    ];
+    let failed = spotify.add_items_to_queue(items.clone(), None).await;
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].0, items[2]);
+    assert!(matches!(failed[0].1, ClientError::Api { status: 404, .. }));
+
+    let requests = http.requests();
+    assert_eq!(requests.len(), 4);
+    assert!(requests[1]
+        .url
+        .ends_with("uri=spotify%3Aepisode%3A512ojhOuo1ktJprKbVcKyQ"));
+    assert_eq!(requests[1].url, requests[2].url);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_playback_market() {
+    let (spotify, http) = client_with_config(Config {
+        default_market: Some(Market::Country(Country::Spain)),
+        ..Default::default()
+    });
+    http.respond("GET", "/v1/me/player", MockResponse::json(&playback(true)));
+    http.respond(
+        "GET",
+        "/v1/me/player/currently-playing",
+        MockResponse::with_status(204, ""),
+    );
+    spotify
+        .current_playback(Some(Market::FromToken), None::<&[_]>)
+        .await
+        .unwrap();
+    spotify.current_user_playing_item().await.unwrap();
+
+    // Relinking is applied to the playback through the market
+    let requests = http.requests();
+    assert_eq!(requests[0].query["market"], "from_token");
+    assert!(!requests[0].query.contains_key("country"));
+    assert_eq!(requests[1].query["market"], "ES");
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_recently_played_since() {
+    let (spotify, http) = client();
+    let track = cassette_items()[0]["track"].clone();
+    let history_page = |played_at: &[i64], before: i64| {
+        let items = played_at
+            .iter()
+            .map(|millis| {
+                let played_at = chrono::DateTime::from_timestamp_millis(*millis).unwrap();
+                json!({ "track": track, "played_at": played_at, "context": null })
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "href": "",
+            "items": items,
+            "limit": 50,
+            "next": "https://api.spotify.com/v1/me/player/recently-played",
+            "cursors": { "after": played_at[0].to_string(), "before": before.to_string() },
+        })
+    };
+    let path = "/v1/me/player/recently-played";
+    http.respond(
+        "GET",
+        path,
+        MockResponse::json(&history_page(&[5000, 4000], 4000)),
+    );
+    http.respond(
+        "GET",
+        path,
+        MockResponse::json(&history_page(&[3000, 2000], 2000)),
+    );
+
+    // The second page reaches the last collected play, so it stops there
+    let after = chrono::DateTime::from_timestamp_millis(2000).unwrap();
+    let history = spotify
+        .current_user_recently_played_since(Some(after))
+        .await
+        .unwrap();
+    let played_at = history
+        .iter()
+        .map(|item| item.played_at.timestamp_millis())
+        .collect::<Vec<_>>();
+    assert_eq!(played_at, [3000, 4000, 5000]);
+    let requests = http.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(!requests[0].query.contains_key("before"));
+    assert_eq!(requests[1].query["before"], "4000");
+}
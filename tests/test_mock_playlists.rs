@@ -0,0 +1,344 @@
+//! Tests for the playlist endpoints and helpers, with the `client-mock`
+//! feature.
+#![cfg(feature = "client-mock")]
+
+mod mock;
+
+use mock::{cassette_items, client, client_with_config, page};
+use rspotify::{
+    http::MockResponse,
+    model::{
+        AdditionalType, EpisodeId, ItemPositions, PlayableId, PlaylistDetails, PlaylistId, TrackId,
+    },
+    playlist_sync::{playlist_sync, SyncOperation},
+    prelude::*,
+    ClientError, Config, OnSnapshotChange,
+};
+use serde_json::json;
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_remove_specific_occurrences_chunked() {
+    let (spotify, http) = client_with_config(Config {
+        auto_chunk_requests: true,
+        ..Default::default()
+    });
+    let path = "/v1/playlists/37i9dQZF1DXcBWIGoYBM5M/tracks";
+    http.respond(
+        "DELETE",
+        path,
+        MockResponse::json(&json!({"snapshot_id": "s1"})),
+    );
+    http.respond(
+        "DELETE",
+        path,
+        MockResponse::json(&json!({"snapshot_id": "s2"})),
+    );
+
+    // The 202 items of a playlist, as 101 pairs of positions
+    let track = TrackId::from_id("4iV5W9uYEdYUVa79Axb7Rh").unwrap();
+    let positions = (0..101).map(|i| [i * 2, i * 2 + 1]).collect::<Vec<_>>();
+    let items = positions.iter().map(|positions| ItemPositions {
+        id: PlayableId::Track(track.as_ref()),
+        positions,
+    });
+    let playlist = PlaylistId::from_id("37i9dQZF1DXcBWIGoYBM5M").unwrap();
+    let snapshot = spotify
+        .playlist_remove_specific_occurrences_of_items(playlist, items, Some("s0"))
+        .await
+        .unwrap();
+    assert_eq!(snapshot.as_str(), "s2");
+
+    let requests = http.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].body["snapshot_id"], "s0");
+    assert_eq!(requests[0].body["tracks"].as_array().unwrap().len(), 100);
+    assert_eq!(
+        requests[0].body["tracks"][99]["positions"],
+        json!([198, 199])
+    );
+    // The 200 items removed first were before the last two
+    assert_eq!(requests[1].body["snapshot_id"], "s1");
+    assert_eq!(requests[1].body["tracks"][0]["positions"], json!([0, 1]));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_playlist_additional_types() {
+    let (spotify, http) = client();
+    let id = PlaylistId::from_id("37i9dQZF1DXcBWIGoYBM5M").unwrap();
+    let url = "/v1/playlists/37i9dQZF1DXcBWIGoYBM5M/tracks";
+    http.respond("GET", url, MockResponse::json(&page(vec![])));
+
+    spotify
+        .playlist_items_manual(id.as_ref(), None, None, None, None, None)
+        .await
+        .unwrap();
+    let types = [AdditionalType::Track];
+    spotify
+        .playlist_items_manual(id, None, None, Some(&types), None, None)
+        .await
+        .unwrap();
+
+    let requests = http.requests();
+    assert_eq!(requests[0].query["additional_types"], "track,episode");
+    assert_eq!(requests[1].query["additional_types"], "track");
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_playlist_sync() {
+    let (spotify, http) = client();
+    let path = "/v1/playlists/1111111111111111111111";
+    let items_path = "/v1/playlists/1111111111111111111111/tracks";
+    http.respond(
+        "GET",
+        path,
+        MockResponse::json(&json!({ "snapshot_id": "first" })),
+    );
+    http.respond(
+        "GET",
+        items_path,
+        MockResponse::json(&page(cassette_items())),
+    );
+    for method in ["DELETE", "POST"] {
+        http.respond(
+            method,
+            items_path,
+            MockResponse::json(&json!({ "snapshot_id": "next" })),
+        );
+    }
+
+    // The track is removed and another one is added after the episode
+    let playlist_id = PlaylistId::from_id("1111111111111111111111").unwrap();
+    let episode = EpisodeId::from_id("512ojhOuo1ktJprKbVcKyQ").unwrap();
+    let track = TrackId::from_id("4iV5W9uYEdYUVa79Axb7Rh").unwrap();
+    let desired = [
+        PlayableId::Episode(episode),
+        PlayableId::Track(track.clone()),
+    ];
+    let planned = playlist_sync(&spotify, playlist_id.clone(), &desired, true)
+        .await
+        .unwrap();
+    assert_eq!(planned.len(), 2);
+    assert!(http
+        .requests()
+        .iter()
+        .all(|request| request.method == "GET"));
+
+    let applied = playlist_sync(&spotify, playlist_id, &desired, false)
+        .await
+        .unwrap();
+    assert_eq!(applied, planned);
+    assert_eq!(
+        applied[1],
+        SyncOperation::Add {
+            items: vec![PlayableId::Track(track)],
+            position: 1
+        }
+    );
+    let requests = http.requests();
+    let delete = requests.iter().find(|r| r.method == "DELETE").unwrap();
+    assert_eq!(delete.body["snapshot_id"], "first");
+    assert_eq!(
+        delete.body["tracks"],
+        json!([{ "uri": "spotify:track:11dFghVXANMlKmJXsNCbNl", "positions": [0] }])
+    );
+    let post = requests.iter().find(|r| r.method == "POST").unwrap();
+    assert_eq!(post.body["position"], 1);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_playlist_items_snapshot() {
+    let (spotify, http) = client();
+    let path = "/v1/playlists/1111111111111111111111";
+    let items_path = "/v1/playlists/1111111111111111111111/tracks";
+    let playlist_id = PlaylistId::from_id("1111111111111111111111").unwrap();
+    let mut first_page = page(cassette_items());
+    first_page["next"] = json!("https://api.spotify.com/v1/page?offset=2");
+    let snapshot = |id| MockResponse::json(&json!({ "snapshot_id": id }));
+
+    // The snapshot is only checked before and after the two pages
+    http.respond("GET", path, snapshot("first"));
+    http.respond("GET", items_path, MockResponse::json(&first_page));
+    http.respond(
+        "GET",
+        items_path,
+        MockResponse::json(&page(cassette_items())),
+    );
+    let (snapshot_id, items) = spotify
+        .playlist_items_snapshot(playlist_id.as_ref(), None, OnSnapshotChange::Abort)
+        .await
+        .unwrap();
+    assert_eq!(snapshot_id, "first");
+    assert_eq!(items.len(), 4);
+    assert_eq!(http.requests().len(), 4);
+
+    // A change is detected at the end, and the read is restarted
+    http.reset();
+    http.respond("GET", path, snapshot("first"));
+    http.respond("GET", path, snapshot("second"));
+    http.respond(
+        "GET",
+        items_path,
+        MockResponse::json(&page(cassette_items())),
+    );
+    let (snapshot_id, items) = spotify
+        .playlist_items_snapshot(
+            playlist_id.as_ref(),
+            None,
+            OnSnapshotChange::Restart { max_restarts: 1 },
+        )
+        .await
+        .unwrap();
+    assert_eq!(snapshot_id, "second");
+    assert_eq!(items.len(), 2);
+    assert_eq!(http.requests().len(), 6);
+
+    // Or it fails
+    http.reset();
+    http.respond("GET", path, snapshot("first"));
+    http.respond("GET", path, snapshot("second"));
+    http.respond(
+        "GET",
+        items_path,
+        MockResponse::json(&page(cassette_items())),
+    );
+    let result = spotify
+        .playlist_items_snapshot(playlist_id, None, OnSnapshotChange::Abort)
+        .await;
+    assert!(matches!(
+        result,
+        Err(ClientError::SnapshotChanged { expected, found }) if expected == "first" && found == "second"
+    ));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_playlist_change_detail() {
+    let (spotify, http) = client();
+    let path = "/v1/playlists/1111111111111111111111";
+    http.respond("PUT", path, MockResponse::ok(""));
+
+    // Only the given details are sent, without fetching the snapshot ID
+    let playlist_id = PlaylistId::from_id("1111111111111111111111").unwrap();
+    let details = PlaylistDetails::new().name("Road trip").public(false);
+    spotify
+        .playlist_change_detail(playlist_id, details)
+        .await
+        .unwrap();
+    let requests = http.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        requests[0].body,
+        json!({ "name": "Road trip", "public": false })
+    );
+}
+
+/// The details of the playlist, as requested by the `PlaylistWatcher`.
+fn watched_playlist(snapshot_id: &str, name: &str) -> MockResponse {
+    MockResponse::json(&json!({
+        "collaborative": false,
+        "description": null,
+        "name": name,
+        "public": true,
+        "snapshot_id": snapshot_id,
+    }))
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_playlist_watcher() {
+    use rspotify::playlist_watcher::{PlaylistChange, PlaylistWatcher};
+
+    let (spotify, http) = client();
+    let path = "/v1/playlists/1111111111111111111111";
+    let items_path = "/v1/playlists/1111111111111111111111/tracks";
+    let items = cassette_items();
+    // The details and the snapshot ID before and after reading the items,
+    // plus the details of the poll without changes
+    for _ in 0..4 {
+        http.respond("GET", path, watched_playlist("first", "Mix"));
+    }
+    for _ in 0..3 {
+        http.respond("GET", path, watched_playlist("second", "Road trip"));
+    }
+    http.respond(
+        "GET",
+        items_path,
+        MockResponse::json(&page(vec![items[0].clone()])),
+    );
+    http.respond(
+        "GET",
+        items_path,
+        MockResponse::json(&page(vec![items[1].clone()])),
+    );
+
+    // The first poll only records the playlist
+    let playlist_id = PlaylistId::from_id("1111111111111111111111").unwrap();
+    let mut watcher = PlaylistWatcher::default();
+    let changes = watcher.poll(&spotify, playlist_id.clone()).await.unwrap();
+    assert!(changes.is_empty());
+    assert!(watcher.is_watched(&playlist_id));
+    assert_eq!(watcher.snapshot_id(&playlist_id), Some("first"));
+
+    // The items aren't read again while the snapshot stays the same
+    let requests = http.requests().len();
+    let changes = watcher.poll(&spotify, playlist_id.clone()).await.unwrap();
+    assert!(changes.is_empty());
+    assert_eq!(http.requests().len(), requests + 1);
+
+    let changes = watcher.poll(&spotify, playlist_id.clone()).await.unwrap();
+    assert_eq!(watcher.snapshot_id(&playlist_id), Some("second"));
+    let track = TrackId::from_id("11dFghVXANMlKmJXsNCbNl").unwrap();
+    let episode = EpisodeId::from_id("512ojhOuo1ktJprKbVcKyQ").unwrap();
+    assert_eq!(changes.len(), 3);
+    assert_eq!(
+        changes[0],
+        PlaylistChange::ItemsAdded {
+            playlist_id: playlist_id.clone(),
+            items: vec![PlayableId::Episode(episode)],
+        }
+    );
+    assert_eq!(
+        changes[1],
+        PlaylistChange::ItemsRemoved {
+            playlist_id: playlist_id.clone(),
+            items: vec![PlayableId::Track(track)],
+        }
+    );
+    match &changes[2] {
+        PlaylistChange::DetailsChanged { from, to, .. } => {
+            assert_eq!(from.name, "Mix");
+            assert_eq!(to.name, "Road trip");
+        }
+        change => panic!("unexpected change: {change:?}"),
+    }
+}
+
+#[cfg(feature = "__async")]
+#[tokio::test]
+async fn test_playlist_watcher_changes() {
+    use futures::stream::{StreamExt, TryStreamExt};
+    use rspotify::playlist_watcher::{PlaylistChange, PlaylistWatcher};
+
+    let (spotify, http) = client();
+    let path = "/v1/playlists/1111111111111111111111";
+    for _ in 0..3 {
+        http.respond("GET", path, watched_playlist("first", "Mix"));
+    }
+    http.respond("GET", path, watched_playlist("second", "Road trip"));
+    http.respond(
+        "GET",
+        "/v1/playlists/1111111111111111111111/tracks",
+        MockResponse::json(&page(Vec::new())),
+    );
+
+    let playlist_id = PlaylistId::from_id("1111111111111111111111").unwrap();
+    let watcher = PlaylistWatcher::new([playlist_id]);
+    let changes = watcher
+        .into_changes(&spotify, std::time::Duration::ZERO)
+        .take(1)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert!(matches!(
+        &changes[0],
+        PlaylistChange::DetailsChanged { to, .. } if to.name == "Road trip"
+    ));
+}
@@ -1,12 +1,34 @@
 use chrono::{DateTime, Duration};
 use rspotify::model::*;
-use serde::de::DeserializeOwned;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
 use wasm_bindgen_test::*;
 
 #[track_caller]
 fn deserialize<T>(data: impl AsRef<str>) -> T
 where
-    T: DeserializeOwned + PartialEq + std::fmt::Debug,
+    T: DeserializeOwned + Serialize + PartialEq + std::fmt::Debug,
+{
+    let value = deserialize_normalized(data.as_ref());
+
+    // And it must be serialized in the same shape as sent by Spotify, so
+    // that it's accepted wherever the original JSON is. Only the fields that
+    // aren't modeled, and the absent ones serialized as their defaults, may
+    // differ.
+    let input: Value = serde_json::from_str(data.as_ref()).unwrap();
+    let output = serde_json::to_value(&value).unwrap();
+    assert_same_shape(&output, &input, "$");
+    assert_no_unknown_fields(&input, &output, "$");
+
+    value
+}
+
+/// Same as [`deserialize`], for the JSON with values that are normalized when
+/// parsed, such as aliases, so that they aren't serialized back the same.
+#[track_caller]
+fn deserialize_normalized<T>(data: impl AsRef<str>) -> T
+where
+    T: DeserializeOwned + Serialize + PartialEq + std::fmt::Debug,
 {
     println!("from_str");
     let value = serde_json::from_str(data.as_ref()).unwrap();
@@ -15,9 +37,115 @@ where
     println!("from_reader");
     let _: T = serde_json::from_reader(data.as_ref().as_bytes()).unwrap();
 
+    // The models are persisted by some applications, so serializing them
+    // mustn't lose any information
+    println!("round trip");
+    let json = serde_json::to_string(&value).unwrap();
+    let round_trip: T = serde_json::from_str(&json).unwrap();
+    assert_eq!(round_trip, value, "{json}");
+
     value
 }
 
+#[track_caller]
+fn assert_same_shape(output: &Value, input: &Value, path: &str) {
+    match (output, input) {
+        (Value::Object(output), Value::Object(input)) => {
+            for (key, value) in output {
+                let path = format!("{path}.{key}");
+                match input.get(key) {
+                    Some(input) => assert_same_shape(value, input, &path),
+                    None => assert!(is_default(value), "{path}: {value} isn't in the input"),
+                }
+            }
+        }
+        (Value::Array(output), Value::Array(input)) => {
+            assert_eq!(output.len(), input.len(), "{path}");
+            for (i, (output, input)) in output.iter().zip(input).enumerate() {
+                assert_same_shape(output, input, &format!("{path}[{i}]"));
+            }
+        }
+        // Some numbers are stored as `f32`
+        (Value::Number(output), Value::Number(input)) => {
+            let (output, input) = (output.as_f64().unwrap(), input.as_f64().unwrap());
+            assert!(
+                (output - input).abs() <= input.abs() * 1e-6,
+                "{path}: {output} != {input}"
+            );
+        }
+        _ => assert_eq!(output, input, "{path}"),
+    }
+}
+
+/// The fields that Spotify sends but that are deliberately left out of the
+/// models. They're matched against the end of the path of each field, e.g.,
+/// `followers.href` only matches the `href` of the followers.
+const UNMODELED_FIELDS: &[&str] = &[
+    // Redundant with the ID and the model itself
+    "type",
+    "uri",
+    // Always null
+    "followers.href",
+    // The kind of item of a playlist's track, which is in its `type`
+    "track.episode",
+    "track.track",
+    "total_tracks",
+    "primary_color",
+    "video_thumbnail",
+    // Of the simplified playlists
+    "description",
+];
+
+/// Checks that every field in the input is modeled, so that the new fields
+/// added by Spotify are noticed, unless it's one of [`UNMODELED_FIELDS`].
+/// The fields skipped when empty aren't unknown either.
+#[track_caller]
+fn assert_no_unknown_fields(input: &Value, output: &Value, path: &str) {
+    match (input, output) {
+        (Value::Object(input), Value::Object(output)) => {
+            for (key, value) in input {
+                let path = format!("{path}.{key}");
+                match output.get(key) {
+                    Some(output) => assert_no_unknown_fields(value, output, &path),
+                    None => assert!(
+                        is_unmodeled(&path) || is_default(value),
+                        "{path} isn't modeled"
+                    ),
+                }
+            }
+        }
+        (Value::Array(input), Value::Array(output)) => {
+            for (i, (input, output)) in input.iter().zip(output).enumerate() {
+                assert_no_unknown_fields(input, output, &format!("{path}[{i}]"));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Whether the field at the given path is one of [`UNMODELED_FIELDS`].
+fn is_unmodeled(path: &str) -> bool {
+    let mut fields = String::new();
+    for segment in path.split('.') {
+        fields.push('.');
+        fields.push_str(segment.split('[').next().unwrap());
+    }
+    UNMODELED_FIELDS
+        .iter()
+        .any(|field| fields.ends_with(&format!(".{field}")))
+}
+
+fn is_default(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Bool(b) => !b,
+        Value::Number(n) => n.as_f64() == Some(0.0),
+        Value::String(s) => s.is_empty(),
+        Value::Array(a) => a.is_empty(),
+        Value::Object(o) => o.is_empty(),
+    }
+}
+
 #[test]
 #[wasm_bindgen_test]
 fn test_simplified_track() {
@@ -162,8 +290,40 @@ fn test_private_user() {
             "uri": "spotify:user:waq5aexykhm6nlv0cnwdieng0"
           } 
         "#;
-    let private_user: PrivateUser = deserialize(json_str);
+    // The legacy `open` subscription level is parsed as `free`
+    let private_user: PrivateUser = deserialize_normalized(json_str);
     assert_eq!(private_user.country.unwrap(), Country::UnitedStates);
+    assert_eq!(private_user.product, Some(SubscriptionLevel::Free));
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn test_private_user_managed_account() {
+    let json_str = r#"
+        {
+            "display_name": "Kid",
+            "explicit_content": {
+              "filter_locked": true
+            },
+            "id": "kid0account",
+            "images": [
+              { "height": 64, "url": "https://i.scdn.co/image/small", "width": 64 },
+              { "height": 300, "url": "https://i.scdn.co/image/large", "width": 300 }
+            ],
+            "product": "kids",
+            "type": "user",
+            "uri": "spotify:user:kid0account"
+          }
+        "#;
+    let private_user: PrivateUser = deserialize_normalized(json_str);
+    assert_eq!(private_user.product, Some(SubscriptionLevel::Unknown));
+    assert!(private_user.href.is_empty());
+    assert!(!private_user.filters_explicit_content());
+    assert_eq!(
+        private_user.profile_image().unwrap().url,
+        "https://i.scdn.co/image/large"
+    );
+    assert!(private_user.explicit_content.unwrap().filter_locked);
 }
 
 #[test]
@@ -199,6 +359,14 @@ fn test_full_artist() {
     let full_artist: FullArtist = deserialize(json_str);
     assert_eq!(full_artist.name, "Band of Horses");
     assert_eq!(full_artist.followers.total, 833247);
+    assert_eq!(
+        full_artist.open_url(),
+        "https://open.spotify.com/artist/0OdUWJ0sBjDrqHygGUXeCF"
+    );
+    assert_eq!(
+        full_artist.external_url(),
+        Some(full_artist.open_url().as_str())
+    );
 }
 
 #[test]
@@ -351,8 +519,18 @@ fn test_audio_analysis_section() {
         }
         "#;
     let session: AudioAnalysisSection = deserialize(json_str);
-    // Comparison of floating point numbers
-    assert!((session.time_interval.duration - 18.32542).abs() < f32::EPSILON);
+    assert_eq!(
+        session.time_interval.duration,
+        Duration::microseconds(18_325_420)
+    );
+    assert_eq!(session.time_interval.confidence, Confidence(1.0));
+    assert!(session.tempo_confidence.is_at_least(0.7));
+    assert!(session
+        .time_interval
+        .contains(Duration::try_seconds(250).unwrap()));
+    assert!(!session
+        .time_interval
+        .contains(Duration::try_seconds(237).unwrap()));
 }
 
 #[test]
@@ -376,8 +554,14 @@ fn test_audio_analysis_segments() {
             }
             "#;
     let segment: AudioAnalysisSegment = deserialize(json_str);
-    // Comparison of floating point numbers
-    assert!((segment.time_interval.start - 252.156).abs() < f32::EPSILON);
+    assert_eq!(
+        segment.time_interval.start,
+        Duration::microseconds(252_156_010)
+    );
+    assert_eq!(
+        segment.time_interval.end(),
+        Duration::microseconds(255_348_980)
+    );
 }
 
 #[test]
@@ -394,6 +578,62 @@ fn test_actions() {
     assert_eq!(actions.disallows[0], DisallowKey::Resuming);
 }
 
+#[test]
+#[wasm_bindgen_test]
+fn test_json_model() {
+    let token = Token {
+        access_token: "test-access_token".to_owned(),
+        expires_in: Duration::try_seconds(3600).unwrap(),
+        expires_at: Some(chrono::Utc::now()),
+        scopes: ["user-read-private", "user-library-read"]
+            .iter()
+            .map(|scope| (*scope).to_owned())
+            .collect(),
+        refresh_token: Some("test-refresh_token".to_owned()),
+    };
+    let json = token.to_json_string().unwrap();
+    assert_eq!(Token::from_json_str(&json).unwrap(), token);
+
+    // The disallowed actions are kept, in the same order
+    let actions = Actions {
+        disallows: vec![DisallowKey::Pausing, DisallowKey::Seeking],
+    };
+    let json = actions.to_json_string().unwrap();
+    assert_eq!(Actions::from_json_str(&json).unwrap(), actions);
+
+    assert!(matches!(
+        Actions::from_json_str("[]"),
+        Err(ModelError::ParseJson(_))
+    ));
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn test_actions_capabilities() {
+    let json_str = r#"
+        {
+            "disallows": {
+                "pausing": false,
+                "skipping_next": true,
+                "seeking": true
+            }
+        }
+        "#;
+    let actions: Actions = deserialize(json_str);
+    assert!(actions.can_pause());
+    assert!(actions.can_skip_prev());
+    assert!(!actions.can_skip_next());
+    assert!(!actions.can_seek());
+    assert_eq!(
+        actions.check(DisallowKey::Seeking),
+        Err(PlayerError::DisallowedAction(DisallowKey::Seeking))
+    );
+    assert_eq!(
+        PlayerError::DisallowedAction(DisallowKey::SkippingNext).to_string(),
+        "the player currently disallows `skipping_next`"
+    );
+}
+
 #[test]
 #[wasm_bindgen_test]
 fn test_recommendations_seed() {
@@ -547,6 +787,10 @@ fn test_full_playlist() {
         "spotify:playlist:3cEYpjA9oz9GiPac4AsH4n".to_string()
     );
     assert_eq!(full_playlist.followers.total, 109);
+    assert_eq!(
+        full_playlist.external_url(),
+        Some(full_playlist.open_url().as_str())
+    );
 }
 
 #[test]
@@ -657,6 +901,112 @@ fn test_full_track() {
     let full_track: FullTrack = deserialize(json);
     let duration = Duration::try_milliseconds(207959).unwrap();
     assert_eq!(full_track.duration, duration);
+
+    let url = "https://open.spotify.com/track/11dFghVXANMlKmJXsNCbNl";
+    assert_eq!(full_track.external_url(), Some(url));
+    assert_eq!(full_track.open_url().as_deref(), Some(url));
+    let url = "https://open.spotify.com/album/0tGPJ0bkWOUmH7MEOR77qc";
+    assert_eq!(full_track.album.external_url(), Some(url));
+    assert_eq!(full_track.album.open_url().as_deref(), Some(url));
+    let url = "https://open.spotify.com/artist/6sFIWsNpZYqfjUpaCgueju";
+    assert_eq!(full_track.artists[0].open_url().as_deref(), Some(url));
+
+    // The link is still known without the `external_urls`
+    let mut artist = full_track.artists[0].clone();
+    artist.external_urls.clear();
+    assert_eq!(artist.external_url(), None);
+    assert_eq!(artist.open_url().as_deref(), Some(url));
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn test_full_album() {
+    let json = r#"
+{
+  "album_type": "compilation",
+  "total_tracks": 1,
+  "available_markets": ["ES", "US"],
+  "copyrights": [{ "text": "(P) 2010 Sony", "type": "P" }],
+  "external_ids": { "upc": "886443671584" },
+  "external_urls": {
+    "spotify": "https://open.spotify.com/album/4aawyAB9vmqN3uQ7FjRGTy"
+  },
+  "genres": [],
+  "href": "https://api.spotify.com/v1/albums/4aawyAB9vmqN3uQ7FjRGTy",
+  "id": "4aawyAB9vmqN3uQ7FjRGTy",
+  "images": [
+    {
+      "url": "https://i.scdn.co/image/ab67616d0000b2732c5b24ecfa39523a75c993c4",
+      "height": 640,
+      "width": 640
+    }
+  ],
+  "label": "Mr.305/Polo Grounds Music/RCA Records",
+  "name": "Global Warming",
+  "popularity": 57,
+  "release_date": "2012-11-16",
+  "release_date_precision": "day",
+  "type": "album",
+  "uri": "spotify:album:4aawyAB9vmqN3uQ7FjRGTy",
+  "artists": [
+    {
+      "external_urls": {
+        "spotify": "https://open.spotify.com/artist/0TnOYISbd1XYRBk9myaseg"
+      },
+      "href": "https://api.spotify.com/v1/artists/0TnOYISbd1XYRBk9myaseg",
+      "id": "0TnOYISbd1XYRBk9myaseg",
+      "name": "Pitbull",
+      "type": "artist",
+      "uri": "spotify:artist:0TnOYISbd1XYRBk9myaseg"
+    }
+  ],
+  "tracks": {
+    "href": "https://api.spotify.com/v1/albums/4aawyAB9vmqN3uQ7FjRGTy/tracks?offset=0&limit=50",
+    "limit": 50,
+    "next": null,
+    "offset": 0,
+    "previous": null,
+    "total": 1,
+    "items": [
+      {
+        "artists": [
+          {
+            "external_urls": {
+              "spotify": "https://open.spotify.com/artist/0TnOYISbd1XYRBk9myaseg"
+            },
+            "href": "https://api.spotify.com/v1/artists/0TnOYISbd1XYRBk9myaseg",
+            "id": "0TnOYISbd1XYRBk9myaseg",
+            "name": "Pitbull",
+            "type": "artist",
+            "uri": "spotify:artist:0TnOYISbd1XYRBk9myaseg"
+          }
+        ],
+        "available_markets": ["ES", "US"],
+        "disc_number": 1,
+        "duration_ms": 85400,
+        "explicit": true,
+        "external_urls": {
+          "spotify": "https://open.spotify.com/track/6OmhkSOpvYBokMKQxpIGx2"
+        },
+        "href": "https://api.spotify.com/v1/tracks/6OmhkSOpvYBokMKQxpIGx2",
+        "id": "6OmhkSOpvYBokMKQxpIGx2",
+        "name": "Global Warming (feat. Sensato)",
+        "preview_url": null,
+        "track_number": 1,
+        "type": "track",
+        "uri": "spotify:track:6OmhkSOpvYBokMKQxpIGx2",
+        "is_local": false
+      }
+    ]
+  }
+}
+    "#;
+    let album: FullAlbum = deserialize(json);
+    let url = "https://open.spotify.com/album/4aawyAB9vmqN3uQ7FjRGTy";
+    assert_eq!(album.external_url(), Some(url));
+    assert_eq!(album.open_url(), url);
+    let track = &album.tracks.items[0];
+    assert_eq!(track.external_url(), track.open_url().as_deref());
 }
 
 #[test]
@@ -797,6 +1147,50 @@ fn test_currently_playing_context() {
 
     let duration = Duration::try_milliseconds(22270).unwrap();
     assert_eq!(currently_playing_context.progress, Some(duration));
+    assert!(matches!(
+        currently_playing_context.currently_playing(),
+        CurrentlyPlaying::Track(track) if track.name == "Playinwitme (feat. Kehlani)"
+    ));
+
+    // The track was relinked from the requested one
+    let track = match currently_playing_context.item {
+        Some(PlayableItem::Track(track)) => track,
+        _ => panic!("the item should be a track"),
+    };
+    let requested = TrackId::from_id("43cFjTTCD9Cni4aSL0sORz").unwrap();
+    assert_eq!(track.relinked_id(), Some(requested.clone()));
+    assert_eq!(track.requested_id(), Some(requested));
+    assert_ne!(
+        track.id.as_ref().map(|id| id.as_ref()),
+        track.requested_id()
+    );
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn test_currently_playing_ad() {
+    let json = r#"
+{
+  "timestamp": 1607769168429,
+  "context": null,
+  "progress_ms": 1000,
+  "item": null,
+  "currently_playing_type": "ad",
+  "actions": {
+    "disallows": {
+      "skipping_next": true
+    }
+  },
+  "is_playing": true
+}
+    "#;
+    let context: CurrentlyPlayingContext = deserialize(json);
+    assert_eq!(context.currently_playing(), CurrentlyPlaying::Advertisement);
+
+    // Neither an unknown type nor a missing item are errors
+    let json = json.replace(r#""ad""#, r#""unknown""#);
+    let context: CurrentlyPlayingContext = deserialize(json);
+    assert_eq!(context.currently_playing(), CurrentlyPlaying::Unknown);
 }
 
 #[test]
@@ -908,6 +1302,32 @@ fn test_current_playback_context() {
     let dt = DateTime::from_timestamp(second, nanosecond as u32).unwrap();
     assert_eq!(current_playback_context.timestamp, dt);
     assert!(current_playback_context.progress.is_none());
+
+    let mut changed = current_playback_context.clone();
+    assert!(changed.changes_since(&current_playback_context).is_empty());
+    changed.device.volume_percent = Some(10);
+    changed.shuffle_state = !changed.shuffle_state;
+    changed.repeat_state = RepeatState::Track;
+    changed.is_playing = false;
+    changed.item = None;
+    assert_eq!(
+        changed.changes_since(&current_playback_context),
+        vec![
+            PlaybackChange::Item {
+                from: Some(PlayableId::Track(
+                    TrackId::from_id("43cFjTTCD9Cni4aSL0sORz").unwrap()
+                )),
+                to: None,
+            },
+            PlaybackChange::Playing(false),
+            PlaybackChange::Volume {
+                from: current_playback_context.device.volume_percent,
+                to: Some(10)
+            },
+            PlaybackChange::Shuffle(changed.shuffle_state),
+            PlaybackChange::Repeat(RepeatState::Track),
+        ]
+    );
 }
 
 #[test]
@@ -1186,6 +1606,10 @@ fn test_simplified_playlist() {
         "https://api.spotify.com/v1/playlists/37i9dQZF1DX8mBRYewE6or/tracks"
     );
     assert_eq!(simplified_playlist.tracks.total, 62);
+    assert_eq!(
+        simplified_playlist.open_url(),
+        "https://open.spotify.com/playlist/37i9dQZF1DX8mBRYewE6or"
+    );
 }
 
 #[test]
@@ -1223,3 +1647,216 @@ fn test_null_id_in_tracklink() {
     assert!(linked_from.id.is_none());
     assert_eq!(linked_from.r#type, Type::Track);
 }
+
+#[test]
+#[wasm_bindgen_test]
+fn test_full_audiobook() {
+    let json = r#"
+{
+  "authors": [{ "name": "Frank Herbert" }],
+  "available_markets": ["US", "GB"],
+  "chapters": {
+    "href": "https://api.spotify.com/v1/audiobooks/7iHfbu1YPACw6oZPAFJtqe/chapters?offset=0&limit=50",
+    "items": [
+      {
+        "audio_preview_url": null,
+        "available_markets": ["US", "GB"],
+        "chapter_number": 0,
+        "description": "Chapter 1",
+        "html_description": "Chapter 1",
+        "duration_ms": 1843235,
+        "explicit": false,
+        "external_urls": { "spotify": "https://open.spotify.com/episode/0D5wENdkdwbqlrHoaJ9g29" },
+        "href": "https://api.spotify.com/v1/chapters/0D5wENdkdwbqlrHoaJ9g29",
+        "id": "0D5wENdkdwbqlrHoaJ9g29",
+        "images": [],
+        "is_playable": true,
+        "languages": ["en"],
+        "name": "Chapter 1",
+        "release_date": "2019-01-01",
+        "release_date_precision": "day",
+        "resume_point": { "fully_played": false, "resume_position_ms": 60000 },
+        "type": "episode",
+        "uri": "spotify:episode:0D5wENdkdwbqlrHoaJ9g29"
+      }
+    ],
+    "limit": 50,
+    "next": null,
+    "offset": 0,
+    "previous": null,
+    "total": 1
+  },
+  "copyrights": [],
+  "description": "Author(s): Frank Herbert",
+  "html_description": "Author(s): Frank Herbert",
+  "edition": "Unabridged",
+  "explicit": false,
+  "external_urls": { "spotify": "https://open.spotify.com/show/7iHfbu1YPACw6oZPAFJtqe" },
+  "href": "https://api.spotify.com/v1/audiobooks/7iHfbu1YPACw6oZPAFJtqe",
+  "id": "7iHfbu1YPACw6oZPAFJtqe",
+  "images": [],
+  "languages": ["English"],
+  "media_type": "audio",
+  "name": "Dune: Book One in the Dune Chronicles",
+  "narrators": [{ "name": "Scott Brick" }, { "name": "Orlagh Cassidy" }],
+  "publisher": "Frank Herbert",
+  "type": "audiobook",
+  "uri": "spotify:show:7iHfbu1YPACw6oZPAFJtqe",
+  "total_chapters": 1
+}
+"#;
+    let audiobook: FullAudiobook = deserialize(json);
+    assert_eq!(audiobook.narrators.len(), 2);
+    assert_eq!(audiobook.edition.as_deref(), Some("Unabridged"));
+
+    let chapter = &audiobook.chapters.items[0];
+    assert_eq!(
+        chapter.id,
+        ChapterId::from_id("0D5wENdkdwbqlrHoaJ9g29").unwrap()
+    );
+    assert_eq!(
+        chapter.duration,
+        Duration::try_milliseconds(1843235).unwrap()
+    );
+    assert_eq!(
+        chapter.resume_point.as_ref().unwrap().resume_position,
+        Duration::try_minutes(1).unwrap()
+    );
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn test_playlist_snapshot_id() {
+    let json = r#"{"snapshot_id": "MTYsZmFjMzc2ZTQ="}"#;
+    let snapshot_id: PlaylistSnapshotId = deserialize(json);
+    assert_eq!(snapshot_id, "MTYsZmFjMzc2ZTQ=");
+    assert_eq!(snapshot_id.to_string(), "MTYsZmFjMzc2ZTQ=");
+
+    // The previous name still works, along with its field
+    #[allow(deprecated)]
+    let result: PlaylistResult = deserialize(json);
+    assert_eq!(result.snapshot_id, snapshot_id.as_str());
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn test_partial_playlist_item() {
+    let json = r#"
+{
+  "added_at": "2021-02-05T20:31:01Z",
+  "track": {
+    "name": "Aerodynamic",
+    "artists": [{ "name": "Daft Punk" }]
+  }
+}
+"#;
+    let item: PartialPlaylistItem = deserialize(json);
+    assert_eq!(item.get_str("track.name"), Some("Aerodynamic"));
+    assert_eq!(item.get_str("track.artists.0.name"), Some("Daft Punk"));
+    assert_eq!(item.get("track.album"), None);
+    assert_eq!(
+        item.added_at(),
+        Some("2021-02-05T20:31:01Z".parse().unwrap())
+    );
+    assert!(item.to_full().is_none());
+
+    #[derive(serde::Deserialize)]
+    struct Projection {
+        track: ProjectedTrack,
+    }
+    #[derive(serde::Deserialize)]
+    struct ProjectedTrack {
+        name: String,
+    }
+    let projection: Projection = item.project().unwrap();
+    assert_eq!(projection.track.name, "Aerodynamic");
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn test_local_playlist_item() {
+    let json = r#"
+{
+  "added_at": "2021-02-05T20:31:01Z",
+  "added_by": null,
+  "is_local": true,
+  "primary_color": null,
+  "track": {
+    "album": {
+      "album_type": null,
+      "artists": [],
+      "available_markets": [],
+      "external_urls": {},
+      "href": null,
+      "id": null,
+      "images": [],
+      "name": "",
+      "release_date": null,
+      "release_date_precision": null,
+      "type": "album",
+      "uri": null
+    },
+    "artists": [
+      {
+        "external_urls": {},
+        "href": null,
+        "id": null,
+        "name": "Daft Punk",
+        "type": "artist",
+        "uri": null
+      }
+    ],
+    "available_markets": [],
+    "disc_number": 0,
+    "duration_ms": 320000,
+    "explicit": false,
+    "external_ids": {},
+    "external_urls": {},
+    "href": null,
+    "id": null,
+    "is_local": true,
+    "name": "One More Time",
+    "popularity": 0,
+    "preview_url": null,
+    "track_number": 0,
+    "type": "track",
+    "uri": "spotify:local:Daft+Punk:Discovery%3A+Remastered:One+More+Time:320"
+  },
+  "video_thumbnail": { "url": null }
+}
+"#;
+    let item: PlaylistItem = deserialize_normalized(json);
+    let track = match item.track {
+        Some(PlayableItem::LocalTrack(track)) => track,
+        track => panic!("unexpected item: {track:?}"),
+    };
+    assert_eq!(track.name, "One More Time");
+    assert_eq!(track.artists, ["Daft Punk"]);
+    // Taken from the URI, since the tags of the file had no album
+    assert_eq!(track.album.as_deref(), Some("Discovery: Remastered"));
+    assert_eq!(track.duration, Duration::try_seconds(320).unwrap());
+    assert!(PlayableItem::LocalTrack(track).id().is_none());
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn test_search_result_accessors() {
+    let json = r#"
+    {
+        "episodes": {
+            "href": "https://api.spotify.com/v1/search?query=rust&type=episode&offset=0&limit=20",
+            "items": [],
+            "limit": 20,
+            "next": null,
+            "offset": 0,
+            "previous": null,
+            "total": 0
+        }
+    }
+    "#;
+    let result: SearchResult = deserialize(json);
+    assert_eq!(result.search_type(), SearchType::Episode);
+    assert_eq!(result.episodes().map(|page| page.limit), Some(20));
+    assert!(result.shows().is_none());
+    assert!(result.tracks().is_none());
+}
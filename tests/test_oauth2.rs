@@ -1,9 +1,16 @@
 use chrono::prelude::*;
 use chrono::Duration;
 use rspotify::{
-    prelude::*, scopes, AuthCodeSpotify, ClientCredsSpotify, Config, Credentials, OAuth, Token,
+    prelude::*, scopes, AuthCodePkceSpotify, AuthCodeSpotify, ClientCredsSpotify, ClientResult,
+    Config, Credentials, OAuth, PkceVerifier, Token, TokenStore,
+};
+use std::{
+    collections::HashMap,
+    fs,
+    io::Read,
+    path::PathBuf,
+    sync::{Arc, Mutex},
 };
-use std::{collections::HashMap, fs, io::Read, path::PathBuf};
 use url::Url;
 use wasm_bindgen_test::*;
 
@@ -34,6 +41,66 @@ fn test_get_authorize_url() {
     assert_eq!(hash_query.get("state").unwrap(), "fdsafdsfa");
 }
 
+#[test]
+#[wasm_bindgen_test]
+fn test_get_authorize_url_is_deterministic() {
+    let oauth = OAuth {
+        state: "fdsafdsfa".to_owned(),
+        redirect_uri: "http://localhost:8888/callback".to_owned(),
+        scopes: scopes!(
+            "user-read-private",
+            "playlist-read-private",
+            "user-library-read",
+            "streaming"
+        ),
+        ..Default::default()
+    };
+    let creds = Credentials::new("this-is-my-client-id", "this-is-my-client-secret");
+    let spotify = AuthCodeSpotify::new(creds, oauth);
+
+    assert_eq!(
+        spotify.get_authorize_url(true).unwrap(),
+        "https://accounts.spotify.com/authorize?\
+         client_id=this-is-my-client-id&\
+         redirect_uri=http%3A%2F%2Flocalhost%3A8888%2Fcallback&\
+         response_type=code&\
+         scope=playlist-read-private+streaming+user-library-read+user-read-private&\
+         show_dialog=true&\
+         state=fdsafdsfa"
+    );
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn test_get_authorize_url_with_verifier() {
+    let oauth = OAuth {
+        state: "fdsafdsfa".to_owned(),
+        redirect_uri: "http://localhost:8888/callback".to_owned(),
+        scopes: scopes!("user-read-private"),
+        ..Default::default()
+    };
+    let spotify = AuthCodePkceSpotify::new(Credentials::new_pkce("this-is-my-client-id"), oauth);
+
+    let (authorize_url, verifier) = spotify.get_authorize_url_with_verifier(None).unwrap();
+    let hash_query: HashMap<_, _> = Url::parse(&authorize_url)
+        .unwrap()
+        .query_pairs()
+        .into_owned()
+        .collect();
+    assert_eq!(hash_query.get("code_challenge"), Some(&verifier.challenge));
+    assert_eq!(verifier.verifier.len(), 43);
+    assert_eq!(verifier.redirect_uri, "http://localhost:8888/callback");
+    assert_eq!(hash_query.get("state"), Some(&verifier.state));
+    assert!(spotify.verifier.is_none());
+
+    // It may be kept elsewhere until the callback is handled
+    let json = serde_json::to_string(&verifier).unwrap();
+    assert_eq!(
+        serde_json::from_str::<PkceVerifier>(&json).unwrap(),
+        verifier
+    );
+}
+
 #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
 async fn test_read_token_cache() {
     let expires_in = Duration::try_seconds(3600).unwrap();
@@ -118,6 +185,68 @@ async fn test_write_token() {
     fs::remove_file(&spotify.config.cache_path).unwrap();
 }
 
+#[derive(Debug, Default)]
+struct MemoryTokenStore(Mutex<Option<Token>>);
+
+#[cfg_attr(target_arch = "wasm32", maybe_async::maybe_async(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), maybe_async::maybe_async)]
+impl TokenStore for MemoryTokenStore {
+    async fn load(&self) -> ClientResult<Option<Token>> {
+        Ok(self.0.lock().unwrap().clone())
+    }
+
+    async fn save(&self, token: &Token) -> ClientResult<()> {
+        *self.0.lock().unwrap() = Some(token.clone());
+        Ok(())
+    }
+
+    async fn clear(&self) -> ClientResult<()> {
+        *self.0.lock().unwrap() = None;
+        Ok(())
+    }
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_custom_token_store() {
+    let expires_in = Duration::try_seconds(3600).unwrap();
+    let tok = Token {
+        expires_in,
+        expires_at: Some(Utc::now() + expires_in),
+        access_token: "test-access_token".to_owned(),
+        scopes: scopes!("playlist-read-private"),
+        refresh_token: Some("...".to_owned()),
+    };
+
+    let store = Arc::new(MemoryTokenStore::default());
+    let config = Config {
+        token_cached: true,
+        cache_path: PathBuf::from(".test_custom_token_store.json"),
+        token_store: Some(store.clone()),
+        ..Default::default()
+    };
+
+    let mut spotify = ClientCredsSpotify::default();
+    spotify.config = config.clone();
+    // Nothing has been saved yet
+    let cached = spotify.read_token_cache().await.unwrap();
+    assert_eq!(cached, None);
+
+    let mut predefined_spotify = ClientCredsSpotify::from_token(tok.clone());
+    predefined_spotify.config = config;
+    predefined_spotify.write_token_cache().await.unwrap();
+
+    // The token went to the custom store instead of the cache file
+    assert!(!spotify.config.cache_path.exists());
+    let stored = store.load().await.unwrap();
+    assert_eq!(stored, Some(tok.clone()));
+    let cached = spotify.read_token_cache().await.unwrap();
+    assert_eq!(cached, Some(tok));
+
+    store.clear().await.unwrap();
+    let cached = spotify.read_token_cache().await.unwrap();
+    assert_eq!(cached, None);
+}
+
 #[test]
 #[wasm_bindgen_test]
 fn test_token_is_expired() {
@@ -130,6 +259,7 @@ fn test_token_is_expired() {
         refresh_token: Some("...".to_owned()),
     };
     assert!(!tok.is_expired());
+    assert!(tok.is_expired_within(Duration::try_seconds(30).unwrap()));
 
     let expires_in = Duration::try_seconds(3).unwrap(); // There's a margin of 10 seconds
     let tok = Token {
@@ -140,6 +270,7 @@ fn test_token_is_expired() {
         refresh_token: Some("...".to_owned()),
     };
     assert!(tok.is_expired());
+    assert!(!tok.is_expired_within(Duration::zero()));
 }
 
 #[test]
@@ -20,9 +20,10 @@ mod util;
 use rspotify::{
     clients::pagination::Paginator,
     model::{
-        AlbumId, ArtistId, Country, CurrentPlaybackContext, Device, EpisodeId, FullPlaylist,
-        ItemPositions, Market, Offset, PlaylistId, RecommendationsAttribute, RepeatState,
-        SearchType, ShowId, TimeLimits, TimeRange, TrackId, UserId,
+        AlbumId, ArtistId, CategoryId, Country, CurrentPlaybackContext, Device, EpisodeId,
+        FullArtist, FullPlaylist, FullTrack, ItemPositions, Market, Offset, PlaylistDetails,
+        PlaylistId, RecommendationsAttribute, RepeatState, SearchType, ShowId, TimeLimits,
+        TimeRange, TrackId, UserId,
     },
     prelude::*,
     scopes, AuthCodeSpotify, ClientResult, OAuth, Token,
@@ -140,6 +141,25 @@ async fn test_categories() {
         .unwrap();
 }
 
+#[maybe_async::test(
+    feature = "__sync",
+    async(all(feature = "__async", not(target_arch = "wasm32")), tokio::test),
+    async(all(feature = "__async", target_arch = "wasm32"), wasm_bindgen_test)
+)]
+#[ignore]
+async fn test_category() {
+    let category = oauth_client()
+        .await
+        .category(
+            CategoryId::from_id("pop").unwrap(),
+            Some("es_MX"),
+            Some(Market::Country(Country::Mexico)),
+        )
+        .await
+        .unwrap();
+    assert_eq!(category.id, "pop");
+}
+
 #[maybe_async::test(
     feature = "__sync",
     async(all(feature = "__async", not(target_arch = "wasm32")), tokio::test),
@@ -150,7 +170,7 @@ async fn test_category_playlists() {
     oauth_client()
         .await
         .category_playlists_manual(
-            "pop",
+            CategoryId::from_id("pop").unwrap(),
             Some(Market::Country(Country::UnitedStates)),
             Some(10),
             None,
@@ -194,11 +214,14 @@ async fn test_current_playing() {
 )]
 #[ignore]
 async fn test_current_user_followed_artists() {
-    oauth_client()
-        .await
-        .current_user_followed_artists(None, Some(10))
+    let client = oauth_client().await;
+    let page = client
+        .current_user_followed_artists_manual(None, Some(10))
         .await
         .unwrap();
+
+    let all = fetch_all(client.current_user_followed_artists()).await;
+    assert!(all.len() >= page.items.len());
 }
 
 #[maybe_async::test(
@@ -222,12 +245,16 @@ async fn test_current_user_playing_track() {
 )]
 #[ignore]
 async fn test_current_user_recently_played() {
-    let limit = TimeLimits::After(Utc::now() - Duration::try_days(2).unwrap());
-    oauth_client()
-        .await
-        .current_user_recently_played(Some(10), Some(limit))
+    let since = Utc::now() - Duration::try_days(2).unwrap();
+    let limit = TimeLimits::After(since);
+    let client = oauth_client().await;
+    client
+        .current_user_recently_played_manual(Some(10), Some(limit))
         .await
         .unwrap();
+
+    let all = fetch_all(client.current_user_recently_played(Some(limit))).await;
+    assert!(all.iter().all(|item| item.played_at > since));
 }
 
 #[maybe_async::test(
@@ -271,6 +298,49 @@ async fn test_current_user_saved_albums() {
         .unwrap();
 }
 
+#[maybe_async::test(
+    feature = "__sync",
+    async(all(feature = "__async", not(target_arch = "wasm32")), tokio::test),
+    async(all(feature = "__async", target_arch = "wasm32"), wasm_bindgen_test)
+)]
+#[ignore]
+async fn test_current_user_saved_episodes() {
+    let episode_ids = [
+        EpisodeId::from_id("0lbiy3LKzIY2fnyjioC11p").unwrap(),
+        EpisodeId::from_id("4zugY5eJisugQj9rj8TYuh").unwrap(),
+    ];
+
+    let client = oauth_client().await;
+
+    // First adding the episodes
+    client
+        .current_user_saved_episodes_add(episode_ids.iter().map(EpisodeId::as_ref))
+        .await
+        .unwrap();
+    let contains = client
+        .current_user_saved_episodes_contains(episode_ids.iter().map(EpisodeId::as_ref))
+        .await
+        .unwrap();
+    assert_eq!(contains, vec![true, true]);
+
+    // Making sure the new episodes appear
+    let all_ids = fetch_all(client.current_user_saved_episodes(Some(Market::FromToken)))
+        .await
+        .into_iter()
+        .map(|saved| saved.episode.id)
+        .collect::<Vec<_>>();
+    assert!(
+        episode_ids.iter().all(|item| all_ids.contains(item)),
+        "couldn't find the new saved episodes"
+    );
+
+    // And then removing them
+    client
+        .current_user_saved_episodes_delete(episode_ids)
+        .await
+        .unwrap();
+}
+
 #[maybe_async::test(
     feature = "__sync",
     async(all(feature = "__async", not(target_arch = "wasm32")), tokio::test),
@@ -337,6 +407,24 @@ async fn test_current_user_top_tracks() {
         .unwrap();
 }
 
+#[maybe_async::test(
+    feature = "__sync",
+    async(all(feature = "__async", not(target_arch = "wasm32")), tokio::test),
+    async(all(feature = "__async", target_arch = "wasm32"), wasm_bindgen_test)
+)]
+#[ignore]
+async fn test_current_user_top_items() {
+    let client = oauth_client().await;
+    let artists = client
+        .current_user_top_items_manual::<FullArtist>(Some(TimeRange::LongTerm), Some(5), None)
+        .await
+        .unwrap();
+    assert!(artists.items.len() as u32 <= artists.total.min(5));
+
+    let tracks = fetch_all(client.current_user_top_items::<FullTrack>(None)).await;
+    assert!(tracks.iter().all(|track| track.id.is_some()));
+}
+
 #[maybe_async::test(
     feature = "__sync",
     async(all(feature = "__async", not(target_arch = "wasm32")), tokio::test),
@@ -411,19 +499,18 @@ async fn test_playback() {
     let backup = client.current_playback(None, None::<&[_]>).await.unwrap();
 
     for (i, device) in devices.iter().enumerate() {
-        let device_id = device.id.as_ref().unwrap();
+        let device_id = device.device_id().unwrap();
         let next_device_id = devices
             .get(i + 1)
             .unwrap_or(&devices[0])
-            .id
-            .as_ref()
+            .device_id()
             .unwrap();
 
         // Starting playback of some songs
         client
             .start_uris_playback(
                 uris.iter().map(PlayableId::as_ref),
-                Some(device_id),
+                Some(device_id.as_ref()),
                 Some(Offset::Position(chrono::Duration::zero())),
                 None,
             )
@@ -431,23 +518,32 @@ async fn test_playback() {
             .unwrap();
 
         for i in 0..uris.len() - 1 {
-            client.next_track(Some(device_id)).await.unwrap();
+            client.next_track(Some(device_id.as_ref())).await.unwrap();
 
             // Also trying to go to the previous track
             if i != 0 {
-                client.previous_track(Some(device_id)).await.unwrap();
-                client.next_track(Some(device_id)).await.unwrap();
+                client
+                    .previous_track(Some(device_id.as_ref()))
+                    .await
+                    .unwrap();
+                client.next_track(Some(device_id.as_ref())).await.unwrap();
             }
 
             // Making sure pause/resume also works
             let playback = client.current_playback(None, None::<&[_]>).await.unwrap();
             if let Some(playback) = playback {
                 if playback.is_playing {
-                    client.pause_playback(Some(device_id)).await.unwrap();
+                    client
+                        .pause_playback(Some(device_id.as_ref()))
+                        .await
+                        .unwrap();
                     client.resume_playback(None, None).await.unwrap();
                 } else {
                     client.resume_playback(None, None).await.unwrap();
-                    client.pause_playback(Some(device_id)).await.unwrap();
+                    client
+                        .pause_playback(Some(device_id.as_ref()))
+                        .await
+                        .unwrap();
                 }
             }
         }
@@ -463,8 +559,8 @@ async fn test_playback() {
         let uri = backup.item.as_ref().map(|item| item.id());
         if let Some(uri) = uri {
             let offset = None;
-            let device = backup.device.id.as_deref();
-            let position = backup.progress;
+            let device = backup.device.device_id();
+            let position = backup.progress.and_then(|progress| progress.to_std().ok());
             client
                 .start_uris_playback(uri, device, offset, position)
                 .await
@@ -635,7 +731,7 @@ async fn test_seek_track() {
     let backup = client.current_playback(None, None::<&[_]>).await.unwrap();
 
     client
-        .seek_track(chrono::Duration::try_seconds(25).unwrap(), None)
+        .seek_track(std::time::Duration::from_secs(25), None)
         .await
         .unwrap();
 
@@ -644,6 +740,7 @@ async fn test_seek_track() {
         ..
     }) = backup
     {
+        let progress = progress.to_std().unwrap();
         client.seek_track(progress, None).await.unwrap();
     }
 }
@@ -725,6 +822,45 @@ async fn test_user_follow_playlist() {
     client.playlist_unfollow(playlist_id).await.unwrap();
 }
 
+#[maybe_async::test(
+    feature = "__sync",
+    async(all(feature = "__async", not(target_arch = "wasm32")), tokio::test),
+    async(all(feature = "__async", target_arch = "wasm32"), wasm_bindgen_test)
+)]
+#[ignore]
+async fn test_cleanup_empty_playlists() {
+    let client = oauth_client().await;
+    let user = client.me().await.unwrap();
+    let name = "RSpotify cleanup test";
+    let playlist = client
+        .user_playlist_create(user.id.as_ref(), name, Some(false), None, None)
+        .await
+        .unwrap();
+
+    // Nothing is unfollowed in a dry run
+    let found = client
+        .cleanup_empty_playlists(|playlist| playlist.name == name, true)
+        .await
+        .unwrap();
+    assert!(found.iter().any(|found| found.id == playlist.id));
+    let following = client
+        .playlist_check_current_user_follows(playlist.id.as_ref())
+        .await
+        .unwrap();
+    assert!(following);
+
+    let found = client
+        .cleanup_empty_playlists(|playlist| playlist.name == name, false)
+        .await
+        .unwrap();
+    assert!(found.iter().any(|found| found.id == playlist.id));
+    let following = client
+        .playlist_check_current_user_follows(playlist.id.as_ref())
+        .await
+        .unwrap();
+    assert!(!following);
+}
+
 #[maybe_async]
 async fn check_playlist_create(client: &AuthCodeSpotify) -> FullPlaylist {
     let user = client.me().await.unwrap();
@@ -752,10 +888,11 @@ async fn check_playlist_create(client: &AuthCodeSpotify) -> FullPlaylist {
     client
         .playlist_change_detail(
             playlist.id.as_ref(),
-            Some(name),
-            Some(true),
-            Some(description),
-            Some(false),
+            PlaylistDetails::new()
+                .name(name)
+                .public(true)
+                .description(description)
+                .collaborative(false),
         )
         .await
         .unwrap();
@@ -765,7 +902,7 @@ async fn check_playlist_create(client: &AuthCodeSpotify) -> FullPlaylist {
 
 #[maybe_async]
 async fn check_num_tracks(client: &AuthCodeSpotify, playlist_id: PlaylistId<'_>, num: i32) {
-    let fetched_tracks = fetch_all(client.playlist_items(playlist_id, None, None)).await;
+    let fetched_tracks = fetch_all(client.playlist_items(playlist_id, None, None, None)).await;
     assert_eq!(fetched_tracks.len() as i32, num);
 }
 
@@ -790,6 +927,19 @@ async fn check_playlist_tracks(client: &AuthCodeSpotify, playlist: &FullPlaylist
         .unwrap();
     check_num_tracks(client, playlist.id.as_ref(), tracks.len() as i32).await;
 
+    // The items were added by the current user, whose profile is hydrated
+    let me = client.me().await.unwrap();
+    let mut items = client
+        .playlist_items_manual(playlist.id.as_ref(), None, None, None, None, None)
+        .await
+        .unwrap();
+    client.hydrate_added_by(&mut items).await.unwrap();
+    for item in items.items {
+        let added_by = item.added_by.unwrap();
+        assert_eq!(added_by.id, me.id);
+        assert_eq!(added_by.display_name, me.display_name);
+    }
+
     // Reordering some tracks
     client
         .playlist_reorder_items(playlist.id.as_ref(), Some(0), Some(3), Some(2), None)
@@ -808,7 +958,7 @@ async fn check_playlist_tracks(client: &AuthCodeSpotify, playlist: &FullPlaylist
         PlayableId::Episode(EpisodeId::from_id("4zugY5eJisugQj9rj8TYuh").unwrap()),
         PlayableId::Track(TrackId::from_uri("spotify:track:5m2en2ndANCPembKOYr1xL").unwrap()),
     ];
-    client
+    let snapshot_id = client
         .playlist_replace_items(
             playlist.id.as_ref(),
             replaced_tracks.iter().map(|t| t.as_ref()),
@@ -833,10 +983,20 @@ async fn check_playlist_tracks(client: &AuthCodeSpotify, playlist: &FullPlaylist
             positions: &[4, 6],
         },
     ];
-    client
-        .playlist_remove_specific_occurrences_of_items(playlist.id.as_ref(), tracks, None)
+    // The positions refer to the snapshot left by the replacement
+    let snapshot_id = client
+        .playlist_remove_specific_occurrences_of_items(
+            playlist.id.as_ref(),
+            tracks,
+            Some(snapshot_id.as_str()),
+        )
+        .await
+        .unwrap();
+    let current = client
+        .playlist_snapshot_id(playlist.id.as_ref())
         .await
         .unwrap();
+    assert_eq!(current, snapshot_id);
     // Making sure three tracks were removed
     check_num_tracks(
         client,
@@ -877,11 +1037,24 @@ async fn check_playlist_follow(client: &AuthCodeSpotify, playlist: &FullPlaylist
         .unwrap();
     assert_eq!(following, vec![false, false]);
 
+    // The creator of the playlist follows it by default
+    let following = client
+        .playlist_check_current_user_follows(playlist.id.as_ref())
+        .await
+        .unwrap();
+    assert!(following);
+
     // Finally unfollowing the playlist in order to clean it up
     client
         .playlist_unfollow(playlist.id.as_ref())
         .await
         .unwrap();
+
+    let following = client
+        .playlist_check_current_user_follows(playlist.id.as_ref())
+        .await
+        .unwrap();
+    assert!(!following);
 }
 
 #[maybe_async::test(
@@ -961,6 +1134,30 @@ async fn test_get_several_shows() {
         .unwrap();
 }
 
+#[maybe_async::test(
+    feature = "__sync",
+    async(all(feature = "__async", not(target_arch = "wasm32")), tokio::test),
+    async(all(feature = "__async", target_arch = "wasm32"), wasm_bindgen_test)
+)]
+#[ignore]
+async fn test_episodes_by_show_bulk() {
+    let shows = [
+        ShowId::from_id("5CfCWKI5pZ28U0uOzXkDHe").unwrap(),
+        ShowId::from_id("5as3aKmN2k11yfDDDSrvaZ").unwrap(),
+    ];
+
+    let bulk = oauth_client()
+        .await
+        .episodes_by_show_bulk(shows, 3, Some(Market::FromToken))
+        .await;
+    assert!(bulk.failed.is_empty());
+    assert!(bulk.episodes.len() <= 6);
+    assert!(bulk
+        .episodes
+        .windows(2)
+        .all(|pair| pair[0].1.release_date >= pair[1].1.release_date));
+}
+
 #[maybe_async::test(
     feature = "__sync",
     async(all(feature = "__async", not(target_arch = "wasm32")), tokio::test),